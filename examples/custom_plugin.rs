@@ -0,0 +1,58 @@
+//! Observes every phase of the simulation steps with custom [`StepHooks`],
+//! here to find the step whose interactions took longest, while a custom
+//! [`Behavior`] keeps the intelligent biots close to the center of the world.
+//!
+//! ```text
+//! cargo run --release --no-default-features --example custom_plugin
+//! ```
+
+use glam::Vec2;
+use life_web::prelude::*;
+use std::time::Duration;
+
+/// Intelligent biots that do not sense a predator head back to the center
+/// of the world once they strayed too far from it.
+struct Homing {
+    range: f32,
+}
+
+impl Behavior for Homing {
+    fn steer(&self, situation: &Situation) -> Option<Vec2> {
+        if situation.senses.predator != Vec2::ZERO {
+            return None;
+        }
+        let world = situation.world;
+        let center = Vec2::new(world.width, world.height) / 2.0;
+        let home = world.delta(situation.biot.stats.pos, center);
+        (home.length() > self.range).then(|| home.normalize())
+    }
+}
+
+/// Hooks remembering the slowest interaction phase.
+#[derive(Default)]
+struct SlowestInteractions {
+    slowest: Option<(u32, Duration)>,
+}
+
+impl StepHooks for SlowestInteractions {
+    fn end(&mut self, phase: Phase, step: u32, elapsed: Duration) {
+        let slower = self.slowest.is_none_or(|(_, slowest)| elapsed > slowest);
+        if phase == Phase::Interactions && slower {
+            self.slowest = Some((step, elapsed));
+        }
+    }
+}
+
+fn main() {
+    let world = World::new(800.0, 600.0);
+    let mut simulation = Simulation::new(world, SimConfig::default(), 0);
+    simulation.add_behavior(Homing { range: 200.0 });
+    let mut hooks = SlowestInteractions::default();
+    for _ in 0..300 {
+        simulation.step_with(&mut hooks);
+    }
+    if let Some((step, elapsed)) = hooks.slowest {
+        println!("the interactions of step {step} took longest: {elapsed:.2?}");
+    }
+    println!("{} biots are alive", simulation.biots().len());
+}
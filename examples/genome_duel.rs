@@ -0,0 +1,52 @@
+//! Pits two designed genomes against each other: a hunter built for
+//! attack and an armored photosynthesizer placed right next to it.
+//!
+//! ```text
+//! cargo run --release --no-default-features --example genome_duel
+//! ```
+
+use life_web::prelude::*;
+
+fn main() {
+    let hunter = GenomeBuilder::new()
+        .with(Gene::Attack, 18)
+        .with(Gene::Motion, 8)
+        .with(Gene::Venom, 6)
+        .build()
+        .expect("the hunter fits into a genome");
+    let fortress = GenomeBuilder::new()
+        .with(Gene::Photosynthesis, 16)
+        .with(Gene::Armor, 10)
+        .with(Gene::Defense, 6)
+        .build()
+        .expect("the fortress fits into a genome");
+    let mut scenario = Scenario::new();
+    let hunter = scenario.place(
+        |world, rng| Biot::from_genome(hunter, world, rng),
+        100.0,
+        100.0,
+    );
+    let fortress = scenario.place(
+        |world, rng| Biot::from_genome(fortress, world, rng),
+        104.0,
+        100.0,
+    );
+    for round in 1..=10 {
+        scenario.run(20);
+        println!(
+            "after {:3} steps: hunter {:7.2}, fortress {:7.2}",
+            round * 20,
+            scenario.life(hunter).0,
+            scenario.life(fortress).0
+        );
+        if scenario.is_dead(hunter) || scenario.is_dead(fortress) {
+            break;
+        }
+    }
+    let winner = match (scenario.is_dead(hunter), scenario.is_dead(fortress)) {
+        (false, true) => "the hunter",
+        (true, false) => "the fortress",
+        _ => "nobody",
+    };
+    println!("{winner} won the duel");
+}
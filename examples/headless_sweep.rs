@@ -0,0 +1,25 @@
+//! Sweeps the mutation chance of a small ecosystem over a few seeds, the
+//! way the `experiment` subcommand does, and prints a line per run.
+//!
+//! ```text
+//! cargo run --release --no-default-features --example headless_sweep
+//! ```
+
+use life_web::prelude::*;
+
+fn main() {
+    let sweep = "mutation_chance=0.05,0.2"
+        .parse::<Sweep>()
+        .expect("the sweep is well-formed");
+    let experiment = Experiment::new("initial_population = 200\n", vec![sweep], 2, 0, 500)
+        .expect("every combination is a valid configuration");
+    println!("running {} runs", experiment.runs());
+    let jobs = std::thread::available_parallelism().map_or(1, usize::from);
+    let summaries = experiment.run(jobs, |_| {});
+    for summary in &summaries {
+        println!(
+            "combination {} seed {}: {} biots of {} species after {} steps",
+            summary.combination, summary.seed, summary.population, summary.species, summary.steps,
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Starts a simulation from a scenario script instead of a random
+//! population and follows the population as the predators close in.
+//!
+//! ```text
+//! cargo run --release --no-default-features --example scripted_scenario
+//! ```
+
+use life_web::prelude::*;
+
+/// Plants in the middle of the world, surrounded by a ring of predators.
+const SCRIPT: &str = r#"
+[config]
+mutation_chance = 0.1
+
+[[biots]]
+archetype = "plant"
+x = 300.0
+y = 300.0
+count = 150
+radius = 80.0
+
+[[biots]]
+archetype = "predator"
+x = 300.0
+y = 300.0
+count = 20
+radius = 250.0
+"#;
+
+fn main() {
+    let spec = ScenarioSpec::from_toml(SCRIPT).expect("the script is a valid scenario");
+    let mut simulation = spec.simulation(World::new(600.0, 600.0), 0);
+    for _ in 0..5 {
+        for _ in 0..100 {
+            simulation.step();
+        }
+        let biots = simulation.biots();
+        println!(
+            "step {}: {} biots of {} species",
+            biots.steps(),
+            biots.len(),
+            biots.species_count()
+        );
+        if biots.is_empty() {
+            break;
+        }
+    }
+}
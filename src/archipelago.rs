@@ -0,0 +1,234 @@
+//! Several simulations exchanging migrants, the island model of population genetics.
+//!
+//! Isolated islands drift apart and keep diversity that a single well mixed
+//! population would lose, while the occasional migrant spreads successful
+//! genomes between them. The islands form a ring: every few steps each one
+//! sends a fraction of its biots on to the next.
+
+use crate::biot::Biot;
+use crate::config::SimConfig;
+use crate::obstacle;
+use crate::rng::Rng;
+use crate::simulation::Simulation;
+use crate::spawn;
+use crate::world::World;
+use glam::Vec2;
+
+/// Stream of the random number generator of the migrations, apart from the
+/// streams of the islands seeded with the same seed.
+const MIGRATION_STREAM: u64 = 0x6d69_6772;
+
+/// Number of positions tried for a migrant before it is pushed out of the
+/// obstacle it landed in.
+const LANDING_TRIES: usize = 64;
+
+/// When and how many biots move between the islands.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Migration {
+    /// Number of steps between two migrations, zero disables migration.
+    pub interval: u32,
+    /// Fraction of the biots of every island leaving it per migration.
+    pub fraction: f32,
+}
+
+impl Default for Migration {
+    fn default() -> Self {
+        Self {
+            interval: 500,
+            fraction: 0.02,
+        }
+    }
+}
+
+/// Independent simulations with their own parameters, exchanging migrants.
+pub struct Archipelago {
+    islands: Vec<Simulation>,
+    migration: Migration,
+    rng: Rng,
+    /// Number of steps computed so far.
+    steps: u64,
+}
+
+impl Archipelago {
+    /// Creates an island in `world` for each of the `configs`, the first one
+    /// seeded with `seed` and the following ones with the next seeds.
+    pub fn generate(world: World, configs: &[SimConfig], seed: u64, migration: Migration) -> Self {
+        Self::new(seeded(world, configs, seed, 0), migration, seed)
+    }
+
+    /// Like [`Archipelago::generate`] for all islands but the first one, which
+    /// the caller created with `seed` and steps itself, e.g. to show it in the
+    /// viewer, see [`Archipelago::step_around`].
+    ///
+    /// `others` are the configs of the islands after the first one.
+    pub fn generate_around(
+        world: World,
+        others: &[SimConfig],
+        seed: u64,
+        migration: Migration,
+    ) -> Self {
+        Self::new(seeded(world, others, seed, 1), migration, seed)
+    }
+
+    /// Joins existing simulations into an archipelago whose migrants are
+    /// chosen with randomness derived from `seed`.
+    pub fn new(islands: Vec<Simulation>, migration: Migration, seed: u64) -> Self {
+        Self {
+            islands,
+            migration,
+            rng: Rng::with_stream(seed, MIGRATION_STREAM),
+            steps: 0,
+        }
+    }
+
+    /// Returns the islands.
+    pub fn islands(&self) -> &[Simulation] {
+        &self.islands
+    }
+
+    /// Returns the islands, e.g. to intervene on one of them.
+    pub fn islands_mut(&mut self) -> &mut [Simulation] {
+        &mut self.islands
+    }
+
+    /// Returns the migration between the islands.
+    pub fn migration(&self) -> Migration {
+        self.migration
+    }
+
+    /// Returns `true` if the biots of all islands went extinct.
+    pub fn is_extinct(&self) -> bool {
+        self.islands.iter().all(|island| island.biots().is_empty())
+    }
+
+    /// Computes a step of every island, followed by a migration when due.
+    ///
+    /// Returns the number of biots that migrated.
+    pub fn step(&mut self) -> usize {
+        for island in &mut self.islands {
+            island.step();
+        }
+        self.steps += 1;
+        let mut islands = self.islands.iter_mut().collect::<Vec<_>>();
+        migrate_when_due(&mut islands, self.migration, self.steps, &mut self.rng)
+    }
+
+    /// Like [`Archipelago::step`] for the islands except `shown`, which was
+    /// taken out at `index`, e.g. to be shown in the viewer, and has already
+    /// been stepped by the caller.
+    pub fn step_around(&mut self, shown: &mut Simulation, index: usize) -> usize {
+        for island in &mut self.islands {
+            island.step();
+        }
+        self.steps += 1;
+        let mut islands = self.islands.iter_mut().collect::<Vec<_>>();
+        islands.insert(index.min(islands.len()), shown);
+        migrate_when_due(&mut islands, self.migration, self.steps, &mut self.rng)
+    }
+
+    /// Exchanges `shown`, taken out at index `from`, with the island at index
+    /// `to`, which is taken out in turn.
+    ///
+    /// The indices count the islands in their original order, including the
+    /// taken out one.
+    pub fn swap(&mut self, shown: &mut Simulation, from: usize, to: usize) {
+        if from == to || self.islands.is_empty() {
+            return;
+        }
+        // Indices into the islands that are not taken out.
+        let remaining = |index: usize, taken: usize| {
+            let index = if index > taken { index - 1 } else { index };
+            index.min(self.islands.len() - 1)
+        };
+        let (taken, returned) = (remaining(to, from), remaining(from, to));
+        std::mem::swap(shown, &mut self.islands[taken]);
+        let island = self.islands.remove(taken);
+        self.islands.insert(returned, island);
+    }
+}
+
+/// Creates an island in `world` for each of the `configs`, seeded with the
+/// seeds following `seed` from its `first` one on.
+fn seeded(world: World, configs: &[SimConfig], seed: u64, first: u64) -> Vec<Simulation> {
+    configs
+        .iter()
+        .zip(first..)
+        .map(|(config, n)| Simulation::new(world, config.clone(), seed.wrapping_add(n)))
+        .collect()
+}
+
+/// Moves a fraction of the biots of every island on to the next one, if a
+/// migration is due after `steps` steps.
+///
+/// Returns the number of biots that migrated.
+fn migrate_when_due(
+    islands: &mut [&mut Simulation],
+    migration: Migration,
+    steps: u64,
+    rng: &mut Rng,
+) -> usize {
+    if migration.interval == 0 || islands.len() < 2 {
+        return 0;
+    }
+    if !steps.is_multiple_of(migration.interval.into()) {
+        return 0;
+    }
+    let emigrants = islands
+        .iter_mut()
+        .map(|island| emigrate(island, migration.fraction, rng))
+        .collect::<Vec<_>>();
+    let mut migrated = 0;
+    for (n, biots) in emigrants.into_iter().enumerate() {
+        let island = &mut islands[(n + 1) % islands.len()];
+        for mut biot in biots {
+            biot.stats.pos = landing(island, &biot, rng);
+            island.biots_mut().spawn(biot);
+            migrated += 1;
+        }
+    }
+    migrated
+}
+
+/// Returns a random position for the migrant `biot` on `island`.
+///
+/// # Note
+///
+/// Like the initial population, migrants land within the spawn regions of
+/// the island admitting them, or anywhere if there are none, since the
+/// islands may differ in size. They never land within obstacles.
+fn landing(island: &Simulation, biot: &Biot, rng: &mut Rng) -> Vec2 {
+    let world = island.world();
+    let config = island.biots().config();
+    let mut random = || {
+        spawn::random_position(&config.spawn_regions, biot, world, rng)
+            .unwrap_or_else(|| world.random_position(rng))
+    };
+    let mut pos = random();
+    for _ in 1..LANDING_TRIES {
+        if !config
+            .obstacles
+            .iter()
+            .any(|obstacle| obstacle.contains(pos))
+        {
+            break;
+        }
+        pos = random();
+    }
+    // Keeps the bodies clear of the obstacles too, and the migrants of worlds
+    // crowded with obstacles out of them.
+    let mut speed = Vec2::ZERO;
+    obstacle::collide(&config.obstacles, &mut pos, &mut speed, biot.radius().0);
+    world.wrap(pos)
+}
+
+/// Removes a random `fraction` of the biots of `island`, rounded to the
+/// nearest number of biots.
+fn emigrate(island: &mut Simulation, fraction: f32, rng: &mut Rng) -> Vec<Biot> {
+    let population = island.biots().len();
+    let count = ((population as f32 * fraction).round() as usize).min(population);
+    let mut ids = island.biots().iter().map(Biot::id).collect::<Vec<_>>();
+    let emigrants = (0..count)
+        .map(|_| ids.swap_remove(rng.gen_index(ids.len())))
+        .collect::<Vec<_>>();
+    island.biots_mut().emigrate(&emigrants)
+}
@@ -0,0 +1,235 @@
+//! Sound cues for the events of a simulation and an ambient hum following
+//! the population, so a run can be followed while doing other work.
+//!
+//! The sounds are synthesized at startup, so no audio files have to be shipped.
+
+use crate::biot::CauseOfDeath;
+use crate::events::{Event, EventLog};
+use macroquad::audio::{
+    load_sound_from_bytes, play_sound, set_sound_volume, stop_sound, PlaySoundParams, Sound,
+};
+use macroquad::file::FileError;
+use macroquad::prelude::{get_time, is_key_pressed, KeyCode};
+use std::f32::consts::TAU;
+
+/// Sample rate of the synthesized sounds.
+const SAMPLE_RATE: u32 = 44_100;
+
+/// Shortest time between two cues of the same kind in seconds, so that
+/// thousands of births per second do not drown each other out.
+const CUE_INTERVAL: f64 = 0.15;
+
+/// Population at which the ambient hum reaches its full volume.
+const AMBIENT_POPULATION: f32 = 2000.0;
+
+/// Volume of the ambient hum relative to the cues.
+const AMBIENT_VOLUME: f32 = 0.3;
+
+/// Change of the volume per key press.
+const VOLUME_STEP: f32 = 0.1;
+
+/// Returns a tone gliding from `from` to `to` Hz over `seconds`, fading out at `decay` per second.
+fn tone(from: f32, to: f32, seconds: f32, decay: f32) -> Vec<f32> {
+    let len = (seconds * SAMPLE_RATE as f32) as usize;
+    let mut phase = 0.0_f32;
+    (0..len)
+        .map(|n| {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            let frequency = from + (to - from) * t / seconds;
+            phase = (phase + TAU * frequency / SAMPLE_RATE as f32) % TAU;
+            // A short attack avoids clicking at the start.
+            let envelope = (t / 0.005).min(1.0) * (-decay * t).exp();
+            0.8 * envelope * phase.sin()
+        })
+        .collect()
+}
+
+/// Returns a second of low harmonics that loops without a seam.
+fn hum() -> Vec<f32> {
+    (0..SAMPLE_RATE)
+        .map(|n| {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            [(55.0, 0.5), (110.0, 0.3), (165.0, 0.15)]
+                .into_iter()
+                .map(|(frequency, amplitude)| amplitude * (TAU * frequency * t).sin())
+                .sum()
+        })
+        .collect()
+}
+
+/// Encodes `samples` in `-1.0..=1.0` as a mono 16 bit WAV file.
+fn wav(samples: &[f32]) -> Vec<u8> {
+    let data_len = 2 * samples.len() as u32;
+    let mut bytes = Vec::with_capacity(44 + data_len as usize);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+    bytes.extend_from_slice(b"WAVEfmt ");
+    bytes.extend_from_slice(&16_u32.to_le_bytes());
+    // PCM with a single channel.
+    bytes.extend_from_slice(&1_u16.to_le_bytes());
+    bytes.extend_from_slice(&1_u16.to_le_bytes());
+    bytes.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    bytes.extend_from_slice(&(2 * SAMPLE_RATE).to_le_bytes());
+    bytes.extend_from_slice(&2_u16.to_le_bytes());
+    bytes.extend_from_slice(&16_u16.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        let sample = (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16;
+        bytes.extend_from_slice(&sample.to_le_bytes());
+    }
+    bytes
+}
+
+/// The loaded sounds.
+#[derive(Debug, Copy, Clone)]
+pub struct Sounds {
+    kill: Sound,
+    birth: Sound,
+    extinction: Sound,
+    ambient: Sound,
+}
+
+impl Sounds {
+    /// Synthesizes and loads the sounds.
+    pub async fn load() -> Result<Self, FileError> {
+        Ok(Self {
+            kill: load_sound_from_bytes(&wav(&tone(140.0, 60.0, 0.15, 20.0))).await?,
+            birth: load_sound_from_bytes(&wav(&tone(660.0, 990.0, 0.08, 30.0))).await?,
+            extinction: load_sound_from_bytes(&wav(&tone(440.0, 110.0, 0.8, 3.0))).await?,
+            ambient: load_sound_from_bytes(&wav(&hum())).await?,
+        })
+    }
+}
+
+/// Number of events of each kind heard since the last cues.
+#[derive(Debug, Copy, Clone, Default)]
+struct Pending {
+    kills: u32,
+    births: u32,
+    extinctions: u32,
+}
+
+/// Plays the cues for the events of a simulation and the ambient hum.
+///
+/// # Note
+///
+/// `N` mutes and unmutes, `[` and `]` lower and raise the volume. Many
+/// events of a kind within [`CUE_INTERVAL`] are merged into a single,
+/// louder cue.
+pub struct Audio {
+    sounds: Sounds,
+    volume: f32,
+    muted: bool,
+    pending: Pending,
+    /// Times the last kill, birth and extinction cues were played at.
+    played: [f64; 3],
+    /// Whether the ambient hum is looping.
+    humming: bool,
+}
+
+impl Audio {
+    /// Creates the audio at half volume, muted unless `unmuted` is set.
+    pub fn new(sounds: Sounds, unmuted: bool) -> Self {
+        Self {
+            sounds,
+            volume: 0.5,
+            muted: !unmuted,
+            pending: Pending::default(),
+            played: [f64::NEG_INFINITY; 3],
+            humming: false,
+        }
+    }
+
+    /// Handles the keys controlling the audio.
+    pub fn handle_keys(&mut self) {
+        if is_key_pressed(KeyCode::N) {
+            self.muted = !self.muted;
+        }
+        if is_key_pressed(KeyCode::LeftBracket) {
+            self.volume = (self.volume - VOLUME_STEP).max(0.0);
+        }
+        if is_key_pressed(KeyCode::RightBracket) {
+            self.volume = (self.volume + VOLUME_STEP).min(1.0);
+        }
+    }
+
+    /// Notes the events added by the last observation of `log`.
+    pub fn listen(&mut self, log: &EventLog) {
+        for logged in log.latest() {
+            match logged.event {
+                Event::Death {
+                    cause: CauseOfDeath::Predation,
+                    ..
+                } => self.pending.kills += 1,
+                Event::Birth { .. } => self.pending.births += 1,
+                Event::Extinction { .. } => self.pending.extinctions += 1,
+                _ => {}
+            }
+        }
+    }
+
+    /// Plays the cues of the events heard since the last ones and adjusts
+    /// the ambient hum to the `population`.
+    pub fn play(&mut self, population: usize) {
+        if self.muted || self.volume == 0.0 {
+            self.pending = Pending::default();
+            self.silence();
+            return;
+        }
+        let now = get_time();
+        let sounds = self.sounds;
+        let cues = [
+            (sounds.kill, &mut self.pending.kills),
+            (sounds.birth, &mut self.pending.births),
+            (sounds.extinction, &mut self.pending.extinctions),
+        ];
+        for ((sound, count), played) in cues.into_iter().zip(&mut self.played) {
+            if *count == 0 || now - *played < CUE_INTERVAL {
+                continue;
+            }
+            // Merged events sound louder, but not overwhelmingly so.
+            let loudness = (1.0 + (*count as f32).ln()).min(3.0) / 3.0;
+            play_sound(
+                sound,
+                PlaySoundParams {
+                    looped: false,
+                    volume: self.volume * loudness,
+                },
+            );
+            *count = 0;
+            *played = now;
+        }
+        let level = (population as f32 / AMBIENT_POPULATION).min(1.0);
+        let volume = self.volume * AMBIENT_VOLUME * level;
+        if self.humming {
+            set_sound_volume(sounds.ambient, volume);
+        } else {
+            play_sound(
+                sounds.ambient,
+                PlaySoundParams {
+                    looped: true,
+                    volume,
+                },
+            );
+            self.humming = true;
+        }
+    }
+
+    /// Stops the ambient hum, e.g. when the viewer is closed.
+    pub fn silence(&mut self) {
+        if self.humming {
+            stop_sound(self.sounds.ambient);
+            self.humming = false;
+        }
+    }
+
+    /// Returns the state of the audio shown in the status line.
+    pub fn status(&self) -> String {
+        if self.muted {
+            String::from("sound: muted")
+        } else {
+            format!("sound: {:.0}%", 100.0 * self.volume)
+        }
+    }
+}
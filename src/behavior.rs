@@ -0,0 +1,92 @@
+//! Custom behaviors steering the biots and effects of their genes.
+//!
+//! Programs embedding the simulation register behaviors to try out steering
+//! rules without forking the built-in ones. Behaviors are asked in the order
+//! they were registered, the first one returning a direction steers the biot.
+//!
+//! Gene plugins give genes an effect on the biots in every step, e.g. the
+//! custom genes of a scenario. The flocking of the sociality gene is the
+//! built-in [`Flocking`] plugin.
+
+use crate::biot::{Biot, Gene};
+use crate::brain::Senses;
+use crate::config::SimConfig;
+use crate::environment::Environment;
+use crate::world::World;
+use glam::Vec2;
+
+/// What a behavior knows about a biot and its surroundings.
+pub struct Situation<'a> {
+    pub biot: &'a Biot,
+    /// What the biot perceives, as the input of a brain would.
+    pub senses: Senses,
+    pub world: &'a World,
+    /// Days, nights and seasons, e.g. to behave differently at night.
+    pub environment: &'a Environment,
+    pub config: &'a SimConfig,
+}
+
+/// A steering rule of intelligent biots.
+///
+/// # Note
+///
+/// Behaviors are asked whenever an intelligent biot senses its
+/// surroundings, before its brain or built-in steering. They are not part
+/// of snapshots and have to be registered again after loading one.
+pub trait Behavior: Send + Sync {
+    /// Returns the direction the biot heads towards, or `None` to leave the
+    /// steering to the next behavior or the genes of the biot.
+    fn steer(&self, situation: &Situation) -> Option<Vec2>;
+}
+
+/// What a gene plugin knows about a moving biot and its surroundings.
+pub struct Expression<'a> {
+    pub biot: &'a Biot,
+    /// How strongly the biot expresses the gene of the plugin, summed over
+    /// its loci, see [`Genome::expression`](crate::biot::Genome::expression).
+    pub expression: f32,
+    pub world: &'a World,
+    pub environment: &'a Environment,
+    pub config: &'a SimConfig,
+}
+
+/// The effect of a gene on the biots in every step.
+///
+/// # Note
+///
+/// Plugins are asked for every biot moving in a step, after the built-in
+/// ones and in the order they were registered, also for biots not
+/// expressing their gene. Like behaviors, they are not part of snapshots.
+pub trait GenePlugin: Send + Sync {
+    /// Returns the gene whose effect the plugin implements.
+    fn gene(&self) -> Gene;
+
+    /// Returns the direction the moving biot is pushed towards and the
+    /// fraction of its speed it is pushed with, if any.
+    fn push(&self, expression: &Expression) -> Option<(Vec2, f32)>;
+}
+
+/// The sociality gene letting biots flock with their species, see
+/// [`FlockingConfig`](crate::config::FlockingConfig).
+///
+/// # Note
+///
+/// Flocking biots are pushed towards the direction they sensed, see
+/// [`Stats::flock_dir`](crate::biot::Stats::flock_dir), in proportion to
+/// their sociality.
+pub struct Flocking;
+
+impl GenePlugin for Flocking {
+    fn gene(&self) -> Gene {
+        Gene::Sociality
+    }
+
+    fn push(&self, expression: &Expression) -> Option<(Vec2, f32)> {
+        let biot = expression.biot;
+        let sociality = biot.properties.sociality;
+        biot.stats.flock_dir.map(|dir| (dir, sociality))
+    }
+}
+
+/// Gene plugins every simulation steps with.
+pub const BUILT_IN_PLUGINS: [&dyn GenePlugin; 1] = [&Flocking];
@@ -1,86 +1,724 @@
-use core::{ops, slice};
-use macroquad::prelude::{rand, screen_height, screen_width, vec2, Vec2};
-use rstar::{PointDistance, RTree, RTreeObject, AABB};
+use crate::behavior::{Expression, GenePlugin};
+use crate::brain::{Brain, MAX_NEURONS};
+use crate::config::{
+    DiseaseConfig, GeneDefinition, MutationConfig, NonviablePolicy, Reproduction, ResponseConfig,
+    ResponseCurve, SimConfig, MAX_CUSTOM_GENES,
+};
+use crate::environment::Environment;
+use crate::grid::SpatialGrid;
+use crate::ledger::EnergyLedger;
+use crate::obstacle;
+use crate::reserve;
+use crate::rng::{Rng, RngStreams};
+use crate::species::SpeciesId;
+use crate::terrain::{Terrain, Tile};
+use crate::units::{Distance, Energy, SquaredDistance, Ticks};
+use crate::world::World;
+use core::{fmt, slice};
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
 
 /// Genome propeties of biots.
-#[derive(Debug, Copy, Clone)]
-#[repr(u8)]
+///
+/// # Note
+///
+/// Genes are serialized by their stable [`Gene::id`], so that snapshots stay
+/// readable when genes are added.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
 pub enum Gene {
     /// Influences the attack value of the biot.
-    Attack = 0,
+    Attack,
     /// Influences the defensive value of the biot.
-    Defense = 1,
+    Defense,
     /// Influences how well the biot can generate energy from sunlight.
-    Photosynthesis = 2,
+    Photosynthesis,
     /// Influences how fast the biot can move around.
-    Motion = 3,
+    Motion,
     /// Influences the intelligence of the biot.
-    Intelligence = 4,
+    Intelligence,
     /// A gene that does nothing observable.
-    None = 5,
+    None,
+    /// Influences how much energy the biot gains from eating other biots.
+    Digestion,
+    /// Influences how much life an attacker eating the biot loses.
+    Venom,
+    /// Influences how well the biot resists venom and attacks.
+    Armor,
+    /// Influences how close intelligent predators have to come to detect the biot.
+    Camouflage,
+    /// Influences how far the biot can detect food.
+    Vision,
+    /// Influences how early the biot reproduces.
+    Reproduction,
+    /// Influences how well the biot resists pathogens.
+    Immunity,
+    /// Influences how many offsprings the biot bears at once.
+    Fecundity,
+    /// Influences how much life the biot gives each of its offsprings.
+    Nurture,
+    /// Influences how strongly the biot flocks with its species.
+    Sociality,
+    /// Influences how much heat the biot bears without stress.
+    HeatTolerance,
+    /// Influences how distant relatives the biot spares from its attacks.
+    Kinship,
+    /// A gene defined by the scenario with the given identifier, see [`GeneDefinition`].
+    Custom(u8),
 }
 
-impl Gene {
-    /// Creates a new random gene.
-    pub fn random() -> Self {
-        let random = rand::gen_range::<u8>(0, 5);
-        match random {
+/// Stable identifier of the first custom gene.
+const CUSTOM_GENE_BASE: u8 = 128;
+
+/// Error returned when deserializing an unknown gene identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownGene(pub u8);
+
+impl fmt::Display for UnknownGene {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown gene identifier {}", self.0)
+    }
+}
+
+impl From<Gene> for u8 {
+    fn from(gene: Gene) -> Self {
+        gene.id()
+    }
+}
+
+impl TryFrom<u8> for Gene {
+    type Error = UnknownGene;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        let gene = match id {
             0 => Self::Attack,
             1 => Self::Defense,
             2 => Self::Photosynthesis,
             3 => Self::Motion,
             4 => Self::Intelligence,
-            _ => unreachable!("encountered unexpected random gene index {random}"),
+            5 => Self::None,
+            6 => Self::Digestion,
+            7 => Self::Venom,
+            8 => Self::Armor,
+            9 => Self::Camouflage,
+            10 => Self::Vision,
+            11 => Self::Reproduction,
+            12 => Self::Immunity,
+            13 => Self::Fecundity,
+            14 => Self::Nurture,
+            15 => Self::Sociality,
+            16 => Self::HeatTolerance,
+            17 => Self::Kinship,
+            CUSTOM_GENE_BASE.. => Self::Custom(id - CUSTOM_GENE_BASE),
+            _ => return Err(UnknownGene(id)),
+        };
+        Ok(gene)
+    }
+}
+
+impl Gene {
+    /// All genes that have an observable effect.
+    ///
+    /// Digestion, immunity, fecundity, nurture, sociality, heat tolerance and
+    /// kinship come last, so that [`Gene::pool`] can leave them out without
+    /// changing the order of the others.
+    pub const EXPRESSED: [Gene; 17] = [
+        Self::Attack,
+        Self::Defense,
+        Self::Photosynthesis,
+        Self::Motion,
+        Self::Intelligence,
+        Self::Venom,
+        Self::Armor,
+        Self::Camouflage,
+        Self::Vision,
+        Self::Reproduction,
+        Self::Digestion,
+        Self::Immunity,
+        Self::Fecundity,
+        Self::Nurture,
+        Self::Sociality,
+        Self::HeatTolerance,
+        Self::Kinship,
+    ];
+
+    /// Returns the stable identifier of the gene.
+    ///
+    /// Built-in genes have identifiers below 128, custom genes the ones from
+    /// 128 on, offset by their identifier within the scenario.
+    pub fn id(self) -> u8 {
+        match self {
+            Self::Attack => 0,
+            Self::Defense => 1,
+            Self::Photosynthesis => 2,
+            Self::Motion => 3,
+            Self::Intelligence => 4,
+            Self::None => 5,
+            Self::Digestion => 6,
+            Self::Venom => 7,
+            Self::Armor => 8,
+            Self::Camouflage => 9,
+            Self::Vision => 10,
+            Self::Reproduction => 11,
+            Self::Immunity => 12,
+            Self::Fecundity => 13,
+            Self::Nurture => 14,
+            Self::Sociality => 15,
+            Self::HeatTolerance => 16,
+            Self::Kinship => 17,
+            Self::Custom(id) => CUSTOM_GENE_BASE.saturating_add(id),
+        }
+    }
+
+    /// Returns the genes random genomes and mutations draw from: the built-in
+    /// genes and the ones defined by the scenario.
+    ///
+    /// # Note
+    ///
+    /// The digestion, immunity, fecundity, nurture, sociality, heat
+    /// tolerance and kinship genes only evolve if they have an effect, see
+    /// [`crate::config::PredationConfig::digestion`],
+    /// [`crate::config::DiseaseConfig::is_enabled`], [`Biot::litter`],
+    /// [`crate::config::FlockingConfig::enabled`],
+    /// [`crate::config::TemperatureConfig::enabled`] and
+    /// [`crate::config::KinshipConfig::enabled`].
+    pub fn pool(config: &SimConfig) -> Vec<Gene> {
+        let builtin = Self::EXPRESSED.into_iter().filter(|gene| match gene {
+            Self::Digestion => config.predation.digestion != 0.0,
+            Self::Immunity => config.disease.is_enabled(),
+            Self::Fecundity | Self::Nurture => has_litters(config),
+            Self::Sociality => config.flocking.enabled,
+            Self::HeatTolerance => config.temperature.enabled,
+            Self::Kinship => config.kinship.enabled,
+            _ => true,
+        });
+        let custom = config.genes.iter().map(|gene| Self::Custom(gene.id));
+        builtin.chain(custom).collect()
+    }
+
+    /// Returns a single character standing for the gene in compact genome strings.
+    ///
+    /// Custom genes share a symbol, they are only meaningful within their scenario.
+    pub fn symbol(self) -> char {
+        match self {
+            Self::Attack => 'A',
+            Self::Defense => 'D',
+            Self::Photosynthesis => 'P',
+            Self::Motion => 'M',
+            Self::Intelligence => 'I',
+            Self::None => '_',
+            Self::Digestion => 'G',
+            Self::Venom => 'V',
+            Self::Armor => 'R',
+            Self::Camouflage => 'C',
+            Self::Vision => 'S',
+            Self::Reproduction => 'B',
+            Self::Immunity => 'U',
+            Self::Fecundity => 'F',
+            Self::Nurture => 'N',
+            Self::Sociality => 'H',
+            Self::HeatTolerance => 'T',
+            Self::Kinship => 'K',
+            Self::Custom(_) => 'X',
+        }
+    }
+
+    /// Creates a new random gene from the given pool.
+    pub fn random(pool: &[Gene], rng: &mut Rng) -> Self {
+        pool[rng.gen_index(pool.len())]
+    }
+}
+
+/// Weighted chances of the genes in the slots of random genomes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneDistribution {
+    /// Genes random genomes draw from if there are no weights.
+    pool: Vec<Gene>,
+    /// Genes with a positive weight, all genes of the pool are equally likely if empty.
+    weights: Vec<(Gene, f32)>,
+}
+
+impl GeneDistribution {
+    /// Returns the distribution of the random initial population of `config`,
+    /// see [`InitialGenesConfig`](crate::config::InitialGenesConfig).
+    pub fn initial(config: &SimConfig) -> Self {
+        let pool = Gene::pool(config);
+        let chances = config.initial_genes.chances().collect::<Vec<_>>();
+        if chances.is_empty() {
+            return Self {
+                pool,
+                weights: Vec::new(),
+            };
+        }
+        let rest = pool
+            .iter()
+            .copied()
+            .filter(|gene| chances.iter().all(|(chance, _)| chance != gene))
+            .collect::<Vec<_>>();
+        let given: f32 = chances.iter().map(|(_, chance)| chance).sum();
+        let share = (1.0 - given).max(0.0) / rest.len().max(1) as f32;
+        let weights = chances
+            .into_iter()
+            .chain(rest.into_iter().map(|gene| (gene, share)))
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect();
+        Self { pool, weights }
+    }
+
+    /// Returns the chance of `gene` in a single slot.
+    pub fn chance(&self, gene: Gene) -> f32 {
+        if self.weights.is_empty() {
+            let count = self.pool.iter().filter(|other| **other == gene).count();
+            return count as f32 / self.pool.len() as f32;
+        }
+        let total = self.weights.iter().map(|(_, weight)| weight).sum::<f32>();
+        self.weights
+            .iter()
+            .filter(|(other, _)| *other == gene)
+            .map(|(_, weight)| weight / total)
+            .sum()
+    }
+
+    /// Draws the gene of a single slot.
+    pub fn sample(&self, rng: &mut Rng) -> Gene {
+        if self.weights.is_empty() {
+            return Gene::random(&self.pool, rng);
+        }
+        let total = self.weights.iter().map(|(_, weight)| weight).sum::<f32>();
+        let mut pick = rng.gen_range(0.0, total);
+        // Rounding may leave the pick just above the last weight.
+        let mut chosen = self.weights[0].0;
+        for &(gene, weight) in &self.weights {
+            chosen = gene;
+            if pick < weight {
+                break;
+            }
+            pick -= weight;
+        }
+        chosen
+    }
+}
+
+/// A gene within a genome together with how strongly it is expressed.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Locus {
+    pub gene: Gene,
+    /// Factor applied to the contribution of the gene to the properties.
+    ///
+    /// Always within `0.0..=MAX_EXPRESSION`.
+    pub expression: f32,
+}
+
+impl Locus {
+    /// Creates a locus of `gene` at its usual expression.
+    pub fn new(gene: Gene) -> Self {
+        Self {
+            gene,
+            expression: 1.0,
         }
     }
 }
 
-/// The set of genes a biot is made of.
-#[derive(Debug, Clone)]
+/// The sequence of genes a biot is made of.
+///
+/// # Note
+///
+/// The length of a genome evolves through insertions, deletions and
+/// duplications, but always stays within `1..=MAX_GENOME_LEN`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Genome {
-    genes: [Gene; 32],
+    loci: Vec<Locus>,
+    /// Heritable chance for each additional mutation of an offspring's genome.
+    ///
+    /// Unset until the mutation chance evolves, see [`MutationConfig::rate_drift`].
+    mutation_chance: Option<f32>,
+    /// Neural network steering the biot if it is intelligent.
+    ///
+    /// Unset unless brains are enabled, see [`BrainConfig`](crate::config::BrainConfig).
+    brain: Option<Brain>,
 }
 
 impl Genome {
-    /// Creates a random biot genome.
-    pub fn random() -> Self {
-        let mut genes = [Gene::None; 32];
-        for gene in &mut genes {
-            *gene = Gene::random();
+    /// Creates a random biot genome of [`GENOME_LEN`] genes.
+    pub fn random(pool: &[Gene], rng: &mut Rng) -> Self {
+        let loci = (0..GENOME_LEN)
+            .map(|_| Locus::new(Gene::random(pool, rng)))
+            .collect();
+        Self {
+            loci,
+            mutation_chance: None,
+            brain: None,
+        }
+    }
+
+    /// Creates a random biot genome of [`GENOME_LEN`] genes drawn from `distribution`.
+    pub fn sample(distribution: &GeneDistribution, rng: &mut Rng) -> Self {
+        let loci = (0..GENOME_LEN)
+            .map(|_| Locus::new(distribution.sample(rng)))
+            .collect();
+        Self {
+            loci,
+            mutation_chance: None,
+            brain: None,
+        }
+    }
+
+    /// Randomly mutates the genome with new genes from the given pool.
+    ///
+    /// `config` sets the chances of the structural mutations and of the ones
+    /// nudging the expression of a gene, all other mutations replace a single
+    /// gene. Structural mutations that would leave the bounds of the genome
+    /// length do nothing.
+    pub fn mutate(&mut self, pool: &[Gene], config: &MutationConfig, rng: &mut Rng) {
+        let len = self.loci.len();
+        let mut kind = rng.gen_range(0., 1.);
+        let mut is = |chance: f32| {
+            let hit = kind < chance;
+            kind -= chance;
+            hit
+        };
+        if is(config.insertion) {
+            if len < MAX_GENOME_LEN {
+                let at = rng.gen_index(len + 1);
+                self.loci.insert(at, Locus::new(Gene::random(pool, rng)));
+            }
+        } else if is(config.deletion) {
+            if len > 1 {
+                self.loci.remove(rng.gen_index(len));
+            }
+        } else if is(config.duplication) {
+            // Copies a short segment right behind itself.
+            let start = rng.gen_index(len);
+            let end = (start + 1 + rng.gen_index(MAX_DUPLICATION)).min(len);
+            let end = end.min(start + MAX_GENOME_LEN - len);
+            let segment = self.loci[start..end].to_vec();
+            self.loci.splice(end..end, segment);
+        } else if is(config.expression) {
+            let locus = &mut self.loci[rng.gen_index(len)];
+            let delta = rng.gen_range(-EXPRESSION_STEP, EXPRESSION_STEP);
+            locus.expression = (locus.expression + delta).clamp(0.0, MAX_EXPRESSION);
+        } else {
+            let which_gene = rng.gen_index(len);
+            self.loci[which_gene] = Locus::new(Gene::random(pool, rng));
+        }
+    }
+
+    /// Combines two genomes at a random crossover point, with the genes before
+    /// it taken from `self` and the remaining ones from `other`.
+    ///
+    /// The offspring is as long as `other` and inherits the mean of the
+    /// mutation chances of both genomes. Brains do not mix, the offspring
+    /// inherits the one of `self` if it has any.
+    pub fn crossover(&self, other: &Genome, rng: &mut Rng) -> Self {
+        let point = rng.gen_index(self.loci.len().min(other.loci.len()) + 1);
+        let loci = self.loci[..point]
+            .iter()
+            .chain(&other.loci[point..])
+            .copied()
+            .collect();
+        let mutation_chance = match (self.mutation_chance, other.mutation_chance) {
+            (Some(a), Some(b)) => Some((a + b) / 2.0),
+            (a, b) => a.or(b),
+        };
+        Self {
+            loci,
+            mutation_chance,
+            brain: self.brain.clone().or_else(|| other.brain.clone()),
+        }
+    }
+
+    /// Returns the brain of the genome, unless brains are disabled.
+    pub fn brain(&self) -> Option<&Brain> {
+        self.brain.as_ref()
+    }
+
+    /// Returns the heritable mutation chance of the genome, if it evolves.
+    pub fn mutation_chance(&self) -> Option<f32> {
+        self.mutation_chance
+    }
+
+    /// Returns the number of genes in the genome.
+    pub fn len(&self) -> usize {
+        self.loci.len()
+    }
+
+    /// Returns `true` if the genome has no genes.
+    pub fn is_empty(&self) -> bool {
+        self.loci.is_empty()
+    }
+
+    /// Returns the genes as a compact string of their symbols, see [`Gene::symbol`].
+    pub fn code(&self) -> String {
+        self.genes().map(|gene| gene.symbol()).collect()
+    }
+
+    /// Returns an iterator over the genes of the genome, regardless of their expression.
+    pub fn genes(&self) -> impl Iterator<Item = &Gene> + '_ {
+        self.loci.iter().map(|locus| &locus.gene)
+    }
+
+    /// Returns an iterator over the genes of the genome with their expression.
+    pub fn loci(&self) -> slice::Iter<'_, Locus> {
+        self.loci.iter()
+    }
+
+    /// Returns the expression of `gene` summed over all of its loci.
+    pub fn expression(&self, gene: Gene) -> f32 {
+        self.loci()
+            .filter(|locus| locus.gene == gene)
+            .map(|locus| locus.expression)
+            .sum()
+    }
+
+    /// Returns the distance between two genomes in genes.
+    ///
+    /// # Note
+    ///
+    /// This is the total difference of the expression of each gene, so the
+    /// order of the genes does not matter and a missing gene counts as much
+    /// as an additional one.
+    pub fn distance(&self, other: &Genome) -> f32 {
+        let (a, b) = (self.profile(), other.profile());
+        let builtin = a.0.iter().zip(b.0).map(|(a, b)| (a - b).abs()).sum::<f32>();
+        let custom = a.1.iter().zip(b.1).map(|(a, b)| (a - b).abs()).sum::<f32>();
+        builtin + custom
+    }
+
+    /// Returns the total expression of each of the [`Gene::EXPRESSED`] genes
+    /// and of each custom gene in the genome.
+    fn profile(&self) -> ([f32; Gene::EXPRESSED.len()], [f32; MAX_CUSTOM_GENES]) {
+        let mut builtin = [0.0; Gene::EXPRESSED.len()];
+        let mut custom = [0.0; MAX_CUSTOM_GENES];
+        for locus in self.loci() {
+            if let Gene::Custom(id) = locus.gene {
+                if let Some(expression) = custom.get_mut(usize::from(id)) {
+                    *expression += locus.expression;
+                }
+            } else if let Some(index) = Gene::EXPRESSED.iter().position(|g| *g == locus.gene) {
+                builtin[index] += locus.expression;
+            }
+        }
+        (builtin, custom)
+    }
+
+    /// Returns how often each of the [`Gene::EXPRESSED`] genes occurs in the genome.
+    pub fn expressed_counts(&self) -> [u32; Gene::EXPRESSED.len()] {
+        let mut counts = [0; Gene::EXPRESSED.len()];
+        for gene in self.genes() {
+            if let Some(index) = Gene::EXPRESSED.iter().position(|g| g == gene) {
+                counts[index] += 1;
+            }
+        }
+        counts
+    }
+}
+
+/// Errors that can occur while building a genome.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenomeError {
+    /// More genes were requested than a genome has slots.
+    TooManyGenes { requested: usize, slots: usize },
+}
+
+impl fmt::Display for GenomeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyGenes { requested, slots } => write!(
+                f,
+                "requested {requested} genes but a genome only has {slots} slots"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenomeError {}
+
+/// Builds genomes from gene counts, e.g. for scenarios and tests.
+///
+/// Genomes shorter than [`GENOME_LEN`] are filled up with [`Gene::None`].
+/// All genes are built at their usual expression.
+#[derive(Debug, Clone, Default)]
+pub struct GenomeBuilder {
+    genes: Vec<Gene>,
+}
+
+impl GenomeBuilder {
+    /// Creates a builder for an empty genome.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `count` copies of `gene` to the genome.
+    pub fn with(mut self, gene: Gene, count: usize) -> Self {
+        self.genes.resize(self.genes.len() + count, gene);
+        self
+    }
+
+    /// Builds the genome.
+    ///
+    /// # Errors
+    ///
+    /// If more genes were added than a genome has slots.
+    pub fn build(mut self) -> Result<Genome, GenomeError> {
+        if self.genes.len() > MAX_GENOME_LEN {
+            return Err(GenomeError::TooManyGenes {
+                requested: self.genes.len(),
+                slots: MAX_GENOME_LEN,
+            });
         }
-        Self { genes }
+        if self.genes.len() < GENOME_LEN {
+            self.genes.resize(GENOME_LEN, Gene::None);
+        }
+        let loci = self.genes.into_iter().map(Locus::new).collect();
+        Ok(Genome {
+            loci,
+            mutation_chance: None,
+            brain: None,
+        })
     }
+}
+
+/// Maximum age a biot can reach before it dies of old age.
+const MAX_AGE: Ticks = Ticks(10000);
+
+/// Life fraction below which a biot losing energy is considered dying.
+const DYING_LIFE_FRACTION: f32 = 0.05;
+
+/// Age fraction above which a biot is considered dying of old age.
+const DYING_AGE_FRACTION: f32 = 0.95;
+
+/// Number of genes in a random genome.
+pub const GENOME_LEN: usize = 32;
 
-    /// Randomly mutate a single gene.
-    pub fn mutate(&mut self) {
-        let which_gene = rand::gen_range(0, self.genes.len());
-        self.genes[which_gene] = Gene::random();
+/// Maximum number of genes in a genome.
+pub const MAX_GENOME_LEN: usize = 48;
+
+/// Maximum number of genes copied by a single duplication.
+const MAX_DUPLICATION: usize = 4;
+
+/// Weight a single body gene adds to a biot at its usual expression.
+const GENE_WEIGHT: f32 = 0.1;
+
+/// Maximum expression of a gene, relative to its usual expression.
+pub const MAX_EXPRESSION: f32 = 2.0;
+
+/// Upper bound of the heritable mutation chance, so that every offspring
+/// eventually stops mutating.
+pub const MAX_MUTATION_CHANCE: f32 = 0.95;
+
+/// Maximum change of the expression of a gene by a single mutation.
+const EXPRESSION_STEP: f32 = 0.1;
+
+/// Intelligence of a biot per intelligence gene.
+const INTELLIGENCE_PER_GENE: f32 = 10.0;
+
+/// Returns the property a single `gene` adds.
+///
+/// Intelligence does not add any weight, so it comes in larger units.
+fn per_gene(gene: Gene) -> f32 {
+    if gene == Gene::Intelligence {
+        INTELLIGENCE_PER_GENE
+    } else {
+        GENE_WEIGHT
     }
+}
 
-    /// Returns an iterator over the genes of the genome.
-    pub fn genes(&self) -> slice::Iter<Gene> {
-        self.genes.iter()
+/// Returns the response curve of the property expressed by `gene`.
+fn response_curve(responses: &ResponseConfig, gene: Gene) -> &ResponseCurve {
+    match gene {
+        Gene::Attack => &responses.attack,
+        Gene::Defense => &responses.defense,
+        Gene::Photosynthesis => &responses.photosynthesis,
+        Gene::Motion => &responses.motion,
+        Gene::Intelligence => &responses.intelligence,
+        Gene::Digestion => &responses.digestion,
+        Gene::Venom => &responses.venom,
+        Gene::Armor => &responses.armor,
+        Gene::Camouflage => &responses.camouflage,
+        Gene::Vision => &responses.vision,
+        Gene::Reproduction => &responses.reproduction,
+        Gene::Immunity => &responses.immunity,
+        Gene::Fecundity => &responses.fecundity,
+        Gene::Nurture => &responses.nurture,
+        Gene::Sociality => &responses.sociality,
+        Gene::HeatTolerance => &responses.heat_tolerance,
+        Gene::Kinship => &responses.kinship,
+        Gene::None | Gene::Custom(_) => &ResponseCurve::LINEAR,
     }
 }
 
-/// Modulus operator to get toroidal world topology
-fn modulus<T>(a: T, b: T) -> T
-where
-    T: ops::Rem<Output = T> + ops::Add<Output = T> + Copy,
-{
-    ((a % b) + b) % b
+/// Base life of a biot per unit of weight.
+const LIFE_PER_WEIGHT: f32 = 8.0;
+
+/// Radius of a biot per unit of weight.
+const RADIUS_PER_WEIGHT: f32 = 7.0;
+
+/// Distance within which a biot detects food per unit of intelligence.
+const DETECTION_PER_INTELLIGENCE: f32 = 40.0;
+
+/// Closeness of a threat, see [`Stats::flee_dir`], from which on a biot
+/// rather flees than heads towards food.
+const FLEE_CLOSENESS: f32 = 0.5;
+
+/// Distance beyond its body within which an intelligent biot steers around obstacles.
+const OBSTACLE_LOOKAHEAD: f32 = 10.0;
+
+/// Upper bound for the radius of any biot.
+pub const MAX_RADIUS: f32 =
+    RADIUS_PER_WEIGHT * GENE_WEIGHT * MAX_EXPRESSION * MAX_GENOME_LEN as f32;
+
+/// Upper bound for the [`interaction_radius`] of any two biots.
+pub const MAX_INTERACTION_RADIUS: Distance = Distance(2.0 * MAX_RADIUS);
+
+/// Returns `true` if biots bear litters, see [`Biot::litter`].
+///
+/// # Note
+///
+/// Litters only apply to biots cloning themselves, mating pairs always
+/// produce a single offspring.
+fn has_litters(config: &SimConfig) -> bool {
+    config.litters.is_enabled() && config.reproduction == Reproduction::Asexual
+}
+
+/// Biots only reproduce if their sixth nearest neighbour is farther away than this.
+const CROWDING_DISTANCE: SquaredDistance = SquaredDistance(200.0);
+
+/// Distance below which two biots interact with each other.
+///
+/// Biots interact as soon as their bodies touch, so this is the sum of their radii.
+pub fn interaction_radius(a: &Biot, b: &Biot) -> Distance {
+    a.radius() + b.radius()
 }
 
 /// The properties of a biot.
 ///
 /// The properties are fully derived by the genome of the biot.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Properties {
     pub attack: f32,
     pub defense: f32,
     pub photosynthesis: f32,
     pub motion: f32,
     pub intelligence: f32,
+    pub digestion: f32,
+    pub venom: f32,
+    pub armor: f32,
+    pub camouflage: f32,
+    pub vision: f32,
+    pub reproduction: f32,
+    pub immunity: f32,
+    pub fecundity: f32,
+    pub nurture: f32,
+    pub sociality: f32,
+    pub heat_tolerance: f32,
+    pub kinship: f32,
+    /// Metabolism cost of the custom genes on top of the costs of their effects.
+    pub upkeep: f32,
+    /// The properties before their response curves, in the order of
+    /// [`Gene::EXPRESSED`], which the metabolism is paid for.
+    pub expressed: [f32; Gene::EXPRESSED.len()],
+    /// Number of genes in the genome.
+    pub genes: usize,
 }
 
 impl Properties {
@@ -91,71 +729,395 @@ impl Properties {
         self.photosynthesis = 0.0;
         self.motion = 0.0;
         self.intelligence = 0.0;
+        self.digestion = 0.0;
+        self.venom = 0.0;
+        self.armor = 0.0;
+        self.camouflage = 0.0;
+        self.vision = 0.0;
+        self.reproduction = 0.0;
+        self.immunity = 0.0;
+        self.fecundity = 0.0;
+        self.nurture = 0.0;
+        self.sociality = 0.0;
+        self.heat_tolerance = 0.0;
+        self.kinship = 0.0;
+        self.upkeep = 0.0;
+        self.expressed = [0.0; Gene::EXPRESSED.len()];
+        self.genes = 0;
+    }
+
+    /// Returns the property expressed by `gene`, if any.
+    fn property_mut(&mut self, gene: Gene) -> Option<&mut f32> {
+        match gene {
+            Gene::Attack => Some(&mut self.attack),
+            Gene::Defense => Some(&mut self.defense),
+            Gene::Photosynthesis => Some(&mut self.photosynthesis),
+            Gene::Motion => Some(&mut self.motion),
+            Gene::Intelligence => Some(&mut self.intelligence),
+            Gene::Digestion => Some(&mut self.digestion),
+            Gene::Venom => Some(&mut self.venom),
+            Gene::Armor => Some(&mut self.armor),
+            Gene::Camouflage => Some(&mut self.camouflage),
+            Gene::Vision => Some(&mut self.vision),
+            Gene::Reproduction => Some(&mut self.reproduction),
+            Gene::Immunity => Some(&mut self.immunity),
+            Gene::Fecundity => Some(&mut self.fecundity),
+            Gene::Nurture => Some(&mut self.nurture),
+            Gene::Sociality => Some(&mut self.sociality),
+            Gene::HeatTolerance => Some(&mut self.heat_tolerance),
+            Gene::Kinship => Some(&mut self.kinship),
+            Gene::None | Gene::Custom(_) => None,
+        }
     }
 
     /// Compute chacteristics from biot genome
-    pub fn adjust_to_genome(&mut self, genome: &Genome) {
+    ///
+    /// Custom genes take the effects of their definition in `genes`, custom
+    /// genes without a definition have no effect. Each property then follows
+    /// its curve of `responses`.
+    pub fn adjust_to_genome(
+        &mut self,
+        genome: &Genome,
+        genes: &[GeneDefinition],
+        responses: &ResponseConfig,
+    ) {
         // Reset properties before adjustments:
         self.reset();
         // Recalculate stats from genome:
-        for gene in genome.genes() {
-            match gene {
+        self.genes = genome.len();
+        for locus in genome.loci() {
+            match locus.gene {
                 Gene::None => (),
-                Gene::Attack => self.attack += 0.1,
-                Gene::Defense => self.defense += 0.1,
-                Gene::Photosynthesis => self.photosynthesis += 0.1,
-                Gene::Motion => self.motion += 0.1,
-                Gene::Intelligence => self.intelligence += 10.0,
+                Gene::Custom(id) => {
+                    if let Some(definition) = genes.iter().find(|gene| gene.id == id) {
+                        self.express(definition, locus.expression);
+                    }
+                }
+                gene => {
+                    if let Some(index) = Gene::EXPRESSED.iter().position(|g| *g == gene) {
+                        self.expressed[index] += per_gene(gene) * locus.expression;
+                    }
+                }
+            }
+        }
+        for (gene, expressed) in Gene::EXPRESSED.into_iter().zip(self.expressed) {
+            let curve = response_curve(responses, gene);
+            let value = per_gene(gene) * curve.apply(expressed / per_gene(gene));
+            if let Some(property) = self.property_mut(gene) {
+                *property = value;
             }
         }
     }
 
-    /// Calculates the metabolism costs of the properties.
+    /// Adds the effects of a custom gene expressed by `expression`.
+    fn express(&mut self, definition: &GeneDefinition, expression: f32) {
+        let effects = [
+            (Gene::Attack, definition.attack),
+            (Gene::Defense, definition.defense),
+            (Gene::Photosynthesis, definition.photosynthesis),
+            (Gene::Motion, definition.motion),
+            (Gene::Intelligence, definition.intelligence),
+            (Gene::Venom, definition.venom),
+            (Gene::Armor, definition.armor),
+            (Gene::Camouflage, definition.camouflage),
+            (Gene::Vision, definition.vision),
+            (Gene::Reproduction, definition.reproduction),
+            (Gene::Digestion, definition.digestion),
+            (Gene::Immunity, definition.immunity),
+            (Gene::Fecundity, definition.fecundity),
+            (Gene::Nurture, definition.nurture),
+            (Gene::Sociality, definition.sociality),
+            (Gene::HeatTolerance, definition.heat_tolerance),
+            (Gene::Kinship, definition.kinship),
+        ];
+        for (expressed, (gene, effect)) in self.expressed.iter_mut().zip(effects) {
+            *expressed += effect * per_gene(gene) * expression;
+        }
+        self.upkeep += definition.cost * expression;
+    }
+
+    /// Calculates the metabolism costs of the properties on the given tile.
     ///
     /// # Note
     ///
     /// The metabolism indicates how much energy the biot requires for living.
-    fn metabolism(&self) -> f32 {
-        0.07 * (4.5 * self.attack + 2.3 * self.defense + 2.5 * self.motion + 0.1 * self.intelligence)
+    ///
+    /// The properties are paid for as expressed, regardless of their response curves.
+    fn metabolism(&self, config: &SimConfig, tile: Tile) -> Energy {
+        let costs = &config.metabolism;
+        let properties = Gene::EXPRESSED
+            .into_iter()
+            .zip(self.expressed)
+            .map(|(gene, expressed)| {
+                let cost = match gene {
+                    Gene::Attack => costs.attack,
+                    Gene::Defense => costs.defense,
+                    Gene::Motion => costs.motion * tile.motion_cost(),
+                    Gene::Intelligence => costs.intelligence,
+                    Gene::Digestion => costs.digestion,
+                    Gene::Venom => costs.venom,
+                    Gene::Armor => costs.armor,
+                    Gene::Camouflage => costs.camouflage,
+                    Gene::Vision => costs.vision,
+                    Gene::Reproduction => costs.reproduction,
+                    Gene::Immunity => costs.immunity,
+                    Gene::Fecundity => costs.fecundity,
+                    Gene::Nurture => costs.nurture,
+                    Gene::Sociality => costs.sociality,
+                    Gene::HeatTolerance => costs.heat_tolerance,
+                    Gene::Kinship => costs.kinship,
+                    // Sunlight is free.
+                    Gene::Photosynthesis | Gene::None | Gene::Custom(_) => 0.0,
+                };
+                cost * expressed
+            })
+            .sum::<f32>();
+        Energy(costs.scale * (properties + costs.gene * self.genes as f32 + self.upkeep))
     }
 
     /// Total weight of the biot, useful for computing its motion.
-    fn weight(&self) -> f32 {
-        self.attack + self.defense + self.photosynthesis + self.motion
+    pub fn weight(&self) -> f32 {
+        self.attack + self.defense + self.photosynthesis + self.motion + self.armor
+    }
+
+    /// Number of hidden neurons of the brain of the biot: one per intelligence gene.
+    pub fn neurons(&self) -> usize {
+        let genes = (self.intelligence / INTELLIGENCE_PER_GENE).ceil() as usize;
+        genes.clamp(1, MAX_NEURONS)
+    }
+
+    /// Distance within which the biot detects food, before camouflage.
+    pub fn detection_distance(&self) -> Distance {
+        Distance(self.intelligence * DETECTION_PER_INTELLIGENCE * (1.0 + self.vision))
+    }
+
+    /// Factor applied to the detection distance of predators looking for the biot.
+    pub fn visibility(&self) -> f32 {
+        1.0 / (1.0 + self.camouflage)
+    }
+
+    /// Life gained (or lost if negative) per step from photosynthesis and metabolism.
+    ///
+    /// Photosynthesis only uses the given fraction of the full sunlight. The
+    /// metabolism is scaled by `factor`, see [`AgingConfig`](crate::config::AgingConfig)
+    /// and [`Reserve`](crate::reserve::Reserve).
+    fn energy_balance(&self, config: &SimConfig, light: f32, tile: Tile, factor: f32) -> Energy {
+        (self.photosynthesis_gain(light, tile) - self.metabolism(config, tile) * factor)
+            * config.energy_efficiency
+    }
+
+    /// Energy produced per step by photosynthesis on the given tile.
+    fn photosynthesis_gain(&self, light: f32, tile: Tile) -> Energy {
+        Energy(self.photosynthesis * light * tile.photosynthesis())
     }
 }
 
+/// The reason why a biot died.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CauseOfDeath {
+    /// The biot ran out of life.
+    Starvation,
+    /// The biot reached its maximum age.
+    OldAge,
+    /// The biot was eaten by a stronger biot.
+    Predation,
+    /// The biot was born without any weight.
+    Nonviable,
+    /// The biot ran into the edge of a walled world.
+    Wall,
+    /// The biot ate a venomous biot.
+    Venom,
+    /// A pathogen drained the last life of the biot.
+    Disease,
+}
+
+/// The health state of a biot.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Health {
+    /// The biot is alive and well.
+    Alive,
+    /// The biot is still alive but about to die of the given cause.
+    Dying { cause: CauseOfDeath },
+    /// The biot is dead and will be removed at the end of the step.
+    Dead,
+}
+
+/// A biot eating another one, see [`Biot::interact`].
+///
+/// # Note
+///
+/// Fights are decided without any chance: the predator bites its prey as
+/// soon as its attack exceeds the resistance of the prey.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Fight {
+    pub predator: BiotId,
+    pub prey: BiotId,
+    /// Attack of the predator.
+    pub attack: f32,
+    /// Resistance of the prey, see [`Biot::resistance`].
+    pub resistance: f32,
+    /// Life the prey lost to the bite.
+    pub wound: Energy,
+    /// Whether the bite killed the prey.
+    pub killed: bool,
+    /// Life the predator gained from its prey.
+    pub gain: Energy,
+    /// Life the predator lost to the venom of its prey.
+    pub venom: Energy,
+    /// Whether the venom killed the predator.
+    pub poisoned: bool,
+}
+
+/// Two biots touching each other at the start of the interactions of a step.
+///
+/// # Note
+///
+/// Whether one biot preys on the other does not change during the
+/// interactions, whether it is still alive or protected by a reserve may.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Encounter {
+    /// Indices of the biots, the first one below the second one.
+    pub pair: (usize, usize),
+    pub squared_distance: SquaredDistance,
+    /// Whether the first biot preys on the second one.
+    pub first_preys: bool,
+    /// Whether the second biot preys on the first one.
+    pub second_preys: bool,
+}
+
+/// What surrounds the biots stepping in parallel, see [`Biot::step`].
+#[derive(Copy, Clone)]
+pub struct Surroundings<'a> {
+    pub world: &'a World,
+    pub terrain: &'a Terrain,
+    /// Spatial index of the biots as of the beginning of the step.
+    pub grid: &'a SpatialGrid,
+    pub environment: &'a Environment,
+    /// Effects of the genes asked whenever a biot moves, see [`GenePlugin`].
+    pub plugins: &'a [&'a dyn GenePlugin],
+}
+
+/// Prey an intelligent hunter pursues, see
+/// [`PursuitConfig`](crate::config::PursuitConfig).
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Target {
+    /// Identifier of the prey.
+    pub id: BiotId,
+    /// Position the hunter saw the prey at last.
+    pub last_seen: Vec2,
+    /// Number of steps the hunter keeps pursuing the prey, zero once it gave up on it.
+    pub patience: u32,
+}
+
 /// The status values of a biot.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Stats {
-    pub life: f32,
+    pub life: Energy,
     pub pos: Vec2,
     pub speed: Vec2,
-    pub age: u32,
+    pub age: Ticks,
+    /// Set when the biot got killed by another biot.
+    pub killed: Option<CauseOfDeath>,
+    /// Direction towards the prey the biot sensed last.
+    pub feed_dir: Option<Vec2>,
+    /// Direction away from the closest threat the biot sensed last.
+    ///
+    /// The direction is scaled by the closeness of the threat: one right next
+    /// to the biot, zero at the edge of its detection distance.
+    pub flee_dir: Option<Vec2>,
+    /// Prey the biot pursues instead of the closest one, or gave up on last.
+    pub target: Option<Target>,
+    /// Direction towards the partner the biot sensed last while looking for a mate.
+    pub mate_dir: Option<Vec2>,
+    /// Direction the flocking forces of its species pulled the biot in when
+    /// it last sensed them, see [`FlockingConfig`](crate::config::FlockingConfig).
+    pub flock_dir: Option<Vec2>,
+    /// Fraction of the life required for reproduction the biot lacks, as of
+    /// its last step, see [`Biot::drive`].
+    pub hunger: f32,
+    /// Fraction of the sunlight reaching the biot through the shade of its neighbours.
+    pub light: f32,
+    /// Temperature at the position of the biot during its last step, see
+    /// [`crate::config::TemperatureConfig`].
+    pub temperature: f32,
+    /// Terrain the biot was on during its last step.
+    pub tile: Tile,
+    /// Whether the biot carries the pathogen, see [`DiseaseConfig`].
+    pub infected: bool,
+    /// Factor of the attack and motion of the biot at its age, see
+    /// [`AgingConfig`](crate::config::AgingConfig).
+    pub vigor: f32,
+    /// Fraction of the full size of its genome the biot has grown to, see
+    /// [`GrowthConfig`](crate::config::GrowthConfig).
+    pub size: f32,
 }
 
 impl Default for Stats {
     fn default() -> Self {
         Self {
-            life: 0.0,
+            life: Energy::ZERO,
             pos: vec2(0.0, 0.0),
             speed: vec2(0.0, 0.0),
-            age: 0,
+            age: Ticks(0),
+            killed: None,
+            feed_dir: None,
+            flee_dir: None,
+            target: None,
+            mate_dir: None,
+            flock_dir: None,
+            // Neither starving nor sated until the first step.
+            hunger: 0.5,
+            light: 1.0,
+            temperature: 0.5,
+            tile: Tile::Plains,
+            infected: false,
+            vigor: 1.0,
+            size: 1.0,
         }
     }
 }
 
 impl Stats {
-    /// Positions the biot randomly on the screen.
-    pub fn position_randomly(&mut self) {
-        self.pos.x = rand::gen_range(0., 1.) * screen_width();
-        self.pos.y = rand::gen_range(0., 1.) * screen_height();
+    /// Positions the biot randomly in the world.
+    pub fn position_randomly(&mut self, world: &World, rng: &mut Rng) {
+        self.pos = world.random_position(rng);
     }
 }
 
+/// What an intelligent biot prioritizes, depending on its hunger.
+///
+/// # Note
+///
+/// Biots only follow their drives if enabled, see
+/// [`DriveConfig`](crate::config::DriveConfig), and otherwise always forage.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Drive {
+    /// Heads towards food, unless a close threat is in sight.
+    Foraging,
+    /// Hunts regardless of any threats.
+    Starving,
+    /// Saves energy by moving less and flees from any threat instead of hunting.
+    Sated,
+    /// Has the life to reproduce and looks for a partner.
+    Mating,
+}
+
+/// Identifier of a biot, unique within a simulation.
+///
+/// # Note
+///
+/// Identifiers are handed out in increasing order, so younger biots have larger identifiers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct BiotId(pub u64);
+
 /// A biot.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Biot {
+    id: BiotId,
+    /// Identifier of the founder of the lineage the biot descends from.
+    lineage: BiotId,
+    /// Species the biot was assigned to, unless it was not clustered yet.
+    species: Option<SpeciesId>,
     pub stats: Stats,
     genome: Genome,
     pub properties: Properties,
@@ -163,102 +1125,753 @@ pub struct Biot {
 
 impl Biot {
     /// Creates a random biot.
-    pub fn random_biot() -> Self {
-        let genome = Genome::random();
+    pub fn random_biot(world: &World, config: &SimConfig, rng: &mut Rng) -> Self {
+        let genome = Genome::random(&Gene::pool(config), rng);
+        Self::from_random_genome(genome, world, config, rng)
+    }
+
+    /// Creates a random biot of the initial population, see
+    /// [`InitialGenesConfig`](crate::config::InitialGenesConfig).
+    pub fn initial_biot(
+        world: &World,
+        config: &SimConfig,
+        distribution: &GeneDistribution,
+        rng: &mut Rng,
+    ) -> Self {
+        let genome = Genome::sample(distribution, rng);
+        Self::from_random_genome(genome, world, config, rng)
+    }
+
+    /// Creates a biot with a random `genome`, with a brain if brains are enabled.
+    fn from_random_genome(
+        mut genome: Genome,
+        world: &World,
+        config: &SimConfig,
+        rng: &mut Rng,
+    ) -> Self {
+        if config.brains.enabled {
+            genome.brain = Some(Brain::random(rng));
+        }
+        let mut biot = Self::from_genome(genome, world, rng);
+        // Random genomes may carry custom genes of the scenario.
+        biot.properties
+            .adjust_to_genome(&biot.genome, &config.genes, &config.responses);
+        biot
+    }
+
+    /// Creates a plant: a stationary photosynthesizer with some defense.
+    pub fn plant(world: &World, rng: &mut Rng) -> Self {
+        let genome = GenomeBuilder::new()
+            .with(Gene::Photosynthesis, 28)
+            .with(Gene::Defense, 4);
+        Self::from_archetype(genome, world, rng)
+    }
+
+    /// Creates a grazer: a slowly moving biot strong enough to eat plants.
+    pub fn grazer(world: &World, rng: &mut Rng) -> Self {
+        let genome = GenomeBuilder::new()
+            .with(Gene::Photosynthesis, 12)
+            .with(Gene::Attack, 8)
+            .with(Gene::Motion, 8)
+            .with(Gene::Defense, 4);
+        Self::from_archetype(genome, world, rng)
+    }
+
+    /// Creates a predator: a fast moving biot living off other biots.
+    pub fn predator(world: &World, rng: &mut Rng) -> Self {
+        let genome = GenomeBuilder::new()
+            .with(Gene::Attack, 16)
+            .with(Gene::Motion, 10)
+            .with(Gene::Defense, 6);
+        Self::from_archetype(genome, world, rng)
+    }
+
+    /// Creates a smart predator: a predator that moves towards its prey.
+    pub fn smart_predator(world: &World, rng: &mut Rng) -> Self {
+        let genome = GenomeBuilder::new()
+            .with(Gene::Attack, 15)
+            .with(Gene::Motion, 10)
+            .with(Gene::Defense, 6)
+            .with(Gene::Intelligence, 1);
+        Self::from_archetype(genome, world, rng)
+    }
+
+    /// Creates a randomly positioned biot from a curated archetype genome.
+    fn from_archetype(genome: GenomeBuilder, world: &World, rng: &mut Rng) -> Self {
+        let genome = genome
+            .build()
+            .expect("archetype genomes must fit into a genome");
+        Self::from_genome(genome, world, rng)
+    }
+
+    /// Creates a randomly positioned biot with the given genome.
+    ///
+    /// Custom genes have no effect and all properties respond linearly until
+    /// the genome is set with a config, see [`Biot::set_genome`].
+    pub fn from_genome(genome: Genome, world: &World, rng: &mut Rng) -> Self {
         let mut properties = Properties::default();
-        properties.adjust_to_genome(&genome);
+        properties.adjust_to_genome(&genome, &[], &ResponseConfig::default());
         let mut stats = Stats::default();
-        stats.position_randomly();
+        stats.position_randomly(world, rng);
         let mut s = Self {
+            id: BiotId(0),
+            lineage: BiotId(0),
+            species: None,
             stats,
             genome,
             properties,
         };
-        s.stats.life = s.base_life();
+        // Without a config there is no basal weight, see `SimConfig::nonviable`.
+        s.stats.life = Energy(LIFE_PER_WEIGHT * s.properties.weight());
         s
     }
 
+    /// Returns the identifier of the biot.
+    pub fn id(&self) -> BiotId {
+        self.id
+    }
+
+    /// Returns the identifier of the founder of the lineage the biot descends from.
+    pub fn lineage(&self) -> BiotId {
+        self.lineage
+    }
+
+    /// Assigns the identifier of an offspring, keeping the lineage of its parent.
+    pub fn set_id(&mut self, id: BiotId) {
+        self.id = id;
+    }
+
+    /// Assigns the identifier of the biot and makes it the founder of a new lineage.
+    pub fn found_lineage(&mut self, id: BiotId) {
+        self.id = id;
+        self.lineage = id;
+    }
+
+    /// Returns the species of the biot, unless it was not clustered yet.
+    ///
+    /// Newborns share the species of their parent until the next clustering pass.
+    pub fn species(&self) -> Option<SpeciesId> {
+        self.species
+    }
+
+    /// Assigns the biot to a species.
+    pub fn set_species(&mut self, species: SpeciesId) {
+        self.species = Some(species);
+    }
+
+    /// Returns the genome of the biot.
+    pub fn genome(&self) -> &Genome {
+        &self.genome
+    }
+
+    /// Replaces the genome of the biot and re-derives its properties with
+    /// the custom genes and response curves of `config`.
+    pub fn set_genome(&mut self, genome: Genome, config: &SimConfig) {
+        self.genome = genome;
+        self.properties
+            .adjust_to_genome(&self.genome, &config.genes, &config.responses);
+    }
+
     /// Compute the evolution of the biot for one simulation step
-    pub fn step(&mut self, rtree: &RTree<TreePoint>, feed_dir: Option<Vec2>) -> Option<Biot> {
-        let mut offspring = None;
-        let adult_factor = 4.;
-        if self.stats.life >= self.base_life() * adult_factor {
-            let close_by = rtree
-                .nearest_neighbor_iter_with_distance_2(&[
-                    self.stats.pos.x,
-                    self.stats.pos.y,
-                ])
-                .nth(5);
-            if close_by.map_or(true, |(_, d2)| d2 > 200.) {
+    ///
+    /// Intelligent biots head towards the food last sensed in `stats.feed_dir`,
+    /// unless they flee from a close threat in `stats.flee_dir`, as far as
+    /// their drive lets them, see [`Biot::drive`]. Returns the
+    /// litter the biot gave birth to, see [`Biot::litter`].
+    pub fn step(
+        &mut self,
+        surroundings: &Surroundings,
+        config: &SimConfig,
+        rngs: &mut RngStreams,
+        ledger: &mut EnergyLedger,
+    ) -> Vec<Biot> {
+        let Surroundings {
+            world,
+            terrain,
+            grid,
+            environment,
+            plugins,
+        } = *surroundings;
+        self.stats.tile = terrain.tile(self.stats.pos);
+        self.stats.vigor = config.aging.vigor(self.age_fraction());
+        let mut litter = Vec::new();
+        // Sexually reproducing biots are paired up by the collection.
+        if config.reproduction == Reproduction::Asexual && self.can_reproduce(config, grid) {
+            let (size, investment) = self.litter(config);
+            let life = self.base_life(config) * (self.adult_factor(config) - 1.0);
+            ledger.reproduction += self.stats.life - life;
+            for _ in 0..size {
                 let mut off = self.clone();
-                off.stats.age = 0;
-                while rand::gen_range(0., 1.) < 0.2 {
-                    off.mutate();
-                }
-                off.stats.life = off.base_life();
-                off.random_move(1.5);
-                offspring = Some(off);
-                self.stats.life = (adult_factor - 1.0) * self.base_life();
+                off.stats.age = Ticks(0);
+                off.stats.infected = false;
+                off.stats.target = None;
+                off.stats.vigor = config.aging.vigor(0.0);
+                off.stats.size = config.growth.newborn_size;
+                off.mutate_offspring(config, &mut rngs.mutation);
+                off.stats.life = off.base_life(config) * investment;
+                off.stats.hunger = 1.0 - off.life_fraction(config);
+                off.random_move(&mut rngs.movement, 1.5);
+                ledger.reproduction -= off.stats.life;
+                litter.push(off);
             }
+            self.stats.life = life;
         }
-        self.stats.pos += self.stats.speed;
-        self.stats.pos.x = modulus(self.stats.pos.x, screen_width());
-        self.stats.pos.y = modulus(self.stats.pos.y, screen_height());
+        let mut pos = self.stats.pos + self.stats.speed;
+        let radius = self.radius().0;
+        obstacle::collide(&config.obstacles, &mut pos, &mut self.stats.speed, radius);
+        if !world.confine(&mut pos, &mut self.stats.speed) {
+            self.stats.killed = Some(CauseOfDeath::Wall);
+        }
+        self.stats.pos = pos;
         self.stats.speed *= 0.9;
-        self.stats.life += (self.properties.photosynthesis - self.properties.metabolism()) * 0.4;
-        if rand::gen_range(0., 1.) < 0.2 * self.properties.motion {
-            let speed = 7. * self.properties.motion / self.properties.weight();
+        let warmth = config.temperature.photosynthesis(self.stats.temperature);
+        let (light, tile) = (self.stats.light * warmth, self.stats.tile);
+        let factor = self.metabolism_factor(config);
+        self.stats.life += self.properties.energy_balance(config, light, tile, factor);
+        ledger.photosynthesis +=
+            self.properties.photosynthesis_gain(light, tile) * config.energy_efficiency;
+        ledger.metabolism +=
+            self.properties.metabolism(config, tile) * factor * config.energy_efficiency;
+        if self.stats.infected {
+            self.suffer(&config.disease, &mut rngs.encounters, ledger);
+        }
+        let stored = self.stats.life / self.base_life(config);
+        self.stats.size = config.growth.grow(self.stats.size, stored);
+        let drive = self.drive(config);
+        let activity = match drive {
+            Drive::Sated => config.drives.sated_activity,
+            Drive::Foraging | Drive::Starving | Drive::Mating => 1.0,
+        };
+        if rngs.movement.gen_range(0., 1.) < 0.2 * self.properties.motion * activity {
+            let speed = 7. * self.properties.motion * self.stats.vigor / self.weight();
             if self.properties.intelligence > 0.0 {
-                if let Some(feed_dir) = feed_dir {
-                    self.accelerate(feed_dir, speed);
+                if let Some(dir) = self.heading(drive) {
+                    let lookahead = OBSTACLE_LOOKAHEAD + self.radius().0;
+                    let dir = obstacle::steer(&config.obstacles, self.stats.pos, dir, lookahead);
+                    self.accelerate(dir, speed);
                 } else {
-                    self.random_move(speed)
+                    self.random_move(&mut rngs.movement, speed)
                 }
             } else {
-                self.random_move(speed)
+                self.random_move(&mut rngs.movement, speed)
+            }
+            for plugin in plugins {
+                let expression = Expression {
+                    biot: self,
+                    expression: self.genome.expression(plugin.gene()),
+                    world,
+                    environment,
+                    config,
+                };
+                if let Some((dir, fraction)) = plugin.push(&expression) {
+                    self.accelerate(dir, speed * fraction);
+                }
+            }
+        }
+        if let Some(target) = &mut self.stats.target {
+            target.patience = target.patience.saturating_sub(1);
+        }
+        self.stats.age += Ticks(1);
+        self.stats.hunger = 1.0 - self.life_fraction(config);
+        litter
+    }
+
+    /// Returns the direction an intelligent biot following `drive` heads
+    /// towards, if any.
+    fn heading(&self, drive: Drive) -> Option<Vec2> {
+        let stats = &self.stats;
+        let flee = stats.flee_dir.and_then(Vec2::try_normalize);
+        match drive {
+            // Biots without food in sight flee from any threat, the others
+            // only from close ones.
+            Drive::Foraging => stats
+                .flee_dir
+                .filter(|flee| stats.feed_dir.is_none() || flee.length() >= FLEE_CLOSENESS)
+                .and_then(Vec2::try_normalize)
+                .or(stats.feed_dir),
+            Drive::Starving => stats.feed_dir.or(flee),
+            Drive::Sated => flee,
+            Drive::Mating => flee.or(stats.mate_dir),
+        }
+    }
+
+    /// Returns what the biot prioritizes at its current hunger.
+    ///
+    /// # Note
+    ///
+    /// Brains sense the life of the biot themselves, so biots steered by a
+    /// brain always forage.
+    pub fn drive(&self, config: &SimConfig) -> Drive {
+        let drives = &config.drives;
+        let hunger = self.stats.hunger;
+        let has_brain = config.brains.enabled && self.genome.brain.is_some();
+        if !drives.enabled || has_brain {
+            Drive::Foraging
+        } else if hunger <= 0.0 {
+            Drive::Mating
+        } else if hunger <= drives.sated {
+            Drive::Sated
+        } else if hunger >= drives.starving {
+            Drive::Starving
+        } else {
+            Drive::Foraging
+        }
+    }
+
+    /// Returns the factor applied to the metabolism of the biot at its age,
+    /// position and temperature.
+    fn metabolism_factor(&self, config: &SimConfig) -> f32 {
+        let heat_tolerance = self.properties.heat_tolerance;
+        config.aging.metabolism(self.age_fraction())
+            * reserve::metabolism(&config.reserves, self.stats.pos)
+            * config
+                .temperature
+                .metabolism(self.stats.temperature, heat_tolerance)
+    }
+
+    /// Drains the life of the infected biot, which may recover or die of the disease.
+    fn suffer(&mut self, disease: &DiseaseConfig, rng: &mut Rng, ledger: &mut EnergyLedger) {
+        let drain = Energy(disease.drain.min(self.stats.life.0).max(0.0));
+        self.stats.life -= drain;
+        ledger.disease += drain;
+        if drain > Energy::ZERO && self.stats.life <= Energy::ZERO && self.stats.killed.is_none() {
+            self.stats.killed = Some(CauseOfDeath::Disease);
+        }
+        if rng.gen_range(0., 1.) < disease.recovery {
+            self.stats.infected = false;
+        }
+    }
+
+    /// Returns `true` if the biot is adult and not too crowded to reproduce.
+    pub fn can_reproduce(&self, config: &SimConfig, grid: &SpatialGrid) -> bool {
+        if self.stats.life < self.reproduction_threshold(config) {
+            return false;
+        }
+        // The biot itself is one of the neighbours found by the grid.
+        grid.within(self.stats.pos, CROWDING_DISTANCE.sqrt())
+            .nth(5)
+            .is_none()
+    }
+
+    /// Combines the genomes of the adult biots `i` and `j` into an offspring.
+    ///
+    /// # Note
+    ///
+    /// The offspring starts next to `i` and continues its lineage. Both
+    /// parents pay the cost of reproduction.
+    pub fn mate(
+        biots: &mut [Self],
+        (i, j): (usize, usize),
+        config: &SimConfig,
+        rngs: &mut RngStreams,
+        ledger: &mut EnergyLedger,
+    ) -> Biot {
+        let mut off = biots[i].clone();
+        off.set_genome(
+            biots[i]
+                .genome
+                .crossover(&biots[j].genome, &mut rngs.mutation),
+            config,
+        );
+        off.stats.age = Ticks(0);
+        off.stats.infected = false;
+        off.stats.target = None;
+        off.stats.vigor = config.aging.vigor(0.0);
+        off.stats.size = config.growth.newborn_size;
+        off.mutate_offspring(config, &mut rngs.mutation);
+        off.stats.life = off.base_life(config);
+        off.stats.hunger = 1.0 - off.life_fraction(config);
+        off.random_move(&mut rngs.movement, 1.5);
+        ledger.reproduction -= off.stats.life;
+        for parent in [i, j] {
+            let parent = &mut biots[parent];
+            let life = parent.base_life(config) * (parent.adult_factor(config) - 1.0);
+            ledger.reproduction += parent.stats.life - life;
+            parent.stats.life = life;
+        }
+        off
+    }
+
+    /// Lets a carrier of the pathogen among the biots `i` and `j`, which are
+    /// `squared_distance` apart, infect the other one if they touch.
+    ///
+    /// Returns `true` if the other biot got infected.
+    ///
+    /// # Note
+    ///
+    /// Immunity lowers the chance of an infection by its full amount, so
+    /// biots with an immunity of one or more cannot be infected.
+    pub fn contagion(
+        biots: &mut [Self],
+        (i, j): (usize, usize),
+        squared_distance: SquaredDistance,
+        disease: &DiseaseConfig,
+        rng: &mut Rng,
+    ) -> bool {
+        if biots[i].stats.infected == biots[j].stats.infected
+            || squared_distance >= interaction_radius(&biots[i], &biots[j]).squared()
+        {
+            return false;
+        }
+        let healthy = if biots[i].stats.infected { j } else { i };
+        let healthy = &mut biots[healthy];
+        let chance = disease.transmission * (1.0 - healthy.properties.immunity).max(0.0);
+        if healthy.is_dead() || rng.gen_range(0., 1.) >= chance {
+            return false;
+        }
+        healthy.stats.infected = true;
+        true
+    }
+
+    /// Returns the encounter of two biots `squared_distance` apart, if they touch.
+    ///
+    /// # Note
+    ///
+    /// This only reads the biots, so the encounters of a step are found in
+    /// parallel before [`Biot::interact`] applies them one after the other.
+    pub fn encounter(
+        biots: &[Self],
+        (i, j): (usize, usize),
+        squared_distance: SquaredDistance,
+        config: &SimConfig,
+    ) -> Option<Encounter> {
+        if squared_distance >= interaction_radius(&biots[i], &biots[j]).squared() {
+            return None;
+        }
+        Some(Encounter {
+            pair: (i, j),
+            squared_distance,
+            first_preys: biots[i].preys_on(&biots[j], config),
+            second_preys: biots[j].preys_on(&biots[i], config),
+        })
+    }
+
+    /// Compute the interaction of two biots that met in `encounter`.
+    ///
+    /// Returns the fight if one of the biots ate the other. Biots neither of
+    /// which can eat the other push each other apart instead. Biots that died
+    /// earlier in the step no longer interact.
+    pub fn interact(
+        biots: &mut [Self],
+        encounter: &Encounter,
+        world: &World,
+        config: &SimConfig,
+        ledger: &mut EnergyLedger,
+    ) -> Option<Fight> {
+        let (i, j) = encounter.pair;
+        // Earlier interactions of the step may have killed one of the biots.
+        if biots[i].is_dead() || biots[j].is_dead() {
+            return None;
+        }
+        // They may also have pushed the prey into a reserve.
+        let exposed = |prey: &Self| !reserve::is_protected(&config.reserves, prey.stats.pos);
+        if encounter.first_preys && exposed(&biots[j]) {
+            return Some(Self::eat(biots, (i, j), config, ledger));
+        } else if encounter.second_preys && exposed(&biots[i]) {
+            return Some(Self::eat(biots, (j, i), config, ledger));
+        }
+        Self::push(biots, (i, j), world, config.collisions.stiffness);
+        None
+    }
+
+    /// Pushes the biots `i` and `j` apart by the fraction `stiffness` of their overlap.
+    ///
+    /// # Note
+    ///
+    /// Each biot gives way in proportion to the weight of the other one.
+    fn push(biots: &mut [Self], (i, j): (usize, usize), world: &World, stiffness: f32) {
+        let (weight_i, weight_j) = (biots[i].weight(), biots[j].weight());
+        // The positions may have changed since the pair was found.
+        let delta = world.delta(biots[i].stats.pos, biots[j].stats.pos);
+        let overlap = interaction_radius(&biots[i], &biots[j]).0 - delta.length();
+        if stiffness <= 0.0 || overlap <= 0.0 || weight_i + weight_j <= 0.0 {
+            return;
+        }
+        // Biots right on top of each other are pushed apart along an arbitrary axis.
+        let dir = delta.try_normalize().unwrap_or(Vec2::X);
+        let shift = dir * overlap * stiffness.min(1.0);
+        let share = weight_j / (weight_i + weight_j);
+        biots[i].stats.pos = world.wrap(biots[i].stats.pos - shift * share);
+        biots[j].stats.pos = world.wrap(biots[j].stats.pos + shift * (1.0 - share));
+    }
+
+    /// Lets the biot `predator` bite the biot `prey`.
+    ///
+    /// # Note
+    ///
+    /// With a zero [`bite`](crate::config::PredationConfig::bite) the prey is
+    /// eaten whole, otherwise it loses life in proportion to how far the
+    /// attack of the predator exceeds its resistance and only dies once its
+    /// life is used up. The predator gains its share of exactly the life the
+    /// prey lost. Eating the prey, the venom of the prey costs the predator
+    /// life as far as its armor does not shield against it and may kill it.
+    fn eat(
+        biots: &mut [Self],
+        (predator, prey): (usize, usize),
+        config: &SimConfig,
+        ledger: &mut EnergyLedger,
+    ) -> Fight {
+        let attack = biots[predator].attack();
+        let resistance = biots[prey].resistance();
+        let mut fight = Fight {
+            predator: biots[predator].id,
+            prey: biots[prey].id,
+            attack,
+            resistance,
+            wound: Energy::ZERO,
+            killed: false,
+            gain: Energy::ZERO,
+            venom: Energy::ZERO,
+            poisoned: false,
+        };
+        let life = biots[prey].stats.life;
+        let wound = match config.predation.bite {
+            bite if bite > 0.0 => {
+                Energy((bite * (attack - resistance)).clamp(0.0, life.0.max(0.0)))
             }
+            _ => life,
+        };
+        let efficiency = biots[predator].trophic_efficiency(config);
+        let gain = wound * efficiency;
+        ledger.predation += wound * (1.0 - efficiency);
+        biots[prey].stats.life = life - wound;
+        fight.wound = wound;
+        fight.gain = gain;
+        if biots[prey].stats.life > Energy::ZERO {
+            biots[predator].stats.life += gain;
+            return fight;
+        }
+        biots[prey].stats.killed = Some(CauseOfDeath::Predation);
+        fight.killed = true;
+        let venom = (biots[prey].properties.venom - biots[predator].properties.armor).max(0.0);
+        let predator = &mut biots[predator];
+        let life = predator.stats.life + gain;
+        let damage = Energy((config.predation.venom * venom).min(life.0.max(0.0)));
+        ledger.predation += damage;
+        predator.stats.life = life - damage;
+        if damage > Energy::ZERO && predator.stats.life <= Energy::ZERO {
+            predator.stats.killed = Some(CauseOfDeath::Venom);
+            fight.poisoned = true;
         }
-        self.stats.age += 1;
-        offspring
+        fight.venom = damage;
+        fight
     }
 
-    /// Compute the interaction between two biots.
-    pub fn interact(biots: &mut [Self], i: usize, j: usize) {
-        let dist = (biots[i].stats.pos - biots[j].stats.pos).length();
-        if dist < 10.0 * (biots[i].properties.weight() + biots[j].properties.weight()) {
-            if biots[i].is_stronger(&biots[j]) {
-                biots[i].stats.life += biots[j].stats.life * 0.8;
-                biots[j].stats.life = 0.0;
-            } else if biots[j].is_stronger(&biots[i]) {
-                biots[j].stats.life += biots[i].stats.life * 0.8;
-                biots[i].stats.life = 0.0;
+    /// Returns the fraction of the life of its prey the biot gains when eating it.
+    pub fn trophic_efficiency(&self, config: &SimConfig) -> f32 {
+        let predation = &config.predation;
+        (predation.efficiency + predation.digestion * self.properties.digestion).clamp(0.0, 1.0)
+    }
+
+    /// Returns the cause of death if the biot is dead.
+    pub fn cause_of_death(&self) -> Option<CauseOfDeath> {
+        if self.stats.killed.is_some() {
+            self.stats.killed
+        } else if self.stats.life <= Energy::ZERO && !self.is_viable() {
+            Some(CauseOfDeath::Nonviable)
+        } else if self.stats.life <= Energy::ZERO {
+            Some(CauseOfDeath::Starvation)
+        } else if self.stats.age >= MAX_AGE {
+            Some(CauseOfDeath::OldAge)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the health state of the biot.
+    pub fn health(&self, config: &SimConfig) -> Health {
+        if self.cause_of_death().is_some() {
+            Health::Dead
+        } else if self.life_fraction(config) < DYING_LIFE_FRACTION
+            && self.properties.energy_balance(
+                config,
+                self.stats.light,
+                self.stats.tile,
+                self.metabolism_factor(config),
+            ) < Energy::ZERO
+        {
+            Health::Dying {
+                cause: CauseOfDeath::Starvation,
+            }
+        } else if self.age_fraction() >= DYING_AGE_FRACTION {
+            Health::Dying {
+                cause: CauseOfDeath::OldAge,
             }
+        } else {
+            Health::Alive
         }
     }
 
+    /// Returns the life of the biot relative to the life required for reproduction.
+    ///
+    /// # Note
+    ///
+    /// The returned value is clamped to `0.0..=1.0`.
+    pub fn life_fraction(&self, config: &SimConfig) -> f32 {
+        (self.stats.life / self.reproduction_threshold(config)).clamp(0.0, 1.0)
+    }
+
+    /// Returns the life the biot needs to reproduce: the life it keeps after
+    /// giving birth and the life of its whole litter.
+    pub fn reproduction_threshold(&self, config: &SimConfig) -> Energy {
+        let (size, investment) = self.litter(config);
+        let factor = self.adult_factor(config) - 1.0 + size as f32 * investment;
+        self.base_life(config) * factor
+    }
+
+    /// Returns the number of offsprings the biot bears at once and the
+    /// fraction of its base life each of them is born with.
+    ///
+    /// # Note
+    ///
+    /// Fecundity enlarges the litter, which shares the life of a single
+    /// offspring among its members, while nurture adds one base life per
+    /// unit to the share, see [`LitterConfig`](crate::config::LitterConfig).
+    /// Without litters a biot bears a single offspring with its full base life.
+    pub fn litter(&self, config: &SimConfig) -> (usize, f32) {
+        if !has_litters(config) {
+            return (1, 1.0);
+        }
+        let litters = &config.litters;
+        let extra = (self.properties.fecundity * litters.per_fecundity).max(0.0) as usize;
+        let size = (1 + extra).min(litters.max_size);
+        let share = (1.0 + self.properties.nurture) / size as f32;
+        (size, share.max(litters.min_investment))
+    }
+
+    /// Returns the multiple of its base life the biot needs to reproduce.
+    ///
+    /// # Note
+    ///
+    /// The reproduction gene lowers the configured factor towards one, so
+    /// the biot reproduces earlier but keeps less life after giving birth.
+    pub fn adult_factor(&self, config: &SimConfig) -> f32 {
+        1.0 + (config.adult_factor - 1.0) / (1.0 + self.properties.reproduction)
+    }
+
+    /// Returns the age of the biot relative to its maximum age.
+    pub fn age_fraction(&self) -> f32 {
+        self.stats.age / MAX_AGE
+    }
+
     /// Returns `true` if the biot is dead.
     pub fn is_dead(&self) -> bool {
-        self.stats.life <= 0.0 || self.stats.age >= 10000
+        self.cause_of_death().is_some()
     }
 
-    /// Returns `true` if the biot is dead.
+    /// Returns `true` if the biot is alive, including dying biots.
     pub fn is_alive(&self) -> bool {
         !self.is_dead()
     }
 
+    /// Replaces non-finite values in the state of the biot.
+    ///
+    /// Returns `true` if the state had to be sanitized.
+    ///
+    /// # Note
+    ///
+    /// Non-finite values would otherwise spread to other biots through
+    /// interactions and corrupt the spatial index. Biots with non-finite
+    /// positions are moved to a random position, biots with non-finite
+    /// life starve.
+    pub fn sanitize(&mut self, world: &World, rng: &mut Rng) -> bool {
+        let stats = &mut self.stats;
+        let mut sanitized = false;
+        if !stats.pos.is_finite() {
+            stats.position_randomly(world, rng);
+            sanitized = true;
+        }
+        if !stats.speed.is_finite() {
+            stats.speed = Vec2::ZERO;
+            sanitized = true;
+        }
+        if stats.feed_dir.is_some_and(|dir| !dir.is_finite()) {
+            stats.feed_dir = None;
+            sanitized = true;
+        }
+        if stats.flee_dir.is_some_and(|dir| !dir.is_finite()) {
+            stats.flee_dir = None;
+            sanitized = true;
+        }
+        if stats.mate_dir.is_some_and(|dir| !dir.is_finite()) {
+            stats.mate_dir = None;
+            sanitized = true;
+        }
+        if stats.flock_dir.is_some_and(|dir| !dir.is_finite()) {
+            stats.flock_dir = None;
+            sanitized = true;
+        }
+        if !stats.life.0.is_finite() {
+            stats.life = Energy::ZERO;
+            sanitized = true;
+        }
+        sanitized
+    }
+
+    /// Returns `true` if the genome of the biot gives it any weight.
+    pub fn is_viable(&self) -> bool {
+        self.properties.weight() > 0.0
+    }
+
     /// Returns `true` if `self` is stronger than `other`.
     pub fn is_stronger(&self, other: &Self) -> bool {
-        self.properties.attack > other.properties.attack + other.properties.defense * 0.8
+        self.attack() > other.resistance()
+    }
+
+    /// Returns `true` if `self` hunts and eats `other`: it is stronger and
+    /// does not recognise `other` as a relative, see [`Biot::is_kin`].
+    pub fn preys_on(&self, other: &Self, config: &SimConfig) -> bool {
+        self.is_stronger(other) && !self.is_kin(other, config)
+    }
+
+    /// Returns `true` if `self` recognises `other` as a relative it spares, see
+    /// [`KinshipConfig`](crate::config::KinshipConfig).
+    pub fn is_kin(&self, other: &Self, config: &SimConfig) -> bool {
+        if !config.kinship.enabled || self.properties.kinship <= 0.0 {
+            return false;
+        }
+        let range = self.properties.kinship / GENE_WEIGHT * config.kinship.distance;
+        self.genome.distance(&other.genome) <= range
+    }
+
+    /// Returns the attack of the biot, scaled by its vigor.
+    pub fn attack(&self) -> f32 {
+        self.properties.attack * self.stats.vigor
+    }
+
+    /// Returns the attack another biot needs to eat the biot.
+    ///
+    /// Attack, defense and armor all protect the biot, though not fully.
+    pub fn resistance(&self) -> f32 {
+        let properties = &self.properties;
+        self.attack() + properties.defense * 0.8 + properties.armor * 0.5
+    }
+
+    /// Returns the weight of the biot at its current size, see
+    /// [`GrowthConfig`](crate::config::GrowthConfig).
+    pub fn weight(&self) -> f32 {
+        self.properties.weight() * self.stats.size
+    }
+
+    /// Returns the radius of the body of the biot at its current size.
+    pub fn radius(&self) -> Distance {
+        Distance(RADIUS_PER_WEIGHT * self.weight())
+    }
+
+    /// Returns `true` if the biot is classified as a predator.
+    ///
+    /// # Note
+    ///
+    /// A biot is a predator if it invests more into attack than into
+    /// photosynthesis, otherwise it is considered prey.
+    pub fn is_predator(&self) -> bool {
+        self.properties.attack > self.properties.photosynthesis
     }
 
     /// Move the biot in a random direction.
-    fn random_move(&mut self, speed: f32) {
+    fn random_move(&mut self, rng: &mut Rng, speed: f32) {
         self.accelerate(
-            vec2(
-                rand::gen_range(0.0, 1.0) - 0.5,
-                rand::gen_range(0.0, 1.0) - 0.5,
-            )
-            .normalize(),
+            vec2(rng.gen_range(0.0, 1.0) - 0.5, rng.gen_range(0.0, 1.0) - 0.5).normalize_or_zero(),
             speed,
         );
     }
@@ -269,9 +1882,47 @@ impl Biot {
     }
 
     /// Randomly mutates a single gene in the genome of the biot.
-    fn mutate(&mut self) {
-        self.genome.mutate();
-        self.properties.adjust_to_genome(&self.genome);
+    pub fn mutate(&mut self, config: &SimConfig, rng: &mut Rng) {
+        let mut genome = self.genome.clone();
+        genome.mutate(&Gene::pool(config), &config.mutations, rng);
+        self.set_genome(genome, config);
+    }
+
+    /// Returns the chance for each additional mutation of the offspring of the biot.
+    pub fn mutation_chance(&self, config: &SimConfig) -> f32 {
+        if config.mutations.rate_drift > 0.0 {
+            self.genome
+                .mutation_chance
+                .unwrap_or(config.mutation_chance)
+        } else {
+            config.mutation_chance
+        }
+    }
+
+    /// Mutates the genome of a newborn biot.
+    ///
+    /// # Note
+    ///
+    /// If the mutation chance evolves, it drifts before it applies, so that
+    /// the offspring already mutates at its own chance. Brains mutate at
+    /// their own chance, see [`BrainConfig`](crate::config::BrainConfig).
+    fn mutate_offspring(&mut self, config: &SimConfig, rng: &mut Rng) {
+        let drift = config.mutations.rate_drift;
+        let mut chance = self.mutation_chance(config);
+        if drift > 0.0 {
+            chance = (chance + rng.gen_range(-drift, drift)).clamp(0.0, MAX_MUTATION_CHANCE);
+            let mut genome = self.genome.clone();
+            genome.mutation_chance = Some(chance);
+            self.set_genome(genome, config);
+        }
+        while rng.gen_range(0., 1.) < chance {
+            self.mutate(config, rng);
+        }
+        if config.brains.enabled {
+            // Offsprings of biots created without a brain start with a random one.
+            let brain = self.genome.brain.get_or_insert_with(|| Brain::random(rng));
+            brain.mutate(&config.brains, rng);
+        }
     }
 
     /// Original life points of a biot.
@@ -279,32 +1930,65 @@ impl Biot {
     /// # Note
     ///
     /// This is also used to determine when the biot will spawn.
-    fn base_life(&self) -> f32 {
-        8.0 * self.properties.weight()
+    pub fn base_life(&self, config: &SimConfig) -> Energy {
+        let weight = match config.nonviable {
+            NonviablePolicy::DieAtBirth => self.properties.weight(),
+            NonviablePolicy::BasalWeight(basal) => self.properties.weight().max(basal),
+        };
+        Energy(LIFE_PER_WEIGHT * weight)
     }
 }
 
-/// Helper structure used for the rstar geometric data structure. This data structure is used for
-/// computing interaction between biots fluidly even with thousands of them
-pub struct TreePoint {
-    pub x: f32,
-    pub y: f32,
-    pub idx: usize,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CollisionConfig;
+    use crate::scenario::Scenario;
 
-impl RTreeObject for TreePoint {
-    type Envelope = AABB<[f32; 2]>;
-    fn envelope(&self) -> Self::Envelope {
-        AABB::from_point([self.x, self.y])
+    #[test]
+    fn biots_interact_only_while_overlapping() {
+        let mut scenario = Scenario::new();
+        scenario.place(Biot::predator, 50.0, 50.0);
+        scenario.place(Biot::plant, 100.0, 50.0);
+        let simulation = scenario.simulation();
+        let config = simulation.biots().config();
+        let biots = simulation.biots().iter().cloned().collect::<Vec<_>>();
+        let radius = interaction_radius(&biots[0], &biots[1]);
+        let meets = |squared_distance| Biot::encounter(&biots, (0, 1), squared_distance, config);
+        let overlapping = meets(SquaredDistance((radius.0 - 1e-3).powi(2)));
+        assert!(
+            overlapping.is_some_and(|encounter| encounter.first_preys),
+            "overlapping biots met as {overlapping:?}"
+        );
+        let touching = meets(radius.squared());
+        assert!(
+            touching.is_none(),
+            "biots exactly touching met as {touching:?}"
+        );
+        let apart = meets(SquaredDistance((radius.0 + 1e-3).powi(2)));
+        assert!(apart.is_none(), "biots just apart met as {apart:?}");
     }
-}
 
-impl PointDistance for TreePoint {
-    fn distance_2(
-        &self,
-        point: &<<Self as rstar::RTreeObject>::Envelope as rstar::Envelope>::Point,
-    ) -> <<<Self as rstar::RTreeObject>::Envelope as rstar::Envelope>::Point as rstar::Point>::Scalar
-    {
-        (self.x - point[0]) * (self.x - point[0]) + (self.y - point[1]) * (self.y - point[1])
+    #[test]
+    fn collisions_push_only_overlapping_biots() {
+        for (offset, pushed) in [(-0.01, true), (0.01, false)] {
+            let config = SimConfig {
+                collisions: CollisionConfig { stiffness: 1.0 },
+                ..SimConfig::default()
+            };
+            let mut scenario = Scenario::with_config(config);
+            let first = scenario.place(Biot::plant, 100.0, 100.0);
+            let radius = 2.0 * scenario.biot(first).expect("a plant is missing").radius().0;
+            let second = scenario.place(Biot::plant, 100.0 + radius + offset, 100.0);
+            scenario.run(1);
+            let x = |id| scenario.biot(id).expect("a plant died").stats.pos.x;
+            let gap = x(second) - x(first);
+            assert_eq!(
+                gap > radius + offset + 1e-4,
+                pushed,
+                "plants {:.2} apart with radii of {radius:.2} ended up {gap:.2} apart",
+                radius + offset
+            );
+        }
     }
 }
@@ -1,142 +1,1648 @@
-use crate::biot::{Biot, TreePoint};
-use macroquad::prelude::*;
-use rstar::RTree;
+use crate::behavior::{Behavior, GenePlugin, Situation, BUILT_IN_PLUGINS};
+#[cfg(feature = "render")]
+use crate::biot::Gene;
+use crate::biot::{
+    interaction_radius, Biot, BiotId, CauseOfDeath, Drive, Encounter, Fight, GeneDistribution,
+    Genome, GenomeBuilder, Health, Surroundings, Target, MAX_INTERACTION_RADIUS,
+};
+use crate::brain::Senses;
+#[cfg(feature = "render")]
+use crate::camera::Camera;
+use crate::config::{NonviablePolicy, Reproduction, SimConfig};
+use crate::corpse::CorpseCollection;
+use crate::current::{self, Currents};
+use crate::dominance::Strategy;
+use crate::environment::Environment;
+use crate::field::Field;
+#[cfg(feature = "render")]
+use crate::filter::{Filter, DIMMED_ALPHA};
+use crate::food::FoodCollection;
+use crate::grid::SpatialGrid;
+#[cfg(feature = "render")]
+use crate::interpolation::Interpolation;
+use crate::ledger::EnergyLedger;
+use crate::light::LightMap;
+use crate::phase::{Phase, Phases};
+use crate::phylogeny::Phylogeny;
+use crate::rng::{Rng, RngStreams};
+use crate::rules::{Intervention, Rules};
+#[cfg(feature = "render")]
+use crate::selection::gene_color;
+use crate::spawn;
+#[cfg(feature = "render")]
+use crate::species::{species_color, Coloring};
+use crate::species::{SpeciesRegistry, SPECIES_INTERVAL};
+use crate::terrain::Terrain;
+use crate::units::{Distance, Energy, SquaredDistance, Ticks};
+use crate::world::World;
+use core::{iter, mem, slice};
+use glam::Vec2;
+#[cfg(feature = "render")]
+use macroquad::prelude::{
+    draw_circle, draw_circle_lines, draw_rectangle, Color, BLUE, DARKBLUE, GREEN, ORANGE, RED,
+    YELLOW,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Number of simulation steps between two updates of the clustering index.
+const CLUSTERING_INTERVAL: u32 = 100;
+
+/// Relative tolerance for rounding errors when auditing the energy books.
+const ENERGY_TOLERANCE: f32 = 1e-3;
+
+/// Number of rows of grid cells per tile whose encounters are found at once.
+const TILE_ROWS: usize = 4;
+
+/// What a call to [`BiotCollection::modify`] changed about a biot.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub position: bool,
+    pub speed: bool,
+    pub life: bool,
+    pub genome: bool,
+}
+
+impl Changes {
+    /// Compares the state of a biot before and after a modification.
+    fn between(before: &Biot, after: &Biot) -> Self {
+        Self {
+            position: before.stats.pos != after.stats.pos,
+            speed: before.stats.speed != after.stats.speed,
+            life: before.stats.life != after.stats.life,
+            genome: before.genome() != after.genome(),
+        }
+    }
+
+    /// Returns `true` if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A modification of a biot made through [`BiotCollection::modify`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Modification {
+    pub id: BiotId,
+    pub changes: Changes,
+}
+
+/// A biot that died during a step.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Obituary {
+    pub id: BiotId,
+    pub cause: CauseOfDeath,
+    pub age: Ticks,
+}
 
 /// A collection of biots. Responsible for handling interactions between biots
+#[derive(Serialize, Deserialize)]
 pub struct BiotCollection {
     biots: Vec<Biot>,
+    #[serde(skip)]
     offsprings: Vec<Biot>,
+    /// Genomes of the biots that reproduced during the last step.
+    #[serde(skip)]
+    parents: Vec<Genome>,
+    /// Number of offsprings born during the last step.
+    #[serde(skip)]
+    births: u32,
+    /// Number of biots infected during the last step.
+    #[serde(skip)]
+    infections: u32,
+    /// Causes of the deaths that happened during the last step.
+    #[serde(skip)]
+    deaths: Vec<CauseOfDeath>,
+    /// Biots removed for dying during the last step.
+    ///
+    /// # Note
+    ///
+    /// Unlike [`BiotCollection::deaths`] this excludes the nonviable
+    /// offsprings, which die before being added.
+    #[serde(skip)]
+    obituaries: Vec<Obituary>,
+    /// Positions of the biots eaten during the last step.
+    #[serde(skip)]
+    kills: Vec<Vec2>,
+    /// Biots eating each other during the last step.
+    #[serde(skip)]
+    fights: Vec<Fight>,
+    /// Genomes of the biots whose non-finite state was sanitized during the last step.
+    #[serde(skip)]
+    sanitized: Vec<Genome>,
+    /// Number of steps computed so far.
+    steps: u32,
+    /// Last computed Clark-Evans aggregation index of the population.
+    clustering_index: f32,
+    /// Number of steps between two prey searches of an intelligent biot.
+    sensing_interval: u32,
+    /// Parameters of the ecosystem.
+    config: SimConfig,
+    /// Identifier assigned to the next biot added to the collection.
+    next_id: u64,
+    /// Modifications made since they were last taken.
+    #[serde(skip)]
+    modifications: Vec<Modification>,
+    /// Set when a modification moved a biot since the last clustering update.
+    #[serde(skip)]
+    clustering_stale: bool,
+    /// Energy flows of the last step.
+    #[serde(skip)]
+    ledger: EnergyLedger,
+    /// Change of the total life during the last step not explained by the ledger.
+    #[serde(skip)]
+    unbooked: Energy,
+    /// Spatial index of the biots used for locating neighbours.
+    #[serde(skip)]
+    grid: SpatialGrid,
+    /// Remains of dead biots.
+    corpses: CorpseCollection,
+    /// Ancestry of the living biots.
+    phylogeny: Phylogeny,
+    /// Species the biots are grouped into.
+    species: SpeciesRegistry,
+    /// Scent the biots left behind.
+    scent: Field,
+    /// Custom steering rules, see [`BiotCollection::add_behavior`].
+    #[serde(skip)]
+    behaviors: Vec<Box<dyn Behavior>>,
+    /// Custom effects of genes, see [`BiotCollection::add_gene_plugin`].
+    #[serde(skip)]
+    plugins: Vec<Box<dyn GenePlugin>>,
+    /// Custom rules intervening in the steps, see [`BiotCollection::add_rules`].
+    #[serde(skip)]
+    rules: Vec<Box<dyn Rules>>,
+    /// Errors of the rules dropped during the last step.
+    #[serde(skip)]
+    rule_failures: Vec<String>,
 }
 
 impl BiotCollection {
-    /// Create `len` random biots.
-    pub fn new(len: usize) -> Self {
-        let biots = (0..len).map(|_| Biot::random_biot()).collect::<Vec<_>>();
+    /// Create the initial population described by `config` from the given `genomes`.
+    ///
+    /// # Note
+    ///
+    /// The genomes are assigned to the biots in turn, each biot founding a
+    /// lineage of its own. Without any genomes the biots are random.
+    pub fn new_from_genomes(
+        config: SimConfig,
+        world: &World,
+        rngs: &mut RngStreams,
+        genomes: &[Genome],
+    ) -> Self {
+        let distribution = GeneDistribution::initial(&config);
+        let biots = (0..config.initial_population)
+            .map(|idx| {
+                let rng = &mut rngs.placement;
+                let mut biot = match genomes.get(idx % genomes.len().max(1)) {
+                    Some(genome) => {
+                        let mut biot = Biot::from_genome(genome.clone(), world, rng);
+                        biot.set_genome(genome.clone(), &config);
+                        biot
+                    }
+                    None => Biot::initial_biot(world, &config, &distribution, rng),
+                };
+                if let Some(pos) = spawn::random_position(&config.spawn_regions, &biot, world, rng)
+                {
+                    biot.stats.pos = pos;
+                }
+                let disease = &config.disease;
+                biot.stats.infected = disease.is_enabled()
+                    && rngs.encounters.gen_range(0., 1.) < disease.initial_fraction;
+                biot.found_lineage(BiotId(idx as u64));
+                biot.stats.life = biot.base_life(&config);
+                biot
+            })
+            .collect::<Vec<_>>();
+        let scent = Field::new(world, config.scent.cell_size);
+        let next_id = biots.len() as u64;
         let offsprings = Vec::new();
-        Self { biots, offsprings }
+        let mut phylogeny = Phylogeny::new();
+        for biot in &biots {
+            phylogeny.found(biot.id(), 0);
+        }
+        Self {
+            biots,
+            offsprings,
+            parents: Vec::new(),
+            births: 0,
+            infections: 0,
+            deaths: Vec::new(),
+            obituaries: Vec::new(),
+            kills: Vec::new(),
+            fights: Vec::new(),
+            sanitized: Vec::new(),
+            steps: 0,
+            clustering_index: 1.0,
+            sensing_interval: 1,
+            config,
+            next_id,
+            modifications: Vec::new(),
+            clustering_stale: false,
+            ledger: EnergyLedger::default(),
+            unbooked: Energy::ZERO,
+            grid: SpatialGrid::default(),
+            corpses: CorpseCollection::new(),
+            phylogeny,
+            species: SpeciesRegistry::new(),
+            scent,
+            behaviors: Vec::new(),
+            plugins: Vec::new(),
+            rules: Vec::new(),
+            rule_failures: Vec::new(),
+        }
     }
 
-    /// Compute one step of the simulation.
-    pub fn step(&mut self) {
+    /// Compute one step of the simulation, with the biots eating from `food`.
+    ///
+    /// # Note
+    ///
+    /// Enters the phases from [`Phase::Indexing`] to [`Phase::Scent`] on `phases`.
+    pub fn step(
+        &mut self,
+        world: &World,
+        terrain: &Terrain,
+        environment: &Environment,
+        food: &mut FoodCollection,
+        rngs: &mut RngStreams,
+        phases: &mut Phases,
+    ) {
+        phases.enter(Phase::Indexing);
         // Clear offsprings in case there are still some from last step.
         self.offsprings.clear();
-        // R-star datastructure used for quickly locating neighbors.
-        let tree: RTree<TreePoint> = RTree::bulk_load(
-            self.biots
-                .iter()
-                .enumerate()
-                .map(|(idx, biot)| TreePoint {
-                    idx,
-                    x: biot.stats.pos.x,
-                    y: biot.stats.pos.y,
-                })
-                .collect(),
-        );
-        // Move and reproduce biots.
-        for idx in 0..(self.biots.len()) {
-            let mut feed_dir: Option<Vec2> = None;
-            let intelligence = self.biots[idx].properties.intelligence;
-            if intelligence > 0.0 {
-                let pos = [
-                    self.biots[idx].stats.pos.x,
-                    self.biots[idx].stats.pos.y,
-                ];
-                for (neighbour, squared_distance) in
-                    tree.nearest_neighbor_iter_with_distance_2(&pos)
-                {
-                    if idx == neighbour.idx {
-                        // Do not move towards itself or produce with itself.
-                        continue;
-                    }
-                    let max_detection_distance = (intelligence * intelligence) * 1600.0;
-                    if squared_distance as f32 > max_detection_distance {
-                        // Victim is out of reach.
-                        //
-                        // Further iterated elements are farther away so we can break here.
-                        break;
+        self.parents.clear();
+        self.births = 0;
+        self.infections = 0;
+        self.deaths.clear();
+        self.obituaries.clear();
+        self.kills.clear();
+        self.fights.clear();
+        self.sanitized.clear();
+        self.rule_failures.clear();
+        let life_before = self.total_life();
+        // The grid is kept up to date by the step itself, it only needs a full
+        // rebuild after loading a snapshot or spawning biots.
+        if !self.grid.is_synced(world, self.biots.len()) {
+            self.grid = SpatialGrid::new(world, MAX_INTERACTION_RADIUS);
+            self.grid
+                .rebuild(self.biots.iter().map(|biot| biot.stats.pos));
+        }
+        if self.steps.is_multiple_of(CLUSTERING_INTERVAL) || self.clustering_stale {
+            self.clustering_index = Self::compute_clustering_index(world, &self.grid);
+            self.clustering_stale = false;
+        }
+        if self.steps.is_multiple_of(SPECIES_INTERVAL) {
+            self.species.cluster(&mut self.biots);
+        }
+        self.steps += 1;
+        self.corpses.step(world, &self.config.corpses);
+        phases.enter(Phase::Sensing);
+        // Sense prey, food, threats and partners. This only reads the biots,
+        // so it runs in parallel.
+        #[cfg(feature = "parallel")]
+        let indices = (0..self.biots.len()).into_par_iter();
+        #[cfg(not(feature = "parallel"))]
+        let indices = 0..self.biots.len();
+        let directions = indices
+            .map(|idx| {
+                let biot = &self.biots[idx];
+                // Staggered so that only a fraction of the biots sense per step.
+                let senses = (self.steps + idx as u32).is_multiple_of(self.sensing_interval);
+                let flock_dir = if biot.properties.sociality > 0.0 && senses {
+                    self.sense_flock(world, idx)
+                } else {
+                    biot.stats.flock_dir
+                };
+                let (feed_dir, flee_dir, mate_dir, target) =
+                    if biot.properties.intelligence > 0.0 && senses {
+                        match (
+                            self.behave(world, food, environment, idx),
+                            biot.genome().brain(),
+                        ) {
+                            (Some(dir), _) => (Some(dir), None, None, None),
+                            // Brains sense threats themselves.
+                            (None, Some(brain)) if self.config.brains.enabled => {
+                                let senses = self.sense(world, food, idx);
+                                let dir = brain.think(&senses, biot.properties.neurons());
+                                // A brain deciding to stay put lets the biot wander.
+                                (Some(dir).filter(|dir| *dir != Vec2::ZERO), None, None, None)
+                            }
+                            _ => {
+                                let (feed_dir, target) = self.sense_food(world, food, idx);
+                                (
+                                    feed_dir.or_else(|| self.follow_scent(idx)),
+                                    self.sense_threat(world, idx),
+                                    self.sense_mate(world, idx),
+                                    target,
+                                )
+                            }
+                        }
+                    } else {
+                        let stats = &biot.stats;
+                        (stats.feed_dir, stats.flee_dir, stats.mate_dir, stats.target)
+                    };
+                (feed_dir, flee_dir, mate_dir, flock_dir, target)
+            })
+            .collect::<Vec<_>>();
+        phases.enter(Phase::Movement);
+        // Move and reproduce biots. Each biot only modifies itself, so this
+        // runs in parallel too. Every biot draws from its own random number
+        // stream, which keeps the outcome independent of the scheduling.
+        let light = LightMap::new(world, &self.config.light, self.biots.iter());
+        let daylight = environment.daylight(&self.config.environment);
+        let seeds = rngs.step_seeds();
+        // Biots step with the metabolism costs of the current season.
+        let config = &environment.adjust(&self.config);
+        let plugins = BUILT_IN_PLUGINS
+            .into_iter()
+            .chain(self.plugins.iter().map(Box::as_ref))
+            .collect::<Vec<_>>();
+        let surroundings = Surroundings {
+            world,
+            terrain,
+            grid: &self.grid,
+            environment,
+            plugins: &plugins,
+        };
+        #[cfg(feature = "parallel")]
+        let biots = self.biots.par_iter_mut();
+        #[cfg(not(feature = "parallel"))]
+        let biots = self.biots.iter_mut();
+        let offsprings = biots
+            .zip(directions)
+            .map(
+                |(biot, (feed_dir, flee_dir, mate_dir, flock_dir, target))| {
+                    let mut rngs = seeds.item(biot.id().0);
+                    let mut ledger = EnergyLedger::default();
+                    biot.stats.feed_dir = feed_dir;
+                    biot.stats.flee_dir = flee_dir;
+                    biot.stats.target = target;
+                    biot.stats.mate_dir = mate_dir;
+                    biot.stats.flock_dir = flock_dir;
+                    biot.stats.light = light.light(biot) * daylight;
+                    if config.temperature.enabled {
+                        biot.stats.temperature =
+                            environment.temperature(config, world, biot.stats.pos);
                     }
-                    if self.biots[idx].is_stronger(&self.biots[neighbour.idx]) {
-                        feed_dir = Some(
-                            vec2(
-                                neighbour.x as f32 - self.biots[idx].stats.pos.x,
-                                neighbour.y as f32 - self.biots[idx].stats.pos.y,
-                            )
-                            .normalize(),
-                        );
-                        break;
+                    let mut litter = biot.step(&surroundings, config, &mut rngs, &mut ledger);
+                    let mut sanitized = Vec::new();
+                    for biot in iter::once(biot).chain(&mut litter) {
+                        if biot.sanitize(world, &mut rngs.placement) {
+                            sanitized.push(biot.genome().clone());
+                        }
                     }
-                }
+                    (litter, ledger, sanitized)
+                },
+            )
+            .collect::<Vec<_>>();
+        let mut ledger = EnergyLedger::default();
+        for (idx, (litter, biot_ledger, sanitized)) in offsprings.into_iter().enumerate() {
+            ledger = ledger + biot_ledger;
+            self.sanitized.extend(sanitized);
+            for offspring in litter {
+                self.bear(offspring, &[idx], config, &mut ledger);
+            }
+        }
+        for (idx, biot) in self.biots.iter().enumerate() {
+            self.grid.update(idx, biot.stats.pos);
+        }
+        // Streams of an item no biot identifier will reach in practice.
+        let mut shared = seeds.item(u64::MAX);
+        phases.enter(Phase::Mating);
+        if let Reproduction::Sexual(range) = config.reproduction {
+            self.mate(world, config, Distance(range), &mut shared, &mut ledger);
+        }
+        phases.enter(Phase::Interactions);
+        // Find the encounters of the biots, which only reads them, and apply
+        // them one after the other, which changes them.
+        for mut encounter in self.encounters() {
+            // Prey spared by the rules collides with its predator instead.
+            if !self.rules.is_empty() && !self.allows_bite(&encounter) {
+                encounter.first_preys = false;
+                encounter.second_preys = false;
             }
-            let off = self.biots[idx].step(&tree, feed_dir);
-            if let Some(offspring) = off {
-                self.offsprings.push(offspring);
+            let fight = Biot::interact(
+                &mut self.biots,
+                &encounter,
+                world,
+                &self.config,
+                &mut ledger,
+            );
+            self.fights.extend(fight);
+            let disease = &self.config.disease;
+            if disease.is_enabled() {
+                let infected = Biot::contagion(
+                    &mut self.biots,
+                    encounter.pair,
+                    encounter.squared_distance,
+                    disease,
+                    &mut shared.encounters,
+                );
+                self.infections += u32::from(infected);
             }
         }
-        // Compute biot interactions.
-        for f in &tree {
-            for s in tree.locate_within_distance([f.x, f.y], 50.0)
-            //FIXME 30 is hardcoded
-            {
-                if f.idx < s.idx {
-                    // Don't do it twice
-                    Biot::interact(&mut self.biots, f.idx, s.idx);
-                }
+        if self.config.collisions.stiffness > 0.0 {
+            // Biots pushed each other around.
+            for (idx, biot) in self.biots.iter().enumerate() {
+                self.grid.update(idx, biot.stats.pos);
+            }
+        }
+        phases.enter(Phase::Feeding);
+        // Living biots eat the food pellets they touch.
+        for biot in self.biots.iter_mut().filter(|biot| biot.is_alive()) {
+            let energy = food.eat(biot.stats.pos, biot.radius());
+            biot.stats.life += energy;
+            ledger.food += energy;
+        }
+        food.remove_eaten();
+        // Living attackers scavenge the corpses they touch.
+        for biot in self.biots.iter_mut().filter(|biot| biot.is_alive()) {
+            if biot.properties.attack > 0.0 {
+                let energy = self.corpses.scavenge(biot.stats.pos, biot.radius());
+                biot.stats.life += energy;
+                ledger.scavenging += energy;
+            }
+        }
+        self.corpses.remove_empty();
+        phases.enter(Phase::Lifecycle);
+        // Offsprings are clones of their (first) parent and still carry its
+        // identifier. They are registered first, so that parents dying in
+        // the same step are kept as their ancestors.
+        for offspring in &mut self.offsprings {
+            let parent = offspring.id();
+            offspring.set_id(BiotId(self.next_id));
+            self.next_id += 1;
+            self.phylogeny.birth(offspring.id(), parent, self.steps);
+        }
+        // Record why biots died, remove them leaving their corpses and append
+        // the offsprings to the collection.
+        for biot in self.biots.iter().filter(|biot| biot.is_dead()) {
+            self.phylogeny.death(biot.id(), self.steps);
+            ledger.removed += biot.stats.life;
+            let energy = biot.stats.life * self.config.corpses.fraction;
+            self.corpses.leave(world, biot.stats.pos, energy);
+            if biot.cause_of_death() == Some(CauseOfDeath::Predation) {
+                self.kills.push(biot.stats.pos);
             }
         }
-        // Remove dead biots and append the offsprings to the collection.
+        self.deaths
+            .extend(self.biots.iter().filter_map(Biot::cause_of_death));
+        self.obituaries.extend(self.biots.iter().filter_map(|biot| {
+            let (id, age) = (biot.id(), biot.stats.age);
+            biot.cause_of_death()
+                .map(|cause| Obituary { id, cause, age })
+        }));
+        let biots = &self.biots;
+        self.grid.retain(|idx| biots[idx].is_alive());
         self.biots.retain(Biot::is_alive);
+        for offspring in &self.offsprings {
+            self.grid.push(offspring.stats.pos);
+        }
         self.biots.append(&mut self.offsprings);
+        phases.enter(Phase::Scent);
+        // Living biots leave their scent, which then spreads and fades.
+        let scent = &self.config.scent;
+        if scent.deposit > 0.0 {
+            for biot in &self.biots {
+                let amount = scent.deposit * biot.properties.weight();
+                self.scent.deposit(biot.stats.pos, amount);
+            }
+            self.scent.step(scent.diffusion, scent.decay);
+        }
+        phases.finish();
+        self.unbooked = self.total_life() - (life_before + ledger.balance());
+        self.ledger = ledger;
+        if self.config.audit_energy {
+            if let Err(message) = self.check_energy_books() {
+                panic!("energy audit failed in step {}: {message}", self.steps);
+            }
+        }
+        if !self.rules.is_empty() {
+            self.apply_rules(world, &mut rngs.placement);
+        }
     }
 
-    /// Display the biot collection
-    pub fn draw(&self) {
-        for biot in self.biots.iter() {
-            if biot.properties.intelligence > 0. {
-                let size = 14.
-                    * (biot.properties.photosynthesis
-                        + biot.properties.attack
-                        + biot.properties.defense
-                        + biot.properties.motion);
-                draw_rectangle(
-                    biot.stats.pos.x - size / 2.,
-                    biot.stats.pos.y - size / 2.,
-                    size,
-                    size,
-                    GREEN,
-                );
+    /// Asks the rules whether the bite of `encounter`, if any, may happen.
+    fn allows_bite(&mut self, encounter: &Encounter) -> bool {
+        let (i, j) = encounter.pair;
+        let (predator, prey) = match (encounter.first_preys, encounter.second_preys) {
+            (true, _) => (&self.biots[i], &self.biots[j]),
+            (false, true) => (&self.biots[j], &self.biots[i]),
+            (false, false) => return true,
+        };
+        if predator.is_dead() || prey.is_dead() {
+            return true;
+        }
+        let mut allowed = true;
+        let failures = &mut self.rule_failures;
+        self.rules
+            .retain_mut(|rules| match rules.allows_bite(predator, prey) {
+                Ok(allows) => {
+                    allowed &= allows;
+                    true
+                }
+                Err(error) => {
+                    failures.push(error);
+                    false
+                }
+            });
+        allowed
+    }
+
+    /// Lets the rules react to the completed step and applies their interventions.
+    ///
+    /// # Note
+    ///
+    /// Like the tools of the viewer, the interventions are not booked in the
+    /// energy flows of the step.
+    fn apply_rules(&mut self, world: &World, rng: &mut Rng) {
+        let mut rules = mem::take(&mut self.rules);
+        let mut interventions = Vec::new();
+        let mut failures = Vec::new();
+        rules.retain_mut(|rules| match rules.react(self) {
+            Ok(requested) => {
+                interventions.extend(requested);
+                true
             }
-            draw_circle(
-                biot.stats.pos.x,
-                biot.stats.pos.y,
-                7. * (biot.properties.photosynthesis
-                    + biot.properties.attack
-                    + biot.properties.defense
-                    + biot.properties.motion),
-                GREEN,
-            );
-            draw_circle(
-                biot.stats.pos.x,
-                biot.stats.pos.y,
-                7. * (biot.properties.attack + biot.properties.defense + biot.properties.motion),
-                RED,
-            );
-            draw_circle(
-                biot.stats.pos.x,
-                biot.stats.pos.y,
-                7. * (biot.properties.defense + biot.properties.motion),
-                DARKBLUE,
-            );
-            draw_circle(
-                biot.stats.pos.x,
-                biot.stats.pos.y,
-                7. * (biot.properties.motion),
-                BLUE,
-            );
+            Err(error) => {
+                failures.push(error);
+                false
+            }
+        });
+        self.rules = rules;
+        self.rule_failures.append(&mut failures);
+        for intervention in interventions {
+            match intervention {
+                Intervention::Place { archetype, pos } => {
+                    let mut biot = archetype.create(world, &self.config, rng);
+                    biot.stats.pos = world.wrap(pos);
+                    biot.stats.life = biot.base_life(&self.config);
+                    self.spawn(biot);
+                }
+                Intervention::Feed { id, life } => {
+                    let config = &self.config;
+                    let base_life = self.get(id).map(|biot| biot.base_life(config));
+                    if let Some(base_life) = base_life {
+                        // Like the feed brush, rules can neither drain nor overfeed biots.
+                        let life = Energy(life.0.clamp(0.0, base_life.0));
+                        self.modify(id, |biot| biot.stats.life += life);
+                    }
+                }
+                Intervention::Kill { id } => {
+                    self.kill(world, id);
+                }
+            }
+        }
+    }
+
+    /// Returns the energy flows of the last step.
+    pub fn energy_ledger(&self) -> &EnergyLedger {
+        &self.ledger
+    }
+
+    /// Returns the change of the total life during the last step that the
+    /// energy flows do not explain, see [`BiotCollection::check_energy_books`].
+    pub fn unbooked_energy(&self) -> Energy {
+        self.unbooked
+    }
+
+    /// Returns the total life of all biots.
+    pub fn total_life(&self) -> Energy {
+        self.biots
+            .iter()
+            .fold(Energy::ZERO, |total, biot| total + biot.stats.life)
+    }
+
+    /// Checks that the energy flows of the last step explain the change of the total life.
+    ///
+    /// # Note
+    ///
+    /// Rounding errors of the summed up lives are tolerated relative to the
+    /// magnitude of the total life.
+    pub fn check_energy_books(&self) -> Result<(), String> {
+        let tolerance = ENERGY_TOLERANCE * (self.total_life().0.abs() + 1.0);
+        if self.unbooked.0.abs() > tolerance {
+            return Err(format!(
+                "{:.3} of unbooked energy, ledger: {:?}",
+                self.unbooked.0, self.ledger
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the encounters of all pairs of touching biots, ordered by the
+    /// first biot of each pair.
+    ///
+    /// # Note
+    ///
+    /// The world is split into tiles of [`TILE_ROWS`] rows of grid cells,
+    /// whose encounters are found independently, in parallel if enabled.
+    /// Each pair is found by the tile of its first biot only, even if the
+    /// second one lies across the border of the tile. The grid only yields
+    /// candidates touching the largest biot, [`Biot::encounter`] checks the
+    /// actual radius of each pair.
+    fn encounters(&self) -> Vec<Encounter> {
+        let largest = self
+            .biots
+            .iter()
+            .map(|biot| biot.radius().0)
+            .fold(0.0, f32::max);
+        let cap = self
+            .config
+            .max_interaction_radius
+            .min(MAX_INTERACTION_RADIUS.0);
+        let find = |tile: &[&[usize]]| {
+            tile.iter()
+                .flat_map(|cell| cell.iter().copied())
+                .flat_map(|first| {
+                    let pos = self.grid.position(first);
+                    let radius = Distance((self.biots[first].radius().0 + largest).min(cap));
+                    self.grid
+                        .within(pos, radius)
+                        // Every pair is found by both biots, it only meets once.
+                        .filter(move |(second, _)| first < *second)
+                        .filter_map(move |(second, squared_distance)| {
+                            let pair = (first, second);
+                            Biot::encounter(&self.biots, pair, squared_distance, &self.config)
+                        })
+                })
+                .collect::<Vec<_>>()
+        };
+        let cells = self.grid.cells().collect::<Vec<_>>();
+        let tile_size = self.grid.columns() * TILE_ROWS;
+        #[cfg(feature = "parallel")]
+        let tiles = cells.par_chunks(tile_size);
+        #[cfg(not(feature = "parallel"))]
+        let tiles = cells.chunks(tile_size);
+        let mut encounters = tiles.map(find).collect::<Vec<_>>().concat();
+        // Older biots meet others first, regardless of the tiles. The sort is
+        // stable, so the encounters of each biot keep the order of the grid.
+        encounters.sort_by_key(|encounter| encounter.pair.0);
+        encounters
+    }
+
+    /// Returns the direction towards the closest biot, food pellet or corpse
+    /// the biot at `idx` can eat, and the prey it pursues, if any.
+    ///
+    /// # Note
+    ///
+    /// A hunter pursuing a prey heads towards it, or where it saw it last,
+    /// regardless of closer food, see
+    /// [`PursuitConfig`](crate::config::PursuitConfig).
+    fn sense_food(
+        &self,
+        world: &World,
+        food: &FoodCollection,
+        idx: usize,
+    ) -> (Option<Vec2>, Option<Target>) {
+        let biot = &self.biots[idx];
+        if let Some(target) = self.pursue(world, idx) {
+            let dir = world
+                .delta(biot.stats.pos, target.last_seen)
+                .try_normalize();
+            return (dir, Some(target));
+        }
+        // Hunters leave the prey they gave up on alone until they find another one.
+        let abandoned = biot.stats.target.filter(|target| target.patience == 0);
+        let prey = self.nearest_visible_index(idx, |other| {
+            biot.preys_on(other, &self.config)
+                && Some(other.id()) != abandoned.map(|target| target.id)
+        });
+        let prey_pos = prey.map(|prey| self.grid.position(prey));
+        let closest = [prey_pos, self.nearest_food(world, food, idx)]
+            .into_iter()
+            .flatten()
+            .min_by(|a, b| {
+                let to_a = world.delta(biot.stats.pos, *a).length_squared();
+                let to_b = world.delta(biot.stats.pos, *b).length_squared();
+                to_a.total_cmp(&to_b)
+            });
+        let Some(closest) = closest else {
+            return (None, abandoned);
+        };
+        // Hunters lock onto the prey they head towards.
+        let target = prey
+            .filter(|_| self.config.pursuit.is_enabled() && prey_pos == Some(closest))
+            .map(|prey| Target {
+                id: self.biots[prey].id(),
+                last_seen: closest,
+                patience: self.config.pursuit.patience,
+            })
+            .or(abandoned);
+        // There is no direction towards food at the very same position.
+        (world.delta(biot.stats.pos, closest).try_normalize(), target)
+    }
+
+    /// Returns the prey the biot at `idx` keeps pursuing, with the position
+    /// it saw it at last.
+    ///
+    /// # Note
+    ///
+    /// Hunters give up on prey that died or grew too strong for them, on prey
+    /// out of sight once they reach the position they saw it at last and on
+    /// any prey once they run out of patience.
+    fn pursue(&self, world: &World, idx: usize) -> Option<Target> {
+        let biot = &self.biots[idx];
+        let target = biot
+            .stats
+            .target
+            .filter(|target| target.patience > 0 && self.config.pursuit.is_enabled())?;
+        let prey = self
+            .biots
+            .binary_search_by_key(&target.id, Biot::id)
+            .ok()
+            .map(|prey| &self.biots[prey])
+            .filter(|prey| prey.is_alive() && biot.preys_on(prey, &self.config))?;
+        let distance = world.delta(biot.stats.pos, prey.stats.pos).length();
+        if distance < biot.properties.detection_distance().0 * prey.properties.visibility() {
+            let last_seen = prey.stats.pos;
+            return Some(Target {
+                last_seen,
+                ..target
+            });
+        }
+        let searched = world.delta(biot.stats.pos, target.last_seen).length() <= biot.radius().0;
+        (!searched).then_some(target)
+    }
+
+    /// Returns the direction in which the scent around the biot at `idx`
+    /// grows the most, unless the scent is disabled.
+    fn follow_scent(&self, idx: usize) -> Option<Vec2> {
+        if self.config.scent.deposit <= 0.0 {
+            return None;
+        }
+        self.scent
+            .gradient(self.biots[idx].stats.pos)
+            .try_normalize()
+    }
+
+    /// Returns the direction away from the closest biot that could eat the
+    /// biot at `idx`, scaled by its closeness, see [`Stats::flee_dir`].
+    ///
+    /// [`Stats::flee_dir`]: crate::biot::Stats::flee_dir
+    fn sense_threat(&self, world: &World, idx: usize) -> Option<Vec2> {
+        let biot = &self.biots[idx];
+        let threat = self.nearest_visible(idx, |other| other.preys_on(biot, &self.config))?;
+        let delta = world.delta(biot.stats.pos, threat);
+        let range = biot.properties.detection_distance().0;
+        let closeness = (1.0 - delta.length() / range).max(0.0);
+        // There is no direction away from a threat at the very same position.
+        (-delta).try_normalize().map(|away| away * closeness)
+    }
+
+    /// Returns the direction towards the closest partner of the biot at `idx`
+    /// looking for a mate, see [`Drive::Mating`].
+    ///
+    /// # Note
+    ///
+    /// Partners are biots looking for a mate as well that neither could eat
+    /// the other, and only sexually reproducing biots look for them.
+    fn sense_mate(&self, world: &World, idx: usize) -> Option<Vec2> {
+        let biot = &self.biots[idx];
+        let mating = |biot: &Biot| biot.drive(&self.config) == Drive::Mating;
+        if !matches!(self.config.reproduction, Reproduction::Sexual(_)) || !mating(biot) {
+            return None;
+        }
+        let partner = self.nearest_visible(idx, |other| {
+            mating(other) && !biot.is_stronger(other) && !other.is_stronger(biot)
+        })?;
+        world.delta(biot.stats.pos, partner).try_normalize()
+    }
+
+    /// Returns the direction the flocking forces of the other members of its
+    /// species within reach pull the biot at `idx` in, see [`FlockingConfig`].
+    ///
+    /// # Note
+    ///
+    /// Biots keep apart from members closer than the spacing, head the same
+    /// way as the others and towards their center. Biots not clustered yet
+    /// flock with each other.
+    ///
+    /// [`FlockingConfig`]: crate::config::FlockingConfig
+    fn sense_flock(&self, world: &World, idx: usize) -> Option<Vec2> {
+        let biot = &self.biots[idx];
+        let flocking = &self.config.flocking;
+        let (mut separation, mut heading, mut center) = (Vec2::ZERO, Vec2::ZERO, Vec2::ZERO);
+        let mut members = 0;
+        let neighbours = self
+            .grid
+            .within(biot.stats.pos, Distance(flocking.radius))
+            .filter(|(neighbour, _)| *neighbour != idx);
+        for (neighbour, _) in neighbours {
+            let other = &self.biots[neighbour];
+            if other.species() != biot.species() {
+                continue;
+            }
+            let delta = world.delta(biot.stats.pos, other.stats.pos);
+            let spacing = interaction_radius(biot, other).0 + flocking.spacing;
+            let distance = delta.length();
+            if distance < spacing {
+                // The closer the member, the stronger the biot keeps away from it.
+                separation -= delta.normalize_or_zero() * (1.0 - distance / spacing);
+            }
+            heading += other.stats.speed;
+            center += delta;
+            members += 1;
+        }
+        if members == 0 {
+            return None;
+        }
+        let members = members as f32;
+        let force = separation * flocking.separation
+            + (heading / members).normalize_or_zero() * flocking.alignment
+            + (center / members).normalize_or_zero() * flocking.cohesion;
+        force.try_normalize()
+    }
+
+    /// Returns the direction the first of the registered behaviors steers the
+    /// biot at `idx` towards, if any.
+    fn behave(
+        &self,
+        world: &World,
+        food: &FoodCollection,
+        environment: &Environment,
+        idx: usize,
+    ) -> Option<Vec2> {
+        if self.behaviors.is_empty() {
+            return None;
+        }
+        let situation = Situation {
+            biot: &self.biots[idx],
+            senses: self.sense(world, food, idx),
+            world,
+            environment,
+            config: &self.config,
+        };
+        self.behaviors
+            .iter()
+            .find_map(|behavior| behavior.steer(&situation))
+    }
+
+    /// Registers a `behavior` steering the intelligent biots, asked after
+    /// the ones registered before.
+    pub fn add_behavior(&mut self, behavior: Box<dyn Behavior>) {
+        self.behaviors.push(behavior);
+    }
+
+    /// Registers a `plugin` giving its gene an effect, asked after the
+    /// built-in ones and the ones registered before.
+    pub fn add_gene_plugin(&mut self, plugin: Box<dyn GenePlugin>) {
+        self.plugins.push(plugin);
+    }
+
+    /// Registers `rules` intervening in the steps, asked after the ones
+    /// registered before.
+    pub fn add_rules(&mut self, rules: Box<dyn Rules>) {
+        self.rules.push(rules);
+    }
+
+    /// Removes the registered rules, e.g. to move them to a loaded snapshot.
+    pub fn take_rules(&mut self) -> Vec<Box<dyn Rules>> {
+        mem::take(&mut self.rules)
+    }
+
+    /// Returns the errors of the rules dropped during the last step.
+    pub fn rule_failures(&self) -> &[String] {
+        &self.rule_failures
+    }
+
+    /// Returns the genomes of the biots whose non-finite state was sanitized
+    /// during the last step, see [`Biot::sanitize`].
+    ///
+    /// # Note
+    ///
+    /// Sanitizing keeps the simulation running, but points to a genome that
+    /// drives some property out of bounds.
+    pub fn sanitized(&self) -> &[Genome] {
+        &self.sanitized
+    }
+
+    /// Returns what the biot at `idx` perceives as input of its brain.
+    fn sense(&self, world: &World, food: &FoodCollection, idx: usize) -> Senses {
+        let biot = &self.biots[idx];
+        let range = biot.properties.detection_distance().0;
+        let towards = |target: Option<Vec2>| {
+            let delta = target.map_or(Vec2::ZERO, |target| world.delta(biot.stats.pos, target));
+            // Targets at the very same position have no direction.
+            let closeness = (1.0 - delta.length() / range).max(0.0);
+            delta.normalize_or_zero() * closeness
+        };
+        Senses {
+            prey: towards(self.nearest_visible(idx, |other| biot.preys_on(other, &self.config))),
+            predator: towards(
+                self.nearest_visible(idx, |other| other.preys_on(biot, &self.config)),
+            ),
+            food: towards(self.nearest_food(world, food, idx)),
+            energy: biot.life_fraction(&self.config),
+        }
+    }
+
+    /// Returns the position of the closest other biot matching `predicate`
+    /// the biot at `idx` detects.
+    fn nearest_visible(&self, idx: usize, predicate: impl Fn(&Biot) -> bool) -> Option<Vec2> {
+        self.nearest_visible_index(idx, predicate)
+            .map(|other| self.grid.position(other))
+    }
+
+    /// Returns the index of the closest other biot matching `predicate` the
+    /// biot at `idx` detects.
+    fn nearest_visible_index(
+        &self,
+        idx: usize,
+        predicate: impl Fn(&Biot) -> bool,
+    ) -> Option<usize> {
+        let biot = &self.biots[idx];
+        let max_detection_distance = biot.properties.detection_distance();
+        self.grid
+            .within(biot.stats.pos, max_detection_distance)
+            .filter(|(neighbour, _)| *neighbour != idx)
+            .filter(|(neighbour, distance)| {
+                let other = &self.biots[*neighbour];
+                let visible = max_detection_distance * other.properties.visibility();
+                *distance < visible.squared() && predicate(other)
+            })
+            .min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+            .map(|(other, _)| other)
+    }
+
+    /// Returns the position of the closest food pellet or, for biots that
+    /// can attack, corpse the biot at `idx` detects.
+    fn nearest_food(&self, world: &World, food: &FoodCollection, idx: usize) -> Option<Vec2> {
+        let biot = &self.biots[idx];
+        let max_detection_distance = biot.properties.detection_distance();
+        let pellet = food.nearest(biot.stats.pos, max_detection_distance);
+        let corpse = if biot.properties.attack > 0.0 {
+            self.corpses.nearest(biot.stats.pos, max_detection_distance)
+        } else {
+            None
+        };
+        [pellet, corpse].into_iter().flatten().min_by(|a, b| {
+            let to_a = world.delta(biot.stats.pos, *a).length_squared();
+            let to_b = world.delta(biot.stats.pos, *b).length_squared();
+            to_a.total_cmp(&to_b)
+        })
+    }
+
+    /// Pairs up the biots ready to reproduce with the closest other one within
+    /// `range`, every pair producing a single offspring.
+    ///
+    /// # Note
+    ///
+    /// Biots pick their partner in the order of the collection, so the
+    /// pairing is the same in every run.
+    fn mate(
+        &mut self,
+        world: &World,
+        config: &SimConfig,
+        range: Distance,
+        rngs: &mut RngStreams,
+        ledger: &mut EnergyLedger,
+    ) {
+        let mut ready = self
+            .biots
+            .iter()
+            .map(|biot| biot.is_alive() && biot.can_reproduce(config, &self.grid))
+            .collect::<Vec<_>>();
+        for first in 0..self.biots.len() {
+            if !ready[first] {
+                continue;
+            }
+            let partner = self
+                .grid
+                .within(self.grid.position(first), range)
+                .filter(|(second, _)| *second != first && ready[*second])
+                .min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+                .map(|(second, _)| second);
+            let Some(second) = partner else {
+                continue;
+            };
+            ready[first] = false;
+            ready[second] = false;
+            let mut offspring = Biot::mate(&mut self.biots, (first, second), config, rngs, ledger);
+            if offspring.sanitize(world, &mut rngs.placement) {
+                self.sanitized.push(offspring.genome().clone());
+            }
+            self.bear(offspring, &[first, second], config, ledger);
         }
     }
 
+    /// Adds an offspring of the biots at the indices `parents`, unless it is
+    /// nonviable and dies at birth.
+    fn bear(
+        &mut self,
+        offspring: Biot,
+        parents: &[usize],
+        config: &SimConfig,
+        ledger: &mut EnergyLedger,
+    ) {
+        if !offspring.is_viable() && config.nonviable == NonviablePolicy::DieAtBirth {
+            ledger.removed += offspring.stats.life;
+            self.deaths.push(CauseOfDeath::Nonviable);
+            return;
+        }
+        self.offsprings.push(offspring);
+        self.births += 1;
+        for &parent in parents {
+            self.parents.push(self.biots[parent].genome().clone());
+        }
+    }
+
+    /// Returns the number of steps between two prey searches of an intelligent biot.
+    pub fn sensing_interval(&self) -> u32 {
+        self.sensing_interval
+    }
+
+    /// Sets the number of steps between two prey searches of an intelligent biot.
+    ///
+    /// # Note
+    ///
+    /// With an interval of 1 every intelligent biot searches for prey each step.
+    /// Larger intervals trade a slight lag in behavior for far fewer spatial
+    /// queries: in between searches biots keep heading towards their last target.
+    pub fn set_sensing_interval(&mut self, interval: u32) {
+        self.sensing_interval = interval.max(1);
+    }
+
+    /// Returns the parameters of the ecosystem.
+    pub fn config(&self) -> &SimConfig {
+        &self.config
+    }
+
+    /// Replaces the parameters of the ecosystem, see [`Simulation::reconfigure`].
+    ///
+    /// [`Simulation::reconfigure`]: crate::simulation::Simulation::reconfigure
+    pub fn set_config(&mut self, config: SimConfig) {
+        self.config = config;
+    }
+
+    /// Returns the biot closest to `pos` whose body is at most `tolerance` away from it.
+    ///
+    /// # Note
+    ///
+    /// The spatial index only lives for the duration of a step since the
+    /// indices it stores are invalidated by births and deaths, so this
+    /// scans all biots. On a torus, biots reaching across an edge of `world`
+    /// are picked on either side of it.
+    pub fn pick(&self, world: &World, pos: Vec2, tolerance: Distance) -> Option<&Biot> {
+        self.biots
+            .iter()
+            .map(|biot| {
+                let delta = world.delta(pos, biot.stats.pos);
+                (biot, SquaredDistance(delta.length_squared()))
+            })
+            .filter(|(biot, squared_distance)| {
+                *squared_distance <= (biot.radius() + tolerance).squared()
+            })
+            .min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+            .map(|(biot, _)| biot)
+    }
+
+    /// Adds a biot to the collection as the founder of a new lineage.
+    ///
+    /// The properties of the biot are re-derived with the custom genes and
+    /// response curves of the simulation. Returns the identifier assigned to the biot.
+    pub fn spawn(&mut self, mut biot: Biot) -> BiotId {
+        let genome = biot.genome().clone();
+        biot.set_genome(genome, &self.config);
+        let id = BiotId(self.next_id);
+        biot.found_lineage(id);
+        self.phylogeny.found(id, self.steps);
+        self.next_id += 1;
+        self.biots.push(biot);
+        id
+    }
+
+    /// Mutates the genomes of a random `fraction` of the biots following `strategy`.
+    ///
+    /// Returns the number of mutated biots.
+    pub fn diversify(&mut self, strategy: &Strategy, fraction: f32, rng: &mut Rng) -> usize {
+        if fraction <= 0.0 {
+            return 0;
+        }
+        let mut mutated = 0;
+        for biot in &mut self.biots {
+            if biot.genome().expressed_counts() == *strategy && rng.gen_range(0., 1.) < fraction {
+                biot.mutate(&self.config, rng);
+                mutated += 1;
+            }
+        }
+        mutated
+    }
+
+    /// Injects the novel genomes described by the
+    /// [`NoveltyConfig`](crate::config::NoveltyConfig) at random positions.
+    ///
+    /// Returns the identifiers of the injected biots.
+    pub fn inject(&mut self, world: &World, rng: &mut Rng) -> Vec<BiotId> {
+        let novelty = self.config.novelty.clone();
+        (0..novelty.count)
+            .map(|_| {
+                let mut biot = if novelty.library.is_empty() {
+                    Biot::random_biot(world, &self.config, rng)
+                } else {
+                    let genes = &novelty.library[rng.gen_index(novelty.library.len())];
+                    let genome = genes
+                        .iter()
+                        .fold(GenomeBuilder::new(), |genome, &gene| genome.with(gene, 1))
+                        .build()
+                        .expect("library genomes are checked when loading the config");
+                    Biot::from_genome(genome, world, rng)
+                };
+                biot.stats.life = biot.base_life(&self.config);
+                self.spawn(biot)
+            })
+            .collect()
+    }
+
+    /// Kills the biots within `radius` of `center`, leaving their corpses.
+    ///
+    /// Returns the number of killed biots.
+    ///
+    /// # Note
+    ///
+    /// The biots are removed right away, so they are neither counted as
+    /// deaths nor logged as events of the next step.
+    pub fn kill_within(&mut self, world: &World, center: Vec2, radius: Distance) -> usize {
+        let within = |biot: &Biot| world.delta(center, biot.stats.pos).length() <= radius.0;
+        let mut killed = 0;
+        for biot in self.biots.iter().filter(|biot| within(biot)) {
+            self.phylogeny.death(biot.id(), self.steps);
+            let energy = biot.stats.life * self.config.corpses.fraction;
+            self.corpses.leave(world, biot.stats.pos, energy);
+            killed += 1;
+        }
+        self.biots.retain(|biot| !within(biot));
+        // The next step rebuilds the spatial index.
+        self.grid = SpatialGrid::default();
+        self.clustering_stale = true;
+        killed
+    }
+
+    /// Kills the biot `id`, leaving its corpse.
+    ///
+    /// Returns `false` if there is no such biot. Like
+    /// [`BiotCollection::kill_within`], the death is not counted.
+    pub fn kill(&mut self, world: &World, id: BiotId) -> bool {
+        let Some(biot) = self.remove(id) else {
+            return false;
+        };
+        let energy = biot.stats.life * self.config.corpses.fraction;
+        self.corpses.leave(world, biot.stats.pos, energy);
+        true
+    }
+
+    /// Removes the biot `id` without leaving a corpse.
+    ///
+    /// Like the biots killed by [`BiotCollection::kill_within`], the removed
+    /// biot is not counted as a death, but it leaves the family tree.
+    pub fn remove(&mut self, id: BiotId) -> Option<Biot> {
+        let index = self.biots.iter().position(|biot| biot.id() == id)?;
+        let biot = self.biots.remove(index);
+        self.phylogeny.death(id, self.steps);
+        // The next step rebuilds the spatial index.
+        self.grid = SpatialGrid::default();
+        self.clustering_stale = true;
+        Some(biot)
+    }
+
+    /// Removes the biots `ids` to move them to another simulation.
+    ///
+    /// Returns the removed biots, ordered by identifier. They leave the family
+    /// tree as emigrants, see [`Phylogeny::emigration`], and are not counted
+    /// as deaths.
+    pub fn emigrate(&mut self, ids: &[BiotId]) -> Vec<Biot> {
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        let (emigrants, staying) = mem::take(&mut self.biots)
+            .into_iter()
+            .partition::<Vec<_>, _>(|biot| ids.binary_search(&biot.id()).is_ok());
+        self.biots = staying;
+        for biot in &emigrants {
+            self.phylogeny.emigration(biot.id(), self.steps);
+        }
+        // The next step rebuilds the spatial index.
+        self.grid = SpatialGrid::default();
+        self.clustering_stale = true;
+        emigrants
+    }
+
+    /// Adds `fraction` of their base life to the life of the biots within `radius` of `center`.
+    ///
+    /// Returns the number of fed biots. The added life is recorded as a
+    /// modification, see [`BiotCollection::modify`].
+    pub fn feed_within(
+        &mut self,
+        world: &World,
+        center: Vec2,
+        radius: Distance,
+        fraction: f32,
+    ) -> usize {
+        let fed = self
+            .biots
+            .iter()
+            .filter(|biot| world.delta(center, biot.stats.pos).length() <= radius.0)
+            .map(|biot| (biot.id(), biot.base_life(&self.config) * fraction))
+            .collect::<Vec<_>>();
+        for &(id, energy) in &fed {
+            self.modify(id, |biot| biot.stats.life += energy);
+        }
+        fed.len()
+    }
+
     /// The number of biots currently in our collection
     pub fn len(&self) -> usize {
         self.biots.len()
     }
+
+    /// Returns `true` if the collection has no biots.
+    pub fn is_empty(&self) -> bool {
+        self.biots.is_empty()
+    }
+
+    /// Returns an iterator over the biots of the collection.
+    pub fn iter(&self) -> slice::Iter<'_, Biot> {
+        self.biots.iter()
+    }
+
+    /// Returns the genomes of the biots that reproduced during the last step.
+    ///
+    /// # Note
+    ///
+    /// With sexual reproduction every offspring has two parents.
+    pub fn parents(&self) -> &[Genome] {
+        &self.parents
+    }
+
+    /// Lets the water carry the biots along, adding the velocity of the
+    /// `currents` and configured flows at their positions to their speed.
+    pub fn drift(&mut self, currents: &Currents) {
+        let flows = &self.config.currents.flows;
+        if currents.is_calm() && flows.is_empty() {
+            return;
+        }
+        for biot in &mut self.biots {
+            biot.stats.speed += currents.velocity(flows, biot.stats.pos) * current::DRAG;
+        }
+    }
+
+    /// Moves the biots and corpses into the resized `world`.
+    ///
+    /// Everything moves to the position `remap` returns for it, biots and
+    /// corpses it returns `None` for are removed. The scent is resampled from
+    /// the position `source` maps each of its new cells to. Returns the
+    /// number of removed biots.
+    pub fn resize(
+        &mut self,
+        world: &World,
+        remap: impl Fn(Vec2) -> Option<Vec2>,
+        source: impl Fn(Vec2) -> Vec2,
+    ) -> usize {
+        let mut culled = Vec::new();
+        for biot in &mut self.biots {
+            match remap(biot.stats.pos) {
+                Some(pos) => biot.stats.pos = pos,
+                None => culled.push(biot.id()),
+            }
+            biot.stats.feed_dir = None;
+            biot.stats.flee_dir = None;
+            biot.stats.target = None;
+            biot.stats.mate_dir = None;
+            biot.stats.flock_dir = None;
+        }
+        for &id in &culled {
+            self.phylogeny.death(id, self.steps);
+        }
+        // Both are sorted by identifier.
+        self.biots
+            .retain(|biot| culled.binary_search(&biot.id()).is_err());
+        self.corpses.remap(&remap);
+        self.scent = self.scent.resized(world, &source);
+        // The next step rebuilds the spatial index.
+        self.grid = SpatialGrid::default();
+        self.clustering_stale = true;
+        culled.len()
+    }
+
+    /// Returns the number of offsprings born during the last step.
+    pub fn births(&self) -> u32 {
+        self.births
+    }
+
+    /// Returns the number of biots infected during the last step.
+    pub fn infections(&self) -> u32 {
+        self.infections
+    }
+
+    /// Returns the number of biots carrying the pathogen.
+    pub fn infected_count(&self) -> usize {
+        self.biots.iter().filter(|biot| biot.stats.infected).count()
+    }
+
+    /// Returns the causes of the deaths that happened during the last step.
+    pub fn deaths(&self) -> &[CauseOfDeath] {
+        &self.deaths
+    }
+
+    /// Returns the biots removed for dying during the last step.
+    pub fn obituaries(&self) -> &[Obituary] {
+        &self.obituaries
+    }
+
+    /// Returns the positions of the biots eaten during the last step.
+    pub fn kills(&self) -> &[Vec2] {
+        &self.kills
+    }
+
+    /// Returns the scent the biots left behind.
+    pub fn scent(&self) -> &Field {
+        &self.scent
+    }
+
+    /// Returns the biots that ate each other during the last step.
+    pub fn fights(&self) -> &[Fight] {
+        &self.fights
+    }
+
+    /// Returns the remains of dead biots.
+    pub fn corpses(&self) -> &CorpseCollection {
+        &self.corpses
+    }
+
+    /// Returns the number of biots that are about to die.
+    pub fn dying_count(&self) -> usize {
+        self.biots
+            .iter()
+            .filter(|biot| matches!(biot.health(&self.config), Health::Dying { .. }))
+            .count()
+    }
+
+    /// Returns the number of predators and prey in the collection.
+    pub fn predator_prey_counts(&self) -> (usize, usize) {
+        let predators = self.biots.iter().filter(|biot| biot.is_predator()).count();
+        (predators, self.biots.len() - predators)
+    }
+
+    /// Returns the ancestry of the living biots.
+    pub fn phylogeny(&self) -> &Phylogeny {
+        &self.phylogeny
+    }
+
+    /// Returns the number of species with living members as of the last clustering pass.
+    pub fn species_count(&self) -> usize {
+        self.species.len()
+    }
+
+    /// Returns the number of steps computed so far.
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// Returns the Clark-Evans aggregation index of the population.
+    ///
+    /// # Note
+    ///
+    /// The index is refreshed every [`CLUSTERING_INTERVAL`] steps.
+    /// A value below 1 indicates a clumped population, a value around 1 a
+    /// random distribution and a value above 1 an over-dispersed population.
+    pub fn clustering_index(&self) -> f32 {
+        self.clustering_index
+    }
+
+    /// Checks that the spatial index is consistent and lists every biot at its position.
+    ///
+    /// # Note
+    ///
+    /// Biots spawned since the last step are not indexed yet, so the index
+    /// is only compared to the biots if it holds as many.
+    pub fn check_index(&self, world: &World) -> Result<(), String> {
+        self.grid.check()?;
+        if !self.grid.is_synced(world, self.biots.len()) {
+            return Ok(());
+        }
+        let moved = self
+            .biots
+            .iter()
+            .enumerate()
+            .find(|(idx, biot)| self.grid.position(*idx) != biot.stats.pos);
+        match moved {
+            Some((idx, biot)) => Err(format!(
+                "biot {} is indexed at {} instead of {}",
+                biot.id().0,
+                self.grid.position(idx),
+                biot.stats.pos
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the spatial index of the biots.
+    ///
+    /// # Note
+    ///
+    /// The indices of the grid match the biots only after a step, biots
+    /// spawned since are not indexed yet, see [`SpatialGrid::is_synced`].
+    pub fn grid(&self) -> &SpatialGrid {
+        &self.grid
+    }
+
+    /// Computes the Clark-Evans aggregation index from the spatial index.
+    ///
+    /// This is the ratio between the observed mean nearest neighbour distance
+    /// and the one expected for a uniformly random distribution of the same
+    /// density. Wrap-around at the world edges is ignored.
+    fn compute_clustering_index(world: &World, grid: &SpatialGrid) -> f32 {
+        let len = grid.len();
+        if len < 2 {
+            return 1.0;
+        }
+        let total_distance: f32 = (0..len)
+            .filter_map(|idx| grid.nearest(idx))
+            .map(|(_, squared_distance)| squared_distance.sqrt().0)
+            .sum();
+        let observed = total_distance / len as f32;
+        let density = len as f32 / world.area();
+        let expected = 0.5 / density.sqrt();
+        observed / expected
+    }
+}
+
+/// Queries and modifications for tools inspecting and editing the simulation.
+///
+/// # Note
+///
+/// Not every query is used by the bundled frontends.
+#[allow(dead_code)]
+impl BiotCollection {
+    /// Returns the biot with the given identifier if it is still alive.
+    ///
+    /// # Note
+    ///
+    /// Biots are only ever appended with increasing identifiers and removed
+    /// without reordering, so the collection is always sorted by identifier.
+    pub fn get(&self, id: BiotId) -> Option<&Biot> {
+        self.biots
+            .binary_search_by_key(&id, Biot::id)
+            .ok()
+            .map(|idx| &self.biots[idx])
+    }
+
+    /// Returns an iterator over the biots within the axis aligned box from `min` to `max`.
+    pub fn biots_in_region(&self, min: Vec2, max: Vec2) -> impl Iterator<Item = &Biot> {
+        self.biots.iter().filter(move |biot| {
+            let pos = biot.stats.pos;
+            (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y)
+        })
+    }
+
+    /// Returns an iterator over the biots descending from the founder `lineage`.
+    pub fn biots_in_lineage(&self, lineage: BiotId) -> impl Iterator<Item = &Biot> {
+        self.biots
+            .iter()
+            .filter(move |biot| biot.lineage() == lineage)
+    }
+
+    /// Returns an iterator over the biots whose genome satisfies `predicate`.
+    pub fn biots_with_genome<P>(&self, mut predicate: P) -> impl Iterator<Item = &Biot>
+    where
+        P: FnMut(&Genome) -> bool,
+    {
+        self.biots
+            .iter()
+            .filter(move |biot| predicate(biot.genome()))
+    }
+
+    /// Applies `modify` to the biot with the given identifier.
+    ///
+    /// Returns `None` if there is no such biot.
+    ///
+    /// # Note
+    ///
+    /// The changes are recorded and can be retrieved with
+    /// [`BiotCollection::take_modifications`]. Values derived from the
+    /// modified state, like the sensed prey direction or the clustering
+    /// index, are refreshed accordingly. Genomes must be replaced with
+    /// [`Biot::set_genome`] to keep the properties of the biot in sync.
+    pub fn modify<F, R>(&mut self, id: BiotId, modify: F) -> Option<R>
+    where
+        F: FnOnce(&mut Biot) -> R,
+    {
+        let idx = self.biots.binary_search_by_key(&id, Biot::id).ok()?;
+        let biot = &mut self.biots[idx];
+        let before = biot.clone();
+        let result = modify(biot);
+        let changes = Changes::between(&before, biot);
+        if changes.position {
+            biot.stats.feed_dir = None;
+            biot.stats.flee_dir = None;
+            biot.stats.target = None;
+            biot.stats.mate_dir = None;
+            biot.stats.flock_dir = None;
+            self.clustering_stale = true;
+            if idx < self.grid.len() {
+                self.grid.update(idx, biot.stats.pos);
+            }
+        }
+        if !changes.is_empty() {
+            self.modifications.push(Modification { id, changes });
+        }
+        Some(result)
+    }
+
+    /// Returns and clears the modifications recorded since the last call.
+    pub fn take_modifications(&mut self) -> Vec<Modification> {
+        mem::take(&mut self.modifications)
+    }
+}
+
+#[cfg(feature = "render")]
+impl BiotCollection {
+    /// Display the biot collection as seen through `camera`, colored by `coloring`.
+    ///
+    /// The scent of the biots is drawn below them if `scent` is set and the
+    /// biots at the positions given by `interpolation`.
+    pub fn draw(
+        &self,
+        world: &World,
+        camera: &Camera,
+        filter: &Filter,
+        coloring: Coloring,
+        scent: bool,
+        interpolation: &Interpolation,
+    ) {
+        let offsets = camera.wrap_offsets(world);
+        if scent {
+            self.scent.draw(&offsets, ORANGE);
+        }
+        self.corpses.draw(&offsets);
+        for offset in offsets {
+            for biot in self.biots.iter() {
+                let pos = interpolation.position(world, biot) + offset;
+                self.draw_biot(biot, pos, filter, coloring);
+            }
+        }
+    }
+
+    /// Draws a single biot at `pos`, dimmed unless it matches `filter`.
+    pub fn draw_biot(&self, biot: &Biot, pos: Vec2, filter: &Filter, coloring: Coloring) {
+        // Dying biots are drawn translucent, the ones not matching the filter dimmed.
+        let mut alpha = match biot.health(&self.config) {
+            Health::Alive => 1.0,
+            Health::Dying { .. } | Health::Dead => 0.4,
+        };
+        if !filter.matches(biot) {
+            alpha *= DIMMED_ALPHA;
+        }
+        let tint = |color: Color| Color { a: alpha, ..color };
+        // Biots not clustered yet keep the trait colors until the next pass.
+        let [body, attack, defense, motion] = match (coloring, biot.species()) {
+            (Coloring::Species, Some(species)) => {
+                [0.3, 0.45, 0.6, 0.75].map(|lightness| species_color(species, lightness))
+            }
+            _ => [GREEN, RED, DARKBLUE, BLUE],
+        };
+        if biot.properties.intelligence > 0. {
+            let size = 2. * biot.radius().0;
+            draw_rectangle(pos.x - size / 2., pos.y - size / 2., size, size, tint(body));
+        }
+        draw_circle(pos.x, pos.y, biot.radius().0, tint(body));
+        draw_circle(
+            pos.x,
+            pos.y,
+            7. * (biot.properties.attack + biot.properties.defense + biot.properties.motion),
+            tint(attack),
+        );
+        draw_circle(
+            pos.x,
+            pos.y,
+            7. * (biot.properties.defense + biot.properties.motion),
+            tint(defense),
+        );
+        draw_circle(pos.x, pos.y, 7. * (biot.properties.motion), tint(motion));
+        // Traits that do not shape the body are drawn as rings around it.
+        let properties = &biot.properties;
+        let rings = [
+            (Gene::Venom, properties.venom),
+            (Gene::Armor, properties.armor),
+            (Gene::Camouflage, properties.camouflage),
+            (Gene::Vision, properties.vision),
+            (Gene::Reproduction, properties.reproduction),
+            (Gene::Immunity, properties.immunity),
+            (Gene::Fecundity, properties.fecundity),
+            (Gene::Nurture, properties.nurture),
+            (Gene::Sociality, properties.sociality),
+            (Gene::HeatTolerance, properties.heat_tolerance),
+            (Gene::Kinship, properties.kinship),
+        ];
+        let mut radius = biot.radius().0;
+        for (gene, value) in rings.into_iter().filter(|(_, value)| *value > 0.0) {
+            let thickness = 10. * value;
+            radius += thickness / 2.;
+            draw_circle_lines(pos.x, pos.y, radius, thickness, tint(gene_color(gene)));
+            radius += thickness / 2.;
+        }
+        if biot.stats.infected {
+            draw_circle(pos.x, pos.y, 2., tint(YELLOW));
+        }
+    }
 }
@@ -0,0 +1,121 @@
+//! Evolvable neural networks steering intelligent biots.
+//!
+//! A brain is a small feed-forward network with a single hidden layer. Its
+//! weights are part of the genome, so the behavior of a lineage evolves
+//! along with its body.
+
+use crate::config::BrainConfig;
+use crate::rng::Rng;
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// Number of sensory inputs of a brain, see [`Senses::inputs`].
+pub const INPUTS: usize = 10;
+
+/// Maximum number of hidden neurons of a brain.
+pub const MAX_NEURONS: usize = 8;
+
+/// Number of outputs of a brain: the two components of the movement vector.
+const OUTPUTS: usize = 2;
+
+/// Number of weights of a brain, including one bias per neuron.
+const WEIGHTS: usize = MAX_NEURONS * (INPUTS + 1) + OUTPUTS * (MAX_NEURONS + 1);
+
+/// What a biot perceives of its surroundings.
+///
+/// # Note
+///
+/// Each target is given as the direction towards it, scaled by its closeness:
+/// one right next to the biot, zero at the edge of the detection distance or
+/// if there is nothing in sight.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Senses {
+    /// Closest biot the biot could eat.
+    pub prey: Vec2,
+    /// Closest biot that could eat the biot.
+    pub predator: Vec2,
+    /// Closest food pellet or corpse.
+    pub food: Vec2,
+    /// Life of the biot relative to the life it needs to reproduce, in `0.0..=1.0`.
+    pub energy: f32,
+}
+
+impl Senses {
+    /// Returns the inputs of a brain perceiving these senses.
+    fn inputs(&self) -> [f32; INPUTS] {
+        [
+            self.prey.x,
+            self.prey.y,
+            self.prey.length(),
+            self.predator.x,
+            self.predator.y,
+            self.predator.length(),
+            self.food.x,
+            self.food.y,
+            self.food.length(),
+            self.energy,
+        ]
+    }
+}
+
+/// A feed-forward network turning [`Senses`] into a movement vector.
+///
+/// # Note
+///
+/// The weights of all [`MAX_NEURONS`] hidden neurons are always inherited,
+/// but only as many neurons think as the intelligence of the biot allows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Brain {
+    weights: Vec<f32>,
+}
+
+impl Brain {
+    /// Creates a brain with random weights.
+    pub fn random(rng: &mut Rng) -> Self {
+        let weights = (0..WEIGHTS).map(|_| rng.gen_range(-1.0, 1.0)).collect();
+        Self { weights }
+    }
+
+    /// Nudges each weight with the chance `config.mutation_chance`.
+    pub fn mutate(&mut self, config: &BrainConfig, rng: &mut Rng) {
+        for weight in &mut self.weights {
+            if rng.gen_range(0., 1.) < config.mutation_chance {
+                *weight += rng.gen_range(-config.weight_step, config.weight_step);
+            }
+        }
+    }
+
+    /// Returns the weights of the brain.
+    pub fn weights(&self) -> &[f32] {
+        &self.weights
+    }
+
+    /// Returns the movement vector of a biot perceiving `senses` with `neurons` hidden neurons.
+    ///
+    /// The vector is at most one long.
+    pub fn think(&self, senses: &Senses, neurons: usize) -> Vec2 {
+        let inputs = senses.inputs();
+        let (hidden, output) = self.weights.split_at(MAX_NEURONS * (INPUTS + 1));
+        let mut activations = [0.0; MAX_NEURONS];
+        for (activation, weights) in activations
+            .iter_mut()
+            .zip(hidden.chunks_exact(INPUTS + 1))
+            .take(neurons.min(MAX_NEURONS))
+        {
+            let sum = weights[INPUTS] + dot(&weights[..INPUTS], &inputs);
+            *activation = sum.tanh();
+        }
+        let mut outputs = output.chunks_exact(MAX_NEURONS + 1).map(|weights| {
+            let sum = weights[MAX_NEURONS] + dot(&weights[..MAX_NEURONS], &activations);
+            sum.tanh()
+        });
+        let x = outputs.next().unwrap_or(0.0);
+        let y = outputs.next().unwrap_or(0.0);
+        vec2(x, y).clamp_length_max(1.0)
+    }
+}
+
+/// Returns the dot product of `weights` and `values`.
+fn dot(weights: &[f32], values: &[f32]) -> f32 {
+    weights.iter().zip(values).map(|(w, v)| w * v).sum()
+}
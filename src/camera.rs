@@ -0,0 +1,196 @@
+use crate::world::World;
+use glam::{vec2, Vec2};
+use macroquad::prelude::{
+    is_mouse_button_down, mouse_position, mouse_wheel, screen_height, screen_width, set_camera,
+    touches, Camera2D, MouseButton, TouchPhase,
+};
+
+/// Smallest zoom factor, showing the world a few times over.
+const MIN_ZOOM: f32 = 0.5;
+
+/// Largest zoom factor.
+const MAX_ZOOM: f32 = 8.0;
+
+/// Zoom factor applied per step of the mouse wheel.
+const ZOOM_STEP: f32 = 1.1;
+
+/// The view onto the world, panned by dragging with the right mouse button
+/// or a finger and zoomed with the mouse wheel or by pinching.
+pub struct Camera {
+    /// World position shown at the center of the screen.
+    target: Vec2,
+    /// Number of screen pixels per world unit.
+    zoom: f32,
+    /// Mouse position of the last frame, used for dragging.
+    last_mouse: Vec2,
+    /// Identifiers and positions of the fingers on the screen in the last frame.
+    last_touches: Vec<(u64, Vec2)>,
+    /// Whether the screen has been touched, e.g. to show buttons for the hotkeys.
+    touched: bool,
+}
+
+impl Camera {
+    /// Creates a camera showing the whole `world` at its original size.
+    pub fn new(world: &World) -> Self {
+        Self {
+            target: vec2(world.width / 2.0, world.height / 2.0),
+            zoom: 1.0,
+            last_mouse: Self::mouse(),
+            last_touches: Vec::new(),
+            touched: false,
+        }
+    }
+
+    /// Pans and zooms the camera according to the mouse and touch input of this frame.
+    pub fn update(&mut self) {
+        self.update_touches();
+        let mouse = Self::mouse();
+        if is_mouse_button_down(MouseButton::Right) {
+            self.target -= (mouse - self.last_mouse) / self.zoom;
+        }
+        self.last_mouse = mouse;
+        let (_, wheel) = mouse_wheel();
+        if wheel != 0.0 {
+            // Zoom around the mouse position, keeping the world position below it fixed.
+            let anchor = self.screen_to_world(mouse);
+            let factor = if wheel > 0.0 {
+                ZOOM_STEP
+            } else {
+                ZOOM_STEP.recip()
+            };
+            self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+            self.target += anchor - self.screen_to_world(mouse);
+        }
+    }
+
+    /// Pans the camera with a single finger and pinches it with two.
+    ///
+    /// # Note
+    ///
+    /// Touches also act as the left mouse button, so tapping a biot selects it.
+    fn update_touches(&mut self) {
+        let fingers = touches()
+            .into_iter()
+            .filter(|touch| !matches!(touch.phase, TouchPhase::Ended | TouchPhase::Cancelled))
+            .map(|touch| (touch.id, vec2(touch.position.x, touch.position.y)))
+            .collect::<Vec<_>>();
+        self.touched |= !fingers.is_empty();
+        let last = |id: u64| {
+            self.last_touches
+                .iter()
+                .find(|(last_id, _)| *last_id == id)
+                .map(|(_, pos)| *pos)
+        };
+        match fingers[..] {
+            [(id, pos)] => {
+                if let Some(last_pos) = last(id) {
+                    self.target -= (pos - last_pos) / self.zoom;
+                }
+            }
+            [(id_a, a), (id_b, b)] => {
+                if let (Some(last_a), Some(last_b)) = (last(id_a), last(id_b)) {
+                    // Keep the world position between the fingers below them.
+                    let (center, last_center) = ((a + b) / 2.0, (last_a + last_b) / 2.0);
+                    let anchor = self.screen_to_world(last_center);
+                    let spread = last_a.distance(last_b);
+                    if spread > 0.0 {
+                        let zoom = self.zoom * a.distance(b) / spread;
+                        self.zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+                    }
+                    self.target += anchor - self.screen_to_world(center);
+                }
+            }
+            _ => {}
+        }
+        self.last_touches = fingers;
+    }
+
+    /// Returns `true` once the screen has been touched.
+    pub fn is_touched(&self) -> bool {
+        self.touched
+    }
+
+    /// Moves the camera the given `rate` of the remaining way towards showing
+    /// `goal` at the given `zoom`, taking the shortest way across the edges
+    /// of a torus.
+    pub fn glide(&mut self, world: &World, goal: Vec2, zoom: f32, rate: f32) {
+        self.target = world.wrap(self.target);
+        self.target += world.delta(self.target, goal) * rate;
+        self.zoom += (zoom.clamp(MIN_ZOOM, MAX_ZOOM) - self.zoom) * rate;
+    }
+
+    /// Centers the camera on `target`, keeping its zoom.
+    pub fn jump(&mut self, target: Vec2) {
+        self.target = target;
+    }
+
+    /// Makes all following draw calls render through the camera.
+    ///
+    /// # Note
+    ///
+    /// Use `set_default_camera` to go back to drawing in screen coordinates.
+    pub fn activate(&self) {
+        set_camera(&Camera2D {
+            target: macroquad::prelude::vec2(self.target.x, self.target.y),
+            zoom: macroquad::prelude::vec2(
+                2.0 * self.zoom / screen_width(),
+                -2.0 * self.zoom / screen_height(),
+            ),
+            ..Default::default()
+        });
+    }
+
+    /// Converts a position on the screen to the unwrapped world position below it.
+    pub fn screen_to_world(&self, pos: Vec2) -> Vec2 {
+        let center = vec2(screen_width(), screen_height()) / 2.0;
+        self.target + (pos - center) / self.zoom
+    }
+
+    /// Converts an unwrapped world position to the position on the screen it is shown at.
+    pub fn world_to_screen(&self, pos: Vec2) -> Vec2 {
+        let center = vec2(screen_width(), screen_height()) / 2.0;
+        center + (pos - self.target) * self.zoom
+    }
+
+    /// Returns the number of screen pixels per world unit.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Returns the world position below the mouse, wrapped into the world.
+    pub fn mouse_world_position(&self, world: &World) -> Vec2 {
+        world.wrap(self.screen_to_world(Self::mouse()))
+    }
+
+    /// Returns the offsets at which `world` has to be drawn to fill the view.
+    ///
+    /// # Note
+    ///
+    /// The view may extend past the edges of the world when panned or zoomed out.
+    /// The wrapped-around parts of a torus are drawn with an offset of whole
+    /// world sizes so that biots crossing an edge reappear on the other side.
+    /// Bounded worlds are drawn only once.
+    pub fn wrap_offsets(&self, world: &World) -> Vec<Vec2> {
+        if !world.is_torus() {
+            return vec![Vec2::ZERO];
+        }
+        let top_left = self.screen_to_world(Vec2::ZERO);
+        let bottom_right = self.screen_to_world(vec2(screen_width(), screen_height()));
+        let tiles = |min: f32, max: f32, size: f32| {
+            (min / size).floor() as i32..=(max / size).floor() as i32
+        };
+        let mut offsets = Vec::new();
+        for ty in tiles(top_left.y, bottom_right.y, world.height) {
+            for tx in tiles(top_left.x, bottom_right.x, world.width) {
+                offsets.push(vec2(tx as f32 * world.width, ty as f32 * world.height));
+            }
+        }
+        offsets
+    }
+
+    /// Returns the current mouse position in screen coordinates.
+    fn mouse() -> Vec2 {
+        let (x, y) = mouse_position();
+        vec2(x, y)
+    }
+}
@@ -0,0 +1,70 @@
+//! Export of the shown frames as PNG files, either as single screenshots or
+//! as timelapses whose frames can be turned into videos externally, e.g.
+//! `ffmpeg -framerate 30 -pattern_type glob -i 'frames/*.png' run.mp4`.
+
+use macroquad::prelude::get_screen_data;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Returns the file name of the screenshot taken after `step` steps.
+pub fn screenshot_path(step: u32) -> String {
+    format!("life_web_screenshot_{step}.png")
+}
+
+/// Writes the frame drawn so far to `path`.
+///
+/// # Note
+///
+/// Must be called after everything was drawn and before the frame ends.
+pub fn save_frame(path: &str) {
+    get_screen_data().export_png(path);
+}
+
+/// Writes a frame to a directory every few simulation steps.
+#[derive(Debug, Clone)]
+pub struct Timelapse {
+    dir: PathBuf,
+    /// Number of steps between two frames.
+    interval: u32,
+    /// Number of steps simulated when the last frame was written.
+    last: Option<u32>,
+}
+
+impl Timelapse {
+    /// Creates a timelapse writing a frame every `interval` steps to `dir`,
+    /// creating the directory if needed.
+    pub fn new(dir: impl Into<PathBuf>, interval: u32) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            interval: interval.max(1),
+            last: None,
+        })
+    }
+
+    /// Writes the shown frame, named after the number of steps simulated, if
+    /// the simulation crossed another multiple of `interval` steps since the
+    /// last one.
+    ///
+    /// # Note
+    ///
+    /// The first frame is written right away, as is the first after
+    /// rewinding or loading a snapshot. At most one frame is written per
+    /// shown frame, so fast-forwarding by more than `interval` steps per
+    /// frame thins out the timelapse. The step numbers are zero-padded, so
+    /// the files sort in the order of the frames.
+    pub fn capture(&mut self, step: u32) {
+        let due = match self.last {
+            Some(last) if step >= last => step / self.interval > last / self.interval,
+            _ => true,
+        };
+        if !due {
+            return;
+        }
+        self.last = Some(step);
+        let path = self.dir.join(format!("frame_{step:010}.png"));
+        save_frame(&path.to_string_lossy());
+    }
+}
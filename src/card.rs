@@ -0,0 +1,111 @@
+//! Shareable image cards of single biots.
+//!
+//! A card shows the rendered biot next to its genome, its properties, how
+//! many generations its lineage reaches back and the seed of the run, so
+//! that others can reproduce the simulation it evolved in.
+
+use crate::biot::{Biot, BiotId};
+use crate::filter::Filter;
+use crate::simulation::Simulation;
+use crate::species::Coloring;
+use glam::Vec2;
+use macroquad::prelude::{
+    clear_background, draw_rectangle_lines, draw_text, render_target, set_camera,
+    set_default_camera, Camera2D, Color, Rect, GOLD, LIGHTGRAY,
+};
+
+/// Size of a card in pixels.
+const CARD_WIDTH: f32 = 640.0;
+const CARD_HEIGHT: f32 = 360.0;
+
+/// Center of the rendered biot on the card.
+const GLYPH_CENTER: (f32, f32) = (110.0, 150.0);
+
+/// Radius in pixels the rendered biot is scaled to.
+const GLYPH_RADIUS: f32 = 80.0;
+
+/// Number of gene symbols per line of the genome string.
+const GENOME_LINE_LEN: usize = 40;
+
+/// Returns the file name of the card of the biot with the given identifier.
+pub fn file_name(id: BiotId) -> String {
+    format!("life_web_card_{}.png", id.0)
+}
+
+/// Renders the card of `biot` of `simulation` and writes it to `path` as PNG.
+///
+/// # Note
+///
+/// The card is rendered offscreen, so the shown frame is not disturbed.
+/// Biots are colored by their traits.
+pub fn export(simulation: &Simulation, biot: &Biot, path: &str) {
+    let target = render_target(CARD_WIDTH as u32, CARD_HEIGHT as u32);
+    // The biot is drawn around the origin, scaled to fill the glyph area.
+    let scale = GLYPH_RADIUS / biot.radius().0.max(7. * biot.properties.motion).max(1.0);
+    let (x, y) = GLYPH_CENTER;
+    set_camera(&Camera2D {
+        render_target: Some(target),
+        ..Camera2D::from_display_rect(Rect::new(
+            -x / scale,
+            -y / scale,
+            CARD_WIDTH / scale,
+            CARD_HEIGHT / scale,
+        ))
+    });
+    clear_background(Color::new(0.08, 0.1, 0.12, 1.0));
+    simulation
+        .biots()
+        .draw_biot(biot, Vec2::ZERO, &Filter::new(), Coloring::Traits);
+    set_camera(&Camera2D {
+        render_target: Some(target),
+        ..Camera2D::from_display_rect(Rect::new(0.0, 0.0, CARD_WIDTH, CARD_HEIGHT))
+    });
+    draw_rectangle_lines(4.0, 4.0, CARD_WIDTH - 8.0, CARD_HEIGHT - 8.0, 2.0, GOLD);
+    for (n, line) in lines(simulation, biot).iter().enumerate() {
+        let (size, color) = if n == 0 {
+            (26.0, GOLD)
+        } else {
+            (17.0, LIGHTGRAY)
+        };
+        draw_text(line, 230.0, 40.0 + 20.0 * n as f32, size, color);
+    }
+    set_default_camera();
+    target.texture.get_texture_data().export_png(path);
+}
+
+/// Returns the lines of text shown on the card, starting with its title.
+fn lines(simulation: &Simulation, biot: &Biot) -> Vec<String> {
+    let config = simulation.biots().config();
+    let properties = &biot.properties;
+    let depth = simulation.biots().phylogeny().depth(biot.id());
+    let mut lines = vec![
+        format!("biot {}", biot.id().0),
+        format!("lineage {}, {depth} generations deep", biot.lineage().0),
+        format!("age {}, life {:.1}", biot.stats.age.0, biot.stats.life.0),
+        format!(
+            "attack {:.1}, defense {:.1}, motion {:.1}",
+            properties.attack, properties.defense, properties.motion
+        ),
+        format!(
+            "photosynthesis {:.1}, intelligence {:.1}",
+            properties.photosynthesis, properties.intelligence
+        ),
+        format!(
+            "venom {:.1}, armor {:.1}, camouflage {:.1}",
+            properties.venom, properties.armor, properties.camouflage
+        ),
+        format!("mutation chance {:.2}", biot.mutation_chance(config)),
+        format!("genome of {} genes:", biot.genome().len()),
+    ];
+    let code = biot.genome().code().chars().collect::<Vec<_>>();
+    lines.extend(
+        code.chunks(GENOME_LINE_LEN)
+            .map(|chunk| format!("  {}", chunk.iter().collect::<String>())),
+    );
+    lines.push(format!(
+        "seed {}, step {}",
+        simulation.seed(),
+        simulation.biots().steps()
+    ));
+    lines
+}
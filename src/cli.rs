@@ -0,0 +1,236 @@
+//! Command line options of the binary.
+//!
+//! Every option of a run can be scripted, so experiments do not require
+//! editing constants in the source. `--help` lists all of them.
+
+use clap::{Args, Parser, Subcommand};
+use life_web::archipelago::Migration;
+#[cfg(feature = "render")]
+use life_web::clock::DEFAULT_TICK_RATE;
+use life_web::experiment::Sweep;
+use life_web::fixture;
+#[cfg(feature = "render")]
+use life_web::labels::Labels;
+use life_web::preset::Preset;
+use life_web::recorder::Column;
+
+/// Number of steps between two autosaves unless passed with `--save-every`.
+const AUTOSAVE_INTERVAL: u64 = 10_000;
+
+/// Number of steps between two frames of a timelapse unless passed with `--timelapse-interval`.
+#[cfg(feature = "render")]
+const TIMELAPSE_INTERVAL: u32 = 100;
+
+/// Number of steps between two recorded rows unless passed with `--record-interval`.
+const RECORD_INTERVAL: u32 = 100;
+
+/// Number of runs of every combination of an experiment unless passed with `--runs`.
+const EXPERIMENT_RUNS: u64 = 4;
+
+/// Number of steps of every run of an experiment unless passed with `--steps`.
+const EXPERIMENT_STEPS: u32 = 20_000;
+
+/// Evolution of biots competing for sunlight, food and each other.
+#[derive(Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub run: RunArgs,
+}
+
+/// Tasks run instead of a simulation.
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Runs seeded micro-simulations and fails if an invariant is violated.
+    Validate,
+    /// Turns a recording into a self-contained HTML report.
+    Report {
+        /// Recording written with `--record`.
+        recording: String,
+        /// Path of the report, next to the recording by default.
+        #[arg(long)]
+        output: Option<String>,
+    },
+    /// Caches a mature ecosystem for performance work and times a few steps of it.
+    Warm {
+        /// Seed of the ecosystem.
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Number of steps the ecosystem is simulated for.
+        #[arg(long, default_value_t = fixture::MATURE_STEPS)]
+        steps: u32,
+    },
+    /// Runs headless simulations for every combination of swept parameters.
+    Experiment(ExperimentArgs),
+}
+
+/// Options of the `experiment` subcommand.
+#[derive(Debug, Args)]
+pub struct ExperimentArgs {
+    /// A config parameter and the values it is swept over, e.g. `mutation_chance=0.1,0.2`.
+    #[arg(long = "sweep", value_name = "PARAMETER=VALUES")]
+    pub sweeps: Vec<Sweep>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+    /// Number of runs of every combination, with consecutive seeds.
+    #[arg(long, default_value_t = EXPERIMENT_RUNS)]
+    pub runs: u64,
+    /// Seed of the first run of every combination.
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+    /// Number of steps of every run.
+    #[arg(long, default_value_t = EXPERIMENT_STEPS)]
+    pub steps: u32,
+    /// Number of threads, one per core by default.
+    #[arg(long)]
+    pub jobs: Option<usize>,
+    /// Path of the results.
+    #[arg(long, default_value = "experiment.csv")]
+    pub output: String,
+}
+
+/// Options selecting the configuration of the ecosystem.
+#[derive(Debug, Args)]
+pub struct ConfigArgs {
+    /// TOML file with the parameters of the ecosystem.
+    #[arg(long, value_name = "PATH", conflicts_with = "preset")]
+    pub config: Option<String>,
+    /// Curated configuration, see the README.
+    #[arg(long, value_name = "NAME")]
+    pub preset: Option<Preset>,
+}
+
+/// Options of a simulation run.
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Seed of the simulation, random by default.
+    #[arg(long)]
+    pub seed: Option<u64>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+    /// Built-in scenario or scenario file to start from.
+    #[arg(long, value_name = "NAME|PATH", conflicts_with_all = ["config", "preset"])]
+    pub scenario: Option<String>,
+    /// Number of random biots the simulation starts with, overriding the configuration.
+    #[arg(long, conflicts_with = "scenario")]
+    pub population: Option<usize>,
+    /// Comma separated genome files and library directories seeding the initial population.
+    #[arg(long, value_name = "PATHS")]
+    pub genomes: Option<String>,
+    /// Snapshot to continue instead of starting a new simulation.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["config", "preset", "scenario", "population", "genomes"]
+    )]
+    pub load: Option<String>,
+    /// Runs without a window, which builds without the `render` feature always do.
+    #[arg(long)]
+    pub headless: bool,
+    /// Stops a headless run after this many steps instead of at the extinction of the population.
+    #[arg(long)]
+    pub ticks: Option<u64>,
+    /// Snapshot a headless run is autosaved to.
+    #[arg(long, value_name = "PATH")]
+    pub autosave: Option<String>,
+    /// Number of steps between two autosaves.
+    #[arg(long, value_name = "STEPS", default_value_t = AUTOSAVE_INTERVAL)]
+    pub save_every: u64,
+    /// Path the event log is written to at the end of the run.
+    #[arg(long, value_name = "PATH")]
+    pub events: Option<String>,
+    /// Path the family tree of a headless run is exported to.
+    #[arg(long, value_name = "PATH")]
+    pub phylogeny: Option<String>,
+    /// Path the lineage history of a headless run is exported to.
+    #[arg(long, value_name = "PATH")]
+    pub lineages: Option<String>,
+    /// Directory, `s3://` prefix or `cmd:` command the files of a headless run are uploaded to.
+    #[arg(long, value_name = "DESTINATION")]
+    pub upload: Option<String>,
+    /// Rhai script intervening in the steps of the run.
+    #[cfg(feature = "scripting")]
+    #[arg(long, value_name = "PATH")]
+    pub script: Option<String>,
+    /// Address remote observers poll the statistics and snapshots of a headless run at, e.g. `0.0.0.0:8080`.
+    #[cfg(feature = "server")]
+    #[arg(long, value_name = "ADDRESS")]
+    pub serve: Option<String>,
+    #[command(flatten)]
+    pub record: RecordArgs,
+    #[command(flatten)]
+    pub islands: IslandArgs,
+    /// Starts the unattended demo right away.
+    #[cfg(feature = "render")]
+    #[arg(long)]
+    pub demo: bool,
+    /// Simulation steps per second of the viewer, adjustable with `+` and `-`.
+    #[cfg(feature = "render")]
+    #[arg(long, value_name = "STEPS", default_value_t = DEFAULT_TICK_RATE)]
+    pub tps: u32,
+    /// Comma separated fields shown in the labels of the biots when zoomed in.
+    #[cfg(feature = "render")]
+    #[arg(long, value_name = "FIELDS")]
+    pub labels: Option<Labels>,
+    /// Plays sound cues for kills, births and extinctions right away instead
+    /// of starting muted, see `N` in the viewer.
+    #[cfg(feature = "render")]
+    #[arg(long)]
+    pub sound: bool,
+    /// Directory the viewer writes a frame to every `--timelapse-interval` steps.
+    #[cfg(feature = "render")]
+    #[arg(long, value_name = "DIR")]
+    pub timelapse: Option<String>,
+    /// Number of steps between two frames of the timelapse.
+    #[cfg(feature = "render")]
+    #[arg(long, value_name = "STEPS", default_value_t = TIMELAPSE_INTERVAL, requires = "timelapse")]
+    pub timelapse_interval: u32,
+}
+
+/// Options of runs on several islands exchanging migrants.
+#[derive(Debug, Args)]
+pub struct IslandArgs {
+    /// Number of islands with the configuration of the run.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        conflicts_with_all = ["load", "scenario", "island_configs"]
+    )]
+    pub islands: Option<usize>,
+    /// Comma separated configuration files of islands with their own parameters.
+    #[arg(
+        long,
+        value_name = "PATHS",
+        value_delimiter = ',',
+        conflicts_with_all = ["load", "scenario", "config", "preset"]
+    )]
+    pub island_configs: Vec<String>,
+    /// Number of steps between two migrations between the islands, zero disables them.
+    #[arg(long, value_name = "STEPS", default_value_t = Migration::default().interval)]
+    pub migration_interval: u32,
+    /// Fraction of the biots of every island migrating on to the next one.
+    #[arg(long, value_name = "FRACTION", default_value_t = Migration::default().fraction)]
+    pub migration_fraction: f32,
+}
+
+/// Options of the statistics recorded for offline analysis.
+#[derive(Debug, Args)]
+pub struct RecordArgs {
+    /// Path the statistics are recorded to, as JSON lines if it ends in `.json` or `.jsonl`.
+    #[arg(long, value_name = "PATH")]
+    pub record: Option<String>,
+    /// Number of steps between two recorded rows.
+    #[arg(long, value_name = "STEPS", default_value_t = RECORD_INTERVAL)]
+    pub record_interval: u32,
+    /// Comma separated columns to record, all by default.
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    pub record_columns: Vec<Column>,
+    /// Path the state of every single biot is additionally recorded to.
+    #[arg(long, value_name = "PATH", requires = "record")]
+    pub record_biots: Option<String>,
+    /// Path the size distribution is additionally recorded to.
+    #[arg(long, value_name = "PATH", requires = "record")]
+    pub record_sizes: Option<String>,
+}
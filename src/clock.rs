@@ -0,0 +1,76 @@
+//! Fixed timestep of the interactive frontend.
+//!
+//! The simulation advances by a fixed number of steps per second of real
+//! time, whatever the frame rate, so biots move equally fast on every
+//! machine and with or without vsync.
+
+/// Selectable simulation speeds in steps per second, from slow motion to fast-forward.
+pub const TICK_RATES: [u32; 11] = [10, 30, 60, 120, 180, 240, 360, 480, 600, 900, 1200];
+
+/// Speed of new simulations in steps per second.
+pub const DEFAULT_TICK_RATE: u32 = 60;
+
+/// Longest frame time caught up with, slower frames, e.g. hitches, slow the
+/// simulation down instead of piling up steps it could never catch up with.
+const MAX_FRAME_TIME: f32 = 0.1;
+
+/// Accumulates the real time that passed and turns it into simulation steps.
+#[derive(Debug, Clone)]
+pub struct Clock {
+    ticks_per_second: u32,
+    /// Fraction of a step the simulation is behind the time that passed.
+    debt: f32,
+}
+
+impl Clock {
+    /// Creates a clock advancing the simulation by `ticks_per_second` steps per second.
+    pub fn new(ticks_per_second: u32) -> Self {
+        Self {
+            ticks_per_second: ticks_per_second.clamp(1, TICK_RATES[TICK_RATES.len() - 1]),
+            debt: 0.0,
+        }
+    }
+
+    /// Returns the number of simulation steps per second.
+    pub fn ticks_per_second(&self) -> u32 {
+        self.ticks_per_second
+    }
+
+    /// Switches to the next faster of the [`TICK_RATES`], if any.
+    pub fn faster(&mut self) {
+        if let Some(&rate) = TICK_RATES
+            .iter()
+            .find(|&&rate| rate > self.ticks_per_second)
+        {
+            self.ticks_per_second = rate;
+        }
+    }
+
+    /// Switches to the next slower of the [`TICK_RATES`], if any.
+    pub fn slower(&mut self) {
+        if let Some(&rate) = TICK_RATES
+            .iter()
+            .rev()
+            .find(|&&rate| rate < self.ticks_per_second)
+        {
+            self.ticks_per_second = rate;
+        }
+    }
+
+    /// Returns the number of steps due after a frame of `frame_time` seconds.
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        self.debt += frame_time.clamp(0.0, MAX_FRAME_TIME) * self.ticks_per_second as f32;
+        let steps = self.debt.floor();
+        self.debt -= steps;
+        steps as u32
+    }
+
+    /// Returns how far the simulation is into the next step, from `0.0` to `1.0`.
+    ///
+    /// # Note
+    ///
+    /// Used to draw the biots in between the last two steps.
+    pub fn alpha(&self) -> f32 {
+        self.debt
+    }
+}
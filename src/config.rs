@@ -0,0 +1,1627 @@
+use crate::biot::{Gene, MAX_GENOME_LEN, MAX_INTERACTION_RADIUS};
+use crate::current::Flow;
+use crate::migration;
+use crate::obstacle::Obstacle;
+use crate::reserve::Reserve;
+use crate::spawn::SpawnRegion;
+use crate::world::Topology;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Errors that can occur while loading a configuration file.
+#[derive(Debug)]
+pub enum ConfigError {
+    /// Reading the configuration file failed.
+    Io(io::Error),
+    /// The configuration file is not valid TOML or contains unknown parameters.
+    Parse(toml::de::Error),
+    /// The parameters are valid TOML but contradict each other.
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read config: {error}"),
+            Self::Parse(error) => write!(f, "failed to parse config: {error}"),
+            Self::Invalid(message) => write!(f, "invalid config: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(error: toml::de::Error) -> Self {
+        Self::Parse(error)
+    }
+}
+
+/// Tunable parameters of the ecosystem.
+///
+/// # Note
+///
+/// Parameters missing from a configuration file keep their default values,
+/// so a file only needs to list the parameters it changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SimConfig {
+    /// Number of random biots the simulation starts with.
+    pub initial_population: usize,
+    /// Regions the initial population is placed in, anywhere in the world if empty.
+    pub spawn_regions: Vec<SpawnRegion>,
+    /// Chances of the genes of the initial population.
+    pub initial_genes: InitialGenesConfig,
+    /// Chance for each additional mutation of an offspring's genome.
+    pub mutation_chance: f32,
+    /// Kinds of mutations and the evolution of the mutation chance.
+    pub mutations: MutationConfig,
+    /// Neural networks steering intelligent biots.
+    pub brains: BrainConfig,
+    /// Behaviors of intelligent biots switching with their hunger.
+    pub drives: DriveConfig,
+    /// Persistent pursuit of a single prey by intelligent hunters.
+    pub pursuit: PursuitConfig,
+    /// Steering of social biots towards the members of their species.
+    pub flocking: FlockingConfig,
+    /// Recognition of relatives that biots with the kinship gene spare.
+    pub kinship: KinshipConfig,
+    /// Multiple of its base life a biot must accumulate before it reproduces.
+    pub adult_factor: f32,
+    /// Litters of several offsprings born at once.
+    pub litters: LitterConfig,
+    /// Fraction of the net energy gain of a biot that is turned into life.
+    pub energy_efficiency: f32,
+    /// Energy costs of the properties of a biot.
+    pub metabolism: MetabolismConfig,
+    /// How the properties of a biot grow with the genes expressing them.
+    pub responses: ResponseConfig,
+    /// Energy transfer from eaten biots to their predators.
+    pub predation: PredationConfig,
+    /// Pushing between overlapping biots neither of which can eat the other.
+    pub collisions: CollisionConfig,
+    /// Largest distance at which two biots interact, however large they are.
+    ///
+    /// # Note
+    ///
+    /// Biots interact as soon as their bodies touch, so only pairs of very
+    /// large biots are affected. By default it is the largest distance at
+    /// which any two biots can touch, see
+    /// [`MAX_INTERACTION_RADIUS`](crate::biot::MAX_INTERACTION_RADIUS).
+    pub max_interaction_radius: f32,
+    /// A pathogen spreading between biots that touch each other.
+    pub disease: DiseaseConfig,
+    /// Changes of the vigor and metabolism of biots over their life.
+    pub aging: AgingConfig,
+    /// Growth of newborns towards the full size of their genome.
+    pub growth: GrowthConfig,
+    /// Food pellets spawned in the environment.
+    pub food: FoodConfig,
+    /// Remains of dead biots.
+    pub corpses: CorpseConfig,
+    /// Tiles of water, plains and rock covering the world.
+    pub terrain: TerrainConfig,
+    /// Water currents carrying the biots along.
+    pub currents: CurrentsConfig,
+    /// Warm equator and cold poles, see [`TemperatureConfig`].
+    pub temperature: TemperatureConfig,
+    /// Static obstacles biots cannot pass through.
+    pub obstacles: Vec<Obstacle>,
+    /// Protected regions where biots are safe from predators.
+    pub reserves: Vec<Reserve>,
+    /// What happens at the edges of the world.
+    pub topology: Topology,
+    /// Competition of the biots for sunlight.
+    pub light: LightConfig,
+    /// Scent the biots leave behind.
+    pub scent: ScentConfig,
+    /// Days, nights and seasons.
+    pub environment: EnvironmentConfig,
+    /// What happens to biots whose genome gives them no weight.
+    pub nonviable: NonviablePolicy,
+    /// Whether adults clone themselves or mate.
+    pub reproduction: Reproduction,
+    /// Alarm raised when a single strategy dominates the population.
+    pub dominance: DominanceConfig,
+    /// Novel genomes injected now and then to counteract a loss of diversity.
+    pub novelty: NoveltyConfig,
+    /// Feedback control of the population size, see [`ControllerConfig`].
+    pub controller: ControllerConfig,
+    /// Detection of pathological states of unattended runs.
+    pub watchdog: WatchdogConfig,
+    /// Abort if the energy flows of a step do not explain the change of the total life.
+    ///
+    /// # Note
+    ///
+    /// This is a debugging aid for changes of the energy model.
+    pub audit_energy: bool,
+    /// Genes defined by the scenario in addition to the built-in ones.
+    pub genes: Vec<GeneDefinition>,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            initial_population: 600,
+            spawn_regions: Vec::new(),
+            initial_genes: InitialGenesConfig::default(),
+            mutation_chance: 0.2,
+            mutations: MutationConfig::default(),
+            brains: BrainConfig::default(),
+            drives: DriveConfig::default(),
+            pursuit: PursuitConfig::default(),
+            flocking: FlockingConfig::default(),
+            kinship: KinshipConfig::default(),
+            adult_factor: 4.0,
+            litters: LitterConfig::default(),
+            energy_efficiency: 0.4,
+            metabolism: MetabolismConfig::default(),
+            responses: ResponseConfig::default(),
+            predation: PredationConfig::default(),
+            collisions: CollisionConfig::default(),
+            max_interaction_radius: MAX_INTERACTION_RADIUS.0,
+            disease: DiseaseConfig::default(),
+            aging: AgingConfig::default(),
+            growth: GrowthConfig::default(),
+            food: FoodConfig::default(),
+            corpses: CorpseConfig::default(),
+            terrain: TerrainConfig::default(),
+            currents: CurrentsConfig::default(),
+            temperature: TemperatureConfig::default(),
+            obstacles: Vec::new(),
+            reserves: Vec::new(),
+            topology: Topology::Torus,
+            light: LightConfig::default(),
+            scent: ScentConfig::default(),
+            environment: EnvironmentConfig::default(),
+            nonviable: NonviablePolicy::DieAtBirth,
+            reproduction: Reproduction::Asexual,
+            dominance: DominanceConfig::default(),
+            novelty: NoveltyConfig::default(),
+            controller: ControllerConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            audit_energy: false,
+            genes: Vec::new(),
+        }
+    }
+}
+
+impl SimConfig {
+    /// Loads the configuration from the TOML file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses the configuration from the contents of a TOML file.
+    ///
+    /// Files of older versions are upgraded first, see [`migration`].
+    pub fn from_toml(contents: &str) -> Result<Self, ConfigError> {
+        let config: Self = match migration::upgrade_config(contents, &[])? {
+            Some(upgraded) => toml::from_str(&upgraded)?,
+            None => toml::from_str(contents)?,
+        };
+        config.check()?;
+        Ok(config)
+    }
+
+    /// Checks the consistency of a configuration that was deserialized
+    /// without [`SimConfig::from_toml`], e.g. as part of another file.
+    pub fn check(&self) -> Result<(), ConfigError> {
+        self.check_genes()?;
+        self.responses.check()?;
+        if !self.spawn_regions.iter().all(SpawnRegion::is_valid) {
+            let message = "spawn regions need a non-negative extent and weight";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if self.max_interaction_radius.is_nan() || self.max_interaction_radius <= 0.0 {
+            let message = "the maximum interaction radius needs to be positive";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if !self.watchdog.is_valid() {
+            let message = "the watchdog needs a positive interval, a growth of at least 1 and a \
+                           non-negative step time";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if !self.initial_genes.is_valid() {
+            let message =
+                "initial gene chances need to be within `0.0..=1.0` and add up to at most 1";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if !self.currents.is_valid() {
+            let message = "currents need a non-negative strength, positive sizes and flows with a \
+                           non-negative extent and a finite velocity";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if !self.temperature.is_valid() {
+            let message = "temperature needs an equator and poles in `0.0..=1.0`, a drift in \
+                           `0.0..=1.0` and non-negative effects";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if !self.reserves.iter().all(Reserve::is_valid) {
+            let message = "reserves need a non-negative extent and metabolism";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if let Some(genome) = self
+            .novelty
+            .library
+            .iter()
+            .find(|genome| genome.len() > MAX_GENOME_LEN)
+        {
+            let (len, slots) = (genome.len(), MAX_GENOME_LEN);
+            let message = format!("novelty genome of {len} genes exceeds the {slots} slots");
+            return Err(ConfigError::Invalid(message));
+        }
+        if !self.drives.is_valid() {
+            let message = "drives need hunger thresholds in `0.0..=1.0`, the sated one below the \
+                           starving one, and an activity in `0.0..=1.0`";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if !self.flocking.is_valid() {
+            let message = "flocking needs a positive radius and non-negative spacing and weights";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if !self.kinship.is_valid() {
+            let message = "kinship needs a non-negative genome distance";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if !self.litters.is_valid() {
+            let message = "litters need a size of at least 1, a non-negative fecundity factor \
+                           and a minimum investment in `0.0..=1.0`";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if !self.aging.is_valid() {
+            let message = "aging needs ages in `0.0..=1.0` and a positive senescence exponent";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        if !self.growth.is_valid() {
+            let message =
+                "growth needs a newborn size in `0.0..=1.0` above zero and a positive rate";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        Ok(())
+    }
+
+    /// Checks that the custom genes have distinct identifiers and effects a
+    /// built-in gene could have.
+    fn check_genes(&self) -> Result<(), ConfigError> {
+        for (n, gene) in self.genes.iter().enumerate() {
+            let invalid = |message: &str| {
+                let name = &gene.name;
+                Err(ConfigError::Invalid(format!("gene `{name}` {message}")))
+            };
+            if usize::from(gene.id) >= MAX_CUSTOM_GENES {
+                return invalid(&format!("has an id above {}", MAX_CUSTOM_GENES - 1));
+            }
+            if self.genes[..n].iter().any(|other| other.id == gene.id) {
+                return invalid(&format!("reuses the id {}", gene.id));
+            }
+            let valid = |value: f32| value.is_finite() && value >= 0.0;
+            if !gene.effects().into_iter().chain([gene.cost]).all(valid) {
+                return invalid("has a negative or non-finite effect or cost");
+            }
+            if gene.weight() > 1.0 {
+                return invalid("adds more weight than a built-in gene");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Maximum number of custom genes of a scenario.
+pub const MAX_CUSTOM_GENES: usize = 128;
+
+/// A gene defined by a scenario in addition to the built-in ones.
+///
+/// # Note
+///
+/// The effects are given in units of the built-in genes, e.g. an `attack` of
+/// 0.5 makes the gene half as aggressive as an attack gene. Like a built-in
+/// gene, a custom gene adds at most one unit of weight, i.e. of attack,
+/// defense, photosynthesis, motion and armor together.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GeneDefinition {
+    /// Identifier of the gene, stable across versions of the scenario and
+    /// below [`MAX_CUSTOM_GENES`].
+    pub id: u8,
+    /// Name of the gene shown to the user.
+    pub name: String,
+    pub attack: f32,
+    pub defense: f32,
+    pub photosynthesis: f32,
+    pub motion: f32,
+    pub intelligence: f32,
+    pub digestion: f32,
+    pub venom: f32,
+    pub armor: f32,
+    pub camouflage: f32,
+    pub vision: f32,
+    pub reproduction: f32,
+    pub immunity: f32,
+    pub fecundity: f32,
+    pub nurture: f32,
+    pub sociality: f32,
+    pub heat_tolerance: f32,
+    pub kinship: f32,
+    /// Metabolism cost per step on top of the costs of its effects.
+    pub cost: f32,
+}
+
+impl GeneDefinition {
+    /// Returns all effects of the gene.
+    fn effects(&self) -> [f32; 17] {
+        [
+            self.attack,
+            self.defense,
+            self.photosynthesis,
+            self.motion,
+            self.intelligence,
+            self.digestion,
+            self.venom,
+            self.armor,
+            self.camouflage,
+            self.vision,
+            self.reproduction,
+            self.immunity,
+            self.fecundity,
+            self.nurture,
+            self.sociality,
+            self.heat_tolerance,
+            self.kinship,
+        ]
+    }
+
+    /// Returns the weight the gene adds, in units of the weight of a built-in gene.
+    fn weight(&self) -> f32 {
+        self.attack + self.defense + self.photosynthesis + self.motion + self.armor
+    }
+}
+
+/// Policy for biots whose genome gives them no weight, e.g. all-`None` genomes.
+///
+/// # Note
+///
+/// Without any weight a biot has no base life, so it could neither live nor reproduce.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NonviablePolicy {
+    /// Nonviable offsprings die at birth.
+    DieAtBirth,
+    /// Biots get the base life of at least the given weight.
+    BasalWeight(f32),
+}
+
+/// How adult biots produce offsprings.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reproduction {
+    /// Adults clone themselves.
+    Asexual,
+    /// Two adults within the given mating range combine their genomes.
+    ///
+    /// # Note
+    ///
+    /// Both parents pay the same cost as if they cloned themselves, so every
+    /// offspring costs twice as much as with asexual reproduction.
+    Sexual(f32),
+}
+
+/// Litters of several offsprings born at once by biots cloning themselves.
+///
+/// # Note
+///
+/// A litter shares the life of a single offspring among its members, so
+/// biots trade many poorly provisioned offsprings against few well
+/// provisioned ones. The fecundity and nurture genes only evolve while
+/// litters are enabled, see [`Biot::litter`](crate::biot::Biot::litter).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LitterConfig {
+    /// Largest number of offsprings born at once. One disables litters.
+    pub max_size: usize,
+    /// Additional offsprings per unit of fecundity.
+    pub per_fecundity: f32,
+    /// Smallest fraction of its base life an offspring is born with.
+    pub min_investment: f32,
+}
+
+impl Default for LitterConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 1,
+            per_fecundity: 5.0,
+            min_investment: 0.25,
+        }
+    }
+}
+
+impl LitterConfig {
+    /// Returns `true` if biots can bear more than one offspring at once.
+    pub fn is_enabled(&self) -> bool {
+        self.max_size > 1
+    }
+
+    /// Returns `true` if the parameters are within their bounds.
+    fn is_valid(&self) -> bool {
+        self.max_size >= 1
+            && self.per_fecundity.is_finite()
+            && self.per_fecundity >= 0.0
+            && self.min_investment > 0.0
+            && self.min_investment <= 1.0
+    }
+}
+
+/// Chances of the mutations changing the length of a genome or the
+/// expression of a gene, and the evolution of the mutation chance.
+///
+/// # Note
+///
+/// Every mutation is one of these or otherwise replaces a single gene, so
+/// the chances must not add up to more than one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MutationConfig {
+    /// Chance that a mutation inserts a random gene.
+    pub insertion: f32,
+    /// Chance that a mutation deletes a gene.
+    pub deletion: f32,
+    /// Chance that a mutation duplicates a short segment of genes.
+    pub duplication: f32,
+    /// Chance that a mutation slightly raises or lowers the expression of a gene.
+    pub expression: f32,
+    /// Maximum change of the heritable mutation chance from a parent to its offspring.
+    ///
+    /// Zero keeps the mutation chance of all biots at
+    /// [`SimConfig::mutation_chance`], otherwise every genome carries its own
+    /// chance starting from it.
+    pub rate_drift: f32,
+}
+
+impl Default for MutationConfig {
+    fn default() -> Self {
+        Self {
+            insertion: 0.1,
+            deletion: 0.1,
+            duplication: 0.05,
+            expression: 0.4,
+            rate_drift: 0.0,
+        }
+    }
+}
+
+/// Neural networks steering intelligent biots, see [`Brain`](crate::brain::Brain).
+///
+/// # Note
+///
+/// Without brains, intelligent biots head towards the closest food they sense.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BrainConfig {
+    /// Whether the genomes carry brains.
+    pub enabled: bool,
+    /// Chance that an offspring inherits a slightly changed version of each weight.
+    pub mutation_chance: f32,
+    /// Maximum change of a mutated weight.
+    pub weight_step: f32,
+}
+
+impl Default for BrainConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mutation_chance: 0.1,
+            weight_step: 0.3,
+        }
+    }
+}
+
+/// Behaviors of intelligent biots switching with their hunger, see
+/// [`Drive`](crate::biot::Drive).
+///
+/// # Note
+///
+/// The hunger of a biot is the fraction of the life required for
+/// reproduction it lacks. Biots steered by a brain ignore their drives.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DriveConfig {
+    /// Whether intelligent biots follow their drives instead of always foraging.
+    pub enabled: bool,
+    /// Hunger from which on biots hunt regardless of any threats.
+    pub starving: f32,
+    /// Hunger up to which biots save energy and avoid conflicts.
+    pub sated: f32,
+    /// Fraction of their usual activity sated biots move with.
+    pub sated_activity: f32,
+}
+
+impl Default for DriveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            starving: 0.8,
+            sated: 0.25,
+            sated_activity: 0.5,
+        }
+    }
+}
+
+impl DriveConfig {
+    /// Returns `true` if the thresholds and the activity lie in `0.0..=1.0`
+    /// and sated biots are less hungry than starving ones.
+    fn is_valid(&self) -> bool {
+        (0.0..=1.0).contains(&self.starving)
+            && (0.0..=1.0).contains(&self.sated)
+            && (0.0..=1.0).contains(&self.sated_activity)
+            && self.sated < self.starving
+    }
+}
+
+/// Persistent pursuit of a single prey by intelligent hunters.
+///
+/// # Note
+///
+/// Without pursuit, hunters head towards the closest prey they sense and
+/// switch targets whenever another one comes closer. With pursuit, a hunter
+/// locks onto the prey it senses first and chases it, to the position it
+/// was last seen at once it gets out of sight, until it catches it or runs
+/// out of `patience`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PursuitConfig {
+    /// Number of steps a hunter pursues a single prey before giving up. Zero disables pursuit.
+    pub patience: u32,
+}
+
+impl PursuitConfig {
+    /// Returns `true` if hunters lock onto their prey.
+    pub fn is_enabled(&self) -> bool {
+        self.patience > 0
+    }
+}
+
+/// Steering of social biots towards the members of their species, following
+/// the separation, alignment and cohesion forces of classic flocking.
+///
+/// # Note
+///
+/// The sociality gene only evolves while flocking is enabled. Its carriers
+/// add the flocking direction to every move, with the full strength of
+/// their motion at a sociality of one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FlockingConfig {
+    /// Whether the sociality gene evolves.
+    pub enabled: bool,
+    /// Distance within which a biot flocks with the members of its species.
+    pub radius: f32,
+    /// Gap between the bodies of two members below which they keep apart.
+    pub spacing: f32,
+    /// Weight of keeping apart from too close members.
+    pub separation: f32,
+    /// Weight of heading the same way as the other members.
+    pub alignment: f32,
+    /// Weight of heading towards the center of the other members.
+    pub cohesion: f32,
+}
+
+impl Default for FlockingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            radius: 60.0,
+            spacing: 5.0,
+            separation: 1.5,
+            alignment: 1.0,
+            cohesion: 1.0,
+        }
+    }
+}
+
+impl FlockingConfig {
+    /// Returns `true` if the radius is positive and the spacing and weights are not negative.
+    fn is_valid(&self) -> bool {
+        let non_negative = [self.spacing, self.separation, self.alignment, self.cohesion];
+        self.radius.is_finite()
+            && self.radius > 0.0
+            && non_negative
+                .into_iter()
+                .all(|value| value.is_finite() && value >= 0.0)
+    }
+}
+
+/// Recognition of relatives that biots with the kinship gene spare.
+///
+/// # Note
+///
+/// The kinship gene only evolves while kin recognition is enabled. Its
+/// carriers neither hunt nor eat biots whose genome is at most `distance`
+/// genes apart from their own per kinship gene, so cooperative clusters of
+/// relatives can evolve. Relatives are told apart with the genome distance
+/// that also clusters the biots into species.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KinshipConfig {
+    /// Whether the kinship gene evolves.
+    pub enabled: bool,
+    /// Genome distance, in genes, within which a single kinship gene recognises relatives.
+    pub distance: f32,
+}
+
+impl Default for KinshipConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            distance: 3.0,
+        }
+    }
+}
+
+impl KinshipConfig {
+    /// Returns `true` if the distance is not negative.
+    fn is_valid(&self) -> bool {
+        self.distance.is_finite() && self.distance >= 0.0
+    }
+}
+
+/// Energy costs per step of the properties of a biot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MetabolismConfig {
+    /// Factor applied to the sum of all property costs.
+    pub scale: f32,
+    /// Cost per unit of attack.
+    pub attack: f32,
+    /// Cost per unit of defense.
+    pub defense: f32,
+    /// Cost per unit of motion.
+    pub motion: f32,
+    /// Cost per unit of intelligence.
+    pub intelligence: f32,
+    /// Cost per unit of digestion.
+    pub digestion: f32,
+    /// Cost per unit of venom.
+    pub venom: f32,
+    /// Cost per unit of armor.
+    pub armor: f32,
+    /// Cost per unit of camouflage.
+    pub camouflage: f32,
+    /// Cost per unit of vision.
+    pub vision: f32,
+    /// Cost per unit of the reproduction property.
+    pub reproduction: f32,
+    /// Cost per unit of immunity.
+    pub immunity: f32,
+    /// Cost per unit of fecundity.
+    pub fecundity: f32,
+    /// Cost per unit of nurture.
+    pub nurture: f32,
+    /// Cost per unit of sociality.
+    pub sociality: f32,
+    /// Cost per unit of heat tolerance.
+    pub heat_tolerance: f32,
+    /// Cost per unit of kinship.
+    pub kinship: f32,
+    /// Cost per gene of the genome, so that longer genomes have to pay off.
+    pub gene: f32,
+}
+
+impl Default for MetabolismConfig {
+    fn default() -> Self {
+        Self {
+            scale: 0.07,
+            attack: 4.5,
+            defense: 2.3,
+            motion: 2.5,
+            intelligence: 0.1,
+            digestion: 2.0,
+            venom: 3.0,
+            armor: 1.0,
+            camouflage: 3.0,
+            vision: 5.0,
+            reproduction: 3.0,
+            immunity: 2.0,
+            fecundity: 1.0,
+            nurture: 1.0,
+            sociality: 1.0,
+            heat_tolerance: 1.5,
+            kinship: 1.0,
+            gene: 0.02,
+        }
+    }
+}
+
+/// Shape of a [`ResponseCurve`] over the number of genes beyond its threshold.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Shape {
+    /// Every gene adds as much as the previous one.
+    Linear,
+    /// Diminishing returns: `n` genes are worth `sqrt(n)` genes.
+    Sqrt,
+    /// `n` genes are worth `n^exponent` genes, with diminishing returns for
+    /// exponents below 1.
+    Power(f32),
+    /// An S-curve rising to `height` genes worth, steepest at `midpoint` genes.
+    Logistic {
+        midpoint: f32,
+        steepness: f32,
+        height: f32,
+    },
+}
+
+impl Shape {
+    /// Returns the genes worth of the property expressed by `genes` genes.
+    fn apply(self, genes: f32) -> f32 {
+        match self {
+            Self::Linear => genes,
+            Self::Sqrt => genes.sqrt(),
+            Self::Power(exponent) => genes.powf(exponent),
+            Self::Logistic {
+                midpoint,
+                steepness,
+                height,
+            } => height / (1.0 + (-steepness * (genes - midpoint)).exp()),
+        }
+    }
+
+    /// Returns `true` if the parameters of the shape are finite and positive.
+    fn is_valid(self) -> bool {
+        match self {
+            Self::Linear | Self::Sqrt => true,
+            Self::Power(exponent) => exponent.is_finite() && exponent > 0.0,
+            Self::Logistic {
+                midpoint,
+                steepness,
+                height,
+            } => [midpoint, steepness, height]
+                .into_iter()
+                .all(|value| value.is_finite() && value >= 0.0),
+        }
+    }
+}
+
+/// Response of a property to the number of genes expressing it.
+///
+/// # Note
+///
+/// Of `n` genes, the first `threshold` ones have no effect and the others are
+/// worth as many genes as `shape` gives, at most `cap`. No shape gives more
+/// than a linear response though, so that biots never grow beyond
+/// [`MAX_RADIUS`](crate::biot::MAX_RADIUS). The metabolism is still paid
+/// for all `n` genes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResponseCurve {
+    /// Number of genes without any effect.
+    pub threshold: f32,
+    /// Shape of the response beyond the threshold.
+    pub shape: Shape,
+    /// Genes worth of the property no biot grows beyond, unlimited if not given.
+    pub cap: Option<f32>,
+}
+
+impl Default for ResponseCurve {
+    fn default() -> Self {
+        Self::LINEAR
+    }
+}
+
+impl ResponseCurve {
+    /// The curve where every gene adds as much as the previous one.
+    pub const LINEAR: Self = Self {
+        threshold: 0.0,
+        shape: Shape::Linear,
+        cap: None,
+    };
+
+    /// Returns the genes worth of the property expressed by `genes` genes.
+    pub fn apply(&self, genes: f32) -> f32 {
+        let genes = (genes - self.threshold).max(0.0);
+        let effective = self.shape.apply(genes).min(genes);
+        self.cap.map_or(effective, |cap| effective.min(cap))
+    }
+
+    /// Checks that the parameters of the curve are valid.
+    fn check(&self, property: &str) -> Result<(), ConfigError> {
+        let valid = self.threshold.is_finite()
+            && self.threshold >= 0.0
+            && self.shape.is_valid()
+            && self.cap.is_none_or(|cap| cap.is_finite() && cap >= 0.0);
+        if !valid {
+            return Err(ConfigError::Invalid(format!(
+                "response of `{property}` needs a non-negative threshold, cap and shape parameters"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Response curves of the properties of a biot, see [`ResponseCurve`].
+///
+/// # Note
+///
+/// All curves are linear by default, so every gene adds as much as the previous one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResponseConfig {
+    pub attack: ResponseCurve,
+    pub defense: ResponseCurve,
+    pub photosynthesis: ResponseCurve,
+    pub motion: ResponseCurve,
+    pub intelligence: ResponseCurve,
+    pub digestion: ResponseCurve,
+    pub venom: ResponseCurve,
+    pub armor: ResponseCurve,
+    pub camouflage: ResponseCurve,
+    pub vision: ResponseCurve,
+    pub reproduction: ResponseCurve,
+    pub immunity: ResponseCurve,
+    pub fecundity: ResponseCurve,
+    pub nurture: ResponseCurve,
+    pub sociality: ResponseCurve,
+    pub heat_tolerance: ResponseCurve,
+    pub kinship: ResponseCurve,
+}
+
+impl ResponseConfig {
+    /// Checks all response curves.
+    fn check(&self) -> Result<(), ConfigError> {
+        let curves = [
+            ("attack", &self.attack),
+            ("defense", &self.defense),
+            ("photosynthesis", &self.photosynthesis),
+            ("motion", &self.motion),
+            ("intelligence", &self.intelligence),
+            ("digestion", &self.digestion),
+            ("venom", &self.venom),
+            ("armor", &self.armor),
+            ("camouflage", &self.camouflage),
+            ("vision", &self.vision),
+            ("reproduction", &self.reproduction),
+            ("immunity", &self.immunity),
+            ("fecundity", &self.fecundity),
+            ("nurture", &self.nurture),
+            ("sociality", &self.sociality),
+            ("heat_tolerance", &self.heat_tolerance),
+            ("kinship", &self.kinship),
+        ];
+        curves
+            .into_iter()
+            .try_for_each(|(property, curve)| curve.check(property))
+    }
+}
+
+/// Chances of the genes in every slot of the genomes of the random initial population.
+///
+/// # Note
+///
+/// Genes without a chance share the rest of it evenly among the genes
+/// random genomes draw from, see [`Gene::pool`], so a population can start
+/// plant-heavy with just a chance of photosynthesis. Without any chances
+/// all genes are equally likely.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InitialGenesConfig {
+    pub attack: Option<f32>,
+    pub defense: Option<f32>,
+    pub photosynthesis: Option<f32>,
+    pub motion: Option<f32>,
+    pub intelligence: Option<f32>,
+    pub digestion: Option<f32>,
+    pub venom: Option<f32>,
+    pub armor: Option<f32>,
+    pub camouflage: Option<f32>,
+    pub vision: Option<f32>,
+    pub reproduction: Option<f32>,
+    pub immunity: Option<f32>,
+    pub fecundity: Option<f32>,
+    pub nurture: Option<f32>,
+    pub sociality: Option<f32>,
+    pub heat_tolerance: Option<f32>,
+    pub kinship: Option<f32>,
+    /// Chance of an empty slot, which random genomes otherwise never have.
+    pub none: Option<f32>,
+}
+
+impl InitialGenesConfig {
+    /// Returns the genes with a chance and their chances.
+    pub fn chances(&self) -> impl Iterator<Item = (Gene, f32)> {
+        [
+            (Gene::Attack, self.attack),
+            (Gene::Defense, self.defense),
+            (Gene::Photosynthesis, self.photosynthesis),
+            (Gene::Motion, self.motion),
+            (Gene::Intelligence, self.intelligence),
+            (Gene::Digestion, self.digestion),
+            (Gene::Venom, self.venom),
+            (Gene::Armor, self.armor),
+            (Gene::Camouflage, self.camouflage),
+            (Gene::Vision, self.vision),
+            (Gene::Reproduction, self.reproduction),
+            (Gene::Immunity, self.immunity),
+            (Gene::Fecundity, self.fecundity),
+            (Gene::Nurture, self.nurture),
+            (Gene::Sociality, self.sociality),
+            (Gene::HeatTolerance, self.heat_tolerance),
+            (Gene::Kinship, self.kinship),
+            (Gene::None, self.none),
+        ]
+        .into_iter()
+        .filter_map(|(gene, chance)| chance.map(|chance| (gene, chance)))
+    }
+
+    /// Returns `true` if all chances are within `0.0..=1.0` and add up to at most 1.
+    fn is_valid(&self) -> bool {
+        let total = self.chances().map(|(_, chance)| chance).sum::<f32>();
+        self.chances()
+            .all(|(_, chance)| (0.0..=1.0).contains(&chance))
+            && total <= 1.0 + 1e-6
+    }
+}
+
+/// Energy transfer from eaten biots to their predators.
+///
+/// # Note
+///
+/// The energy of the prey that does not reach the predator is lost, like
+/// the heat lost between the levels of a real food chain. Biting predators
+/// wound their prey step by step, giving it a chance to escape.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PredationConfig {
+    /// Fraction of the life of its prey a predator gains.
+    pub efficiency: f32,
+    /// Efficiency a predator gains per unit of digestion.
+    ///
+    /// The digestion gene does not evolve at all if this is zero.
+    pub digestion: f32,
+    /// Life a predator loses per unit of venom of its prey that its armor
+    /// does not shield against.
+    pub venom: f32,
+    /// Life a predator bites off its prey per step and unit of attack
+    /// exceeding the resistance of the prey. Zero lets predators swallow
+    /// their prey whole.
+    pub bite: f32,
+}
+
+impl Default for PredationConfig {
+    fn default() -> Self {
+        Self {
+            efficiency: 0.8,
+            digestion: 0.0,
+            venom: 20.0,
+            bite: 0.0,
+        }
+    }
+}
+
+/// Pushing between overlapping biots neither of which can eat the other.
+///
+/// # Note
+///
+/// Of two biots pushing each other apart, the lighter one gives way further,
+/// so crowds spread out instead of piling up on a single point.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CollisionConfig {
+    /// Fraction of the overlap of two biots resolved per step, in `0.0..=1.0`.
+    /// Zero lets biots overlap freely.
+    pub stiffness: f32,
+}
+
+impl Default for CollisionConfig {
+    fn default() -> Self {
+        Self { stiffness: 0.0 }
+    }
+}
+
+/// A pathogen spreading between biots that touch each other.
+///
+/// # Note
+///
+/// The immunity gene only evolves while the disease is enabled. It lowers
+/// the chance of an infection, see [`Biot::contagion`](crate::biot::Biot::contagion).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DiseaseConfig {
+    /// Chance per step that a carrier infects a biot it touches. Zero disables the disease.
+    pub transmission: f32,
+    /// Fraction of the initial population carrying the pathogen.
+    pub initial_fraction: f32,
+    /// Life a carrier loses per step.
+    pub drain: f32,
+    /// Chance per step that a carrier recovers.
+    pub recovery: f32,
+}
+
+impl Default for DiseaseConfig {
+    fn default() -> Self {
+        Self {
+            transmission: 0.0,
+            initial_fraction: 0.05,
+            drain: 0.02,
+            recovery: 0.005,
+        }
+    }
+}
+
+impl DiseaseConfig {
+    /// Returns `true` if the pathogen spreads at all.
+    pub fn is_enabled(&self) -> bool {
+        self.transmission > 0.0
+    }
+}
+
+/// Changes of the vigor and metabolism of biots over their life.
+///
+/// # Note
+///
+/// Ages are given as fractions of the maximum age. Newborns start with
+/// `juvenile_vigor` and grow to full vigor at `maturity`. From
+/// `senescence_onset` on, their vigor declines towards `senile_vigor` and
+/// their metabolism rises towards `senile_metabolism` at the maximum age.
+/// The vigor scales the attack and motion of a biot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AgingConfig {
+    /// Age at which juveniles reach full vigor. Zero disables the juvenile phase.
+    pub maturity: f32,
+    /// Vigor of a newborn biot.
+    pub juvenile_vigor: f32,
+    /// Age from which biots deteriorate. One disables senescence.
+    pub senescence_onset: f32,
+    /// Exponent of the senescence curve. Above one biots deteriorate slowly
+    /// at first and quickly towards the maximum age.
+    pub senescence_exponent: f32,
+    /// Vigor at the maximum age.
+    pub senile_vigor: f32,
+    /// Factor of the metabolism at the maximum age.
+    pub senile_metabolism: f32,
+}
+
+impl Default for AgingConfig {
+    fn default() -> Self {
+        Self {
+            maturity: 0.0,
+            juvenile_vigor: 0.5,
+            senescence_onset: 1.0,
+            senescence_exponent: 2.0,
+            senile_vigor: 0.3,
+            senile_metabolism: 2.0,
+        }
+    }
+}
+
+impl AgingConfig {
+    /// Returns `true` if the ages lie in `0.0..=1.0` and the exponent is positive.
+    fn is_valid(&self) -> bool {
+        (0.0..=1.0).contains(&self.maturity)
+            && (0.0..=1.0).contains(&self.senescence_onset)
+            && self.senescence_exponent > 0.0
+            && self.juvenile_vigor >= 0.0
+            && self.senile_vigor >= 0.0
+            && self.senile_metabolism >= 0.0
+    }
+
+    /// Returns the progress of senescence at the age fraction `age`, in `0.0..=1.0`.
+    fn senescence(&self, age: f32) -> f32 {
+        if age <= self.senescence_onset {
+            return 0.0;
+        }
+        let progress = (age - self.senescence_onset) / (1.0 - self.senescence_onset);
+        progress.min(1.0).powf(self.senescence_exponent)
+    }
+
+    /// Returns the factor scaling attack and motion at the age fraction `age`.
+    pub fn vigor(&self, age: f32) -> f32 {
+        let growth = if age < self.maturity {
+            self.juvenile_vigor + (1.0 - self.juvenile_vigor) * age / self.maturity
+        } else {
+            1.0
+        };
+        growth * (1.0 + (self.senile_vigor - 1.0) * self.senescence(age))
+    }
+
+    /// Returns the factor scaling the metabolism at the age fraction `age`.
+    pub fn metabolism(&self, age: f32) -> f32 {
+        1.0 + (self.senile_metabolism - 1.0) * self.senescence(age)
+    }
+}
+
+/// Growth of newborns towards the full size of their genome.
+///
+/// # Note
+///
+/// Sizes are given as fractions of the full size, the weight the genes of a
+/// biot add up to. Newborns start at `newborn_size` and grow as long as they
+/// store more life than the base life of their current size, by at most
+/// `rate` per step. Biots never shrink. The current size determines the
+/// weight, radius and speed of a biot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GrowthConfig {
+    /// Size of a newborn biot. One disables growth.
+    pub newborn_size: f32,
+    /// Size a biot gains per step at most.
+    pub rate: f32,
+}
+
+impl Default for GrowthConfig {
+    fn default() -> Self {
+        Self {
+            newborn_size: 1.0,
+            rate: 0.005,
+        }
+    }
+}
+
+impl GrowthConfig {
+    /// Returns `true` if newborns are born smaller than their full size.
+    pub fn is_enabled(&self) -> bool {
+        self.newborn_size < 1.0
+    }
+
+    /// Returns `true` if the newborn size lies in `0.0..=1.0` above zero and the rate is positive.
+    fn is_valid(&self) -> bool {
+        self.newborn_size > 0.0 && self.newborn_size <= 1.0 && self.rate > 0.0
+    }
+
+    /// Returns the size of a biot of size `size` after a step in which it
+    /// stored the fraction `stored` of the base life of its full size.
+    pub fn grow(&self, size: f32, stored: f32) -> f32 {
+        if !self.is_enabled() {
+            return 1.0;
+        }
+        let target = stored.min(1.0);
+        if target <= size {
+            return size;
+        }
+        target.min(size + self.rate)
+    }
+}
+
+/// Food pellets spawned in the environment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FoodConfig {
+    /// Expected number of pellets spawned per step. Zero disables food.
+    pub spawn_rate: f32,
+    /// Life a biot gains by eating a single pellet.
+    pub energy: f32,
+    /// No pellets are spawned while this many lie around uneaten.
+    pub max_pellets: usize,
+}
+
+impl Default for FoodConfig {
+    fn default() -> Self {
+        Self {
+            spawn_rate: 0.2,
+            energy: 5.0,
+            max_pellets: 200,
+        }
+    }
+}
+
+/// Remains of dead biots.
+///
+/// # Note
+///
+/// Only biots dying with life left, e.g. of old age, leave a corpse.
+/// Corpses are scavenged by living biots with attack touching them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CorpseConfig {
+    /// Fraction of its remaining life a dying biot leaves as a corpse. Zero disables corpses.
+    pub fraction: f32,
+    /// Fraction of its energy a corpse loses per step.
+    pub decay: f32,
+}
+
+impl Default for CorpseConfig {
+    fn default() -> Self {
+        Self {
+            fraction: 0.5,
+            decay: 0.005,
+        }
+    }
+}
+
+/// Tiles of water, plains and rock covering the world.
+///
+/// # Note
+///
+/// Water and rock are placed by a random noise. Where they would overlap,
+/// water wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TerrainConfig {
+    /// Size of the square tiles.
+    pub tile_size: f32,
+    /// Typical size of lakes and rocky areas.
+    pub feature_size: f32,
+    /// Fraction of the world covered by water.
+    pub water: f32,
+    /// Fraction of the world covered by rock.
+    pub rock: f32,
+}
+
+impl Default for TerrainConfig {
+    fn default() -> Self {
+        Self {
+            tile_size: 20.0,
+            feature_size: 200.0,
+            water: 0.0,
+            rock: 0.0,
+        }
+    }
+}
+
+/// Water currents carrying the biots along.
+///
+/// # Note
+///
+/// Every step, the water adds a tenth of its velocity to the speed of the
+/// biots in it, so passive drifters move with the currents while strong
+/// swimmers can move against them. The random currents are generated at
+/// the start, the flows apply as long as they are configured.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CurrentsConfig {
+    /// Speed of the fastest random current. Zero leaves the water calm.
+    pub strength: f32,
+    /// Size of the square cells of the random currents.
+    pub cell_size: f32,
+    /// Typical size of the eddies of the random currents.
+    pub feature_size: f32,
+    /// Steady flows within areas of the world, on top of the random currents.
+    pub flows: Vec<Flow>,
+}
+
+impl Default for CurrentsConfig {
+    fn default() -> Self {
+        Self {
+            strength: 0.0,
+            cell_size: 20.0,
+            feature_size: 300.0,
+            flows: Vec::new(),
+        }
+    }
+}
+
+impl CurrentsConfig {
+    /// Returns `true` if any water moves.
+    pub fn is_enabled(&self) -> bool {
+        self.strength > 0.0 || !self.flows.is_empty()
+    }
+
+    /// Returns `true` if the strength is finite and non-negative, the sizes
+    /// are positive and all flows are valid.
+    fn is_valid(&self) -> bool {
+        self.strength.is_finite()
+            && self.strength >= 0.0
+            && self.cell_size > 0.0
+            && self.feature_size > 0.0
+            && self.flows.iter().all(Flow::is_valid)
+    }
+}
+
+/// Temperature across the world, from warm at the equator, a band through
+/// the middle of the world, to cold at its top and bottom edges.
+///
+/// # Note
+///
+/// Temperatures range from 0 (cold) to 1 (hot). Warmth speeds up
+/// photosynthesis, but every degree beyond the heat tolerance of a biot
+/// raises its metabolism. The heat tolerance gene only evolves while the
+/// temperature is enabled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TemperatureConfig {
+    /// Whether the temperature varies and the heat tolerance gene evolves.
+    pub enabled: bool,
+    /// Temperature at the equator.
+    pub equator: f32,
+    /// Temperature at the poles.
+    pub poles: f32,
+    /// Fraction of half the world height the equator moves north in summer
+    /// and south in winter, zero keeps it in place.
+    pub drift: f32,
+    /// Relative change of the photosynthesis per degree above or below 0.5.
+    pub photosynthesis: f32,
+    /// Relative increase of the metabolism per degree beyond the heat tolerance.
+    pub heat_stress: f32,
+}
+
+impl Default for TemperatureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            equator: 1.0,
+            poles: 0.0,
+            drift: 0.0,
+            photosynthesis: 0.5,
+            heat_stress: 1.0,
+        }
+    }
+}
+
+impl TemperatureConfig {
+    /// Returns the factor applied to the photosynthesis at `temperature`.
+    pub fn photosynthesis(&self, temperature: f32) -> f32 {
+        if !self.enabled {
+            return 1.0;
+        }
+        (1.0 + self.photosynthesis * 2.0 * (temperature - 0.5)).max(0.0)
+    }
+
+    /// Returns the factor applied to the metabolism of a biot with
+    /// `heat_tolerance` at `temperature`.
+    pub fn metabolism(&self, temperature: f32, heat_tolerance: f32) -> f32 {
+        if !self.enabled {
+            return 1.0;
+        }
+        1.0 + self.heat_stress * (temperature - heat_tolerance).max(0.0)
+    }
+
+    /// Returns `true` if the temperatures and the drift are within
+    /// `0.0..=1.0` and the effects are not negative.
+    fn is_valid(&self) -> bool {
+        let unit = [self.equator, self.poles, self.drift];
+        unit.into_iter().all(|value| (0.0..=1.0).contains(&value))
+            && [self.photosynthesis, self.heat_stress]
+                .into_iter()
+                .all(|value| value.is_finite() && value >= 0.0)
+    }
+}
+
+/// Competition of the biots for sunlight.
+///
+/// # Note
+///
+/// The bodies of the biots in a cell shade every other biot in the same cell,
+/// reducing their photosynthesis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LightConfig {
+    /// Size of the square cells biots compete for light in.
+    pub cell_size: f32,
+    /// How strongly the canopy of a cell dims the light. Zero disables shading.
+    pub shading: f32,
+}
+
+impl Default for LightConfig {
+    fn default() -> Self {
+        Self {
+            cell_size: 50.0,
+            shading: 0.5,
+        }
+    }
+}
+
+/// Scent the biots leave behind, see [`Field`](crate::field::Field).
+///
+/// # Note
+///
+/// Intelligent biots without any food in sight follow the scent towards
+/// other biots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScentConfig {
+    /// Scent a biot leaves per step and unit of weight. Zero disables the scent.
+    pub deposit: f32,
+    /// Fraction of the scent of a cell spreading to its neighbours per step.
+    pub diffusion: f32,
+    /// Fraction of the scent fading per step.
+    pub decay: f32,
+    /// Size of the square cells of the scent field.
+    pub cell_size: f32,
+}
+
+impl Default for ScentConfig {
+    fn default() -> Self {
+        Self {
+            deposit: 0.0,
+            diffusion: 0.2,
+            decay: 0.05,
+            cell_size: 20.0,
+        }
+    }
+}
+
+/// Days, nights and seasons.
+///
+/// # Note
+///
+/// A length of zero disables the corresponding cycle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnvironmentConfig {
+    /// Number of steps of a full day and night.
+    pub day_length: u32,
+    /// Relative change of the sunlight between noon and midnight.
+    pub daylight_amplitude: f32,
+    /// Number of steps of a full cycle of seasons.
+    pub year_length: u32,
+    /// Relative change of the food spawn rate and metabolism costs between summer and winter.
+    pub season_amplitude: f32,
+}
+
+impl Default for EnvironmentConfig {
+    fn default() -> Self {
+        Self {
+            day_length: 1000,
+            daylight_amplitude: 0.5,
+            year_length: 20000,
+            season_amplitude: 0.2,
+        }
+    }
+}
+
+/// Alarm raised when a single strategy dominates the population.
+///
+/// # Note
+///
+/// Biots whose genomes contain the same number of each gene follow the same
+/// strategy, see [`crate::dominance::Strategy`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DominanceConfig {
+    /// Fraction of the population above which a strategy dominates. One disables the alarm.
+    pub threshold: f32,
+    /// Number of steps a strategy has to dominate before the alarm is raised.
+    pub duration: u32,
+    /// Fraction of the biots following the dominant strategy that are mutated
+    /// when the alarm is raised. Zero only raises the alarm.
+    pub mutation: f32,
+}
+
+impl Default for DominanceConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            duration: 2000,
+            mutation: 0.0,
+        }
+    }
+}
+
+/// Novel genomes injected now and then to counteract a loss of diversity.
+///
+/// # Note
+///
+/// Closed worlds tend to lose their diversity as a few strategies take over.
+/// Every `interval` steps, `count` biots with random genomes, or with genomes
+/// picked from the `library` if it is not empty, are placed at random
+/// positions and found lineages of their own.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NoveltyConfig {
+    /// Number of steps between two injections. Zero disables injections.
+    pub interval: u32,
+    /// Number of biots injected at once.
+    pub count: usize,
+    /// Genomes to pick from, each given as the list of the ids of its genes,
+    /// see [`Gene::id`]. Random genomes are injected if empty.
+    pub library: Vec<Vec<Gene>>,
+}
+
+impl Default for NoveltyConfig {
+    fn default() -> Self {
+        Self {
+            interval: 0,
+            count: 3,
+            library: Vec::new(),
+        }
+    }
+}
+
+impl NoveltyConfig {
+    /// Returns `true` if novel genomes are injected at all.
+    pub fn is_enabled(&self) -> bool {
+        self.interval > 0 && self.count > 0
+    }
+}
+
+/// Feedback control scaling the sunlight and food supply to keep the
+/// population near a target.
+///
+/// # Note
+///
+/// The controller is meant for demos and distorts the dynamics of the
+/// ecosystem. It is disabled by default and flagged in the status line and
+/// the recorded `supply` column whenever it is active.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ControllerConfig {
+    /// Population the controller steers towards. Zero disables the controller.
+    pub target: u32,
+    /// Relative change of the supply per step at a population of zero or twice the target.
+    pub gain: f32,
+    /// Largest factor the supply is scaled by, its inverse is the smallest.
+    pub max_factor: f32,
+}
+
+impl ControllerConfig {
+    /// Returns `true` if the controller adjusts the supply.
+    pub fn is_enabled(&self) -> bool {
+        self.target > 0
+    }
+}
+
+impl Default for ControllerConfig {
+    fn default() -> Self {
+        Self {
+            target: 0,
+            gain: 0.001,
+            max_factor: 4.0,
+        }
+    }
+}
+
+/// Detection of pathological states, see [`Watchdog`](crate::watchdog::Watchdog).
+///
+/// # Note
+///
+/// The watchdog checks every step for biots with a non-finite state and an
+/// inconsistent spatial index, and every `interval` steps for a population
+/// growing too fast.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WatchdogConfig {
+    /// Whether the watchdog checks the simulation at all.
+    pub enabled: bool,
+    /// Number of steps between two checks of the population growth.
+    pub interval: u32,
+    /// Largest factor the population may grow by within `interval` steps.
+    pub max_growth: f32,
+    /// Longest wall time of a single step in seconds. Zero disables the check.
+    pub max_step_seconds: f32,
+    /// What happens once a pathological state is detected.
+    pub action: WatchdogAction,
+}
+
+impl WatchdogConfig {
+    /// Returns `true` if the interval is positive, the growth at least 1 and
+    /// the step time finite and non-negative.
+    fn is_valid(&self) -> bool {
+        self.interval > 0
+            && self.max_growth >= 1.0
+            && self.max_step_seconds.is_finite()
+            && self.max_step_seconds >= 0.0
+    }
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 100,
+            max_growth: 4.0,
+            max_step_seconds: 5.0,
+            action: WatchdogAction::Log,
+        }
+    }
+}
+
+/// What happens once the watchdog detects a pathological state.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchdogAction {
+    /// Print a warning and carry on.
+    #[default]
+    Log,
+    /// Print a warning and pause the viewer, or stop a headless run.
+    Pause,
+    /// Print a warning and save a snapshot of the state for a post-mortem.
+    Checkpoint,
+}
@@ -0,0 +1,180 @@
+//! Panel tuning the running simulation, so experimenting with its parameters
+//! does not require restarting it.
+
+use crate::biot::{Biot, MAX_MUTATION_CHANCE};
+use crate::config::SimConfig;
+use crate::layer::Layer;
+use macroquad::prelude::*;
+
+/// A parameter or view option adjusted by the panel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Setting {
+    MutationChance,
+    /// Factor applied to the sum of all property costs.
+    MetabolismScale,
+    AttackCost,
+    DefenseCost,
+    MotionCost,
+    FoodSpawnRate,
+    /// Simulation steps per second.
+    TickRate,
+    /// Layer shown below the biots.
+    Layer,
+    Trails,
+}
+
+impl Setting {
+    /// All settings in the order they are listed in the panel.
+    pub const ALL: [Setting; 9] = [
+        Self::MutationChance,
+        Self::MetabolismScale,
+        Self::AttackCost,
+        Self::DefenseCost,
+        Self::MotionCost,
+        Self::FoodSpawnRate,
+        Self::TickRate,
+        Self::Layer,
+        Self::Trails,
+    ];
+
+    /// Changes the parameter of `config` by `steps` increments, nothing for view options.
+    pub fn adjust(self, config: &mut SimConfig, steps: f32) {
+        let costs = &mut config.metabolism;
+        match self {
+            Self::MutationChance => {
+                config.mutation_chance =
+                    (config.mutation_chance + 0.01 * steps).clamp(0.0, MAX_MUTATION_CHANCE);
+            }
+            Self::MetabolismScale => costs.scale = (costs.scale + 0.01 * steps).max(0.0),
+            Self::AttackCost => costs.attack = (costs.attack + 0.25 * steps).max(0.0),
+            Self::DefenseCost => costs.defense = (costs.defense + 0.25 * steps).max(0.0),
+            Self::MotionCost => costs.motion = (costs.motion + 0.25 * steps).max(0.0),
+            Self::FoodSpawnRate => {
+                config.food.spawn_rate = (config.food.spawn_rate + 0.1 * steps).max(0.0);
+            }
+            Self::TickRate | Self::Layer | Self::Trails => {}
+        }
+    }
+
+    /// Returns the description of the setting shown in the panel.
+    fn label(self, config: &SimConfig, views: &Views) -> String {
+        let costs = &config.metabolism;
+        match self {
+            Self::MutationChance => format!("mutation chance: {:.2}", config.mutation_chance),
+            Self::MetabolismScale => format!("metabolism scale: {:.2}", costs.scale),
+            Self::AttackCost => format!("attack cost: {:.2}", costs.attack),
+            Self::DefenseCost => format!("defense cost: {:.2}", costs.defense),
+            Self::MotionCost => format!("motion cost: {:.2}", costs.motion),
+            Self::FoodSpawnRate => format!("food spawn rate: {:.1}", config.food.spawn_rate),
+            Self::TickRate => format!("steps per second: {}", views.ticks_per_second),
+            Self::Layer => format!("layer: {}", views.layer.map_or("none", Layer::name)),
+            Self::Trails => format!("trails: {}", if views.trails { "on" } else { "off" }),
+        }
+    }
+}
+
+/// State of the view options shown in the panel.
+#[derive(Debug, Copy, Clone)]
+pub struct Views {
+    pub ticks_per_second: u32,
+    pub layer: Option<Layer>,
+    pub trails: bool,
+}
+
+/// Panel adjusting the parameters of the running simulation and the view.
+///
+/// # Note
+///
+/// `O` toggles the panel, the up and down keys choose a setting and the left
+/// and right keys adjust it. While the panel is shown the arrow keys are
+/// reserved for it.
+pub struct ControlPanel {
+    visible: bool,
+    selected: usize,
+}
+
+impl Default for ControlPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ControlPanel {
+    /// Creates a hidden panel with the first setting selected.
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            selected: 0,
+        }
+    }
+
+    /// Returns `true` if the panel is shown.
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    /// Handles the keys controlling the panel.
+    ///
+    /// Returns the selected setting and the number of increments to adjust
+    /// it by if it was adjusted in this frame.
+    pub fn update(&mut self) -> Option<(Setting, f32)> {
+        if is_key_pressed(KeyCode::O) {
+            self.visible = !self.visible;
+        }
+        if !self.visible {
+            return None;
+        }
+        let len = Setting::ALL.len();
+        if is_key_pressed(KeyCode::Down) {
+            self.selected = (self.selected + 1) % len;
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.selected = (self.selected + len - 1) % len;
+        }
+        let steps = if is_key_pressed(KeyCode::Right) {
+            1.0
+        } else if is_key_pressed(KeyCode::Left) {
+            -1.0
+        } else {
+            return None;
+        };
+        Some((Setting::ALL[self.selected], steps))
+    }
+
+    /// Display the panel right of the filter panel.
+    ///
+    /// The selected biot, if any, is summarized below the settings.
+    pub fn draw(&self, config: &SimConfig, views: &Views, selected: Option<&Biot>) {
+        if !self.visible {
+            return;
+        }
+        let mut lines = vec![(String::from("tune (up/down, left/right):"), LIGHTGRAY)];
+        for (n, setting) in Setting::ALL.iter().enumerate() {
+            let label = setting.label(config, views);
+            let (marker, color) = if n == self.selected {
+                ("> ", YELLOW)
+            } else {
+                ("  ", LIGHTGRAY)
+            };
+            lines.push((format!("{marker}{label}"), color));
+        }
+        let summary = match selected {
+            Some(biot) => format!(
+                "biot {}: life {:.1}, age {}",
+                biot.id().0,
+                biot.stats.life.0,
+                biot.stats.age.0
+            ),
+            None => String::from("no biot selected"),
+        };
+        lines.push((summary, LIGHTGRAY));
+        let (width, line_height) = (260., 18.);
+        let height = line_height * lines.len() as f32 + 10.;
+        let (x, y) = (550., screen_height() - height - 10.);
+        draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.6));
+        draw_rectangle_lines(x, y, width, height, 1., LIGHTGRAY);
+        for (n, (line, color)) in lines.iter().enumerate() {
+            draw_text(line, x + 5., y + line_height * (n + 1) as f32, 16., *color);
+        }
+    }
+}
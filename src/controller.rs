@@ -0,0 +1,47 @@
+//! Feedback control of the population size.
+//!
+//! Meant for demos and screensavers that should neither die out nor
+//! overflow, it distorts the dynamics of the ecosystem and is disabled by
+//! default so that experiments are unaffected.
+
+use crate::config::ControllerConfig;
+use serde::{Deserialize, Serialize};
+
+/// Scales the sunlight and food supply to keep the population near a target.
+///
+/// # Note
+///
+/// The supply changes by a small factor every step, in proportion to the
+/// relative deviation of the population from the target, so it follows
+/// trends rather than single births and deaths.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct PopulationController {
+    /// Factor applied to the sunlight and the food spawn rate.
+    supply: f32,
+}
+
+impl Default for PopulationController {
+    fn default() -> Self {
+        Self { supply: 1.0 }
+    }
+}
+
+impl PopulationController {
+    /// Adjusts the supply to the population after a step.
+    pub fn update(&mut self, population: usize, config: &ControllerConfig) {
+        if !config.is_enabled() {
+            self.supply = 1.0;
+            return;
+        }
+        let target = config.target as f32;
+        let error = ((target - population as f32) / target).clamp(-1.0, 1.0);
+        let max_factor = config.max_factor.max(1.0);
+        self.supply =
+            (self.supply * (config.gain * error).exp()).clamp(1.0 / max_factor, max_factor);
+    }
+
+    /// Returns the factor applied to the sunlight and the food spawn rate.
+    pub fn supply(&self) -> f32 {
+        self.supply
+    }
+}
@@ -0,0 +1,148 @@
+//! Remains of dead biots that decay over time and can be scavenged.
+
+use crate::biot::MAX_INTERACTION_RADIUS;
+use crate::config::CorpseConfig;
+use crate::grid::SpatialGrid;
+use crate::units::{Distance, Energy};
+use crate::world::World;
+use glam::Vec2;
+#[cfg(feature = "render")]
+use macroquad::prelude::{draw_circle, Color};
+use serde::{Deserialize, Serialize};
+
+/// Radius of a corpse.
+const CORPSE_RADIUS: Distance = Distance(2.0);
+
+/// Corpses with less energy than this are fully decomposed.
+const MIN_ENERGY: Energy = Energy(0.01);
+
+/// The remains of a dead biot.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct Corpse {
+    pub pos: Vec2,
+    /// Life a biot gains by scavenging the corpse.
+    pub energy: Energy,
+}
+
+/// The corpses of a simulation.
+#[derive(Serialize, Deserialize)]
+pub struct CorpseCollection {
+    corpses: Vec<Corpse>,
+    /// Spatial index of the corpses used by scavengers.
+    #[serde(skip)]
+    grid: SpatialGrid,
+}
+
+impl Default for CorpseCollection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CorpseCollection {
+    /// Creates a collection without any corpses.
+    pub fn new() -> Self {
+        Self {
+            corpses: Vec::new(),
+            grid: SpatialGrid::default(),
+        }
+    }
+
+    /// Lets the corpses decay as described by `config`.
+    pub fn step(&mut self, world: &World, config: &CorpseConfig) {
+        if !self.grid.is_synced(world, self.corpses.len()) {
+            self.grid = SpatialGrid::new(world, MAX_INTERACTION_RADIUS);
+            self.grid
+                .rebuild(self.corpses.iter().map(|corpse| corpse.pos));
+        }
+        for corpse in &mut self.corpses {
+            corpse.energy = corpse.energy * (1.0 - config.decay);
+            if corpse.energy < MIN_ENERGY {
+                corpse.energy = Energy::ZERO;
+            }
+        }
+        self.remove_empty();
+    }
+
+    /// Leaves a corpse with `energy` at `pos` of `world` unless there is too little energy.
+    ///
+    /// # Note
+    ///
+    /// Corpses left before the spatial index was built, e.g. right after
+    /// loading a snapshot, are indexed by the next step.
+    pub fn leave(&mut self, world: &World, pos: Vec2, energy: Energy) {
+        if energy >= MIN_ENERGY {
+            if self.grid.is_synced(world, self.corpses.len()) {
+                self.grid.push(pos);
+            }
+            self.corpses.push(Corpse { pos, energy });
+        }
+    }
+
+    /// Returns the position of the closest corpse within `radius` of `pos`.
+    pub fn nearest(&self, pos: Vec2, radius: Distance) -> Option<Vec2> {
+        self.grid
+            .within(pos, radius)
+            .min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+            .map(|(idx, _)| self.corpses[idx].pos)
+    }
+
+    /// Scavenges all corpses touching a biot of the given radius at `pos`.
+    ///
+    /// Returns the energy of the scavenged corpses. They are only removed by
+    /// [`CorpseCollection::remove_empty`], but cannot be scavenged twice.
+    pub fn scavenge(&mut self, pos: Vec2, radius: Distance) -> Energy {
+        let mut energy = Energy::ZERO;
+        for (idx, _) in self.grid.within(pos, radius + CORPSE_RADIUS) {
+            let corpse = &mut self.corpses[idx];
+            energy += corpse.energy;
+            corpse.energy = Energy::ZERO;
+        }
+        energy
+    }
+
+    /// Removes the scavenged and fully decomposed corpses.
+    pub fn remove_empty(&mut self) {
+        let corpses = &self.corpses;
+        self.grid.retain(|idx| corpses[idx].energy > Energy::ZERO);
+        self.corpses.retain(|corpse| corpse.energy > Energy::ZERO);
+    }
+
+    /// The number of corpses currently in the environment.
+    pub fn len(&self) -> usize {
+        self.corpses.len()
+    }
+
+    /// Returns `true` if the environment has no corpses.
+    pub fn is_empty(&self) -> bool {
+        self.corpses.is_empty()
+    }
+
+    /// Moves every corpse to the position `remap` returns for it and removes
+    /// the ones it returns `None` for.
+    pub fn remap(&mut self, remap: impl Fn(Vec2) -> Option<Vec2>) {
+        self.corpses.retain_mut(|corpse| match remap(corpse.pos) {
+            Some(pos) => {
+                corpse.pos = pos;
+                true
+            }
+            None => false,
+        });
+        // The next step rebuilds the spatial index.
+        self.grid = SpatialGrid::default();
+    }
+}
+
+#[cfg(feature = "render")]
+impl CorpseCollection {
+    /// Display the corpses, repeated at the given offsets for a wrapped world.
+    pub fn draw(&self, offsets: &[Vec2]) {
+        let color = Color::new(0.45, 0.35, 0.25, 1.0);
+        for offset in offsets {
+            for corpse in &self.corpses {
+                let pos = corpse.pos + *offset;
+                draw_circle(pos.x, pos.y, CORPSE_RADIUS.0, color);
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Water currents carrying the biots along.
+//!
+//! The random currents follow the contour lines of a smooth noise, so they
+//! swirl around in eddies without piling the biots up anywhere. Flows laid
+//! over areas of the world add to them, e.g. a river crossing a lake.
+
+use crate::config::CurrentsConfig;
+use crate::rng::Rng;
+use crate::spawn::Area;
+use crate::terrain::ValueNoise;
+use crate::world::World;
+use glam::{vec2, Vec2};
+#[cfg(feature = "render")]
+use macroquad::prelude::{draw_line, Color};
+use serde::{Deserialize, Serialize};
+
+/// Fraction of the velocity of the current added to the speed of a biot per step.
+///
+/// Balances the friction of the biots, so that passive drifters move at
+/// nearly the speed of the current.
+pub const DRAG: f32 = 0.1;
+
+/// Number of segments of a streamline shown for the currents.
+#[cfg(feature = "render")]
+const STREAMLINE_SEGMENTS: usize = 6;
+
+/// A steady flow within an area of the world.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Flow {
+    pub area: Area,
+    /// Distance the flow carries a passive biot per step.
+    pub velocity: Vec2,
+}
+
+impl Default for Flow {
+    fn default() -> Self {
+        Self {
+            area: Area::Rectangle {
+                min: Vec2::ZERO,
+                max: Vec2::ZERO,
+            },
+            velocity: Vec2::ZERO,
+        }
+    }
+}
+
+impl Flow {
+    /// Returns `true` if the flow has a valid area and a finite velocity.
+    pub fn is_valid(&self) -> bool {
+        self.area.is_valid() && self.velocity.is_finite()
+    }
+}
+
+/// The velocities of the random currents in square cells covering the world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Currents {
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    /// Velocity of each cell row by row, empty if the water is calm.
+    velocities: Vec<Vec2>,
+}
+
+impl Currents {
+    /// Generates random currents for `world` as described by `config`.
+    ///
+    /// # Note
+    ///
+    /// Calm water draws no random numbers, so the rest of the simulation
+    /// evolves the same as without currents.
+    pub fn generate(world: &World, config: &CurrentsConfig, rng: &mut Rng) -> Self {
+        let cell_size = config.cell_size.max(1.0);
+        let columns = (world.width / cell_size).ceil().max(1.0) as usize;
+        let rows = (world.height / cell_size).ceil().max(1.0) as usize;
+        let mut currents = Self {
+            cell_size,
+            columns,
+            rows,
+            velocities: Vec::new(),
+        };
+        if config.strength <= 0.0 {
+            return currents;
+        }
+        let noise = ValueNoise::new(world, config.feature_size.max(cell_size), rng);
+        let bounds = vec2(world.width, world.height);
+        let height = |pos: Vec2| noise.sample(pos.clamp(Vec2::ZERO, bounds));
+        let (dx, dy) = (vec2(cell_size / 2.0, 0.0), vec2(0.0, cell_size / 2.0));
+        // Flowing along the contour lines, i.e. perpendicular to the gradient.
+        let mut velocities = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .map(|(row, column)| {
+                let center = vec2(column as f32 + 0.5, row as f32 + 0.5) * cell_size;
+                let slope_x = height(center + dx) - height(center - dx);
+                let slope_y = height(center + dy) - height(center - dy);
+                vec2(slope_y, -slope_x)
+            })
+            .collect::<Vec<_>>();
+        let fastest = velocities
+            .iter()
+            .map(|velocity| velocity.length())
+            .fold(0.0, f32::max);
+        if fastest > 0.0 {
+            for velocity in &mut velocities {
+                *velocity *= config.strength / fastest;
+            }
+        }
+        currents.velocities = velocities;
+        currents
+    }
+
+    /// Returns the currents covering the resized `world`, each cell copied
+    /// from the one at the position `source` maps its center to.
+    ///
+    /// Beyond the edges of the old world its edge cells continue.
+    pub fn resized(&self, world: &World, source: impl Fn(Vec2) -> Vec2) -> Self {
+        let columns = (world.width / self.cell_size).ceil().max(1.0) as usize;
+        let rows = (world.height / self.cell_size).ceil().max(1.0) as usize;
+        let velocities = if self.velocities.is_empty() {
+            Vec::new()
+        } else {
+            (0..rows)
+                .flat_map(|row| (0..columns).map(move |column| (row, column)))
+                .map(|(row, column)| {
+                    let center = vec2(column as f32 + 0.5, row as f32 + 0.5) * self.cell_size;
+                    self.random_velocity(source(center))
+                })
+                .collect()
+        };
+        Self {
+            cell_size: self.cell_size,
+            columns,
+            rows,
+            velocities,
+        }
+    }
+
+    /// Returns `true` if there are no random currents.
+    pub fn is_calm(&self) -> bool {
+        self.velocities.is_empty()
+    }
+
+    /// Returns the velocity of the water at `pos`, adding up the random
+    /// currents and the `flows` over it.
+    pub fn velocity(&self, flows: &[Flow], pos: Vec2) -> Vec2 {
+        flows
+            .iter()
+            .filter(|flow| flow.area.contains(pos))
+            .fold(self.random_velocity(pos), |velocity, flow| {
+                velocity + flow.velocity
+            })
+    }
+
+    /// Returns the velocity of the random currents at `pos`.
+    fn random_velocity(&self, pos: Vec2) -> Vec2 {
+        if self.velocities.is_empty() {
+            return Vec2::ZERO;
+        }
+        // Casts saturate, so negative and NaN coordinates end up in the first cell.
+        let column = ((pos.x / self.cell_size) as usize).min(self.columns - 1);
+        let row = ((pos.y / self.cell_size) as usize).min(self.rows - 1);
+        self.velocities[row * self.columns + column]
+    }
+}
+
+#[cfg(feature = "render")]
+impl Currents {
+    /// Display the water in `world` as short streamlines following it,
+    /// repeated at the given offsets for a wrapped world.
+    pub fn draw(&self, flows: &[Flow], world: &World, offsets: &[Vec2]) {
+        let spacing = (self.cell_size * 2.0).max(40.0);
+        let columns = (world.width / spacing).ceil() as usize;
+        let rows = (world.height / spacing).ceil() as usize;
+        let segment = spacing / STREAMLINE_SEGMENTS as f32;
+        for offset in offsets {
+            for (row, column) in (0..rows).flat_map(|row| (0..columns).map(move |c| (row, c))) {
+                let mut pos = vec2(column as f32 + 0.5, row as f32 + 0.5) * spacing;
+                for n in 0..STREAMLINE_SEGMENTS {
+                    let Some(dir) = self.velocity(flows, pos).try_normalize() else {
+                        break;
+                    };
+                    let next = pos + dir * segment;
+                    // Brightening towards the head shows which way the water flows.
+                    let alpha = 0.5 * (n + 1) as f32 / STREAMLINE_SEGMENTS as f32;
+                    let (from, to) = (pos + *offset, next + *offset);
+                    draw_line(
+                        from.x,
+                        from.y,
+                        to.x,
+                        to.y,
+                        1.5,
+                        Color::new(0.6, 0.85, 1.0, alpha),
+                    );
+                    pos = next;
+                }
+            }
+        }
+    }
+}
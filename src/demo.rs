@@ -0,0 +1,103 @@
+//! Unattended demo mode, e.g. for a lobby display.
+//!
+//! The demo runs a balanced ecosystem without any overlays, follows the
+//! hotspots of the world with the auto-camera and starts over whenever the
+//! population goes extinct. Any key or mouse button ends it.
+
+use crate::camera::Camera;
+use crate::clock::{Clock, DEFAULT_TICK_RATE};
+use crate::config::SimConfig;
+use crate::filter::Filter;
+use crate::hotspot::AutoCamera;
+use crate::interpolation::Interpolation;
+use crate::preset::Preset;
+use crate::scene::{Context, Scene, Transition};
+use crate::simulation::Simulation;
+use crate::species::Coloring;
+use macroquad::prelude::*;
+
+/// Number of seconds of a full cycle of zooming in and out.
+const ZOOM_SECONDS: f64 = 120.0;
+
+/// Returns the configuration of the demo ecosystem.
+///
+/// # Note
+///
+/// The population controller keeps the balanced savanna preset from dying
+/// out or overcrowding the screen, unless it already configures one.
+fn demo_config() -> SimConfig {
+    let mut config = Preset::Savanna.config().unwrap_or_default();
+    if !config.controller.is_enabled() {
+        config.controller.target = config.initial_population as u32;
+    }
+    config
+}
+
+/// Scene running the demo.
+pub struct Demo {
+    simulation: Simulation,
+    camera: Camera,
+    auto_camera: AutoCamera,
+    /// Advances the simulation at the same speed at any frame rate.
+    clock: Clock,
+    /// Positions the biots are drawn at in between two steps.
+    interpolation: Interpolation,
+}
+
+impl Demo {
+    /// Creates the demo with a new simulation.
+    pub fn new(context: &mut Context) -> Self {
+        let simulation = context.new_simulation_with(demo_config());
+        Self {
+            camera: Camera::new(simulation.world()),
+            auto_camera: AutoCamera::new(simulation.world()),
+            clock: Clock::new(DEFAULT_TICK_RATE),
+            interpolation: Interpolation::new(),
+            simulation,
+        }
+    }
+
+    /// Returns `true` if any key or mouse button was pressed in this frame.
+    fn interrupted() -> bool {
+        get_last_key_pressed().is_some()
+            || [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+                .into_iter()
+                .any(is_mouse_button_pressed)
+    }
+}
+
+impl Scene for Demo {
+    fn frame(&mut self, context: &mut Context) -> Transition {
+        if Self::interrupted() {
+            return Transition::Pop;
+        }
+        if self.simulation.biots().is_empty() {
+            *self = Self::new(context);
+        }
+        let steps = self.clock.advance(get_frame_time());
+        for n in 0..steps {
+            if n + 1 == steps {
+                self.interpolation.capture(self.simulation.biots());
+            }
+            self.simulation.step();
+            self.auto_camera.record(&self.simulation);
+        }
+        self.interpolation.set_alpha(self.clock.alpha());
+        let zoom = 1.5 - 0.5 * (std::f64::consts::TAU * get_time() / ZOOM_SECONDS).cos() as f32;
+        let world = self.simulation.world();
+        self.auto_camera.steer(&mut self.camera, world, zoom);
+        let config = &self.simulation.biots().config().environment;
+        clear_background(self.simulation.environment().background(config));
+        self.camera.activate();
+        self.simulation.draw(
+            &self.camera,
+            &Filter::new(),
+            Coloring::Traits,
+            false,
+            None,
+            &self.interpolation,
+        );
+        set_default_camera();
+        Transition::Stay
+    }
+}
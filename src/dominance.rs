@@ -0,0 +1,118 @@
+//! Detection of a single strategy taking over the population.
+//!
+//! Runs where one genome outcompetes all others tend to stall, so the
+//! monitor raises an alarm once a strategy dominated the population for a
+//! while, optionally answered by mutating some of its followers.
+
+use crate::biot::Gene;
+use crate::biot_collection::BiotCollection;
+use crate::config::DominanceConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Number of steps between two checks of the population.
+const CHECK_INTERVAL: u32 = 10;
+
+/// How often each of the [`Gene::EXPRESSED`] genes occurs in the genomes following a strategy.
+///
+/// # Note
+///
+/// Genomes with the same gene counts express similar properties regardless
+/// of the order and the exact expression of their genes, so they count as
+/// the same strategy.
+pub type Strategy = [u32; Gene::EXPRESSED.len()];
+
+/// Raised when a strategy dominated the population for the configured duration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alarm {
+    pub strategy: Strategy,
+    /// Fraction of the population following the strategy.
+    pub share: f32,
+    /// Number of biots whose genome was mutated in response.
+    pub mutated: usize,
+}
+
+impl fmt::Display for Alarm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let genes = Gene::EXPRESSED
+            .iter()
+            .zip(self.strategy)
+            .filter(|(_, count)| *count > 0)
+            .map(|(gene, count)| format!("{gene:?} {count}"))
+            .collect::<Vec<_>>();
+        write!(
+            f,
+            "strategy [{}] dominates {:.0}% of the population",
+            genes.join(", "),
+            self.share * 100.0
+        )?;
+        if self.mutated > 0 {
+            write!(f, ", mutated {} of its biots", self.mutated)?;
+        }
+        Ok(())
+    }
+}
+
+/// Tracks how long the most common strategy has dominated the population.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DominanceMonitor {
+    /// The strategy currently dominating the population, if any.
+    dominant: Option<Strategy>,
+    /// Step at which the dominant strategy took over.
+    since: u32,
+    /// Whether the alarm was already raised for the dominant strategy.
+    raised: bool,
+    /// Number of steps monitored so far.
+    steps: u32,
+}
+
+impl DominanceMonitor {
+    /// Creates a monitor of a population without a dominant strategy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks the biots after a simulation step.
+    ///
+    /// Returns an alarm once a strategy dominated the population for the
+    /// configured duration. It is raised only once until the strategy loses
+    /// its dominance.
+    pub fn check(&mut self, biots: &BiotCollection, config: &DominanceConfig) -> Option<Alarm> {
+        self.steps += 1;
+        if !self.steps.is_multiple_of(CHECK_INTERVAL) {
+            return None;
+        }
+        let Some((strategy, share)) = dominant_strategy(biots, config.threshold) else {
+            self.dominant = None;
+            return None;
+        };
+        if self.dominant != Some(strategy) {
+            self.dominant = Some(strategy);
+            self.since = self.steps;
+            self.raised = false;
+        }
+        if self.raised || self.steps - self.since < config.duration {
+            return None;
+        }
+        self.raised = true;
+        Some(Alarm {
+            strategy,
+            share,
+            mutated: 0,
+        })
+    }
+}
+
+/// Returns the most common strategy and its share if it is followed by more
+/// than `threshold` of the biots.
+fn dominant_strategy(biots: &BiotCollection, threshold: f32) -> Option<(Strategy, f32)> {
+    // Ordered, so that ties are broken the same way in every run.
+    let mut counts = BTreeMap::<Strategy, usize>::new();
+    for biot in biots.iter() {
+        *counts.entry(biot.genome().expressed_counts()).or_default() += 1;
+    }
+    let (strategy, count) = counts.into_iter().max_by_key(|(_, count)| *count)?;
+    let share = count as f32 / biots.len() as f32;
+    (share > threshold).then_some((strategy, share))
+}
@@ -0,0 +1,155 @@
+//! Periodic changes of the environment: days, nights and seasons.
+
+use crate::config::{ControllerConfig, EnvironmentConfig, SimConfig};
+use crate::controller::PopulationController;
+use crate::world::World;
+use glam::Vec2;
+#[cfg(feature = "render")]
+use macroquad::prelude::Color;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::TAU;
+use std::fmt;
+
+/// A date of the simulation calendar, counting from year 1, day 1.
+///
+/// A day lasts [`EnvironmentConfig::day_length`] steps and a year
+/// [`EnvironmentConfig::year_length`] steps.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Date {
+    pub year: u64,
+    /// Day within the year.
+    pub day: u64,
+}
+
+impl Default for Date {
+    fn default() -> Self {
+        Self { year: 1, day: 1 }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "year {}, day {}", self.year, self.day)
+    }
+}
+
+/// The clock driving the cycles of the environment.
+///
+/// # Note
+///
+/// All cycles are sine waves around the configured values, so their average
+/// effect over a full cycle is neutral.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+pub struct Environment {
+    /// Number of steps since the start of the simulation.
+    steps: u64,
+    /// Scales the sunlight and food supply if enabled.
+    controller: PopulationController,
+}
+
+impl Environment {
+    /// Creates an environment at the start of the first day.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advances the clock by a single step.
+    pub fn tick(&mut self) {
+        self.steps += 1;
+    }
+
+    /// Lets the population controller react to the current `population`.
+    pub fn regulate(&mut self, population: usize, config: &ControllerConfig) {
+        self.controller.update(population, config);
+    }
+
+    /// Returns the factor the population controller applies to the sunlight and food supply.
+    pub fn supply(&self) -> f32 {
+        self.controller.supply()
+    }
+
+    /// Returns the current date of the calendar.
+    ///
+    /// Disabled cycles never advance their part of the date.
+    pub fn date(&self, config: &EnvironmentConfig) -> Date {
+        let (year, in_year) = match u64::from(config.year_length) {
+            0 => (0, self.steps),
+            length => (self.steps / length, self.steps % length),
+        };
+        let day = match u64::from(config.day_length) {
+            0 => 0,
+            length => in_year / length,
+        };
+        Date {
+            year: year + 1,
+            day: day + 1,
+        }
+    }
+
+    /// Returns the phase of a cycle of `length` steps as sine wave in `-1..=1`.
+    ///
+    /// Cycles of length zero are disabled and always return zero.
+    fn wave(&self, length: u32) -> f32 {
+        if length == 0 {
+            return 0.0;
+        }
+        let phase = (self.steps % u64::from(length)) as f32 / length as f32;
+        (TAU * phase).sin()
+    }
+
+    /// Returns the factor applied to the sunlight, above 1 at day and below at night.
+    ///
+    /// It includes the supply of the population controller.
+    pub fn daylight(&self, config: &EnvironmentConfig) -> f32 {
+        let daylight = (1.0 + config.daylight_amplitude * self.wave(config.day_length)).max(0.0);
+        daylight * self.supply()
+    }
+
+    /// Returns the seasonal wave, positive in summer and negative in winter.
+    pub fn season(&self, config: &EnvironmentConfig) -> f32 {
+        config.season_amplitude * self.wave(config.year_length)
+    }
+
+    /// Returns the temperature in `0..=1` at `pos` in `world`, falling from
+    /// the equator towards the poles, see [`crate::config::TemperatureConfig`].
+    ///
+    /// The equator drifts north in summer and south in winter.
+    pub fn temperature(&self, config: &SimConfig, world: &World, pos: Vec2) -> f32 {
+        let temperature = &config.temperature;
+        let half_height = world.height / 2.0;
+        let drift = temperature.drift * self.wave(config.environment.year_length);
+        let equator = half_height * (1.0 - drift);
+        let latitude = ((pos.y - equator).abs() / half_height).min(1.0);
+        temperature.equator + (temperature.poles - temperature.equator) * latitude
+    }
+
+    /// Returns the configuration adjusted to the current season and the
+    /// supply of the population controller.
+    ///
+    /// Summers bring more food and lower metabolism costs, winters the opposite.
+    pub fn adjust(&self, config: &SimConfig) -> SimConfig {
+        let season = self.season(&config.environment);
+        let mut adjusted = config.clone();
+        let spawn_rate = config.food.spawn_rate * (1.0 + season) * self.supply();
+        adjusted.food.spawn_rate = spawn_rate.max(0.0);
+        adjusted.metabolism.scale = (config.metabolism.scale * (1.0 - season)).max(0.0);
+        adjusted
+    }
+}
+
+#[cfg(feature = "render")]
+impl Environment {
+    /// Returns the background color showing the time of day and the season.
+    pub fn background(&self, config: &EnvironmentConfig) -> Color {
+        let day = self.wave(config.day_length) * config.daylight_amplitude.min(1.0);
+        let season = self.wave(config.year_length) * config.season_amplitude.min(1.0);
+        // Nights are darker, summers greener and winters bluer.
+        let brightness = 0.06 + 0.04 * day;
+        Color::new(
+            brightness * 0.5,
+            brightness * (1.0 + 0.5 * season),
+            brightness * (1.5 - 0.5 * season) + 0.04,
+            1.0,
+        )
+    }
+}
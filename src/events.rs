@@ -0,0 +1,282 @@
+//! Log of the notable events of a simulation.
+//!
+//! The log observes the simulation after every step and keeps its latest
+//! events in a ring buffer, so that the viewer can notify about them and
+//! the full log can be written to a file for later analysis.
+
+use crate::biot::{BiotId, CauseOfDeath};
+use crate::simulation::Simulation;
+use crate::species::SpeciesId;
+use crate::units::Ticks;
+#[cfg(feature = "render")]
+use macroquad::prelude::{
+    draw_rectangle, draw_text, get_time, measure_text, screen_height, Color, GOLD,
+};
+use std::collections::{BTreeSet, VecDeque};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Number of events kept by the log before the oldest ones are dropped.
+pub const EVENT_CAPACITY: usize = 100_000;
+
+/// Age below which record ages are not logged, so that the first deaths of
+/// a simulation do not all set records.
+const MIN_RECORD_AGE: Ticks = Ticks(1000);
+
+/// Fraction of the highest population the population has to drop below for
+/// the highest one to count as a peak.
+const PEAK_DROP: f32 = 0.9;
+
+/// Number of seconds a notification is shown.
+#[cfg(feature = "render")]
+const TOAST_SECONDS: f64 = 6.0;
+
+/// Maximum number of notifications shown at once.
+#[cfg(feature = "render")]
+const MAX_TOASTS: usize = 4;
+
+/// Something that happened during a simulation step.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// An offspring was born into the lineage of its parent.
+    Birth { id: BiotId, lineage: BiotId },
+    /// A biot died.
+    Death {
+        id: BiotId,
+        cause: CauseOfDeath,
+        age: Ticks,
+    },
+    /// The last member of a species died or joined another species.
+    Extinction { species: SpeciesId },
+    /// A biot died older than any biot before it.
+    RecordAge { id: BiotId, age: Ticks },
+    /// The population reached a maximum and then dropped considerably.
+    PopulationPeak { population: usize },
+}
+
+impl Event {
+    /// Returns `true` for the rare events worth a notification, i.e. all but births and deaths.
+    pub fn is_notable(&self) -> bool {
+        !matches!(self, Self::Birth { .. } | Self::Death { .. })
+    }
+
+    /// Returns the kind, the biot and the value of the event as CSV fields.
+    fn fields(&self) -> (&'static str, Option<BiotId>, String) {
+        match *self {
+            Self::Birth { id, lineage } => ("birth", Some(id), lineage.0.to_string()),
+            Self::Death { id, cause, age } => ("death", Some(id), format!("{cause:?} {}", age.0)),
+            Self::Extinction { species } => ("extinction", None, species.0.to_string()),
+            Self::RecordAge { id, age } => ("record_age", Some(id), age.0.to_string()),
+            Self::PopulationPeak { population } => {
+                ("population_peak", None, population.to_string())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Birth { id, lineage } => {
+                write!(f, "biot {} was born into lineage {}", id.0, lineage.0)
+            }
+            Self::Death { id, cause, age } => {
+                write!(f, "biot {} died of {cause:?} at age {}", id.0, age.0)
+            }
+            Self::Extinction { species } => write!(f, "species {} went extinct", species.0),
+            Self::RecordAge { id, age } => {
+                write!(f, "biot {} died at the record age of {}", id.0, age.0)
+            }
+            Self::PopulationPeak { population } => {
+                write!(f, "the population peaked at {population} biots")
+            }
+        }
+    }
+}
+
+/// An event together with the step it happened in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LoggedEvent {
+    pub step: u32,
+    pub event: Event,
+}
+
+impl fmt::Display for LoggedEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step {}: {}", self.step, self.event)
+    }
+}
+
+/// Ring buffer of the latest events of a simulation.
+///
+/// # Note
+///
+/// Births are detected by the increasing identifiers of the biots, so
+/// offsprings dying within the step they are born in are not logged.
+/// Extinctions are only noticed when the species are reassigned, see
+/// [`SPECIES_INTERVAL`](crate::species::SPECIES_INTERVAL).
+#[derive(Debug, Clone)]
+pub struct EventLog {
+    events: VecDeque<LoggedEvent>,
+    capacity: usize,
+    /// Number of events added by the last observation.
+    added: usize,
+    /// Identifier of the youngest biot observed so far.
+    youngest: Option<BiotId>,
+    /// Species with living members as of the last observation.
+    species: BTreeSet<SpeciesId>,
+    /// Highest age any biot died at so far.
+    record_age: Ticks,
+    /// Highest population since the last peak.
+    highest: usize,
+}
+
+impl EventLog {
+    /// Creates an empty log keeping at most `capacity` events.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::new(),
+            capacity: capacity.max(1),
+            added: 0,
+            youngest: None,
+            species: BTreeSet::new(),
+            record_age: Ticks(0),
+            highest: 0,
+        }
+    }
+
+    /// Records the events of the last step of `simulation`.
+    ///
+    /// # Note
+    ///
+    /// The biots present at the first observation are not logged as births.
+    pub fn record(&mut self, simulation: &Simulation) {
+        let biots = simulation.biots();
+        let step = biots.steps();
+        let mut events = Vec::new();
+        let youngest = self.youngest;
+        if youngest.is_some() {
+            events.extend(
+                biots
+                    .iter()
+                    .filter(|biot| Some(biot.id()) > youngest && biot.lineage() != biot.id())
+                    .map(|biot| Event::Birth {
+                        id: biot.id(),
+                        lineage: biot.lineage(),
+                    }),
+            );
+        }
+        self.youngest = biots.iter().map(|biot| biot.id()).max().max(youngest);
+        for obituary in biots.obituaries() {
+            let (id, age) = (obituary.id, obituary.age);
+            events.push(Event::Death {
+                id,
+                cause: obituary.cause,
+                age,
+            });
+            if age > self.record_age {
+                self.record_age = age;
+                if age >= MIN_RECORD_AGE {
+                    events.push(Event::RecordAge { id, age });
+                }
+            }
+        }
+        let species = biots.iter().filter_map(|biot| biot.species()).collect();
+        events.extend(
+            self.species
+                .difference(&species)
+                .map(|&species| Event::Extinction { species }),
+        );
+        self.species = species;
+        let population = biots.len();
+        if (population as f32) < PEAK_DROP * self.highest as f32 {
+            events.push(Event::PopulationPeak {
+                population: self.highest,
+            });
+            self.highest = population;
+        }
+        self.highest = self.highest.max(population);
+        self.added = events.len();
+        for event in events {
+            if self.events.len() == self.capacity {
+                self.events.pop_front();
+            }
+            self.events.push_back(LoggedEvent { step, event });
+        }
+    }
+
+    /// Returns the logged events, from the oldest to the latest.
+    pub fn iter(&self) -> impl Iterator<Item = &LoggedEvent> + '_ {
+        self.events.iter()
+    }
+
+    /// Returns the events added by the last observation.
+    pub fn latest(&self) -> impl Iterator<Item = &LoggedEvent> + '_ {
+        let added = self.added.min(self.events.len());
+        self.events.iter().skip(self.events.len() - added)
+    }
+
+    /// Writes the logged events to the file at `path` as CSV.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "step,event,biot,value")?;
+        for logged in &self.events {
+            let (kind, biot, value) = logged.event.fields();
+            let biot = biot.map(|id| id.0.to_string()).unwrap_or_default();
+            writeln!(writer, "{},{kind},{biot},{value}", logged.step)?;
+        }
+        writer.flush()
+    }
+}
+
+/// Notifications about notable events, shown for a few seconds each.
+#[cfg(feature = "render")]
+#[derive(Debug, Clone, Default)]
+pub struct Toasts {
+    /// Texts of the shown notifications and the times they were raised at.
+    shown: VecDeque<(String, f64)>,
+}
+
+#[cfg(feature = "render")]
+impl Toasts {
+    /// Creates an empty set of notifications.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shows notifications about the notable events added by the last observation of `log`.
+    pub fn notify(&mut self, log: &EventLog) {
+        let now = get_time();
+        self.shown
+            .retain(|(_, raised_at)| now - raised_at < TOAST_SECONDS);
+        for logged in log.latest().filter(|logged| logged.event.is_notable()) {
+            if self.shown.len() == MAX_TOASTS {
+                self.shown.pop_front();
+            }
+            self.shown.push_back((logged.to_string(), now));
+        }
+    }
+
+    /// Draws the notifications below each other at the bottom left of the screen.
+    pub fn draw(&self) {
+        let now = get_time();
+        let mut y = screen_height() - 60.;
+        for (text, raised_at) in self.shown.iter().rev() {
+            if now - raised_at >= TOAST_SECONDS {
+                continue;
+            }
+            let size = measure_text(text, None, 18, 1.0);
+            draw_rectangle(
+                6.,
+                y - 18.,
+                size.width + 12.,
+                24.,
+                Color::new(0., 0., 0., 0.6),
+            );
+            draw_text(text, 12., y, 18., GOLD);
+            y -= 28.;
+        }
+    }
+}
@@ -0,0 +1,310 @@
+//! Batches of headless simulations sweeping over configuration parameters.
+//!
+//! An experiment runs every combination of the swept parameter values with
+//! several seeds each, spread over all cores, and summarizes each run in one
+//! row of a CSV file ready for statistical analysis.
+
+use crate::biot::Gene;
+use crate::config::{ConfigError, SimConfig};
+use crate::simulation::Simulation;
+use crate::species::SpeciesId;
+use crate::stats::Sample;
+use crate::world::World;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Names of the mean properties in the results, in the order of [`Gene::EXPRESSED`].
+pub const TRAIT_COLUMNS: [&str; Gene::EXPRESSED.len()] = [
+    "attack",
+    "defense",
+    "photosynthesis",
+    "motion",
+    "intelligence",
+    "venom",
+    "armor",
+    "camouflage",
+    "vision",
+    "reproduction",
+    "digestion",
+    "immunity",
+    "fecundity",
+    "nurture",
+    "sociality",
+    "heat_tolerance",
+    "kinship",
+];
+
+/// A configuration parameter and the values it is swept over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sweep {
+    /// Key of the parameter, dotted for nested ones, e.g. `light.period`.
+    pub parameter: String,
+    /// Values of the parameter as TOML literals, e.g. `0.1` or `"torus"`.
+    pub values: Vec<String>,
+}
+
+/// Error of parsing a sweep missing its parameter or values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSweep(pub String);
+
+impl fmt::Display for InvalidSweep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid sweep `{}`, expected `<parameter>=<value>,<value>,...`",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for InvalidSweep {}
+
+impl FromStr for Sweep {
+    type Err = InvalidSweep;
+
+    /// Parses `<parameter>=<value>,<value>,...`.
+    fn from_str(sweep: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidSweep(sweep.to_string());
+        let (parameter, values) = sweep.split_once('=').ok_or_else(invalid)?;
+        let values = values
+            .split(',')
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(String::from)
+            .collect::<Vec<_>>();
+        let parameter = parameter.trim();
+        if parameter.is_empty() || values.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            parameter: parameter.to_string(),
+            values,
+        })
+    }
+}
+
+/// Summary of a single run of an experiment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// Index of the combination of parameter values within the experiment.
+    pub combination: usize,
+    /// Seed the run started from.
+    pub seed: u64,
+    /// Number of steps simulated, fewer than requested if the population died out.
+    pub steps: u32,
+    /// Number of biots at the end of the run.
+    pub population: usize,
+    /// Number of species at the end of the run.
+    pub species: usize,
+    /// Number of lineages with living descendants at the end of the run.
+    pub lineages: usize,
+    /// Mean properties of the biots at the end of the run, in the order of [`Gene::EXPRESSED`].
+    pub means: [f32; Gene::EXPRESSED.len()],
+    /// Step at which the first species went extinct, if any did.
+    pub first_extinction: Option<u32>,
+    /// Step at which the whole population died out, if it did.
+    pub collapse: Option<u32>,
+}
+
+/// A parameter sweep with a number of seeded runs for every combination of values.
+#[derive(Debug, Clone)]
+pub struct Experiment {
+    sweeps: Vec<Sweep>,
+    /// Parameter values and resulting configuration of every combination.
+    combinations: Vec<(Vec<String>, SimConfig)>,
+    /// Seeds every combination is run with.
+    seeds: Vec<u64>,
+    /// Number of steps every run is simulated for.
+    steps: u32,
+}
+
+impl Experiment {
+    /// Creates the experiment sweeping over `sweeps` on top of the TOML
+    /// configuration `base`, running each combination `runs` times with the
+    /// seeds from `seed` on for `steps` steps.
+    ///
+    /// # Errors
+    ///
+    /// If any combination results in an invalid configuration, so that the
+    /// experiment fails before any run was started.
+    ///
+    /// # Note
+    ///
+    /// The swept values are prepended to `base` as `<parameter> = <value>`
+    /// lines, so swept parameters must not also be set by `base`.
+    pub fn new(
+        base: &str,
+        sweeps: Vec<Sweep>,
+        runs: u64,
+        seed: u64,
+        steps: u32,
+    ) -> Result<Self, ConfigError> {
+        let mut assignments = vec![Vec::new()];
+        for sweep in &sweeps {
+            assignments = assignments
+                .into_iter()
+                .flat_map(|assignment: Vec<String>| {
+                    sweep.values.iter().map(move |value| {
+                        let mut assignment = assignment.clone();
+                        assignment.push(value.clone());
+                        assignment
+                    })
+                })
+                .collect();
+        }
+        let combinations = assignments
+            .into_iter()
+            .map(|values| {
+                let mut contents = String::new();
+                for (sweep, value) in sweeps.iter().zip(&values) {
+                    contents.push_str(&format!("{} = {value}\n", sweep.parameter));
+                }
+                contents.push_str(base);
+                let config = SimConfig::from_toml(&contents).map_err(|error| {
+                    ConfigError::Invalid(format!("{}: {error}", values.join(", ")))
+                })?;
+                Ok((values, config))
+            })
+            .collect::<Result<_, ConfigError>>()?;
+        Ok(Self {
+            sweeps,
+            combinations,
+            seeds: (seed..seed + runs).collect(),
+            steps,
+        })
+    }
+
+    /// Returns the total number of runs of the experiment.
+    pub fn runs(&self) -> usize {
+        self.combinations.len() * self.seeds.len()
+    }
+
+    /// Runs the experiment on `jobs` threads and returns the summaries of
+    /// all runs, ordered by combination and seed.
+    ///
+    /// # Note
+    ///
+    /// Every run is deterministic, so the results do not depend on the
+    /// number of threads. `progress` is called after every finished run.
+    pub fn run(&self, jobs: usize, progress: impl Fn(&Summary) + Sync) -> Vec<Summary> {
+        let next = AtomicUsize::new(0);
+        let summaries = Mutex::new(Vec::with_capacity(self.runs()));
+        thread::scope(|scope| {
+            for _ in 0..jobs.clamp(1, self.runs().max(1)) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    if index >= self.runs() {
+                        break;
+                    }
+                    let combination = index / self.seeds.len();
+                    let seed = self.seeds[index % self.seeds.len()];
+                    let summary = self.run_one(combination, seed);
+                    progress(&summary);
+                    summaries
+                        .lock()
+                        .expect("a run panicked while storing its summary")
+                        .push(summary);
+                });
+            }
+        });
+        let mut summaries = summaries
+            .into_inner()
+            .expect("a run panicked while storing its summary");
+        summaries.sort_by_key(|summary| (summary.combination, summary.seed));
+        summaries
+    }
+
+    /// Simulates the given combination from `seed` and summarizes the run.
+    fn run_one(&self, combination: usize, seed: u64) -> Summary {
+        let config = self.combinations[combination].1.clone();
+        // Same size as the default window of the interactive frontend.
+        let mut simulation = Simulation::new(World::new(800., 600.), config, seed);
+        let mut species = BTreeSet::<SpeciesId>::new();
+        let mut first_extinction = None;
+        let mut collapse = None;
+        while simulation.biots().steps() < self.steps {
+            simulation.step();
+            let step = simulation.biots().steps();
+            let living = simulation
+                .biots()
+                .iter()
+                .filter_map(|biot| biot.species())
+                .collect::<BTreeSet<_>>();
+            if first_extinction.is_none() && species.difference(&living).next().is_some() {
+                first_extinction = Some(step);
+            }
+            species = living;
+            if simulation.biots().is_empty() {
+                collapse = Some(step);
+                break;
+            }
+        }
+        let biots = simulation.biots();
+        let sample = Sample::take(biots, simulation.date(), 0, 0, 0);
+        Summary {
+            combination,
+            seed,
+            steps: biots.steps(),
+            population: sample.population,
+            species: sample.species,
+            lineages: sample.lineages,
+            means: sample.means,
+            first_extinction,
+            collapse,
+        }
+    }
+
+    /// Writes `summaries` to the file at `path` as CSV, one row per run.
+    ///
+    /// # Note
+    ///
+    /// Each row starts with the values of the swept parameters, steps of
+    /// extinctions that did not happen are left empty.
+    pub fn save(&self, summaries: &[Summary], path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let mut header = self
+            .sweeps
+            .iter()
+            .map(|sweep| sweep.parameter.as_str())
+            .collect::<Vec<_>>();
+        header.extend(["seed", "steps", "population", "species", "lineages"]);
+        header.extend(TRAIT_COLUMNS);
+        header.extend(["first_extinction", "collapse"]);
+        writeln!(writer, "{}", header.join(","))?;
+        let step = |step: Option<u32>| step.map(|step| step.to_string()).unwrap_or_default();
+        for summary in summaries {
+            let mut row = self.combinations[summary.combination]
+                .0
+                .iter()
+                .map(|value| csv_field(value))
+                .collect::<Vec<_>>();
+            row.extend([
+                summary.seed.to_string(),
+                summary.steps.to_string(),
+                summary.population.to_string(),
+                summary.species.to_string(),
+                summary.lineages.to_string(),
+            ]);
+            row.extend(summary.means.iter().map(|mean| format!("{mean:.4}")));
+            row.extend([step(summary.first_extinction), step(summary.collapse)]);
+            writeln!(writer, "{}", row.join(","))?;
+        }
+        writer.flush()
+    }
+}
+
+/// Quotes a parameter value if it contains characters special to CSV.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,144 @@
+//! Scalar fields spreading over the world, e.g. the scent biots leave behind.
+//!
+//! The world is divided into square cells. Every step, each cell exchanges a
+//! fraction of its value with its four neighbours and loses another fraction
+//! to decay, so deposits blur into trails that fade over time.
+
+use crate::world::World;
+use glam::{vec2, Vec2};
+#[cfg(feature = "render")]
+use macroquad::prelude::{draw_rectangle, Color};
+use serde::{Deserialize, Serialize};
+
+/// A diffusing and decaying field over a grid of cells covering the world.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Field {
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    /// Whether the field diffuses across the edges of a torus world.
+    wraps: bool,
+    /// Value of each cell, row by row.
+    values: Vec<f32>,
+}
+
+impl Field {
+    /// Creates an empty field of cells of `cell_size` covering `world`.
+    pub fn new(world: &World, cell_size: f32) -> Self {
+        let columns = (world.width / cell_size).ceil().max(1.0) as usize;
+        let rows = (world.height / cell_size).ceil().max(1.0) as usize;
+        Self {
+            cell_size,
+            columns,
+            rows,
+            wraps: world.is_torus(),
+            values: vec![0.0; columns * rows],
+        }
+    }
+
+    /// Returns the field covering the resized `world`, each cell copied from
+    /// the one at the position `source` maps its center to.
+    ///
+    /// Beyond the edges of the old world its edge cells continue.
+    pub fn resized(&self, world: &World, source: impl Fn(Vec2) -> Vec2) -> Self {
+        let mut field = Self::new(world, self.cell_size);
+        let columns = field.columns;
+        for (idx, value) in field.values.iter_mut().enumerate() {
+            let cell = vec2((idx % columns) as f32 + 0.5, (idx / columns) as f32 + 0.5);
+            *value = self.value(source(cell * self.cell_size));
+        }
+        field
+    }
+
+    /// Returns the column and row of the cell containing `pos`.
+    fn cell(&self, pos: Vec2) -> (usize, usize) {
+        // Casts saturate, so negative and NaN coordinates end up in the first cell.
+        let column = ((pos.x / self.cell_size) as usize).min(self.columns - 1);
+        let row = ((pos.y / self.cell_size) as usize).min(self.rows - 1);
+        (column, row)
+    }
+
+    /// Returns the value of the cell `offset` cells away from the given one.
+    ///
+    /// Beyond the edges of a bounded world the value of the edge cell continues.
+    fn neighbour(&self, (column, row): (usize, usize), (dx, dy): (isize, isize)) -> f32 {
+        let shift = |index: usize, delta: isize, len: usize| {
+            let shifted = index as isize + delta;
+            if self.wraps {
+                shifted.rem_euclid(len as isize) as usize
+            } else {
+                shifted.clamp(0, len as isize - 1) as usize
+            }
+        };
+        self.values[shift(row, dy, self.rows) * self.columns + shift(column, dx, self.columns)]
+    }
+
+    /// Adds `amount` to the cell containing `pos`.
+    pub fn deposit(&mut self, pos: Vec2, amount: f32) {
+        let (column, row) = self.cell(pos);
+        self.values[row * self.columns + column] += amount;
+    }
+
+    /// Spreads the fraction `diffusion` of each cell to its neighbours and
+    /// lets the fraction `decay` of the field fade.
+    ///
+    /// # Note
+    ///
+    /// Diffusion only moves value between cells, so without decay the total
+    /// of the field stays the same. Both fractions are clamped to `0.0..=1.0`.
+    pub fn step(&mut self, diffusion: f32, decay: f32) {
+        let (diffusion, decay) = (diffusion.clamp(0.0, 1.0), decay.clamp(0.0, 1.0));
+        let values = (0..self.values.len())
+            .map(|idx| {
+                let cell = (idx % self.columns, idx / self.columns);
+                let neighbours = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                    .into_iter()
+                    .map(|offset| self.neighbour(cell, offset))
+                    .sum::<f32>();
+                let value = self.values[idx];
+                (value + diffusion * (neighbours / 4.0 - value)) * (1.0 - decay)
+            })
+            .collect();
+        self.values = values;
+    }
+
+    /// Returns the value of the cell containing `pos`.
+    pub fn value(&self, pos: Vec2) -> f32 {
+        let (column, row) = self.cell(pos);
+        self.values[row * self.columns + column]
+    }
+
+    /// Returns the direction in which the field rises the most at `pos`,
+    /// scaled by the difference per cell.
+    pub fn gradient(&self, pos: Vec2) -> Vec2 {
+        let cell = self.cell(pos);
+        let dx = self.neighbour(cell, (1, 0)) - self.neighbour(cell, (-1, 0));
+        let dy = self.neighbour(cell, (0, 1)) - self.neighbour(cell, (0, -1));
+        vec2(dx, dy) / 2.0
+    }
+}
+
+#[cfg(feature = "render")]
+impl Field {
+    /// Display the field as a heatmap tinted with `color`, repeated at the
+    /// given offsets for a wrapped world.
+    ///
+    /// # Note
+    ///
+    /// The opacity is relative to the highest value of the field.
+    pub fn draw(&self, offsets: &[Vec2], color: Color) {
+        let max = self.values.iter().copied().fold(f32::EPSILON, f32::max);
+        for offset in offsets {
+            for (idx, value) in self.values.iter().enumerate() {
+                let alpha = 0.5 * value / max;
+                if alpha > 0.01 {
+                    let column = (idx % self.columns) as f32;
+                    let row = (idx / self.columns) as f32;
+                    let pos = vec2(column, row) * self.cell_size + *offset;
+                    let color = Color { a: alpha, ..color };
+                    draw_rectangle(pos.x, pos.y, self.cell_size, self.cell_size, color);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! Filters highlighting the biots matching a criterion and dimming all others.
+
+use crate::biot::{Biot, BiotId};
+use crate::units::Ticks;
+use macroquad::prelude::*;
+
+/// Biots younger than this match [`Criterion::Young`].
+const YOUNG_AGE: Ticks = Ticks(100);
+
+/// Opacity of the biots not matching the active filter.
+pub const DIMMED_ALPHA: f32 = 0.15;
+
+/// What biots have to satisfy to be shown undimmed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Criterion {
+    All,
+    /// Descendants of the same founder as the biot selected in the inspector.
+    Lineage,
+    Intelligent,
+    Young,
+    Predators,
+    Prey,
+}
+
+impl Criterion {
+    /// All criteria in the order they are listed in the panel.
+    const ALL: [Criterion; 6] = [
+        Self::All,
+        Self::Lineage,
+        Self::Intelligent,
+        Self::Young,
+        Self::Predators,
+        Self::Prey,
+    ];
+
+    /// Returns the description of the criterion shown in the panel.
+    fn label(self, lineage: Option<BiotId>) -> String {
+        match self {
+            Self::All => String::from("all biots"),
+            Self::Lineage => match lineage {
+                Some(lineage) => format!("lineage {}", lineage.0),
+                None => String::from("lineage of the selected biot"),
+            },
+            Self::Intelligent => String::from("intelligence > 0"),
+            Self::Young => format!("age < {}", YOUNG_AGE.0),
+            Self::Predators => String::from("predators"),
+            Self::Prey => String::from("prey"),
+        }
+    }
+}
+
+/// Panel choosing which biots are highlighted.
+///
+/// # Note
+///
+/// `F` toggles the panel and the arrow keys choose the criterion. The
+/// filter only applies while the panel is shown.
+pub struct Filter {
+    visible: bool,
+    criterion: Criterion,
+    /// Lineage matched by [`Criterion::Lineage`].
+    ///
+    /// It is kept after the selected biot died, so the lineage can be
+    /// followed until it goes extinct.
+    lineage: Option<BiotId>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Filter {
+    /// Creates a hidden filter matching all biots.
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            criterion: Criterion::All,
+            lineage: None,
+        }
+    }
+
+    /// Handles the keys controlling the filter.
+    ///
+    /// `selected` is the biot selected in the inspector, whose lineage is
+    /// followed by the lineage filter.
+    pub fn update(&mut self, selected: Option<&Biot>) {
+        if is_key_pressed(KeyCode::F) {
+            self.visible = !self.visible;
+        }
+        if !self.visible {
+            return;
+        }
+        let len = Criterion::ALL.len();
+        let index = Criterion::ALL
+            .iter()
+            .position(|criterion| *criterion == self.criterion)
+            .unwrap_or(0);
+        if is_key_pressed(KeyCode::Down) {
+            self.criterion = Criterion::ALL[(index + 1) % len];
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.criterion = Criterion::ALL[(index + len - 1) % len];
+        }
+        if let Some(biot) = selected {
+            self.lineage = Some(biot.lineage());
+        }
+    }
+
+    /// Returns `true` if the biot is highlighted by the filter.
+    pub fn matches(&self, biot: &Biot) -> bool {
+        if !self.visible {
+            return true;
+        }
+        match self.criterion {
+            Criterion::All => true,
+            Criterion::Lineage => self.lineage.is_none_or(|lineage| biot.lineage() == lineage),
+            Criterion::Intelligent => biot.properties.intelligence > 0.0,
+            Criterion::Young => biot.stats.age < YOUNG_AGE,
+            Criterion::Predators => biot.is_predator(),
+            Criterion::Prey => !biot.is_predator(),
+        }
+    }
+
+    /// Display the panel next to the selection plot if it is shown.
+    pub fn draw_panel(&self) {
+        if !self.visible {
+            return;
+        }
+        let (width, line_height) = (220., 18.);
+        let height = line_height * (Criterion::ALL.len() + 1) as f32 + 10.;
+        let (x, y) = (320., screen_height() - height - 10.);
+        draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.6));
+        draw_rectangle_lines(x, y, width, height, 1., LIGHTGRAY);
+        draw_text("show (up/down):", x + 5., y + line_height, 16., LIGHTGRAY);
+        for (n, criterion) in Criterion::ALL.iter().enumerate() {
+            let (marker, color) = if *criterion == self.criterion {
+                ("> ", YELLOW)
+            } else {
+                ("  ", LIGHTGRAY)
+            };
+            draw_text(
+                &format!("{marker}{}", criterion.label(self.lineage)),
+                x + 5.,
+                y + line_height * (n + 2) as f32,
+                16.,
+                color,
+            );
+        }
+    }
+}
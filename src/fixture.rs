@@ -0,0 +1,47 @@
+//! Cached states of mature ecosystems.
+//!
+//! The random initial population is a poor stand-in for the worlds a long
+//! run ends up in, which are larger, clustered and full of corpses. Checks
+//! and performance measurements instead load a state simulated for many
+//! steps once and cached as a snapshot.
+
+use crate::config::SimConfig;
+use crate::simulation::{Simulation, SnapshotError};
+use crate::world::World;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Number of steps a mature ecosystem is simulated for unless requested otherwise.
+pub const MATURE_STEPS: u32 = 50_000;
+
+/// Directory the mature ecosystems are cached in.
+pub const FIXTURE_DIR: &str = "target/fixtures";
+
+/// Returns the path of the cached ecosystem after `steps` steps from `seed` within `dir`.
+pub fn fixture_path(dir: impl AsRef<Path>, seed: u64, steps: u32) -> PathBuf {
+    dir.as_ref().join(format!("mature-{seed}-{steps}.snapshot"))
+}
+
+/// Returns the ecosystem of the default configuration after `steps` steps
+/// from `seed`, loading it from the cache in `dir` if possible.
+///
+/// # Note
+///
+/// Missing or outdated snapshots, e.g. of an older snapshot version, are
+/// simulated anew and written to the cache. The simulation stops early if
+/// the population goes extinct. The cache is keyed by seed and steps only,
+/// so it has to be cleared after changing the default configuration.
+pub fn mature(dir: impl AsRef<Path>, seed: u64, steps: u32) -> Result<Simulation, SnapshotError> {
+    let path = fixture_path(&dir, seed, steps);
+    if let Ok(simulation) = Simulation::load(&path) {
+        return Ok(simulation);
+    }
+    // Same size as the default window of the interactive frontend.
+    let mut simulation = Simulation::new(World::new(800., 600.), SimConfig::default(), seed);
+    while simulation.biots().steps() < steps && !simulation.biots().is_empty() {
+        simulation.step();
+    }
+    fs::create_dir_all(dir)?;
+    simulation.save(&path)?;
+    Ok(simulation)
+}
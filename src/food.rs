@@ -0,0 +1,156 @@
+//! Food pellets scattered in the environment as a second source of energy.
+
+use crate::biot::MAX_INTERACTION_RADIUS;
+use crate::config::FoodConfig;
+use crate::grid::SpatialGrid;
+use crate::obstacle::Obstacle;
+use crate::rng::Rng;
+use crate::terrain::Terrain;
+use crate::units::{Distance, Energy};
+use crate::world::World;
+use glam::Vec2;
+#[cfg(feature = "render")]
+use macroquad::prelude::{draw_circle, ORANGE};
+use serde::{Deserialize, Serialize};
+
+/// Radius of a food pellet.
+const FOOD_RADIUS: Distance = Distance(1.5);
+
+/// A pellet of food that biots eat by touching it.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+pub struct Food {
+    pub pos: Vec2,
+    /// Life a biot gains by eating the pellet.
+    pub energy: Energy,
+}
+
+/// The food pellets of a simulation.
+#[derive(Serialize, Deserialize)]
+pub struct FoodCollection {
+    pellets: Vec<Food>,
+    /// Spatial index of the pellets used by biots looking for food.
+    #[serde(skip)]
+    grid: SpatialGrid,
+}
+
+impl Default for FoodCollection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FoodCollection {
+    /// Creates a collection without any food.
+    pub fn new() -> Self {
+        Self {
+            pellets: Vec::new(),
+            grid: SpatialGrid::default(),
+        }
+    }
+
+    /// Spawns new pellets as described by `config`.
+    ///
+    /// # Note
+    ///
+    /// `config.spawn_rate` is the expected number of pellets per step,
+    /// fractions are spawned with the corresponding chance. Pellets landing
+    /// on barren terrain only spawn with the chance of their tile, and never
+    /// within `obstacles`.
+    pub fn step(
+        &mut self,
+        world: &World,
+        terrain: &Terrain,
+        obstacles: &[Obstacle],
+        config: &FoodConfig,
+        rng: &mut Rng,
+    ) {
+        if !self.grid.is_synced(world, self.pellets.len()) {
+            self.grid = SpatialGrid::new(world, MAX_INTERACTION_RADIUS);
+            self.grid.rebuild(self.pellets.iter().map(|food| food.pos));
+        }
+        let mut budget = config.spawn_rate;
+        while budget > 0.0 && self.pellets.len() < config.max_pellets {
+            if rng.gen_range(0., 1.) >= budget {
+                break;
+            }
+            budget -= 1.0;
+            let food = Food {
+                pos: world.random_position(rng),
+                energy: Energy(config.energy),
+            };
+            if rng.gen_range(0., 1.) >= terrain.tile(food.pos).food_spawn()
+                || obstacles.iter().any(|obstacle| obstacle.contains(food.pos))
+            {
+                continue;
+            }
+            self.grid.push(food.pos);
+            self.pellets.push(food);
+        }
+    }
+
+    /// Returns the position of the closest pellet within `radius` of `pos`.
+    pub fn nearest(&self, pos: Vec2, radius: Distance) -> Option<Vec2> {
+        self.grid
+            .within(pos, radius)
+            .min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0))
+            .map(|(idx, _)| self.pellets[idx].pos)
+    }
+
+    /// Eats all pellets touching a biot of the given radius at `pos`.
+    ///
+    /// Returns the energy of the eaten pellets. They are only removed by
+    /// [`FoodCollection::remove_eaten`], but cannot be eaten twice.
+    pub fn eat(&mut self, pos: Vec2, radius: Distance) -> Energy {
+        let mut energy = Energy::ZERO;
+        for (idx, _) in self.grid.within(pos, radius + FOOD_RADIUS) {
+            let food = &mut self.pellets[idx];
+            energy += food.energy;
+            food.energy = Energy::ZERO;
+        }
+        energy
+    }
+
+    /// Removes the pellets eaten during the current step.
+    pub fn remove_eaten(&mut self) {
+        let pellets = &self.pellets;
+        self.grid.retain(|idx| pellets[idx].energy > Energy::ZERO);
+        self.pellets.retain(|food| food.energy > Energy::ZERO);
+    }
+
+    /// The number of pellets currently in the environment.
+    pub fn len(&self) -> usize {
+        self.pellets.len()
+    }
+
+    /// Returns `true` if the environment has no pellets.
+    pub fn is_empty(&self) -> bool {
+        self.pellets.is_empty()
+    }
+
+    /// Moves every pellet to the position `remap` returns for it and removes
+    /// the ones it returns `None` for.
+    pub fn remap(&mut self, remap: impl Fn(Vec2) -> Option<Vec2>) {
+        self.pellets.retain_mut(|food| match remap(food.pos) {
+            Some(pos) => {
+                food.pos = pos;
+                true
+            }
+            None => false,
+        });
+        // The next step rebuilds the spatial index.
+        self.grid = SpatialGrid::default();
+    }
+}
+
+#[cfg(feature = "render")]
+impl FoodCollection {
+    /// Display the pellets, repeated at the given offsets for a wrapped world.
+    pub fn draw(&self, offsets: &[Vec2]) {
+        for offset in offsets {
+            for food in &self.pellets {
+                let pos = food.pos + *offset;
+                draw_circle(pos.x, pos.y, FOOD_RADIUS.0, ORANGE);
+            }
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! Uniform spatial hash grid used for neighbour queries between biots.
+//!
+//! Unlike a tree that has to be rebuilt every step, the grid is updated
+//! incrementally: a biot only changes its cell when it crosses a cell border.
+
+use crate::units::{Distance, SquaredDistance};
+use crate::world::World;
+use glam::Vec2;
+
+/// Marks removed items while renumbering the remaining ones.
+const REMOVED: usize = usize::MAX;
+
+/// An indexed item of the grid.
+#[derive(Debug, Copy, Clone)]
+struct Item {
+    pos: Vec2,
+    cell: usize,
+}
+
+/// A grid of square cells covering the world, storing the indices of the
+/// biots within each cell.
+///
+/// # Note
+///
+/// Items are identified by their index, which must match the index of the
+/// corresponding biot in its collection. On a torus, queries find items
+/// across the edges of the world.
+#[derive(Debug, Clone, Default)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    world: World,
+    /// Indices of the items in each cell, row by row.
+    cells: Vec<Vec<usize>>,
+    items: Vec<Item>,
+}
+
+impl SpatialGrid {
+    /// Creates an empty grid covering `world` with cells of the given size.
+    pub fn new(world: &World, cell_size: Distance) -> Self {
+        let columns = (world.width / cell_size.0).ceil().max(1.0) as usize;
+        let rows = (world.height / cell_size.0).ceil().max(1.0) as usize;
+        Self {
+            cell_size: cell_size.0,
+            columns,
+            rows,
+            world: *world,
+            cells: vec![Vec::new(); columns * rows],
+            items: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the grid covers `world` and indexes `len` items.
+    pub fn is_synced(&self, world: &World, len: usize) -> bool {
+        self.world.width == world.width
+            && self.world.height == world.height
+            && self.world.topology == world.topology
+            && self.items.len() == len
+    }
+
+    /// Returns the number of indexed items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Returns `true` if the index has no items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Returns the size of the cells.
+    pub fn cell_size(&self) -> Distance {
+        Distance(self.cell_size)
+    }
+
+    /// Returns the number of columns of cells.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the indices of the items in each cell, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = &[usize]> + '_ {
+        self.cells.iter().map(Vec::as_slice)
+    }
+
+    /// Returns the index of the cell containing `pos`.
+    ///
+    /// Positions outside of the world are assigned to the closest cell.
+    fn cell(&self, pos: Vec2) -> usize {
+        let (column, row) = self.cell_coordinates(pos);
+        row * self.columns + column
+    }
+
+    /// Returns the column and row of the cell containing `pos`.
+    fn cell_coordinates(&self, pos: Vec2) -> (usize, usize) {
+        // Casts saturate, so negative and NaN coordinates end up in the first cell.
+        let column = ((pos.x / self.cell_size) as usize).min(self.columns - 1);
+        let row = ((pos.y / self.cell_size) as usize).min(self.rows - 1);
+        (column, row)
+    }
+
+    /// Appends an item at `pos`, its index is the previous length of the grid.
+    pub fn push(&mut self, pos: Vec2) {
+        let cell = self.cell(pos);
+        self.cells[cell].push(self.items.len());
+        self.items.push(Item { pos, cell });
+    }
+
+    /// Moves the item at `idx` to `pos`.
+    pub fn update(&mut self, idx: usize, pos: Vec2) {
+        let cell = self.cell(pos);
+        let item = &mut self.items[idx];
+        if item.cell != cell {
+            let old = &mut self.cells[item.cell];
+            if let Some(slot) = old.iter().position(|other| *other == idx) {
+                old.swap_remove(slot);
+            }
+            self.cells[cell].push(idx);
+            item.cell = cell;
+        }
+        item.pos = pos;
+    }
+
+    /// Removes all items and indexes the given positions instead.
+    pub fn rebuild(&mut self, positions: impl Iterator<Item = Vec2>) {
+        for cell in &mut self.cells {
+            cell.clear();
+        }
+        self.items.clear();
+        for pos in positions {
+            self.push(pos);
+        }
+    }
+
+    /// Removes all items for which `keep` returns `false`.
+    ///
+    /// The remaining items are renumbered in order, just like [`Vec::retain`]
+    /// does for the biots.
+    pub fn retain(&mut self, mut keep: impl FnMut(usize) -> bool) {
+        let mut len = 0;
+        let remap = (0..self.items.len())
+            .map(|idx| {
+                if keep(idx) {
+                    len += 1;
+                    len - 1
+                } else {
+                    REMOVED
+                }
+            })
+            .collect::<Vec<_>>();
+        let mut idx = 0;
+        self.items.retain(|_| {
+            idx += 1;
+            remap[idx - 1] != REMOVED
+        });
+        for cell in &mut self.cells {
+            cell.retain(|idx| remap[*idx] != REMOVED);
+            for idx in cell.iter_mut() {
+                *idx = remap[*idx];
+            }
+        }
+    }
+
+    /// Checks that every item is listed exactly once, in the cell containing it.
+    pub fn check(&self) -> Result<(), String> {
+        let mut listed = vec![0; self.items.len()];
+        for (cell, items) in self.cells.iter().enumerate() {
+            for &idx in items {
+                let Some(item) = self.items.get(idx) else {
+                    return Err(format!("cell {cell} lists the missing item {idx}"));
+                };
+                if item.cell != cell || self.cell(item.pos) != cell {
+                    return Err(format!(
+                        "item {idx} at {} is listed in cell {cell}",
+                        item.pos
+                    ));
+                }
+                listed[idx] += 1;
+            }
+        }
+        match listed.iter().position(|count| *count != 1) {
+            Some(idx) => Err(format!("item {idx} is listed {} times", listed[idx])),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the position of the item at `idx`.
+    pub fn position(&self, idx: usize) -> Vec2 {
+        self.items[idx].pos
+    }
+
+    /// Returns an iterator over the items within `radius` of `pos` and their squared distances.
+    ///
+    /// # Note
+    ///
+    /// The items are yielded in no particular order.
+    pub fn within(
+        &self,
+        pos: Vec2,
+        radius: Distance,
+    ) -> impl Iterator<Item = (usize, SquaredDistance)> + '_ {
+        let columns = self.span(pos.x, radius.0, self.world.width, self.columns);
+        let rows = self.span(pos.y, radius.0, self.world.height, self.rows);
+        let max_squared_distance = radius.squared();
+        rows.into_iter()
+            .flat_map(move |row| {
+                let columns = columns.clone();
+                columns
+                    .into_iter()
+                    .map(move |column| row * self.columns + column)
+            })
+            .flat_map(move |cell| self.cells[cell].iter().copied())
+            .filter_map(move |idx| {
+                let delta = self.world.delta(pos, self.items[idx].pos);
+                let squared_distance = SquaredDistance(delta.length_squared());
+                (squared_distance <= max_squared_distance).then_some((idx, squared_distance))
+            })
+    }
+
+    /// Returns the columns or rows of cells within `radius` of the coordinate `center`.
+    ///
+    /// `size` is the extent of the world and `count` the number of cells
+    /// along the same axis.
+    fn span(&self, center: f32, radius: f32, size: f32, count: usize) -> Vec<usize> {
+        let index = |value: f32| ((value / self.cell_size) as usize).min(count - 1);
+        if !self.world.is_torus() {
+            return (index(center - radius)..=index(center + radius)).collect();
+        }
+        if 2.0 * radius >= size {
+            return (0..count).collect();
+        }
+        // Split the range at the edges it crosses into ranges within the world.
+        let (min, max) = (center - radius, center + radius);
+        let mut span: Vec<usize> = if min < 0.0 {
+            (index(min + size)..count).chain(0..=index(max)).collect()
+        } else if max > size {
+            (index(min)..count).chain(0..=index(max - size)).collect()
+        } else {
+            (index(min)..=index(max)).collect()
+        };
+        span.sort_unstable();
+        span.dedup();
+        span
+    }
+
+    /// Returns the closest other item to the item at `idx` and its squared distance.
+    pub fn nearest(&self, idx: usize) -> Option<(usize, SquaredDistance)> {
+        let pos = self.items[idx].pos;
+        let diagonal = Distance(self.world.width.hypot(self.world.height));
+        let mut radius = Distance(self.cell_size);
+        loop {
+            // All items within the radius are found, so the closest of them is
+            // closer than any item outside of it.
+            let nearest = self
+                .within(pos, radius)
+                .filter(|(other, _)| *other != idx)
+                .min_by(|(_, a), (_, b)| a.0.total_cmp(&b.0));
+            if nearest.is_some() || radius > diagonal {
+                return nearest;
+            }
+            radius = radius * 2.0;
+        }
+    }
+}
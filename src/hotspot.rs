@@ -0,0 +1,152 @@
+//! Regions of the world where something interesting happens, and a camera
+//! mode travelling between them.
+
+use crate::biot::BiotId;
+use crate::camera::Camera;
+use crate::simulation::Simulation;
+use crate::units::Ticks;
+use crate::world::World;
+use glam::{vec2, Vec2};
+use macroquad::prelude::get_time;
+use std::collections::HashSet;
+
+/// Size of the square regions the interest is tracked in.
+const CELL_SIZE: f32 = 100.0;
+
+/// Fraction of its interest a region keeps per step.
+const DECAY: f32 = 0.995;
+
+/// Interest added by a biot being eaten.
+const KILL_INTEREST: f32 = 5.0;
+
+/// Interest added by a birth.
+const BIRTH_INTEREST: f32 = 1.0;
+
+/// Interest added per lineage beyond the first one living in a region.
+const FRONT_INTEREST: f32 = 2.0;
+
+/// Number of steps between two searches for lineage fronts.
+const FRONT_INTERVAL: u32 = 10;
+
+/// Number of seconds the auto-camera stays with a hotspot.
+const HOTSPOT_SECONDS: f64 = 15.0;
+
+/// Fraction of the remaining way to its goal the auto-camera moves per frame.
+const GLIDE_RATE: f32 = 0.01;
+
+/// Recent events weighted by how interesting they are to watch, per region of the world.
+///
+/// # Note
+///
+/// Kills, births and regions where several lineages meet raise the interest
+/// of a region, which fades over time so that it follows the action.
+pub struct InterestMap {
+    columns: usize,
+    rows: usize,
+    /// Interest in each region, row by row.
+    interest: Vec<f32>,
+    /// Number of steps recorded so far.
+    steps: u32,
+}
+
+impl InterestMap {
+    /// Creates a map of `world` without any interest.
+    pub fn new(world: &World) -> Self {
+        let columns = (world.width / CELL_SIZE).ceil().max(1.0) as usize;
+        let rows = (world.height / CELL_SIZE).ceil().max(1.0) as usize;
+        Self {
+            columns,
+            rows,
+            interest: vec![0.0; columns * rows],
+            steps: 0,
+        }
+    }
+
+    /// Returns the index of the region containing `pos`.
+    fn cell(&self, pos: Vec2) -> usize {
+        // Casts saturate, so negative and NaN coordinates end up in the first region.
+        let column = ((pos.x / CELL_SIZE) as usize).min(self.columns - 1);
+        let row = ((pos.y / CELL_SIZE) as usize).min(self.rows - 1);
+        row * self.columns + column
+    }
+
+    /// Records the events of the last step of `simulation`.
+    pub fn record(&mut self, simulation: &Simulation) {
+        for interest in &mut self.interest {
+            *interest *= DECAY;
+        }
+        let biots = simulation.biots();
+        for pos in biots.kills() {
+            let cell = self.cell(*pos);
+            self.interest[cell] += KILL_INTEREST;
+        }
+        for biot in biots.iter().filter(|biot| biot.stats.age == Ticks(0)) {
+            let cell = self.cell(biot.stats.pos);
+            self.interest[cell] += BIRTH_INTEREST;
+        }
+        self.steps += 1;
+        if self.steps.is_multiple_of(FRONT_INTERVAL) {
+            let mut lineages = vec![HashSet::<BiotId>::new(); self.interest.len()];
+            for biot in biots.iter() {
+                lineages[self.cell(biot.stats.pos)].insert(biot.lineage());
+            }
+            for (interest, lineages) in self.interest.iter_mut().zip(lineages) {
+                let fronts = lineages.len().saturating_sub(1) as f32;
+                *interest += FRONT_INTEREST * fronts;
+            }
+        }
+    }
+
+    /// Returns the center of the most interesting region, if any is interesting at all.
+    pub fn hotspot(&self) -> Option<Vec2> {
+        let (cell, _) = self
+            .interest
+            .iter()
+            .enumerate()
+            .filter(|(_, interest)| **interest > 0.0)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))?;
+        let (column, row) = (cell % self.columns, cell / self.columns);
+        Some(vec2(column as f32 + 0.5, row as f32 + 0.5) * CELL_SIZE)
+    }
+}
+
+/// Camera mode smoothly travelling between the hotspots of an [`InterestMap`].
+pub struct AutoCamera {
+    interest: InterestMap,
+    /// The hotspot the camera travels to.
+    goal: Option<Vec2>,
+    /// Time the goal was chosen at.
+    chosen_at: f64,
+}
+
+impl AutoCamera {
+    /// Creates an auto-camera for `world` that has not chosen a hotspot yet.
+    pub fn new(world: &World) -> Self {
+        Self {
+            interest: InterestMap::new(world),
+            goal: None,
+            chosen_at: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Records the events of the last step of `simulation`.
+    pub fn record(&mut self, simulation: &Simulation) {
+        self.interest.record(simulation);
+    }
+
+    /// Moves `camera` towards the current hotspot at the given `zoom`.
+    ///
+    /// The hotspot is chosen anew every few seconds.
+    pub fn steer(&mut self, camera: &mut Camera, world: &World, zoom: f32) {
+        let now = get_time();
+        if self.goal.is_none() || now - self.chosen_at > HOTSPOT_SECONDS {
+            if let Some(hotspot) = self.interest.hotspot() {
+                self.goal = Some(hotspot);
+                self.chosen_at = now;
+            }
+        }
+        if let Some(goal) = self.goal {
+            camera.glide(world, goal, zoom, GLIDE_RATE);
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use crate::biot::{Biot, BiotId, Gene, Health};
+use crate::camera::Camera;
+use crate::interpolation::Interpolation;
+use crate::simulation::Simulation;
+use crate::units::{Distance, Energy};
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+/// Distance from the body of a biot within which a click still selects it.
+const PICK_TOLERANCE: Distance = Distance(5.0);
+
+/// Number of fights kept in the combat log.
+const MAX_LOG_LINES: usize = 8;
+
+/// Panel showing the live state of a biot selected by clicking on it.
+pub struct Inspector {
+    /// The selected biot, if any, even if it died since.
+    selected: Option<BiotId>,
+    /// The latest fights of the selected biot, oldest first.
+    log: VecDeque<String>,
+}
+
+impl Default for Inspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inspector {
+    /// Creates an inspector without a selected biot.
+    pub fn new() -> Self {
+        Self {
+            selected: None,
+            log: VecDeque::new(),
+        }
+    }
+
+    /// Adds the fights of the selected biot during the last step to its combat log.
+    ///
+    /// # Note
+    ///
+    /// Must be called after every step, fights are only kept for a single step.
+    pub fn record(&mut self, simulation: &Simulation) {
+        let id = match self.selected {
+            Some(id) => id,
+            None => return,
+        };
+        let step = simulation.biots().steps();
+        for fight in simulation.biots().fights() {
+            let (attack, resistance) = (fight.attack, fight.resistance);
+            let line = if fight.predator == id {
+                let verb = if fight.killed { "ate" } else { "bit" };
+                let mut line = format!(
+                    "{step}: {verb} {} ({attack:.1} > {resistance:.1}), +{:.1}",
+                    fight.prey.0, fight.gain.0
+                );
+                if fight.venom > Energy::ZERO {
+                    line += &format!(", venom -{:.1}", fight.venom.0);
+                }
+                if fight.poisoned {
+                    line += " (fatal)";
+                }
+                line
+            } else if fight.prey == id {
+                let verb = if fight.killed { "eaten" } else { "bitten" };
+                format!(
+                    "{step}: {verb} by {} ({attack:.1} > {resistance:.1}), -{:.1}",
+                    fight.predator.0, fight.wound.0
+                )
+            } else {
+                continue;
+            };
+            self.log.push_back(line);
+            if self.log.len() > MAX_LOG_LINES {
+                self.log.pop_front();
+            }
+        }
+    }
+
+    /// Selects the biot below the mouse on a left click.
+    ///
+    /// # Note
+    ///
+    /// Clicking on empty space clears the selection. Once the selected biot
+    /// dies, only its combat log stays visible.
+    pub fn update(&mut self, simulation: &Simulation, camera: &Camera) {
+        if is_mouse_button_pressed(MouseButton::Left) {
+            let world = simulation.world();
+            let pos = camera.mouse_world_position(world);
+            self.selected = simulation
+                .biots()
+                .pick(world, pos, PICK_TOLERANCE)
+                .map(Biot::id);
+            self.log.clear();
+        }
+        if self.selected(simulation).is_none() && self.log.is_empty() {
+            self.selected = None;
+        }
+    }
+
+    /// Selects the biot with the given identifier, e.g. after searching for it.
+    pub fn select(&mut self, id: BiotId) {
+        self.selected = Some(id);
+        self.log.clear();
+    }
+
+    /// Returns the selected biot if it is still alive.
+    pub fn selected<'a>(&self, simulation: &'a Simulation) -> Option<&'a Biot> {
+        self.selected.and_then(|id| simulation.biots().get(id))
+    }
+
+    /// Marks the selected biot in the world.
+    ///
+    /// # Note
+    ///
+    /// Must be called while `camera` is active.
+    pub fn draw_marker(
+        &self,
+        simulation: &Simulation,
+        camera: &Camera,
+        interpolation: &Interpolation,
+    ) {
+        if let Some(biot) = self.selected(simulation) {
+            let world = simulation.world();
+            for offset in camera.wrap_offsets(world) {
+                let pos = interpolation.position(world, biot) + offset;
+                draw_circle_lines(pos.x, pos.y, biot.radius().0 + 4., 2., YELLOW);
+            }
+        }
+    }
+
+    /// Display the state of the selected biot in the top right corner of the screen.
+    pub fn draw_panel(&self, simulation: &Simulation) {
+        let biot = match (self.selected(simulation), self.selected) {
+            (Some(biot), _) => biot,
+            (None, Some(id)) if !self.log.is_empty() => {
+                let mut lines = vec![format!("biot {} died", id.0), String::from("combat log:")];
+                lines.extend(self.log.iter().map(|line| format!("  {line}")));
+                Self::draw_lines(&lines);
+                return;
+            }
+            (None, _) => return,
+        };
+        let config = simulation.biots().config();
+        let health = match biot.health(config) {
+            Health::Alive => String::from("alive"),
+            Health::Dying { cause } => format!("dying ({cause:?})"),
+            Health::Dead => String::from("dead"),
+        };
+        let properties = &biot.properties;
+        let mut lines = vec![
+            format!("biot {} of lineage {}", biot.id().0, biot.lineage().0),
+            match simulation.biots().phylogeny().parent(biot.id()) {
+                Some(parent) => format!("parent: {}", parent.0),
+                None => String::from("founder"),
+            },
+            match biot.genome().brain() {
+                Some(_) if properties.intelligence > 0.0 => {
+                    format!("brain: {} neurons", properties.neurons())
+                }
+                Some(_) => String::from("brain: idle"),
+                None => String::from("brain: none"),
+            },
+            match biot.species() {
+                Some(species) => format!("species: {}", species.0),
+                None => String::from("species: not clustered yet"),
+            },
+            format!("health: {health}"),
+            format!(
+                "infected: {}",
+                if biot.stats.infected { "yes" } else { "no" }
+            ),
+            format!(
+                "life: {:.1} ({:.0}%)",
+                biot.stats.life.0,
+                100. * biot.life_fraction(config)
+            ),
+            format!(
+                "drive: {:?}, hunger: {:.0}%",
+                biot.drive(config),
+                100. * biot.stats.hunger
+            ),
+            format!(
+                "age: {} ({:.0}%), vigor: {:.0}%, size: {:.0}%",
+                biot.stats.age.0,
+                100. * biot.age_fraction(),
+                100. * biot.stats.vigor,
+                100. * biot.stats.size
+            ),
+            format!(
+                "speed: {:.2}, light: {:.0}%",
+                biot.stats.speed.length(),
+                100. * biot.stats.light
+            ),
+            format!(
+                "terrain: {:?}, temperature: {:.2}",
+                biot.stats.tile, biot.stats.temperature
+            ),
+            format!(
+                "attack: {:.1}, defense: {:.1}",
+                properties.attack, properties.defense
+            ),
+            format!(
+                "photosynthesis: {:.1}, motion: {:.1}",
+                properties.photosynthesis, properties.motion
+            ),
+            format!(
+                "intelligence: {:.1}, digestion: {:.1}",
+                properties.intelligence, properties.digestion
+            ),
+            format!(
+                "venom: {:.1}, armor: {:.1}, camouflage: {:.1}",
+                properties.venom, properties.armor, properties.camouflage
+            ),
+            format!(
+                "vision: {:.1}, reproduction: {:.1}, immunity: {:.1}",
+                properties.vision, properties.reproduction, properties.immunity
+            ),
+            format!(
+                "fecundity: {:.1}, nurture: {:.1}, sociality: {:.1}",
+                properties.fecundity, properties.nurture, properties.sociality
+            ),
+            format!(
+                "heat tolerance: {:.1}, kinship: {:.1}",
+                properties.heat_tolerance, properties.kinship
+            ),
+            format!("mutation chance: {:.2}", biot.mutation_chance(config)),
+            format!("genome of {} genes:", biot.genome().len()),
+        ];
+        let counts = biot.genome().expressed_counts();
+        lines.extend(
+            Gene::EXPRESSED
+                .iter()
+                .zip(counts)
+                .map(|(gene, count)| format!("  {gene:?}: {count}")),
+        );
+        lines.extend(config.genes.iter().map(|definition| {
+            let gene = Gene::Custom(definition.id);
+            let count = biot.genome().genes().filter(|g| **g == gene).count();
+            format!("  {}: {count}", definition.name)
+        }));
+        if !self.log.is_empty() {
+            lines.push(String::from("combat log:"));
+            lines.extend(self.log.iter().map(|line| format!("  {line}")));
+        }
+        Self::draw_lines(&lines);
+    }
+
+    /// Draws a panel with the given lines in the top right corner of the screen.
+    fn draw_lines(lines: &[String]) {
+        let (width, line_height) = (300., 18.);
+        let height = line_height * lines.len() as f32 + 10.;
+        let (x, y) = (screen_width() - width - 10., 10.);
+        draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.6));
+        draw_rectangle_lines(x, y, width, height, 1., LIGHTGRAY);
+        for (n, line) in lines.iter().enumerate() {
+            draw_text(
+                line,
+                x + 5.,
+                y + line_height * (n + 1) as f32,
+                16.,
+                LIGHTGRAY,
+            );
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Smooth rendering of biots between two simulation steps.
+//!
+//! The simulation advances in whole steps, while frames are rendered at
+//! their own pace. Drawing the biots in between their positions before and
+//! after the last step keeps their motion smooth at any speed.
+
+use crate::biot::{Biot, BiotId};
+use crate::biot_collection::BiotCollection;
+use crate::world::World;
+use glam::Vec2;
+use std::collections::HashMap;
+
+/// Positions of the biots before the last step and how far to move them towards the current ones.
+#[derive(Debug, Clone)]
+pub struct Interpolation {
+    previous: HashMap<BiotId, Vec2>,
+    /// Fraction of the last step that passed by the time of the frame, in `0.0..=1.0`.
+    alpha: f32,
+}
+
+impl Default for Interpolation {
+    fn default() -> Self {
+        Self {
+            previous: HashMap::new(),
+            alpha: 1.0,
+        }
+    }
+}
+
+impl Interpolation {
+    /// Creates an interpolation drawing all biots at their current positions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers the positions of the biots before the next step.
+    pub fn capture(&mut self, biots: &BiotCollection) {
+        self.previous.clear();
+        self.previous
+            .extend(biots.iter().map(|biot| (biot.id(), biot.stats.pos)));
+    }
+
+    /// Sets the fraction of the last step that passed by the time of the frame.
+    pub fn set_alpha(&mut self, alpha: f32) {
+        self.alpha = alpha.clamp(0.0, 1.0);
+    }
+
+    /// Returns the position `biot` is drawn at.
+    ///
+    /// # Note
+    ///
+    /// Biots born during the last step are drawn at their current position.
+    /// On a torus, biots crossing an edge move along the shorter way around.
+    pub fn position(&self, world: &World, biot: &Biot) -> Vec2 {
+        let current = biot.stats.pos;
+        match self.previous.get(&biot.id()) {
+            Some(&previous) if self.alpha < 1.0 => {
+                previous + world.delta(previous, current) * self.alpha
+            }
+            _ => current,
+        }
+    }
+}
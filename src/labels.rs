@@ -0,0 +1,148 @@
+//! Labels showing the state of the biots when zoomed in closely.
+
+use crate::biot::Biot;
+use crate::camera::Camera;
+use crate::interpolation::Interpolation;
+use crate::simulation::Simulation;
+use glam::vec2;
+use macroquad::prelude::{draw_text, screen_height, screen_width, Color};
+use std::fmt;
+use std::str::FromStr;
+
+/// Zoom factor from which the labels start to fade in.
+const FADE_IN_ZOOM: f32 = 2.0;
+
+/// Zoom factor from which the labels are fully opaque.
+const OPAQUE_ZOOM: f32 = 3.0;
+
+/// Font size of the labels in screen pixels.
+const FONT_SIZE: f32 = 14.0;
+
+/// Maximum number of labels drawn per frame, keeping crowded views fast.
+const MAX_LABELS: usize = 400;
+
+/// A value of a biot shown in its label.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LabelField {
+    Age,
+    Life,
+    Lineage,
+}
+
+impl LabelField {
+    /// All fields in the order they are shown.
+    pub const ALL: [LabelField; 3] = [Self::Age, Self::Life, Self::Lineage];
+
+    /// Returns the name of the field on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Age => "age",
+            Self::Life => "life",
+            Self::Lineage => "lineage",
+        }
+    }
+
+    /// Returns the text of the field for `biot`.
+    fn text(self, biot: &Biot) -> String {
+        match self {
+            Self::Age => format!("age {}", biot.stats.age.0),
+            Self::Life => format!("life {:.0}", biot.stats.life.0),
+            Self::Lineage => format!("lineage {}", biot.lineage().0),
+        }
+    }
+}
+
+/// Error of parsing an unknown label field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownLabelField(pub String);
+
+impl fmt::Display for UnknownLabelField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = LabelField::ALL.map(LabelField::name).join(", ");
+        write!(
+            f,
+            "unknown label field `{}`, expected one of: {names}",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownLabelField {}
+
+impl FromStr for LabelField {
+    type Err = UnknownLabelField;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.name() == name)
+            .ok_or_else(|| UnknownLabelField(name.to_string()))
+    }
+}
+
+/// Labels next to the biots, faded in when zooming in past a threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Labels {
+    /// Fields shown in every label, from top to bottom.
+    fields: Vec<LabelField>,
+}
+
+impl Default for Labels {
+    fn default() -> Self {
+        Self {
+            fields: LabelField::ALL.to_vec(),
+        }
+    }
+}
+
+impl FromStr for Labels {
+    type Err = UnknownLabelField;
+
+    /// Parses a comma separated list of fields, an empty list disables the labels.
+    fn from_str(fields: &str) -> Result<Self, Self::Err> {
+        let fields = fields
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { fields })
+    }
+}
+
+impl Labels {
+    /// Draws the labels of the biots in view right of them.
+    ///
+    /// # Note
+    ///
+    /// Must be called in screen coordinates, so that the text keeps its size
+    /// at any zoom. Only the first [`MAX_LABELS`] biots in view are labeled.
+    pub fn draw(&self, simulation: &Simulation, camera: &Camera, interpolation: &Interpolation) {
+        let alpha = ((camera.zoom() - FADE_IN_ZOOM) / (OPAQUE_ZOOM - FADE_IN_ZOOM)).clamp(0.0, 1.0);
+        if alpha == 0.0 || self.fields.is_empty() {
+            return;
+        }
+        let color = Color::new(1.0, 1.0, 1.0, alpha);
+        let world = simulation.world();
+        let (width, height) = (screen_width(), screen_height());
+        let mut drawn = 0;
+        for offset in camera.wrap_offsets(world) {
+            for biot in simulation.biots().iter() {
+                let pos = interpolation.position(world, biot) + offset;
+                let radius = biot.radius().0 * camera.zoom();
+                let anchor = camera.world_to_screen(pos) + vec2(radius + 3.0, -radius);
+                if anchor.x < 0.0 || anchor.x > width || anchor.y < 0.0 || anchor.y > height {
+                    continue;
+                }
+                for (line, field) in self.fields.iter().enumerate() {
+                    let y = anchor.y + FONT_SIZE * line as f32;
+                    draw_text(&field.text(biot), anchor.x, y, FONT_SIZE, color);
+                }
+                drawn += 1;
+                if drawn == MAX_LABELS {
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Visualization layers coloring the background of the world by the local
+//! state of the biots, e.g. to spot ecological zones forming in big worlds.
+//!
+//! Layers are computed from the spatial index of the biots every few steps,
+//! one value per cell of the index.
+
+use crate::biot::Biot;
+use crate::biot_collection::BiotCollection;
+use crate::world::World;
+#[cfg(feature = "render")]
+use glam::vec2;
+use glam::Vec2;
+#[cfg(feature = "render")]
+use macroquad::prelude::{draw_rectangle, Color, GREEN, RED, SKYBLUE};
+
+/// Number of steps between two computations of a layer.
+pub const LAYER_INTERVAL: u32 = 10;
+
+/// What a layer shows for each region of the world.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Layer {
+    /// The number of biots.
+    Density,
+    /// The mean attack of the biots.
+    Attack,
+    /// The mean photosynthesis of the biots.
+    Photosynthesis,
+}
+
+impl Layer {
+    /// All layers in the order they are cycled through.
+    pub const ALL: [Layer; 3] = [Self::Density, Self::Attack, Self::Photosynthesis];
+
+    /// Returns the name of the layer.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Density => "density",
+            Self::Attack => "attack",
+            Self::Photosynthesis => "photosynthesis",
+        }
+    }
+
+    /// Returns the layer following `layer`, or none after the last one.
+    pub fn cycle(layer: Option<Self>) -> Option<Self> {
+        match layer {
+            None => Some(Self::ALL[0]),
+            Some(layer) => {
+                let idx = Self::ALL.iter().position(|other| *other == layer)?;
+                Self::ALL.get(idx + 1).copied()
+            }
+        }
+    }
+
+    /// Returns the contribution of `biot` to a region of the layer.
+    fn value(self, biot: &Biot) -> f32 {
+        match self {
+            Self::Density => 1.0,
+            Self::Attack => biot.properties.attack,
+            Self::Photosynthesis => biot.properties.photosynthesis,
+        }
+    }
+
+    /// Returns `true` if the layer shows the mean rather than the total of a region.
+    fn is_mean(self) -> bool {
+        self != Self::Density
+    }
+
+    /// Returns the color the layer tints the regions with.
+    #[cfg(feature = "render")]
+    fn color(self) -> Color {
+        match self {
+            Self::Density => SKYBLUE,
+            Self::Attack => RED,
+            Self::Photosynthesis => GREEN,
+        }
+    }
+}
+
+/// The values of a [`Layer`] for the cells of the spatial index.
+#[derive(Debug, Clone)]
+pub struct LayerMap {
+    layer: Layer,
+    cell_size: f32,
+    columns: usize,
+    /// Value of each cell, row by row, unset for cells without biots.
+    values: Vec<Option<f32>>,
+    /// Number of steps simulated when the layer was computed.
+    step: u32,
+}
+
+impl LayerMap {
+    /// Computes `layer` for the current positions of `biots` in `world`.
+    ///
+    /// Returns `None` if the spatial index does not match the biots, e.g.
+    /// right after biots were spawned, until the next step updates it.
+    pub fn compute(layer: Layer, world: &World, biots: &BiotCollection) -> Option<Self> {
+        let grid = biots.grid();
+        if !grid.is_synced(world, biots.len()) {
+            return None;
+        }
+        let all = biots.iter().as_slice();
+        let values = grid
+            .cells()
+            .map(|cell| {
+                let total = cell
+                    .iter()
+                    .filter_map(|&idx| all.get(idx))
+                    .map(|biot| layer.value(biot))
+                    .sum::<f32>();
+                match cell.len() {
+                    0 => None,
+                    len if layer.is_mean() => Some(total / len as f32),
+                    _ => Some(total),
+                }
+            })
+            .collect();
+        Some(Self {
+            layer,
+            cell_size: grid.cell_size().0,
+            columns: grid.columns(),
+            values,
+            step: biots.steps(),
+        })
+    }
+
+    /// Returns the layer shown by the map.
+    pub fn layer(&self) -> Layer {
+        self.layer
+    }
+
+    /// Returns `true` if the map does not show `layer` after `steps` steps,
+    /// because it shows another layer or is too old.
+    pub fn is_stale(&self, layer: Layer, steps: u32) -> bool {
+        // Rewinding goes back in time.
+        self.layer != layer || steps < self.step || steps - self.step >= LAYER_INTERVAL
+    }
+
+    /// Returns the value of the region containing `pos`, if there are biots in it.
+    pub fn value(&self, pos: Vec2) -> Option<f32> {
+        let column = ((pos.x / self.cell_size) as usize).min(self.columns - 1);
+        let row = (pos.y / self.cell_size) as usize;
+        self.values
+            .get(row * self.columns + column)
+            .copied()
+            .flatten()
+    }
+}
+
+#[cfg(feature = "render")]
+impl LayerMap {
+    /// Tints the regions with biots in proportion to their values, repeated
+    /// at the given offsets for a wrapped world.
+    ///
+    /// # Note
+    ///
+    /// The opacity is relative to the highest value of the layer.
+    pub fn draw(&self, offsets: &[Vec2]) {
+        let max = self
+            .values
+            .iter()
+            .flatten()
+            .copied()
+            .fold(f32::EPSILON, f32::max);
+        for offset in offsets {
+            for (idx, value) in self.values.iter().enumerate() {
+                if let Some(value) = value {
+                    let cell = vec2((idx % self.columns) as f32, (idx / self.columns) as f32);
+                    let pos = cell * self.cell_size + *offset;
+                    let color = Color {
+                        a: 0.1 + 0.4 * value / max,
+                        ..self.layer.color()
+                    };
+                    draw_rectangle(pos.x, pos.y, self.cell_size, self.cell_size, color);
+                }
+            }
+        }
+    }
+}
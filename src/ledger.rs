@@ -0,0 +1,69 @@
+//! Bookkeeping of the energy flowing through the simulation.
+//!
+//! Every change of the life of a biot during a step is booked as one of the
+//! flows of an [`EnergyLedger`]. If the total life of all biots changes by
+//! anything else than the booked flows, energy is created or destroyed by a bug.
+
+use crate::units::Energy;
+use core::ops;
+
+/// Energy flows of a single simulation step.
+///
+/// # Note
+///
+/// Photosynthesis, food and scavenging are the only sources of energy. All
+/// other flows leave the biots, either to the environment or by removing the
+/// biots holding them. The corpses left by removed biots are not part of the books.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct EnergyLedger {
+    /// Energy gained from sunlight.
+    pub photosynthesis: Energy,
+    /// Energy gained from eating food pellets.
+    pub food: Energy,
+    /// Energy gained from scavenging corpses.
+    pub scavenging: Energy,
+    /// Energy spent on the properties of the biots.
+    pub metabolism: Energy,
+    /// Energy parents paid for reproduction beyond the life of their offsprings.
+    pub reproduction: Energy,
+    /// Energy of eaten biots that did not reach their predators.
+    pub predation: Energy,
+    /// Energy drained by the pathogen.
+    pub disease: Energy,
+    /// Life of the biots removed from the simulation.
+    pub removed: Energy,
+}
+
+impl EnergyLedger {
+    /// Returns the change of the total life of all biots implied by the flows.
+    pub fn balance(&self) -> Energy {
+        self.income() - self.expenses() - self.removed
+    }
+
+    /// Returns the energy the biots gained from their environment.
+    pub fn income(&self) -> Energy {
+        self.photosynthesis + self.food + self.scavenging
+    }
+
+    /// Returns the energy the living biots lost to their environment.
+    pub fn expenses(&self) -> Energy {
+        self.metabolism + self.reproduction + self.predation + self.disease
+    }
+}
+
+impl ops::Add for EnergyLedger {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            photosynthesis: self.photosynthesis + rhs.photosynthesis,
+            food: self.food + rhs.food,
+            scavenging: self.scavenging + rhs.scavenging,
+            metabolism: self.metabolism + rhs.metabolism,
+            reproduction: self.reproduction + rhs.reproduction,
+            predation: self.predation + rhs.predation,
+            disease: self.disease + rhs.disease,
+            removed: self.removed + rhs.removed,
+        }
+    }
+}
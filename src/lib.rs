@@ -0,0 +1,100 @@
+//! Evolution of biots competing for sunlight, food and each other.
+//!
+//! The binary is a thin frontend over this library. Code embedding the
+//! simulation, e.g. the programs in `examples/`, starts from the
+//! [`prelude`], see the README for a tour of the public API.
+
+// Parts of the API are only used by the interactive frontend.
+#![cfg_attr(not(feature = "render"), allow(dead_code))]
+
+pub mod archipelago;
+#[cfg(feature = "render")]
+pub mod audio;
+pub mod behavior;
+pub mod biot;
+pub mod biot_collection;
+pub mod brain;
+#[cfg(feature = "render")]
+pub mod camera;
+#[cfg(feature = "render")]
+pub mod capture;
+#[cfg(feature = "render")]
+pub mod card;
+pub mod clock;
+pub mod config;
+#[cfg(feature = "render")]
+pub mod control_panel;
+pub mod controller;
+pub mod corpse;
+pub mod current;
+#[cfg(feature = "render")]
+pub mod demo;
+pub mod dominance;
+pub mod environment;
+pub mod events;
+pub mod experiment;
+pub mod field;
+#[cfg(feature = "render")]
+pub mod filter;
+pub mod fixture;
+pub mod food;
+pub mod grid;
+#[cfg(feature = "render")]
+pub mod hotspot;
+#[cfg(feature = "render")]
+pub mod inspector;
+#[cfg(feature = "render")]
+pub mod interpolation;
+#[cfg(feature = "render")]
+pub mod labels;
+pub mod layer;
+pub mod ledger;
+pub mod library;
+pub mod light;
+pub mod lineages;
+#[cfg(feature = "render")]
+pub mod menu;
+pub mod migration;
+#[cfg(feature = "render")]
+pub mod minimap;
+pub mod obstacle;
+#[cfg(feature = "render")]
+pub mod pack;
+pub mod phase;
+#[cfg(feature = "render")]
+pub mod phase_plot;
+pub mod phylogeny;
+pub mod prelude;
+pub mod preset;
+pub mod recorder;
+pub mod report;
+pub mod reserve;
+pub mod rewind;
+pub mod rng;
+pub mod rules;
+pub mod scenario;
+#[cfg(feature = "render")]
+pub mod scene;
+#[cfg(feature = "scripting")]
+pub mod script;
+#[cfg(feature = "render")]
+pub mod search;
+#[cfg(feature = "render")]
+pub mod selection;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod simulation;
+pub mod spawn;
+pub mod species;
+pub mod stats;
+pub mod terrain;
+pub mod tools;
+#[cfg(feature = "render")]
+pub mod trails;
+pub mod units;
+pub mod upload;
+pub mod validate;
+#[cfg(feature = "render")]
+pub mod viewer;
+pub mod watchdog;
+pub mod world;
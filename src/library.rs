@@ -0,0 +1,80 @@
+//! A library of saved genomes.
+//!
+//! Interesting organisms can be exported from a running simulation and
+//! reintroduced into new simulations later. Each genome is kept in a small
+//! file of its own, so the library is just a directory users can curate
+//! with a file manager.
+
+use crate::biot::Genome;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// Directory genomes are exported to by the viewer.
+pub const LIBRARY_DIR: &str = "genomes";
+
+/// Extension of genome files.
+pub const EXTENSION: &str = "genome";
+
+/// Writes `genome` to the file `<name>.genome` within `dir`, creating the directory if needed.
+///
+/// Returns the path of the written file.
+pub fn export(genome: &Genome, dir: impl AsRef<Path>, name: &str) -> io::Result<PathBuf> {
+    fs::create_dir_all(&dir)?;
+    let path = dir.as_ref().join(name).with_extension(EXTENSION);
+    let mut writer = BufWriter::new(File::create(&path)?);
+    bincode::serialize_into(&mut writer, genome).map_err(invalid_data)?;
+    writer.flush()?;
+    Ok(path)
+}
+
+/// Reads a genome previously written with [`export`].
+pub fn load(path: impl AsRef<Path>) -> io::Result<Genome> {
+    let reader = BufReader::new(File::open(path)?);
+    bincode::deserialize_from(reader).map_err(invalid_data)
+}
+
+/// Returns the paths of the genome files within `dir`, sorted by name.
+pub fn list(dir: impl AsRef<Path>) -> io::Result<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.retain(|path| {
+        path.extension()
+            .is_some_and(|extension| extension == EXTENSION)
+    });
+    paths.sort();
+    Ok(paths)
+}
+
+/// Loads the genomes of a comma separated list of genome files and library directories.
+///
+/// # Note
+///
+/// Directories contribute all genome files they contain, see [`list`].
+pub fn resolve(paths: &str) -> io::Result<Vec<Genome>> {
+    let mut genomes = Vec::new();
+    for path in paths
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        let files = if Path::new(path).is_dir() {
+            list(path)?
+        } else {
+            vec![PathBuf::from(path)]
+        };
+        for file in files {
+            let genome = load(&file).map_err(|error| {
+                io::Error::new(error.kind(), format!("{}: {error}", file.display()))
+            })?;
+            genomes.push(genome);
+        }
+    }
+    Ok(genomes)
+}
+
+/// Wraps a failed encoding or decoding of a genome file as an I/O error.
+fn invalid_data(error: bincode::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
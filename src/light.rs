@@ -0,0 +1,69 @@
+//! Competition of the biots for sunlight.
+//!
+//! The world is divided into square cells. The bodies of the biots in a cell
+//! form a canopy that shades every other biot in the same cell.
+
+use crate::biot::Biot;
+use crate::config::LightConfig;
+use crate::world::World;
+use core::f32::consts::PI;
+
+/// The canopy of the biots over a grid of cells covering the world.
+pub struct LightMap {
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    /// Fraction of each cell covered by biot bodies, row by row.
+    ///
+    /// Overlapping bodies are counted multiple times, so this can exceed 1.
+    canopy: Vec<f32>,
+    /// How strongly the canopy dims the light.
+    shading: f32,
+}
+
+impl LightMap {
+    /// Computes the canopy the given biots form in `world`.
+    pub fn new<'a>(
+        world: &World,
+        config: &LightConfig,
+        biots: impl Iterator<Item = &'a Biot>,
+    ) -> Self {
+        let columns = (world.width / config.cell_size).ceil().max(1.0) as usize;
+        let rows = (world.height / config.cell_size).ceil().max(1.0) as usize;
+        let mut map = Self {
+            cell_size: config.cell_size,
+            columns,
+            rows,
+            canopy: vec![0.0; columns * rows],
+            shading: config.shading,
+        };
+        for biot in biots {
+            let cell = map.cell(biot);
+            map.canopy[cell] += map.coverage(biot);
+        }
+        map
+    }
+
+    /// Returns the index of the cell containing the biot.
+    fn cell(&self, biot: &Biot) -> usize {
+        // Casts saturate, so negative and NaN coordinates end up in the first cell.
+        let column = ((biot.stats.pos.x / self.cell_size) as usize).min(self.columns - 1);
+        let row = ((biot.stats.pos.y / self.cell_size) as usize).min(self.rows - 1);
+        row * self.columns + column
+    }
+
+    /// Returns the fraction of its cell the body of the biot covers.
+    fn coverage(&self, biot: &Biot) -> f32 {
+        PI * biot.radius().0.powi(2) / self.cell_size.powi(2)
+    }
+
+    /// Returns the fraction of the sunlight reaching the biot.
+    ///
+    /// # Note
+    ///
+    /// A biot is only shaded by the others in its cell, not by itself.
+    pub fn light(&self, biot: &Biot) -> f32 {
+        let shade = (self.canopy[self.cell(biot)] - self.coverage(biot)).max(0.0);
+        (-self.shading * shade).exp()
+    }
+}
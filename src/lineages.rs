@@ -0,0 +1,192 @@
+//! Statistics of the lineages of the population.
+//!
+//! Lineages usually dwindle for a while before they vanish, so a short
+//! history is kept per lineage to estimate which ones are at risk of going
+//! extinct.
+
+use crate::biot::BiotId;
+use crate::biot_collection::BiotCollection;
+use glam::Vec2;
+#[cfg(feature = "render")]
+use macroquad::prelude::{
+    draw_rectangle, draw_rectangle_lines, draw_text, Color, LIGHTGRAY, RED, WHITE,
+};
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// Number of simulation steps between two samples of the lineages.
+const SAMPLE_INTERVAL: u32 = 50;
+
+/// Maximum number of samples kept per lineage.
+const MAX_SAMPLES: usize = 20;
+
+/// Population below which a lineage counts as small.
+const SMALL_POPULATION: usize = 10;
+
+/// Extinction risk from which a lineage counts as endangered.
+const ENDANGERED_RISK: f32 = 0.5;
+
+/// Number of lineages listed in the leaderboard.
+#[cfg(feature = "render")]
+const LEADERBOARD_LEN: usize = 12;
+
+/// State of a lineage at a single step.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LineageSample {
+    /// Step the sample was taken at.
+    pub step: u32,
+    /// Number of living members.
+    pub population: usize,
+    /// Root mean square distance of the members from their centroid.
+    pub extent: f32,
+    /// Mean life of the members.
+    pub mean_life: f32,
+}
+
+/// Time series of the living lineages.
+///
+/// # Note
+///
+/// Lineages are sampled every [`SAMPLE_INTERVAL`] steps and forgotten as
+/// soon as they have no living members left. Like the clustering index, the
+/// extent ignores the wrap-around at the world edges.
+#[derive(Debug, Clone, Default)]
+pub struct LineageStats {
+    /// Past samples of every living lineage, oldest first.
+    history: BTreeMap<BiotId, VecDeque<LineageSample>>,
+    /// Number of steps recorded so far.
+    steps: u32,
+}
+
+impl LineageStats {
+    /// Creates lineage statistics without any history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the state of the biot collection after a simulation step.
+    pub fn record(&mut self, biots: &BiotCollection) {
+        self.steps += 1;
+        if !self.steps.is_multiple_of(SAMPLE_INTERVAL) {
+            return;
+        }
+        let mut members = BTreeMap::<BiotId, Vec<(Vec2, f32)>>::new();
+        for biot in biots.iter() {
+            let member = (biot.stats.pos, biot.stats.life.0);
+            members.entry(biot.lineage()).or_default().push(member);
+        }
+        self.history
+            .retain(|lineage, _| members.contains_key(lineage));
+        let step = biots.steps();
+        for (lineage, members) in members {
+            let population = members.len();
+            let centroid = members.iter().map(|(pos, _)| pos).sum::<Vec2>() / population as f32;
+            let spread = members
+                .iter()
+                .map(|(pos, _)| pos.distance_squared(centroid))
+                .sum::<f32>();
+            let life = members.iter().map(|(_, life)| life).sum::<f32>();
+            let samples = self.history.entry(lineage).or_default();
+            if samples.len() == MAX_SAMPLES {
+                samples.pop_front();
+            }
+            samples.push_back(LineageSample {
+                step,
+                population,
+                extent: (spread / population as f32).sqrt(),
+                mean_life: life / population as f32,
+            });
+        }
+    }
+
+    /// Returns the samples of the living `lineage`, oldest first.
+    pub fn history(&self, lineage: BiotId) -> Option<&VecDeque<LineageSample>> {
+        self.history.get(&lineage)
+    }
+
+    /// Returns the living lineages with their latest samples, the most populous first.
+    pub fn ranking(&self) -> Vec<(BiotId, LineageSample)> {
+        let mut ranking = self
+            .history
+            .iter()
+            .filter_map(|(lineage, samples)| Some((*lineage, *samples.back()?)))
+            .collect::<Vec<_>>();
+        ranking.sort_by_key(|(lineage, sample)| (usize::MAX - sample.population, *lineage));
+        ranking
+    }
+
+    /// Returns an estimate of the risk that `lineage` goes extinct soon, in `0.0..=1.0`.
+    ///
+    /// # Note
+    ///
+    /// The estimate is a simple heuristic averaging how far the population
+    /// of the lineage is below [`SMALL_POPULATION`] and how much it declined
+    /// over the kept samples. Extinct lineages have a risk of one.
+    pub fn risk(&self, lineage: BiotId) -> f32 {
+        let Some((first, latest)) = self
+            .history(lineage)
+            .and_then(|samples| Some((samples.front()?, samples.back()?)))
+        else {
+            return 1.0;
+        };
+        let scarcity = 1.0 - (latest.population as f32 / SMALL_POPULATION as f32).min(1.0);
+        let decline = 1.0 - latest.population as f32 / first.population as f32;
+        (scarcity + decline.clamp(0.0, 1.0)) / 2.0
+    }
+
+    /// Returns `true` if the risk of `lineage` going extinct is high.
+    pub fn is_endangered(&self, lineage: BiotId) -> bool {
+        self.risk(lineage) >= ENDANGERED_RISK
+    }
+
+    /// Writes the history of all living lineages to the file at `path` as CSV.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "lineage,step,population,extent,mean_life,risk")?;
+        for (lineage, samples) in &self.history {
+            let risk = self.risk(*lineage);
+            for sample in samples {
+                writeln!(
+                    writer,
+                    "{},{},{},{:.2},{:.3},{risk:.3}",
+                    lineage.0, sample.step, sample.population, sample.extent, sample.mean_life
+                )?;
+            }
+        }
+        writer.flush()
+    }
+}
+
+#[cfg(feature = "render")]
+impl LineageStats {
+    /// Display the most populous lineages right of the size distribution,
+    /// highlighting the endangered ones.
+    pub fn draw(&self) {
+        let ranking = self.ranking();
+        let (x, y, width) = (740., 10., 320.);
+        let height = 30. + 16. * ranking.len().min(LEADERBOARD_LEN) as f32;
+        draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.6));
+        draw_rectangle_lines(x, y, width, height, 1., LIGHTGRAY);
+        let endangered = ranking
+            .iter()
+            .filter(|(lineage, _)| self.is_endangered(*lineage))
+            .count();
+        let title = format!("lineages: {}, endangered: {endangered}", ranking.len());
+        draw_text(&title, x + 5., y + 16., 16., LIGHTGRAY);
+        for (n, (lineage, sample)) in ranking.iter().take(LEADERBOARD_LEN).enumerate() {
+            let risk = self.risk(*lineage);
+            let color = if risk >= ENDANGERED_RISK { RED } else { WHITE };
+            let row = format!(
+                "{:>6}: {:>4} biots, extent {:>4.0}, life {:>5.1}, risk {:>3.0}%",
+                lineage.0,
+                sample.population,
+                sample.extent,
+                sample.mean_life,
+                100. * risk
+            );
+            draw_text(&row, x + 5., y + 34. + 16. * n as f32, 15., color);
+        }
+    }
+}
@@ -1,26 +1,683 @@
+mod cli;
+
+use clap::Parser;
+use cli::{Cli, Command, ConfigArgs, ExperimentArgs, RecordArgs, RunArgs};
+use life_web::archipelago::{Archipelago, Migration};
+#[cfg(feature = "render")]
+use life_web::audio::{Audio, Sounds};
+use life_web::biot::Genome;
+#[cfg(feature = "render")]
+use life_web::capture::Timelapse;
+use life_web::config::SimConfig;
+#[cfg(feature = "render")]
+use life_web::demo::Demo;
+use life_web::events::{EventLog, EVENT_CAPACITY};
+use life_web::experiment::{Experiment, TRAIT_COLUMNS};
+use life_web::lineages::LineageStats;
+#[cfg(feature = "render")]
+use life_web::menu::MainMenu;
+use life_web::phase::PhaseProfile;
+use life_web::recorder::{record, Column, Format, Recorder, RecorderOptions};
+use life_web::scenario::{ScenarioSpec, Start};
+#[cfg(feature = "render")]
+use life_web::scene::{Context, SceneStack};
+#[cfg(feature = "scripting")]
+use life_web::script::Script;
+#[cfg(feature = "server")]
+use life_web::server::Observer;
+use life_web::simulation::{Simulation, SNAPSHOT_PATH};
+use life_web::stats::Sample;
+use life_web::upload::Uploader;
+#[cfg(feature = "render")]
+use life_web::viewer::Viewer;
+use life_web::watchdog::Watchdog;
+use life_web::world::World;
+use life_web::{fixture, library, report, validate, watchdog};
+#[cfg(feature = "render")]
 use macroquad::prelude::*;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// Number of steps the `warm` subcommand times from the mature ecosystem.
+const WARM_BENCH_STEPS: u32 = 100;
+
+/// Number of steps between two progress reports of a headless run.
+const REPORT_INTERVAL: u64 = 1000;
+
+/// Prints `error` and exits the process with a non-zero status.
+fn fail(error: &dyn std::fmt::Display) -> ! {
+    eprintln!("{error}");
+    std::process::exit(1)
+}
+
+/// Returns the configuration passed with `--config <path>` or `--preset <name>`.
+///
+/// Falls back to the default configuration if neither was passed.
+/// Exits the process if the configuration cannot be loaded.
+fn load_config(args: &ConfigArgs) -> SimConfig {
+    match (&args.config, args.preset) {
+        (Some(path), _) => {
+            SimConfig::load(path).unwrap_or_else(|error| fail(&format!("{path}: {error}")))
+        }
+        (None, Some(preset)) => preset.config().unwrap_or_else(|error| fail(&error)),
+        (None, None) => SimConfig::default(),
+    }
+}
+
+/// Returns the configuration of a run, with the initial population passed
+/// with `--population` if any.
+fn config_from_args(args: &RunArgs) -> SimConfig {
+    let mut config = load_config(&args.config);
+    if let Some(population) = args.population {
+        config.initial_population = population;
+    }
+    config
+}
+
+/// Returns the scenario passed with `--scenario <name or path>`.
+///
+/// Names of built-in scenarios take precedence over paths of scenario files.
+/// Exits the process if the scenario cannot be loaded.
+fn scenario_from_args(args: &RunArgs) -> Option<ScenarioSpec> {
+    let scenario = args.scenario.as_ref()?;
+    let spec = match scenario.parse::<Start>() {
+        Ok(start) => start.spec(),
+        Err(unknown) if !Path::new(scenario).exists() => fail(&unknown),
+        Err(_) => ScenarioSpec::load(scenario),
+    };
+    Some(spec.unwrap_or_else(|error| fail(&format!("{scenario}: {error}"))))
+}
+
+/// Returns the genomes passed with `--genomes <paths>`, see [`library::resolve`].
+///
+/// Exits the process if a genome cannot be loaded.
+fn genomes_from_args(args: &RunArgs) -> Vec<Genome> {
+    let Some(paths) = &args.genomes else {
+        return Vec::new();
+    };
+    library::resolve(paths).unwrap_or_else(|error| fail(&error))
+}
+
+/// Returns the snapshot passed with `--load <path>`.
+///
+/// Exits the process if the snapshot cannot be loaded.
+fn snapshot_from_args(args: &RunArgs) -> Option<Simulation> {
+    let path = args.load.as_ref()?;
+    Some(Simulation::load(path).unwrap_or_else(|error| fail(&format!("{path}: {error}"))))
+}
+
+/// Registers the script passed with `--script <path>` with `simulation`.
+///
+/// Exits the process if the script cannot be loaded.
+#[cfg(feature = "scripting")]
+fn add_script_from_args(args: &RunArgs, simulation: &mut Simulation) {
+    if let Some(path) = &args.script {
+        let script = Script::load(path).unwrap_or_else(|error| fail(&format!("{path}: {error}")));
+        simulation.add_rules(script);
+    }
+}
+
+/// Builds without the `scripting` feature take no scripts.
+#[cfg(not(feature = "scripting"))]
+fn add_script_from_args(_args: &RunArgs, _simulation: &mut Simulation) {}
+
+/// Returns the observer listening on the address passed with `--serve <address>`.
+///
+/// Exits the process if the address cannot be bound.
+#[cfg(feature = "server")]
+fn observer_from_args(args: &RunArgs) -> Option<Observer> {
+    let address = args.serve.as_ref()?;
+    let observer =
+        Observer::bind(address).unwrap_or_else(|error| fail(&format!("{address}: {error}")));
+    println!("serving the run at http://{}", observer.address());
+    Some(observer)
+}
+
+/// Returns the recorder configured by the `--record` family of options.
+///
+/// Exits the process if the files cannot be created.
+fn recorder_from_args(args: &RecordArgs) -> Option<Recorder> {
+    let path = args.record.as_ref()?;
+    let columns = if args.record_columns.is_empty() {
+        Column::ALL.to_vec()
+    } else {
+        args.record_columns.clone()
+    };
+    let options = RecorderOptions {
+        interval: args.record_interval,
+        format: Format::from_path(path),
+        columns,
+    };
+    let recorder = Recorder::create(
+        options,
+        path,
+        args.record_biots.clone(),
+        args.record_sizes.clone(),
+    );
+    Some(recorder.unwrap_or_else(|error| fail(&format!("{path}: {error}"))))
+}
+
+/// Runs `command` and exits.
+///
+/// `validate` exits with a non-zero status if any invariant is violated.
+fn run_command(command: Command) -> ! {
+    match command {
+        Command::Validate => {
+            let passed = validate::run();
+            std::process::exit(if passed { 0 } else { 1 });
+        }
+        Command::Report { recording, output } => {
+            let output = output.unwrap_or_else(|| {
+                Path::new(&recording)
+                    .with_extension("html")
+                    .to_string_lossy()
+                    .into_owned()
+            });
+            if let Err(error) = report::generate(&recording, &output) {
+                fail(&format!("{recording}: {error}"));
+            }
+            println!("report written to {output}");
+        }
+        Command::Warm { seed, steps } => {
+            let mut simulation = fixture::mature(fixture::FIXTURE_DIR, seed, steps)
+                .unwrap_or_else(|error| fail(&format!("failed to warm up the ecosystem: {error}")));
+            let path = fixture::fixture_path(fixture::FIXTURE_DIR, seed, steps);
+            println!(
+                "{}: {} biots after {} steps",
+                path.display(),
+                simulation.biots().len(),
+                simulation.biots().steps()
+            );
+            let mut profile = PhaseProfile::new();
+            let start = Instant::now();
+            for _ in 0..WARM_BENCH_STEPS {
+                simulation.step_with(&mut profile);
+            }
+            let elapsed = start.elapsed() / WARM_BENCH_STEPS;
+            println!("{elapsed:?} per step over {WARM_BENCH_STEPS} steps");
+            for (phase, total) in profile.totals() {
+                println!("  {:<12} {:?}", phase.name(), total / profile.steps());
+            }
+        }
+        Command::Experiment(args) => run_experiment(args),
+    }
+    std::process::exit(0)
+}
+
+/// Runs the parameter sweep of the `experiment` subcommand.
+///
+/// Exits the process if the sweep is invalid or the results cannot be written.
+fn run_experiment(args: ExperimentArgs) {
+    let base = match (&args.config.config, args.config.preset) {
+        (Some(path), _) => {
+            std::fs::read_to_string(path).unwrap_or_else(|error| fail(&format!("{path}: {error}")))
+        }
+        (None, Some(preset)) => preset.source().to_string(),
+        (None, None) => String::new(),
+    };
+    let steps = args.steps;
+    let experiment = Experiment::new(&base, args.sweeps, args.runs, args.seed, steps)
+        .unwrap_or_else(|error| fail(&error));
+    let jobs = args
+        .jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, usize::from));
+    let total = experiment.runs();
+    println!("running {total} simulations of {steps} steps on {jobs} threads");
+    let finished = std::sync::atomic::AtomicUsize::new(0);
+    let summaries = experiment.run(jobs, |summary| {
+        let finished = finished.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+        println!(
+            "[{finished}/{total}] combination {} seed {}: {} biots after {} steps",
+            summary.combination, summary.seed, summary.population, summary.steps
+        );
+    });
+    let output = args.output;
+    if let Err(error) = experiment.save(&summaries, &output) {
+        fail(&format!("{output}: {error}"));
+    }
+    println!("results written to {output}");
+}
+
+/// Returns a flag raised once the process is asked to stop by Ctrl+C or `SIGTERM`.
+///
+/// # Note
+///
+/// A second request exits right away, in case the current step hangs.
+fn shutdown_flag() -> Arc<AtomicBool> {
+    let requested = Arc::new(AtomicBool::new(false));
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let flag = Arc::clone(&requested);
+        let handler = ctrlc::set_handler(move || {
+            if flag.swap(true, Ordering::SeqCst) {
+                std::process::exit(130);
+            }
+            eprintln!("stopping after the current step, interrupt again to exit right away");
+        });
+        if let Err(error) = handler {
+            eprintln!("failed to install the shutdown handler: {error}");
+        }
+    }
+    requested
+}
 
-mod biot;
-mod biot_collection;
-
-use biot_collection::BiotCollection;
-
-#[macroquad::main("Life")]
-async fn main() {
-    rand::srand(miniquad::date::now().to_bits());
-    let mut biots = BiotCollection::new(600);
-
-    loop {
-        biots.step();
-        clear_background(Color::new(0., 0., 0.1, 1.0));
-        biots.draw();
-        draw_text(
-            &format!("FPS: {}, biots: {}", get_fps(), biots.len()),
-            screen_width() - 200.,
-            screen_height() - 5.,
-            18.,
-            LIGHTGRAY,
+/// Prints the final state of a headless run after `steps` steps.
+fn print_summary(simulation: &Simulation, steps: u64) {
+    let biots = simulation.biots();
+    let sample = Sample::take(biots, simulation.date(), 0, 0, 0);
+    println!(
+        "summary after {steps} steps of this run, step {} of seed {}:",
+        biots.steps(),
+        simulation.seed()
+    );
+    println!(
+        "  biots: {}, lineages: {}, species: {}, corpses: {}",
+        sample.population,
+        sample.lineages,
+        sample.species,
+        simulation.biots().corpses().len()
+    );
+    if sample.population > 0 {
+        let means = TRAIT_COLUMNS
+            .iter()
+            .zip(sample.means)
+            .map(|(name, mean)| format!("{name} {mean:.2}"));
+        println!("  mean {}", means.collect::<Vec<_>>().join(", "));
+        println!(
+            "  mean genes: {:.1}, mean mutation chance: {:.3}",
+            sample.genes, sample.mutation_chance
         );
+    }
+}
+
+/// Returns the seed passed with `--seed <seed>`, or one derived from the current time.
+fn seed_from_args(args: &RunArgs) -> u64 {
+    args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default()
+    })
+}
+
+/// Returns the configurations of the islands passed with `--islands <count>`
+/// or `--island-configs <paths>`, empty for a single simulation.
+///
+/// Exits the process if a configuration cannot be loaded.
+fn island_configs_from_args(args: &RunArgs) -> Vec<SimConfig> {
+    let islands = &args.islands;
+    if !islands.island_configs.is_empty() {
+        return islands
+            .island_configs
+            .iter()
+            .map(|path| {
+                let mut config =
+                    SimConfig::load(path).unwrap_or_else(|error| fail(&format!("{path}: {error}")));
+                if let Some(population) = args.population {
+                    config.initial_population = population;
+                }
+                config
+            })
+            .collect();
+    }
+    vec![config_from_args(args); islands.islands.unwrap_or_default()]
+}
+
+/// Returns the migration between the islands passed with the `--migration-*` options.
+fn migration_from_args(args: &RunArgs) -> Migration {
+    Migration {
+        interval: args.islands.migration_interval,
+        fraction: args.islands.migration_fraction,
+    }
+}
+
+/// Prints the genomes of the biots `simulation` had to sanitize in step `step`.
+fn report_sanitized(simulation: &Simulation, step: u64) {
+    for genome in simulation.biots().sanitized() {
+        eprintln!("step {step}: sanitized non-finite state of a biot with genome {genome:?}");
+    }
+}
+
+/// Runs several islands exchanging migrants without a window until all of
+/// them went extinct, `--ticks` steps were simulated or the process is asked
+/// to stop.
+///
+/// # Note
+///
+/// The exports, recordings and checkpoints of single simulations are not
+/// written for islands.
+fn run_islands(args: &RunArgs, configs: &[SimConfig]) {
+    let shutdown = shutdown_flag();
+    let seed = seed_from_args(args);
+    let world = World::new(800., 600.);
+    let mut archipelago = Archipelago::generate(world, configs, seed, migration_from_args(args));
+    println!("seed: {seed}, islands: {}", configs.len());
+    let mut steps: u64 = 0;
+    let mut migrated = 0;
+    while !archipelago.is_extinct()
+        && args.ticks.is_none_or(|ticks| steps < ticks)
+        && !shutdown.load(Ordering::SeqCst)
+    {
+        migrated += archipelago.step();
+        steps += 1;
+        for island in archipelago.islands() {
+            report_sanitized(island, steps);
+        }
+        if steps.is_multiple_of(REPORT_INTERVAL) {
+            let populations = archipelago
+                .islands()
+                .iter()
+                .map(|island| island.biots().len().to_string());
+            println!(
+                "step {steps}: biots per island: {}, migrants: {migrated}",
+                populations.collect::<Vec<_>>().join(", ")
+            );
+            migrated = 0;
+        }
+    }
+    if archipelago.is_extinct() {
+        println!("all islands went extinct after {steps} steps");
+    } else if shutdown.load(Ordering::SeqCst) {
+        println!("interrupted after {steps} steps");
+    } else {
+        println!("stopped after {steps} steps");
+    }
+    for (n, island) in archipelago.islands().iter().enumerate() {
+        println!("island {}:", n + 1);
+        print_summary(island, steps);
+    }
+}
+
+/// Runs a simulation without a window until the population goes extinct,
+/// `--ticks` steps were simulated or the process is asked to stop.
+///
+/// # Note
+///
+/// Runs stopped early write their phylogeny and lineage exports and a final
+/// checkpoint to the `--autosave` snapshot, so that they can be continued
+/// with `--load`. Interrupted runs and runs halted by the watchdog without
+/// `--autosave` checkpoint to [`SNAPSHOT_PATH`] instead, unless they
+/// continue it.
+fn run_headless(args: &RunArgs) {
+    let shutdown = shutdown_flag();
+    let seed = seed_from_args(args);
+    let config = config_from_args(args);
+    // Same size as the default window of the interactive frontend.
+    let world = World::new(800., 600.);
+    let mut simulation = match (snapshot_from_args(args), scenario_from_args(args)) {
+        (Some(simulation), _) => {
+            println!(
+                "continuing {} at step {}",
+                args.load.as_deref().unwrap_or_default(),
+                simulation.biots().steps()
+            );
+            simulation
+        }
+        (None, Some(scenario)) => scenario.simulation(world, seed),
+        (None, None) => Simulation::with_genomes(world, config, seed, &genomes_from_args(args)),
+    };
+    println!("seed: {}", simulation.seed());
+    if simulation.biots().config().controller.is_enabled() {
+        println!(
+            "population controller enabled with a target of {} biots, \
+             sunlight and food are auto-tuned",
+            simulation.biots().config().controller.target
+        );
+    }
+    let mut recorder = recorder_from_args(&args.record);
+    let mut phylogeny = args.phylogeny.clone();
+    let mut events = EventLog::new(EVENT_CAPACITY);
+    let mut lineages = LineageStats::new();
+    let mut lineages_path = args.lineages.clone();
+    let mut autosave = args.autosave.clone();
+    let mut uploader = args
+        .upload
+        .as_deref()
+        .map(|destination| Uploader::new(destination.into()));
+    let mut upload = |path: &str| {
+        if let Some(uploader) = &mut uploader {
+            uploader.upload(path);
+        }
+    };
+    add_script_from_args(args, &mut simulation);
+    #[cfg(feature = "server")]
+    let mut observer = observer_from_args(args);
+    let mut watchdog = Watchdog::new();
+    let mut halted = false;
+    let mut steps: u64 = 0;
+    while !simulation.biots().is_empty()
+        && args.ticks.is_none_or(|ticks| steps < ticks)
+        && !shutdown.load(Ordering::SeqCst)
+        && !halted
+    {
+        let start = Instant::now();
+        simulation.step();
+        let elapsed = start.elapsed();
+        for failure in simulation.biots().rule_failures() {
+            eprintln!("step {}: rules dropped: {failure}", steps + 1);
+        }
+        report_sanitized(&simulation, steps + 1);
+        #[cfg(feature = "server")]
+        if let Some(observer) = &mut observer {
+            observer.serve(&simulation);
+        }
+        if let Some((anomalies, action)) = watchdog.check(&simulation, Some(elapsed)) {
+            halted = watchdog::respond(&simulation, &anomalies, action);
+        }
+        record(&mut recorder, &simulation);
+        steps += 1;
+        if let Some(alarm) = simulation.alarm() {
+            println!("step {steps}: {alarm}");
+        }
+        events.record(&simulation);
+        lineages.record(simulation.biots());
+        for logged in events.latest().filter(|logged| logged.event.is_notable()) {
+            println!("{logged}");
+        }
+        if !simulation.injected().is_empty() {
+            let injected = simulation.injected().iter().map(|id| id.0.to_string());
+            println!(
+                "step {steps}: injected novel biots {}",
+                injected.collect::<Vec<_>>().join(", ")
+            );
+        }
+        if steps.is_multiple_of(REPORT_INTERVAL) {
+            println!(
+                "step {steps}: biots: {}, clustering: {:.2}, supply: {:.2}",
+                simulation.biots().len(),
+                simulation.biots().clustering_index(),
+                simulation.environment().supply()
+            );
+            // The tree is pruned to the living biots, so the last export
+            // before an extinction is kept.
+            if let Some(path) = &phylogeny {
+                match simulation.biots().phylogeny().save(path) {
+                    Ok(()) => upload(path),
+                    Err(error) => {
+                        eprintln!("phylogeny export stopped: {path}: {error}");
+                        phylogeny = None;
+                    }
+                }
+            }
+            if let Some(path) = &lineages_path {
+                match lineages.save(path) {
+                    Ok(()) => upload(path),
+                    Err(error) => {
+                        eprintln!("lineage export stopped: {path}: {error}");
+                        lineages_path = None;
+                    }
+                }
+            }
+        }
+        if steps.is_multiple_of(args.save_every) {
+            if let Some(path) = &autosave {
+                match simulation.save(path) {
+                    Ok(()) => upload(path),
+                    Err(error) => {
+                        eprintln!("autosave stopped: {path}: {error}");
+                        autosave = None;
+                    }
+                }
+            }
+        }
+    }
+    if simulation.biots().is_empty() {
+        println!("population went extinct after {steps} steps");
+    } else {
+        if shutdown.load(Ordering::SeqCst) {
+            println!("interrupted after {steps} steps");
+        } else if halted {
+            println!("stopped by the watchdog after {steps} steps");
+        } else {
+            println!("stopped after {steps} steps");
+        }
+        let interrupted = shutdown.load(Ordering::SeqCst) || halted;
+        // The snapshot the run continues is kept as it was loaded.
+        let continued = args.load.as_deref().is_some_and(|load| {
+            let canonical = |path| fs::canonicalize(path).ok();
+            canonical(load).is_some_and(|load| Some(load) == canonical(SNAPSHOT_PATH))
+        });
+        let checkpoint = match autosave.as_deref() {
+            Some(path) => Some(path),
+            None if interrupted && continued => {
+                println!("no checkpoint written, pass --autosave to continue {SNAPSHOT_PATH}");
+                None
+            }
+            None => interrupted.then_some(SNAPSHOT_PATH),
+        };
+        if let Some(checkpoint) = checkpoint {
+            match simulation.save(checkpoint) {
+                Ok(()) => {
+                    println!("checkpoint written to {checkpoint}");
+                    upload(checkpoint);
+                }
+                Err(error) => {
+                    eprintln!("failed to write the checkpoint to {checkpoint}: {error}")
+                }
+            }
+        }
+        if let Some(path) = &phylogeny {
+            match simulation.biots().phylogeny().save(path) {
+                Ok(()) => upload(path),
+                Err(error) => eprintln!("failed to export the phylogeny to {path}: {error}"),
+            }
+        }
+        if let Some(path) = &lineages_path {
+            match lineages.save(path) {
+                Ok(()) => upload(path),
+                Err(error) => eprintln!("failed to export the lineages to {path}: {error}"),
+            }
+        }
+    }
+    if let Some(path) = &args.events {
+        match events.save(path) {
+            Ok(()) => upload(path),
+            Err(error) => eprintln!("failed to write event log to {path}: {error}"),
+        }
+    }
+    // Closing the recordings flushes them before they are uploaded.
+    drop(recorder);
+    let record = &args.record;
+    [&record.record, &record.record_biots, &record.record_sizes]
+        .into_iter()
+        .flatten()
+        .for_each(|path| upload(path));
+    print_summary(&simulation, steps);
+}
+
+/// Opens the window of the interactive frontend.
+#[cfg(feature = "render")]
+async fn run_viewer(args: RunArgs) {
+    let labels = args.labels.clone().unwrap_or_default();
+    let ticks_per_second = args.tps;
+    let mut context = Context::new(
+        config_from_args(&args),
+        args.seed,
+        recorder_from_args(&args.record),
+        args.events.clone(),
+        genomes_from_args(&args),
+        labels.clone(),
+        scenario_from_args(&args),
+    );
+    context.ticks_per_second = ticks_per_second;
+    context.timelapse = args.timelapse.as_ref().map(|dir| {
+        Timelapse::new(dir, args.timelapse_interval)
+            .unwrap_or_else(|error| fail(&format!("{dir}: {error}")))
+    });
+    match Sounds::load().await {
+        Ok(sounds) => context.audio = Some(Audio::new(sounds, args.sound)),
+        Err(error) => eprintln!("failed to load the sounds: {error}"),
+    }
+    let mut scenes = SceneStack::new(Box::new(MainMenu::new()));
+    // The demo, snapshots and explicitly passed configurations start right
+    // away, closing them still leads to the main menu.
+    let islands = island_configs_from_args(&args);
+    let started = args.config.config.is_some()
+        || args.config.preset.is_some()
+        || args.scenario.is_some()
+        || args.population.is_some();
+    if args.demo {
+        scenes.push(Box::new(Demo::new(&mut context)));
+    } else if let Some(mut simulation) = snapshot_from_args(&args) {
+        add_script_from_args(&args, &mut simulation);
+        scenes.push(Box::new(Viewer::new(
+            simulation,
+            context.recorder.take(),
+            context.events.clone(),
+            labels,
+            ticks_per_second,
+            context.timelapse.clone(),
+        )));
+    } else if let Some((first, others)) = islands.split_first() {
+        let mut simulation = context.new_simulation_with(first.clone());
+        add_script_from_args(&args, &mut simulation);
+        // The islands are seeded like the ones of a headless run.
+        let (world, seed) = (*simulation.world(), simulation.seed());
+        let migration = migration_from_args(&args);
+        let archipelago = Archipelago::generate_around(world, others, seed, migration);
+        let viewer = Viewer::new(
+            simulation,
+            context.recorder.take(),
+            context.events.clone(),
+            labels,
+            ticks_per_second,
+            context.timelapse.clone(),
+        );
+        scenes.push(Box::new(viewer.with_islands(archipelago)));
+    } else if started {
+        let mut simulation = context.new_simulation();
+        add_script_from_args(&args, &mut simulation);
+        scenes.push(Box::new(Viewer::new(
+            simulation,
+            context.recorder.take(),
+            context.events.clone(),
+            labels,
+            ticks_per_second,
+            context.timelapse.clone(),
+        )));
+    }
+    while scenes.frame(&mut context) {
         next_frame().await
     }
 }
+
+fn main() {
+    let cli = Cli::parse();
+    if let Some(command) = cli.command {
+        run_command(command);
+    }
+    #[cfg(feature = "render")]
+    if !cli.run.headless {
+        macroquad::Window::new("Life", run_viewer(cli.run));
+        return;
+    }
+    let islands = island_configs_from_args(&cli.run);
+    if islands.is_empty() {
+        run_headless(&cli.run);
+    } else {
+        run_islands(&cli.run, &islands);
+    }
+}
@@ -0,0 +1,404 @@
+//! Menu scenes of the interactive frontend.
+
+use crate::config::SimConfig;
+use crate::demo::Demo;
+use crate::pack::MODS_DIR;
+use crate::preset::Preset;
+use crate::scene::{Context, Scene, Transition};
+use crate::simulation::{Simulation, SNAPSHOT_PATH};
+use crate::viewer::Viewer;
+use macroquad::prelude::*;
+
+/// Font size of the menu items.
+const ITEM_SIZE: f32 = 30.0;
+
+/// Vertical distance between two menu items.
+const ITEM_SPACING: f32 = 40.0;
+
+/// Vertical position of the first menu item.
+const FIRST_ITEM_Y: f32 = 180.0;
+
+/// A vertical list of items chosen with the arrow keys and enter, or the mouse.
+struct Menu {
+    title: &'static str,
+    selected: usize,
+}
+
+impl Menu {
+    /// Creates a menu with the first item selected.
+    fn new(title: &'static str) -> Self {
+        Self { title, selected: 0 }
+    }
+
+    /// Returns the vertical position of the baseline of the item at `index`.
+    fn item_y(index: usize) -> f32 {
+        FIRST_ITEM_Y + ITEM_SPACING * index as f32
+    }
+
+    /// Handles the input for a menu of `len` items.
+    ///
+    /// Returns the index of the item chosen in this frame, if any.
+    fn update(&mut self, len: usize) -> Option<usize> {
+        if is_key_pressed(KeyCode::Down) {
+            self.selected = (self.selected + 1) % len;
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.selected = (self.selected + len - 1) % len;
+        }
+        let (_, mouse_y) = mouse_position();
+        let hovered = (0..len).find(|index| {
+            let y = Self::item_y(*index);
+            (y - ITEM_SIZE..y + ITEM_SPACING - ITEM_SIZE).contains(&mouse_y)
+        });
+        if let Some(index) = hovered {
+            self.selected = index;
+            if is_mouse_button_pressed(MouseButton::Left) {
+                return Some(index);
+            }
+        }
+        is_key_pressed(KeyCode::Enter).then_some(self.selected)
+    }
+
+    /// Draws the menu with the given items and an optional status line.
+    fn draw(&self, items: &[String], status: Option<&str>) {
+        clear_background(Color::new(0., 0., 0.1, 1.0));
+        let x = screen_width() / 2. - 200.;
+        draw_text(self.title, x, FIRST_ITEM_Y - 80., 50., WHITE);
+        for (index, item) in items.iter().enumerate() {
+            let (marker, color) = if index == self.selected {
+                ("> ", YELLOW)
+            } else {
+                ("  ", LIGHTGRAY)
+            };
+            let text = format!("{marker}{item}");
+            draw_text(&text, x, Self::item_y(index), ITEM_SIZE, color);
+        }
+        if let Some(status) = status {
+            let y = Self::item_y(items.len()) + ITEM_SPACING;
+            draw_text(status, x, y, 20., ORANGE);
+        }
+    }
+}
+
+/// The menu shown at startup.
+pub struct MainMenu {
+    menu: Menu,
+    /// Outcome of the last chosen item, e.g. why loading a snapshot failed.
+    status: Option<String>,
+}
+
+impl Default for MainMenu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MainMenu {
+    /// The items of the main menu.
+    const ITEMS: [&'static str; 7] = [
+        "New simulation",
+        "Load snapshot",
+        "Load scenario",
+        "Mods",
+        "Demo",
+        "Settings",
+        "Quit",
+    ];
+
+    /// Creates the main menu.
+    pub fn new() -> Self {
+        Self {
+            menu: Menu::new("Life"),
+            status: None,
+        }
+    }
+}
+
+impl Scene for MainMenu {
+    fn frame(&mut self, context: &mut Context) -> Transition {
+        let chosen = self.menu.update(Self::ITEMS.len());
+        let items = Self::ITEMS.map(String::from);
+        self.menu.draw(&items, self.status.as_deref());
+        if is_key_pressed(KeyCode::Escape) {
+            return Transition::Pop;
+        }
+        match chosen {
+            Some(0) => {
+                let simulation = context.new_simulation();
+                Transition::Push(Box::new(Viewer::new(
+                    simulation,
+                    context.recorder.take(),
+                    context.events.clone(),
+                    context.labels.clone(),
+                    context.ticks_per_second,
+                    context.timelapse.clone(),
+                )))
+            }
+            Some(1) => match Simulation::load(SNAPSHOT_PATH) {
+                Ok(simulation) => {
+                    self.status = None;
+                    Transition::Push(Box::new(Viewer::new(
+                        simulation,
+                        context.recorder.take(),
+                        context.events.clone(),
+                        context.labels.clone(),
+                        context.ticks_per_second,
+                        context.timelapse.clone(),
+                    )))
+                }
+                Err(error) => {
+                    self.status = Some(format!("{SNAPSHOT_PATH}: {error}"));
+                    Transition::Stay
+                }
+            },
+            Some(2) => Transition::Push(Box::new(ScenarioMenu::new())),
+            Some(3) => Transition::Push(Box::new(ModMenu::new())),
+            Some(4) => Transition::Push(Box::new(Demo::new(context))),
+            Some(5) => Transition::Push(Box::new(SettingsMenu::new())),
+            Some(_) => Transition::Pop,
+            None => Transition::Stay,
+        }
+    }
+}
+
+/// Menu starting a simulation from one of the [`Preset`]s.
+struct ScenarioMenu {
+    menu: Menu,
+    status: Option<String>,
+}
+
+impl ScenarioMenu {
+    fn new() -> Self {
+        Self {
+            menu: Menu::new("Load scenario"),
+            status: None,
+        }
+    }
+}
+
+impl Scene for ScenarioMenu {
+    fn frame(&mut self, context: &mut Context) -> Transition {
+        let mut items = Preset::ALL
+            .iter()
+            .map(|preset| preset.name().replace('_', " "))
+            .collect::<Vec<_>>();
+        items.push(String::from("Back"));
+        let chosen = self.menu.update(items.len());
+        self.menu.draw(&items, self.status.as_deref());
+        if is_key_pressed(KeyCode::Escape) {
+            return Transition::Pop;
+        }
+        let Some(index) = chosen else {
+            return Transition::Stay;
+        };
+        let Some(preset) = Preset::ALL.get(index) else {
+            return Transition::Pop;
+        };
+        match preset.config() {
+            Ok(config) => {
+                context.config = config;
+                let simulation = context.new_simulation();
+                // Closing the simulation goes back to the main menu.
+                Transition::Replace(Box::new(Viewer::new(
+                    simulation,
+                    context.recorder.take(),
+                    context.events.clone(),
+                    context.labels.clone(),
+                    context.ticks_per_second,
+                    context.timelapse.clone(),
+                )))
+            }
+            Err(error) => {
+                self.status = Some(error.to_string());
+                Transition::Stay
+            }
+        }
+    }
+}
+
+/// Menu starting a simulation from a scenario of one of the content packs.
+struct ModMenu {
+    menu: Menu,
+    status: Option<String>,
+}
+
+impl ModMenu {
+    fn new() -> Self {
+        Self {
+            menu: Menu::new("Mods"),
+            status: None,
+        }
+    }
+}
+
+impl Scene for ModMenu {
+    fn frame(&mut self, context: &mut Context) -> Transition {
+        let scenarios = context
+            .packs
+            .iter()
+            .flat_map(|pack| pack.scenarios.iter().map(move |scenario| (pack, scenario)))
+            .collect::<Vec<_>>();
+        let mut items = scenarios
+            .iter()
+            .map(|(pack, scenario)| format!("{}: {}", pack.name, scenario.name.replace('_', " ")))
+            .collect::<Vec<_>>();
+        items.push(String::from("Back"));
+        let chosen = self.menu.update(items.len());
+        let hint = format!("no scenarios found in {MODS_DIR}/<pack>/scenarios");
+        let status = match &self.status {
+            Some(status) => Some(status.as_str()),
+            None => scenarios.is_empty().then_some(hint.as_str()),
+        };
+        self.menu.draw(&items, status);
+        if is_key_pressed(KeyCode::Escape) {
+            return Transition::Pop;
+        }
+        let Some(index) = chosen else {
+            return Transition::Stay;
+        };
+        let Some((_, scenario)) = scenarios.get(index) else {
+            return Transition::Pop;
+        };
+        match SimConfig::load(&scenario.path) {
+            Ok(config) => {
+                context.config = config;
+                let simulation = context.new_simulation();
+                // Closing the simulation goes back to the main menu.
+                Transition::Replace(Box::new(Viewer::new(
+                    simulation,
+                    context.recorder.take(),
+                    context.events.clone(),
+                    context.labels.clone(),
+                    context.ticks_per_second,
+                    context.timelapse.clone(),
+                )))
+            }
+            Err(error) => {
+                self.status = Some(format!("{}: {error}", scenario.path.display()));
+                Transition::Stay
+            }
+        }
+    }
+}
+
+/// Menu adjusting the configuration of new simulations with the left and
+/// right arrow keys, or by tapping the left and right half of a setting.
+struct SettingsMenu {
+    menu: Menu,
+}
+
+impl SettingsMenu {
+    /// Number of adjustable settings, followed by the item going back.
+    const SETTINGS: usize = 5;
+
+    /// Index of the seed of the next simulation, which can also be typed in.
+    const SEED: usize = 4;
+
+    fn new() -> Self {
+        Self {
+            menu: Menu::new("Settings"),
+        }
+    }
+
+    /// Changes the setting at `index` by `steps` increments.
+    fn adjust(context: &mut Context, index: usize, steps: f32) {
+        let config = &mut context.config;
+        match index {
+            0 => {
+                let population = config.initial_population as f32 + 100. * steps;
+                config.initial_population = population.max(0.) as usize;
+            }
+            1 => config.mutation_chance = (config.mutation_chance + 0.05 * steps).clamp(0., 0.95),
+            2 => config.food.spawn_rate = (config.food.spawn_rate + 0.1 * steps).max(0.),
+            3 => config.light.shading = (config.light.shading + 0.1 * steps).max(0.),
+            Self::SEED => {
+                // Stepping from the latest seed makes it easy to restart or try its neighbors.
+                let seed = context.seed().or(context.last_seed()).unwrap_or_default();
+                let seed = if steps < 0. {
+                    seed.saturating_sub(1)
+                } else {
+                    seed.saturating_add(1)
+                };
+                context.set_seed(Some(seed));
+            }
+            _ => {}
+        }
+    }
+
+    /// Edits the seed of the next simulation with the typed digits,
+    /// `Backspace` removes the last digit.
+    fn type_seed(context: &mut Context) {
+        if is_key_pressed(KeyCode::Backspace) {
+            context.set_seed(
+                context
+                    .seed()
+                    .map(|seed| seed / 10)
+                    .filter(|seed| *seed > 0),
+            );
+        }
+        while let Some(character) = get_char_pressed() {
+            let Some(digit) = character.to_digit(10) else {
+                continue;
+            };
+            let seed = context.seed().unwrap_or_default();
+            if let Some(seed) = seed
+                .checked_mul(10)
+                .and_then(|seed| seed.checked_add(digit.into()))
+            {
+                context.set_seed(Some(seed));
+            }
+        }
+    }
+}
+
+impl Scene for SettingsMenu {
+    fn frame(&mut self, context: &mut Context) -> Transition {
+        let config = &context.config;
+        let items = [
+            format!("Initial population: {}", config.initial_population),
+            format!("Mutation chance: {:.2}", config.mutation_chance),
+            format!("Food spawn rate: {:.1}", config.food.spawn_rate),
+            format!("Light shading: {:.1}", config.light.shading),
+            match (context.seed(), context.last_seed()) {
+                (Some(seed), _) => format!("Seed: {seed}"),
+                (None, Some(last)) => format!("Seed: random, last {last}"),
+                (None, None) => String::from("Seed: random"),
+            },
+            String::from("Back"),
+        ];
+        let chosen = self.menu.update(items.len());
+        self.menu.draw(
+            &items,
+            Some(
+                "left and right change the selected setting of new simulations, digits type a seed",
+            ),
+        );
+        if is_key_pressed(KeyCode::Left) {
+            Self::adjust(context, self.menu.selected, -1.0);
+        }
+        if is_key_pressed(KeyCode::Right) {
+            Self::adjust(context, self.menu.selected, 1.0);
+        }
+        if let Some(index) = chosen.filter(|index| *index < Self::SETTINGS) {
+            if is_mouse_button_pressed(MouseButton::Left) {
+                let (mouse_x, _) = mouse_position();
+                let steps = if mouse_x < screen_width() / 2. {
+                    -1.0
+                } else {
+                    1.0
+                };
+                Self::adjust(context, index, steps);
+            }
+        }
+        if self.menu.selected == Self::SEED {
+            Self::type_seed(context);
+        } else {
+            // Drop the characters typed while another setting was selected.
+            while get_char_pressed().is_some() {}
+        }
+        if is_key_pressed(KeyCode::Escape) || chosen == Some(Self::SETTINGS) {
+            return Transition::Pop;
+        }
+        Transition::Stay
+    }
+}
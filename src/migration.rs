@@ -0,0 +1,145 @@
+//! Upgrades of snapshots and configuration files written by older versions.
+//!
+//! Both formats carry a version and every upgrade is keyed by the version it
+//! upgrades from, so files of any supported version are brought up to date
+//! one version at a time before they are decoded.
+//!
+//! Configuration files are upgraded as TOML tables before they are
+//! deserialized. Snapshots are bincode without any field names, so instead
+//! the types whose layout changed decode the layout of the snapshot version
+//! being read, see [`snapshot_version`].
+
+use crate::config::ConfigError;
+use std::cell::Cell;
+use toml::value::Table;
+use toml::Value;
+
+/// Version of the configuration format read by [`SimConfig::from_toml`](crate::config::SimConfig::from_toml).
+///
+/// Files declare their format with a top-level `version`, files without one
+/// predate versions and are of version 1.
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Upgrade of a configuration to the next version.
+type Upgrade = fn(&mut Table);
+
+/// Upgrades of the configuration format, keyed by the version they upgrade from.
+const CONFIG_UPGRADES: [(u32, Upgrade); 1] = [(1, shape_responses)];
+
+/// Oldest snapshot version that can still be loaded.
+pub const OLDEST_SNAPSHOT_VERSION: u32 = 43;
+
+thread_local! {
+    /// Version of the snapshot decoded on this thread, if any.
+    static SNAPSHOT_VERSION: Cell<Option<u32>> = const { Cell::new(None) };
+}
+
+/// Upgrades the configuration file `contents` to [`CONFIG_VERSION`].
+///
+/// The configuration is the table at `path` within the file, e.g. the
+/// `config` table of scenario files. Returns the upgraded file, or `None` if
+/// the file is up to date and declares no version, so that it can be parsed
+/// as it is.
+pub(crate) fn upgrade_config(contents: &str, path: &[&str]) -> Result<Option<String>, ConfigError> {
+    let original: Table = toml::from_str(contents)?;
+    let mut root = original.clone();
+    let version = match root.remove("version") {
+        None => 1,
+        Some(Value::Integer(version)) => u32::try_from(version).unwrap_or(u32::MAX),
+        Some(_) => {
+            let message = "the config version needs to be a positive integer";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+    };
+    if version == 0 || version > CONFIG_VERSION {
+        return Err(ConfigError::Invalid(format!(
+            "unsupported config version {version}, expected at most {CONFIG_VERSION}"
+        )));
+    }
+    let mut config = Some(&mut root);
+    for key in path {
+        config = match config.and_then(|table| table.get_mut(*key)) {
+            Some(Value::Table(table)) => Some(table),
+            _ => None,
+        };
+    }
+    if let Some(config) = config {
+        for (_, upgrade) in CONFIG_UPGRADES.iter().filter(|(from, _)| *from >= version) {
+            upgrade(config);
+        }
+    }
+    if root == original {
+        return Ok(None);
+    }
+    // Nested tables are written inline, the only way `toml` reads enums.
+    let upgraded = root
+        .iter()
+        .map(|(key, value)| format!("{} = {}\n", quote(key), inline(value)));
+    Ok(Some(upgraded.collect()))
+}
+
+/// Returns `key` as a quoted TOML key.
+fn quote(key: &str) -> String {
+    Value::String(String::from(key)).to_string()
+}
+
+/// Returns `value` as TOML on a single line, with inline tables.
+fn inline(value: &Value) -> String {
+    match value {
+        Value::Table(table) => {
+            let entries = table
+                .iter()
+                .map(|(key, value)| format!("{} = {}", quote(key), inline(value)));
+            format!("{{ {} }}", entries.collect::<Vec<_>>().join(", "))
+        }
+        Value::Array(values) => {
+            let values = values.iter().map(inline);
+            format!("[{}]", values.collect::<Vec<_>>().join(", "))
+        }
+        value => value.to_string(),
+    }
+}
+
+/// Version 2 replaced the `exponent` of response curves by their `shape`.
+///
+/// # Note
+///
+/// The curves become power curves of the same exponent, or linear ones for
+/// an exponent of 1. They respond like the old `(1 + n)^exponent - 1` curves
+/// to many genes, but somewhat stronger to the first ones.
+fn shape_responses(config: &mut Table) {
+    let Some(Value::Table(responses)) = config.get_mut("responses") else {
+        return;
+    };
+    for (_, curve) in responses.iter_mut() {
+        let Value::Table(curve) = curve else {
+            continue;
+        };
+        let Some(exponent) = curve.remove("exponent") else {
+            continue;
+        };
+        let shape = if exponent.as_float() == Some(1.0) || exponent.as_integer() == Some(1) {
+            Value::String(String::from("linear"))
+        } else {
+            Value::Table(Table::from_iter([(String::from("power"), exponent)]))
+        };
+        curve.entry("shape").or_insert(shape);
+    }
+}
+
+/// Decodes a snapshot of `version` with `decode`.
+pub(crate) fn decode_snapshot<T>(version: u32, decode: impl FnOnce() -> T) -> T {
+    let outer = SNAPSHOT_VERSION.with(|current| current.replace(Some(version)));
+    let decoded = decode();
+    SNAPSHOT_VERSION.with(|current| current.set(outer));
+    decoded
+}
+
+/// Returns the version of the snapshot decoded on this thread, if any.
+///
+/// Types whose layout changed check it to decode the layout of older
+/// snapshots, e.g. the nodes of the [`Phylogeny`](crate::phylogeny::Phylogeny)
+/// before version 44.
+pub(crate) fn snapshot_version() -> Option<u32> {
+    SNAPSHOT_VERSION.with(Cell::get)
+}
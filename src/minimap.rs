@@ -0,0 +1,128 @@
+//! Overview of the whole world in the bottom right corner of the screen,
+//! shown once the world no longer fits onto the screen.
+//!
+//! Clicking into the minimap moves the camera to the clicked position.
+
+use crate::biot::Biot;
+use crate::biot_collection::BiotCollection;
+use crate::camera::Camera;
+use crate::species::{species_color, Coloring};
+use crate::world::World;
+use glam::{vec2, Vec2};
+use macroquad::prelude::{
+    draw_rectangle, draw_rectangle_lines, is_mouse_button_pressed, mouse_position, screen_height,
+    screen_width, Color, MouseButton, BLUE, DARKBLUE, GREEN, LIGHTGRAY, RED, WHITE,
+};
+
+/// Length of the longer side of the minimap in pixels.
+const SIZE: f32 = 180.0;
+
+/// Distance of the minimap to the right edge of the screen and to the status lines.
+const MARGIN: f32 = 10.0;
+
+/// Height of the status lines below the minimap.
+const STATUS_HEIGHT: f32 = 50.0;
+
+/// Returns `true` if the minimap is shown, i.e. if the view does not show the whole world.
+pub fn is_shown(world: &World, camera: &Camera) -> bool {
+    world.width * camera.zoom() > screen_width() || world.height * camera.zoom() > screen_height()
+}
+
+/// Returns the top left corner of the minimap on the screen and its pixels per world unit.
+fn frame(world: &World) -> (Vec2, f32) {
+    let scale = SIZE / world.width.max(world.height);
+    let corner = vec2(
+        screen_width() - world.width * scale - MARGIN,
+        screen_height() - world.height * scale - STATUS_HEIGHT - MARGIN,
+    );
+    (corner, scale)
+}
+
+/// Moves the camera to the world position below the mouse if the minimap was clicked.
+///
+/// Returns `true` if it was, so that the click does not also apply to the world below.
+pub fn click(world: &World, camera: &mut Camera) -> bool {
+    if !is_shown(world, camera) || !is_mouse_button_pressed(MouseButton::Left) {
+        return false;
+    }
+    let (corner, scale) = frame(world);
+    let (x, y) = mouse_position();
+    let pos = (vec2(x, y) - corner) / scale;
+    if !(0.0..world.width).contains(&pos.x) || !(0.0..world.height).contains(&pos.y) {
+        return false;
+    }
+    camera.jump(pos);
+    true
+}
+
+/// Returns the color of the dot of `biot`.
+///
+/// Biots are colored by species or, with trait coloring or before they were
+/// clustered, by the trait making up most of their body.
+fn dot_color(biot: &Biot, coloring: Coloring) -> Color {
+    if let (Coloring::Species, Some(species)) = (coloring, biot.species()) {
+        return species_color(species, 0.6);
+    }
+    let properties = &biot.properties;
+    [
+        (properties.photosynthesis, GREEN),
+        (properties.attack, RED),
+        (properties.defense, DARKBLUE),
+        (properties.motion, BLUE),
+    ]
+    .into_iter()
+    .max_by(|(a, _), (b, _)| a.total_cmp(b))
+    .map_or(GREEN, |(_, color)| color)
+}
+
+/// Draws the minimap with a dot per biot and the rectangle shown by `camera`.
+///
+/// # Note
+///
+/// Must be called in screen coordinates. Parts of the view beyond the edges
+/// of a torus are drawn at the opposite edges, of a bounded world not at all.
+pub fn draw(world: &World, biots: &BiotCollection, camera: &Camera, coloring: Coloring) {
+    if !is_shown(world, camera) {
+        return;
+    }
+    let (corner, scale) = frame(world);
+    let (width, height) = (world.width * scale, world.height * scale);
+    draw_rectangle(
+        corner.x,
+        corner.y,
+        width,
+        height,
+        Color::new(0., 0., 0., 0.6),
+    );
+    draw_rectangle_lines(corner.x, corner.y, width, height, 1., LIGHTGRAY);
+    for biot in biots.iter() {
+        let pos = corner + biot.stats.pos * scale;
+        draw_rectangle(pos.x - 1., pos.y - 1., 2., 2., dot_color(biot, coloring));
+    }
+    let top_left = camera.screen_to_world(Vec2::ZERO);
+    let bottom_right = camera.screen_to_world(vec2(screen_width(), screen_height()));
+    let spans = |min: f32, max: f32, size: f32| -> Vec<(f32, f32)> {
+        if !world.is_torus() {
+            return vec![(min.max(0.0), max.min(size))];
+        }
+        if max - min >= size {
+            return vec![(0.0, size)];
+        }
+        let start = min.rem_euclid(size);
+        let end = start + max - min;
+        if end <= size {
+            vec![(start, end)]
+        } else {
+            vec![(start, size), (0.0, end - size)]
+        }
+    };
+    for (x0, x1) in spans(top_left.x, bottom_right.x, world.width) {
+        for (y0, y1) in spans(top_left.y, bottom_right.y, world.height) {
+            if x1 > x0 && y1 > y0 {
+                let pos = corner + vec2(x0, y0) * scale;
+                let size = vec2(x1 - x0, y1 - y0) * scale;
+                draw_rectangle_lines(pos.x, pos.y, size.x, size.y, 1., WHITE);
+            }
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! Static obstacles biots cannot pass through, e.g. walls of a maze or the shores of an island.
+
+use glam::Vec2;
+#[cfg(feature = "render")]
+use macroquad::prelude::{draw_circle, draw_rectangle, Color};
+use serde::{Deserialize, Serialize};
+
+/// A static obstacle in the world.
+///
+/// # Note
+///
+/// Obstacles do not wrap around the edges of the world.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Obstacle {
+    /// An axis aligned rectangle from `min` to `max`.
+    Rectangle { min: Vec2, max: Vec2 },
+    /// A circle around `center`.
+    Circle { center: Vec2, radius: f32 },
+}
+
+impl Obstacle {
+    /// Returns the outward normal of the closest surface if a body of the
+    /// given radius at `pos` overlaps the obstacle, together with the
+    /// position just touching the surface.
+    fn penetration(&self, pos: Vec2, radius: f32) -> Option<(Vec2, Vec2)> {
+        match *self {
+            Self::Rectangle { min, max } => {
+                let (min, max) = (min - Vec2::splat(radius), max + Vec2::splat(radius));
+                if pos.x < min.x || pos.y < min.y || pos.x > max.x || pos.y > max.y {
+                    return None;
+                }
+                // Leave through the closest side.
+                let sides = [
+                    (pos.x - min.x, Vec2::new(-1.0, 0.0), Vec2::new(min.x, pos.y)),
+                    (max.x - pos.x, Vec2::new(1.0, 0.0), Vec2::new(max.x, pos.y)),
+                    (pos.y - min.y, Vec2::new(0.0, -1.0), Vec2::new(pos.x, min.y)),
+                    (max.y - pos.y, Vec2::new(0.0, 1.0), Vec2::new(pos.x, max.y)),
+                ];
+                sides
+                    .into_iter()
+                    .min_by(|(a, _, _), (b, _, _)| a.total_cmp(b))
+                    .map(|(_, normal, surface)| (normal, surface))
+            }
+            Self::Circle {
+                center,
+                radius: obstacle_radius,
+            } => {
+                let distance = radius + obstacle_radius;
+                let offset = pos - center;
+                if offset.length_squared() >= distance * distance {
+                    return None;
+                }
+                // A body exactly at the center leaves in an arbitrary direction.
+                let normal = offset.try_normalize().unwrap_or(Vec2::new(1.0, 0.0));
+                Some((normal, center + normal * distance))
+            }
+        }
+    }
+
+    /// Returns `true` if `pos` lies within the obstacle.
+    pub fn contains(&self, pos: Vec2) -> bool {
+        self.penetration(pos, 0.0).is_some()
+    }
+}
+
+/// Moves a body of the given radius at `pos` out of all obstacles it overlaps.
+///
+/// The part of `speed` heading into an obstacle is removed, so the body
+/// slides along its surface.
+pub fn collide(obstacles: &[Obstacle], pos: &mut Vec2, speed: &mut Vec2, radius: f32) {
+    for obstacle in obstacles {
+        if let Some((normal, surface)) = obstacle.penetration(*pos, radius) {
+            *pos = surface;
+            *speed -= normal * speed.dot(normal).min(0.0);
+        }
+    }
+}
+
+/// Bends the direction `dir` of a body at `pos` around the obstacles within `lookahead`.
+pub fn steer(obstacles: &[Obstacle], pos: Vec2, dir: Vec2, lookahead: f32) -> Vec2 {
+    let ahead = pos + dir * lookahead;
+    let mut dir = dir;
+    for obstacle in obstacles {
+        if let Some((normal, _)) = obstacle.penetration(ahead, 0.0) {
+            // Head along the surface instead of into it.
+            let tangent = dir - normal * dir.dot(normal).min(0.0);
+            dir = tangent.try_normalize().unwrap_or(normal);
+        }
+    }
+    dir
+}
+
+/// Display the obstacles.
+#[cfg(feature = "render")]
+pub fn draw(obstacles: &[Obstacle]) {
+    let color = Color::new(0.35, 0.3, 0.3, 1.0);
+    for obstacle in obstacles {
+        match *obstacle {
+            Obstacle::Rectangle { min, max } => {
+                let size = max - min;
+                draw_rectangle(min.x, min.y, size.x, size.y, color);
+            }
+            Obstacle::Circle { center, radius } => {
+                draw_circle(center.x, center.y, radius, color);
+            }
+        }
+    }
+}
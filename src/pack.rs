@@ -0,0 +1,102 @@
+//! Community content packs.
+//!
+//! A content pack is a directory within [`MODS_DIR`] bundling scenarios,
+//! configuration files like the presets, in its `scenarios` subdirectory.
+//! Packs are discovered at startup and offered in the main menu, so
+//! community content is installed by dropping a directory into `mods`.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Directory the content packs are discovered in.
+pub const MODS_DIR: &str = "mods";
+
+/// Subdirectory of a content pack holding its scenarios.
+const SCENARIOS_DIR: &str = "scenarios";
+
+/// A scenario of a content pack.
+#[derive(Debug, Clone)]
+pub struct PackScenario {
+    /// Name of the scenario, i.e. the name of its file without extension.
+    pub name: String,
+    /// Path of the configuration file of the scenario.
+    pub path: PathBuf,
+}
+
+/// A content pack found in [`MODS_DIR`].
+#[derive(Debug, Clone)]
+pub struct ContentPack {
+    /// Name of the pack, i.e. the name of its directory.
+    pub name: String,
+    /// Scenarios of the pack, ordered by name.
+    pub scenarios: Vec<PackScenario>,
+}
+
+impl ContentPack {
+    /// Loads the content pack in `dir`.
+    ///
+    /// # Note
+    ///
+    /// Only the names of the scenarios are read here, their configuration
+    /// files are parsed once a scenario is chosen.
+    fn load(dir: &Path) -> io::Result<Self> {
+        let name = dir
+            .file_name()
+            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+        let mut scenarios = Vec::new();
+        match fs::read_dir(dir.join(SCENARIOS_DIR)) {
+            Ok(entries) => {
+                for entry in entries {
+                    let path = entry?.path();
+                    if path
+                        .extension()
+                        .is_some_and(|extension| extension == "toml")
+                    {
+                        let name = path
+                            .file_stem()
+                            .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+                        scenarios.push(PackScenario { name, path });
+                    }
+                }
+            }
+            // Packs may bundle other content only.
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error),
+        }
+        scenarios.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(Self { name, scenarios })
+    }
+}
+
+/// Discovers the content packs in `dir`, ordered by name.
+///
+/// A missing directory holds no packs, as do platforms without a file
+/// system like the web.
+///
+/// # Errors
+///
+/// If the directory or one of the packs cannot be read.
+pub fn discover(dir: impl AsRef<Path>) -> io::Result<Vec<ContentPack>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error)
+            if matches!(
+                error.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::Unsupported
+            ) =>
+        {
+            return Ok(Vec::new())
+        }
+        Err(error) => return Err(error),
+    };
+    let mut packs = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.is_dir() {
+            packs.push(ContentPack::load(&path)?);
+        }
+    }
+    packs.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packs)
+}
@@ -0,0 +1,178 @@
+//! Phases of a simulation step and hooks observing them.
+//!
+//! Profilers, visualizers or adaptive controllers can follow the pipeline
+//! of a step by implementing [`StepHooks`] and stepping the simulation with
+//! [`Simulation::step_with`](crate::simulation::Simulation::step_with),
+//! without patching the loop itself.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// A phase of a simulation step, in the order they run.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// Regulating the supply, advancing the date and seasons and letting the
+    /// currents carry the biots along.
+    Environment,
+    /// Growing and decaying food pellets.
+    Food,
+    /// Updating the spatial index, clustering, species and corpses.
+    Indexing,
+    /// Intelligent biots sensing prey, food and threats.
+    Sensing,
+    /// Biots moving, spending energy and cloning themselves.
+    Movement,
+    /// Adults pairing up with a partner under sexual reproduction.
+    Mating,
+    /// Attacks, infections and collisions between close biots.
+    Interactions,
+    /// Biots eating food pellets and scavenging corpses.
+    Feeding,
+    /// Registering the offsprings and removing the dead biots.
+    Lifecycle,
+    /// Biots leaving their scent, which spreads and fades.
+    Scent,
+    /// Watching for a dominant strategy and diversifying it.
+    Dominance,
+    /// Injecting novel genomes.
+    Novelty,
+}
+
+impl Phase {
+    /// All phases in the order they run.
+    pub const ALL: [Phase; 12] = [
+        Self::Environment,
+        Self::Food,
+        Self::Indexing,
+        Self::Sensing,
+        Self::Movement,
+        Self::Mating,
+        Self::Interactions,
+        Self::Feeding,
+        Self::Lifecycle,
+        Self::Scent,
+        Self::Dominance,
+        Self::Novelty,
+    ];
+
+    /// Returns the name of the phase.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Environment => "environment",
+            Self::Food => "food",
+            Self::Indexing => "indexing",
+            Self::Sensing => "sensing",
+            Self::Movement => "movement",
+            Self::Mating => "mating",
+            Self::Interactions => "interactions",
+            Self::Feeding => "feeding",
+            Self::Lifecycle => "lifecycle",
+            Self::Scent => "scent",
+            Self::Dominance => "dominance",
+            Self::Novelty => "novelty",
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Callbacks around every phase of a simulation step.
+///
+/// # Note
+///
+/// Every phase of [`Phase::ALL`] begins and ends once per step, in order,
+/// even if it has nothing to do, e.g. mating under asexual reproduction.
+/// `step` is the number of the step, i.e. the number of steps simulated
+/// once it completed.
+pub trait StepHooks {
+    /// Called right before `phase` of `step` runs.
+    fn begin(&mut self, _phase: Phase, _step: u32) {}
+
+    /// Called right after `phase` of `step` ran for `elapsed` wall time.
+    fn end(&mut self, _phase: Phase, _step: u32, _elapsed: Duration) {}
+}
+
+/// Tracks the running phase of a step and reports it to the hooks, if any.
+///
+/// # Note
+///
+/// Without hooks no time is taken, so plain steps do not pay for the
+/// timing and run where no clock is available, e.g. on the web.
+pub struct Phases<'a> {
+    hooks: Option<&'a mut dyn StepHooks>,
+    step: u32,
+    current: Option<(Phase, Instant)>,
+}
+
+impl<'a> Phases<'a> {
+    /// Creates the phases of a step nobody observes.
+    pub fn unobserved() -> Self {
+        Self {
+            hooks: None,
+            step: 0,
+            current: None,
+        }
+    }
+
+    /// Creates the phases of `step` reported to `hooks`.
+    pub fn observed(hooks: &'a mut dyn StepHooks, step: u32) -> Self {
+        Self {
+            hooks: Some(hooks),
+            step,
+            current: None,
+        }
+    }
+
+    /// Ends the running phase, if any, and begins `phase`.
+    pub fn enter(&mut self, phase: Phase) {
+        self.finish();
+        if let Some(hooks) = &mut self.hooks {
+            hooks.begin(phase, self.step);
+            self.current = Some((phase, Instant::now()));
+        }
+    }
+
+    /// Ends the running phase, if any.
+    pub fn finish(&mut self) {
+        if let (Some(hooks), Some((phase, start))) = (&mut self.hooks, self.current.take()) {
+            hooks.end(phase, self.step, start.elapsed());
+        }
+    }
+}
+
+/// Hooks summing up the time spent in every phase.
+#[derive(Debug, Clone, Default)]
+pub struct PhaseProfile {
+    totals: [Duration; Phase::ALL.len()],
+    steps: u32,
+}
+
+impl PhaseProfile {
+    /// Creates an empty profile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of profiled steps.
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    /// Returns the total time spent in every phase, in the order of [`Phase::ALL`].
+    pub fn totals(&self) -> impl Iterator<Item = (Phase, Duration)> + '_ {
+        Phase::ALL.into_iter().zip(self.totals)
+    }
+}
+
+impl StepHooks for PhaseProfile {
+    fn end(&mut self, phase: Phase, _step: u32, elapsed: Duration) {
+        self.totals[phase as usize] += elapsed;
+        if phase == Phase::Novelty {
+            self.steps += 1;
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::biot_collection::BiotCollection;
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+/// Number of simulation steps between two samples of the trajectory.
+const SAMPLE_INTERVAL: u32 = 10;
+
+/// Maximum number of samples kept in the trajectory.
+const MAX_SAMPLES: usize = 500;
+
+/// Live phase-space plot of the predator count against the prey count.
+///
+/// Makes Lotka-Volterra like population cycles visible as loops.
+pub struct PhasePlot {
+    /// Sampled `(predators, prey)` counts, oldest first.
+    samples: VecDeque<(usize, usize)>,
+    /// Number of steps recorded so far.
+    steps: u32,
+}
+
+impl Default for PhasePlot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PhasePlot {
+    /// Creates an empty phase plot.
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::with_capacity(MAX_SAMPLES),
+            steps: 0,
+        }
+    }
+
+    /// Records the state of the biot collection after a simulation step.
+    pub fn record(&mut self, biots: &BiotCollection) {
+        if self.steps.is_multiple_of(SAMPLE_INTERVAL) {
+            if self.samples.len() == MAX_SAMPLES {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(biots.predator_prey_counts());
+        }
+        self.steps += 1;
+    }
+
+    /// Display the trajectory in the top left corner of the screen.
+    pub fn draw(&self) {
+        let (x, y, width, height) = (10., 10., 200., 200.);
+        draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.6));
+        draw_rectangle_lines(x, y, width, height, 1., LIGHTGRAY);
+        draw_text("prey", x + width - 35., y + height - 5., 16., LIGHTGRAY);
+        draw_text("predators", x + 5., y + 15., 16., LIGHTGRAY);
+        let max_predators = self.samples.iter().map(|s| s.0).max().unwrap_or(0).max(1);
+        let max_prey = self.samples.iter().map(|s| s.1).max().unwrap_or(0).max(1);
+        let to_screen = |(predators, prey): (usize, usize)| {
+            vec2(
+                x + width * prey as f32 / max_prey as f32,
+                y + height * (1. - predators as f32 / max_predators as f32),
+            )
+        };
+        let len = self.samples.len();
+        for (n, (from, to)) in self
+            .samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .enumerate()
+        {
+            let from = to_screen(*from);
+            let to = to_screen(*to);
+            // Older parts of the trajectory fade out.
+            let alpha = (n + 1) as f32 / len as f32;
+            draw_line(
+                from.x,
+                from.y,
+                to.x,
+                to.y,
+                1.5,
+                Color::new(1., 1., 0., alpha),
+            );
+        }
+        if let Some(last) = self.samples.back() {
+            let last = to_screen(*last);
+            draw_circle(last.x, last.y, 3., YELLOW);
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! The family tree of the biots of a run.
+//!
+//! The tree is kept pruned to the ancestry of the living biots, so its size
+//! follows the population instead of growing with every birth, and can be
+//! exported to reconstruct when the clades of a run diverged.
+
+use crate::biot::BiotId;
+use crate::migration;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// File formats the tree can be exported to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TreeFormat {
+    /// Newick, read by most phylogenetics tools.
+    Newick,
+    /// GraphViz DOT.
+    Dot,
+}
+
+impl TreeFormat {
+    /// Guesses the format from the extension of `path`, defaulting to Newick.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("dot" | "gv") => Self::Dot,
+            _ => Self::Newick,
+        }
+    }
+}
+
+/// A biot in the tree.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Node {
+    /// The parent of the biot, unless it founded a lineage.
+    parent: Option<BiotId>,
+    /// Step the biot was born or spawned in.
+    born: u32,
+    /// Step the biot died or emigrated in, if it did.
+    died: Option<u32>,
+    /// Whether the biot left the run alive, e.g. to another island.
+    emigrated: bool,
+    /// Number of children of the biot in the tree.
+    children: u32,
+}
+
+/// Registry of the ancestry of the living biots.
+///
+/// # Note
+///
+/// Dead biots are forgotten as soon as none of their descendants is alive,
+/// so every biot in the tree is alive or an ancestor of a living biot.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Phylogeny {
+    #[serde(deserialize_with = "deserialize_nodes")]
+    nodes: BTreeMap<BiotId, Node>,
+}
+
+/// A biot in the tree as written by snapshots before version 44, which did
+/// not record emigration.
+#[derive(Deserialize)]
+struct NodeV43 {
+    parent: Option<BiotId>,
+    born: u32,
+    died: Option<u32>,
+    children: u32,
+}
+
+/// Deserializes the nodes of the tree, upgrading those of older snapshots.
+fn deserialize_nodes<'de, D>(deserializer: D) -> Result<BTreeMap<BiotId, Node>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    if migration::snapshot_version().is_none_or(|version| version >= 44) {
+        return BTreeMap::deserialize(deserializer);
+    }
+    let nodes = BTreeMap::<BiotId, NodeV43>::deserialize(deserializer)?;
+    let nodes = nodes.into_iter().map(|(id, node)| {
+        let node = Node {
+            parent: node.parent,
+            born: node.born,
+            died: node.died,
+            emigrated: false,
+            children: node.children,
+        };
+        (id, node)
+    });
+    Ok(nodes.collect())
+}
+
+impl Phylogeny {
+    /// Creates an empty tree.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the founder of a new lineage spawned in step `step`.
+    pub fn found(&mut self, id: BiotId, step: u32) {
+        self.insert(id, None, step);
+    }
+
+    /// Adds a biot born to `parent` in step `step`.
+    pub fn birth(&mut self, id: BiotId, parent: BiotId, step: u32) {
+        let parent = self.nodes.get_mut(&parent).map(|node| {
+            node.children += 1;
+            parent
+        });
+        self.insert(id, parent, step);
+    }
+
+    fn insert(&mut self, id: BiotId, parent: Option<BiotId>, born: u32) {
+        let node = Node {
+            parent,
+            born,
+            died: None,
+            emigrated: false,
+            children: 0,
+        };
+        self.nodes.insert(id, node);
+    }
+
+    /// Records the death of a biot in step `step`, forgetting it and its
+    /// ancestors without any other living descendants.
+    pub fn death(&mut self, id: BiotId, step: u32) {
+        self.leave(id, step, false);
+    }
+
+    /// Records that a biot left the run alive in step `step`, e.g. to another
+    /// island, forgetting it and its ancestors like a dead biot.
+    pub fn emigration(&mut self, id: BiotId, step: u32) {
+        self.leave(id, step, true);
+    }
+
+    fn leave(&mut self, id: BiotId, step: u32, emigrated: bool) {
+        let Some(node) = self.nodes.get_mut(&id) else {
+            return;
+        };
+        node.died = Some(step);
+        node.emigrated = emigrated;
+        let mut current = id;
+        while let Some(node) = self.nodes.get(&current) {
+            if node.children > 0 || node.died.is_none() {
+                break;
+            }
+            let parent = node.parent;
+            self.nodes.remove(&current);
+            let Some(parent) = parent else {
+                break;
+            };
+            if let Some(node) = self.nodes.get_mut(&parent) {
+                node.children -= 1;
+            }
+            current = parent;
+        }
+    }
+
+    /// Returns the parent of the biot with the given identifier, if it is
+    /// in the tree and did not found a lineage.
+    pub fn parent(&self, id: BiotId) -> Option<BiotId> {
+        self.nodes.get(&id)?.parent
+    }
+
+    /// Returns the number of generations between the biot with the given
+    /// identifier and the founder of its lineage, zero for founders.
+    pub fn depth(&self, id: BiotId) -> usize {
+        let mut depth = 0;
+        let mut current = id;
+        while let Some(parent) = self.parent(current) {
+            depth += 1;
+            current = parent;
+        }
+        depth
+    }
+
+    /// Returns the number of biots in the tree, living or not.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the tree has no biots.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the children of every biot, ordered by identifier, and the founders.
+    fn children(&self) -> (BTreeMap<BiotId, Vec<BiotId>>, Vec<BiotId>) {
+        let mut children = BTreeMap::<BiotId, Vec<BiotId>>::new();
+        let mut roots = Vec::new();
+        for (&id, node) in &self.nodes {
+            match node.parent {
+                Some(parent) => children.entry(parent).or_default().push(id),
+                None => roots.push(id),
+            }
+        }
+        (children, roots)
+    }
+
+    /// Returns the tree in the Newick format.
+    ///
+    /// # Note
+    ///
+    /// Biots are labeled with their identifiers and branch lengths are the
+    /// steps between the births of a parent and its child. All lineages hang
+    /// off a common unlabeled root.
+    pub fn to_newick(&self) -> String {
+        /// Whether a biot is entered, with a flag set for all but the first
+        /// sibling, or left after all its children were written.
+        enum Visit {
+            Enter(BiotId, bool),
+            Leave(BiotId),
+        }
+        let (children, roots) = self.children();
+        let mut newick = String::from("(");
+        // Deep trees are written without recursion.
+        let mut stack = roots
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(n, &root)| Visit::Enter(root, n > 0))
+            .collect::<Vec<_>>();
+        let label = |newick: &mut String, id: BiotId| {
+            let node = &self.nodes[&id];
+            let parent_born = node.parent.map_or(0, |parent| self.nodes[&parent].born);
+            let _ = write!(newick, "{}:{}", id.0, node.born - parent_born);
+        };
+        while let Some(visit) = stack.pop() {
+            match visit {
+                Visit::Enter(id, separated) => {
+                    if separated {
+                        newick.push(',');
+                    }
+                    match children.get(&id) {
+                        Some(children) => {
+                            newick.push('(');
+                            stack.push(Visit::Leave(id));
+                            stack.extend(
+                                children
+                                    .iter()
+                                    .enumerate()
+                                    .rev()
+                                    .map(|(n, &child)| Visit::Enter(child, n > 0)),
+                            );
+                        }
+                        None => label(&mut newick, id),
+                    }
+                }
+                Visit::Leave(id) => {
+                    newick.push(')');
+                    label(&mut newick, id);
+                }
+            }
+        }
+        newick.push_str(");\n");
+        newick
+    }
+
+    /// Returns the tree as a GraphViz DOT graph.
+    ///
+    /// # Note
+    ///
+    /// Nodes are labeled with the identifier of the biot and the step it was
+    /// born in. Living biots are filled, emigrated ones dashed.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph phylogeny {\n    node [shape=box];\n");
+        for (id, node) in &self.nodes {
+            let style = match (node.died, node.emigrated) {
+                (None, _) => ", style=filled",
+                (Some(_), true) => ", style=dashed",
+                (Some(_), false) => "",
+            };
+            let _ = writeln!(
+                dot,
+                "    {} [label=\"{}\\nstep {}\"{style}];",
+                id.0, id.0, node.born
+            );
+            if let Some(parent) = node.parent {
+                let _ = writeln!(dot, "    {} -> {};", parent.0, id.0);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Writes the tree to `path` in the format guessed from its extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let contents = match TreeFormat::from_path(path) {
+            TreeFormat::Newick => self.to_newick(),
+            TreeFormat::Dot => self.to_dot(),
+        };
+        fs::write(path, contents)
+    }
+}
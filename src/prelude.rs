@@ -0,0 +1,23 @@
+//! The types needed by most programs embedding the simulation.
+//!
+//! ```ignore
+//! use life_web::prelude::*;
+//! ```
+
+pub use crate::behavior::{Behavior, Expression, Flocking, GenePlugin, Situation};
+pub use crate::biot::{
+    Biot, BiotId, CauseOfDeath, Fight, Gene, GeneDistribution, Genome, GenomeBuilder, GenomeError,
+};
+pub use crate::biot_collection::{BiotCollection, Obituary};
+pub use crate::config::{ConfigError, SimConfig, WatchdogAction};
+pub use crate::experiment::{Experiment, Summary, Sweep};
+pub use crate::phase::{Phase, PhaseProfile, StepHooks};
+pub use crate::preset::Preset;
+pub use crate::rng::Rng;
+pub use crate::rules::{Intervention, Rules};
+pub use crate::scenario::{Scenario, ScenarioSpec, Start};
+pub use crate::simulation::{Simulation, SnapshotError};
+pub use crate::stats::Sample;
+pub use crate::units::{Distance, Energy, Ticks};
+pub use crate::watchdog::{Anomaly, Watchdog};
+pub use crate::world::{Topology, World};
@@ -0,0 +1,80 @@
+//! Curated ecosystem configurations for interesting dynamics out of the box.
+//!
+//! Every preset is a configuration file in the `presets` directory, embedded
+//! into the binary so that presets are available on the web as well.
+
+use crate::config::{ConfigError, SimConfig};
+use std::fmt;
+use std::str::FromStr;
+
+/// A curated ecosystem configuration.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Preset {
+    /// A plant-friendly world with plenty of sunlight.
+    Garden,
+    /// The balanced default ecosystem.
+    Savanna,
+    /// A dark world where energy is scarce.
+    Abyss,
+    /// Fast evolution of predators and their prey.
+    ArmsRace,
+}
+
+impl Preset {
+    /// All presets in the order they are offered to the user.
+    pub const ALL: [Preset; 4] = [Self::Garden, Self::Savanna, Self::Abyss, Self::ArmsRace];
+
+    /// The name used to select the preset on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Garden => "garden",
+            Self::Savanna => "savanna",
+            Self::Abyss => "abyss",
+            Self::ArmsRace => "arms_race",
+        }
+    }
+
+    /// The contents of the configuration file of the preset.
+    pub fn source(self) -> &'static str {
+        match self {
+            Self::Garden => include_str!("../presets/garden.toml"),
+            Self::Savanna => include_str!("../presets/savanna.toml"),
+            Self::Abyss => include_str!("../presets/abyss.toml"),
+            Self::ArmsRace => include_str!("../presets/arms_race.toml"),
+        }
+    }
+
+    /// Returns the configuration of the preset.
+    ///
+    /// # Errors
+    ///
+    /// If the embedded configuration file is invalid, which the `validate`
+    /// subcommand checks for.
+    pub fn config(self) -> Result<SimConfig, ConfigError> {
+        SimConfig::from_toml(self.source())
+    }
+}
+
+/// Error returned when parsing an unknown preset name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownPreset(pub String);
+
+impl fmt::Display for UnknownPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = Preset::ALL.map(Preset::name).join(", ");
+        write!(f, "unknown preset `{}`, expected one of: {names}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownPreset {}
+
+impl FromStr for Preset {
+    type Err = UnknownPreset;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name() == name)
+            .ok_or_else(|| UnknownPreset(name.to_string()))
+    }
+}
@@ -0,0 +1,528 @@
+use crate::biot::{Biot, Gene};
+use crate::ledger::EnergyLedger;
+use crate::simulation::Simulation;
+use crate::stats::{Sample, SizeDistribution, SIZE_CLASSES};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::iter;
+use std::path::Path;
+use std::str::FromStr;
+
+/// File format of a recording.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Comma separated values with a header line.
+    Csv,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+impl Format {
+    /// Guesses the format from the extension of `path`, defaulting to CSV.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("json" | "jsonl") => Self::JsonLines,
+            _ => Self::Csv,
+        }
+    }
+}
+
+/// A recorded value.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Value {
+    Count(u64),
+    Real(f32),
+}
+
+impl Value {
+    /// Returns `false` for NaN and infinite values.
+    fn is_finite(self) -> bool {
+        match self {
+            Self::Count(_) => true,
+            Self::Real(value) => value.is_finite(),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Count(value) => write!(f, "{value}"),
+            Self::Real(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+/// An aggregate statistic that can be recorded.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Column {
+    Step,
+    Year,
+    Day,
+    Population,
+    Births,
+    Deaths,
+    Lineages,
+    Species,
+    EnergyGained,
+    EnergySpent,
+    Attack,
+    Defense,
+    Photosynthesis,
+    Motion,
+    Intelligence,
+    Venom,
+    Armor,
+    Camouflage,
+    Vision,
+    Reproduction,
+    Digestion,
+    Immunity,
+    Fecundity,
+    Nurture,
+    Sociality,
+    HeatTolerance,
+    Kinship,
+    Infected,
+    Infections,
+    Genes,
+    MutationChance,
+    Clustering,
+    Supply,
+}
+
+impl Column {
+    /// All columns in the order they are recorded by default.
+    pub const ALL: [Column; 33] = [
+        Self::Step,
+        Self::Year,
+        Self::Day,
+        Self::Population,
+        Self::Births,
+        Self::Deaths,
+        Self::Lineages,
+        Self::Species,
+        Self::EnergyGained,
+        Self::EnergySpent,
+        Self::Attack,
+        Self::Defense,
+        Self::Photosynthesis,
+        Self::Motion,
+        Self::Intelligence,
+        Self::Venom,
+        Self::Armor,
+        Self::Camouflage,
+        Self::Vision,
+        Self::Reproduction,
+        Self::Digestion,
+        Self::Immunity,
+        Self::Fecundity,
+        Self::Nurture,
+        Self::Sociality,
+        Self::HeatTolerance,
+        Self::Kinship,
+        Self::Infected,
+        Self::Infections,
+        Self::Genes,
+        Self::MutationChance,
+        Self::Clustering,
+        Self::Supply,
+    ];
+
+    /// The name of the column in the header or as JSON key.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Step => "step",
+            Self::Year => "year",
+            Self::Day => "day",
+            Self::Population => "population",
+            Self::Births => "births",
+            Self::Deaths => "deaths",
+            Self::Lineages => "lineages",
+            Self::Species => "species",
+            Self::EnergyGained => "energy_gained",
+            Self::EnergySpent => "energy_spent",
+            Self::Attack => "attack",
+            Self::Defense => "defense",
+            Self::Photosynthesis => "photosynthesis",
+            Self::Motion => "motion",
+            Self::Intelligence => "intelligence",
+            Self::Venom => "venom",
+            Self::Armor => "armor",
+            Self::Camouflage => "camouflage",
+            Self::Vision => "vision",
+            Self::Reproduction => "reproduction",
+            Self::Digestion => "digestion",
+            Self::Immunity => "immunity",
+            Self::Fecundity => "fecundity",
+            Self::Nurture => "nurture",
+            Self::Sociality => "sociality",
+            Self::HeatTolerance => "heat_tolerance",
+            Self::Kinship => "kinship",
+            Self::Infected => "infected",
+            Self::Infections => "infections",
+            Self::Genes => "genes",
+            Self::MutationChance => "mutation_chance",
+            Self::Clustering => "clustering",
+            Self::Supply => "supply",
+        }
+    }
+
+    /// Returns the value of the column for a sample taken at `step`.
+    ///
+    /// `ledger` holds the energy flows since the previous sample and `supply`
+    /// is the factor applied by the population controller.
+    fn value(
+        self,
+        step: u32,
+        sample: &Sample,
+        clustering: f32,
+        supply: f32,
+        ledger: &EnergyLedger,
+    ) -> Value {
+        let mean = |gene: Gene| {
+            let index = Gene::EXPRESSED.iter().position(|g| *g == gene);
+            index.map_or(0.0, |index| sample.means[index])
+        };
+        match self {
+            Self::Step => Value::Count(step.into()),
+            Self::Year => Value::Count(sample.date.year),
+            Self::Day => Value::Count(sample.date.day),
+            Self::Population => Value::Count(sample.population as u64),
+            Self::Births => Value::Count(sample.births.into()),
+            Self::Deaths => Value::Count(sample.deaths.into()),
+            Self::Lineages => Value::Count(sample.lineages as u64),
+            Self::Species => Value::Count(sample.species as u64),
+            Self::EnergyGained => Value::Real(ledger.income().0),
+            Self::EnergySpent => Value::Real(ledger.expenses().0),
+            Self::Attack => Value::Real(mean(Gene::Attack)),
+            Self::Defense => Value::Real(mean(Gene::Defense)),
+            Self::Photosynthesis => Value::Real(mean(Gene::Photosynthesis)),
+            Self::Motion => Value::Real(mean(Gene::Motion)),
+            Self::Intelligence => Value::Real(mean(Gene::Intelligence)),
+            Self::Venom => Value::Real(mean(Gene::Venom)),
+            Self::Armor => Value::Real(mean(Gene::Armor)),
+            Self::Camouflage => Value::Real(mean(Gene::Camouflage)),
+            Self::Vision => Value::Real(mean(Gene::Vision)),
+            Self::Reproduction => Value::Real(mean(Gene::Reproduction)),
+            Self::Digestion => Value::Real(mean(Gene::Digestion)),
+            Self::Immunity => Value::Real(mean(Gene::Immunity)),
+            Self::Fecundity => Value::Real(mean(Gene::Fecundity)),
+            Self::Nurture => Value::Real(mean(Gene::Nurture)),
+            Self::Sociality => Value::Real(mean(Gene::Sociality)),
+            Self::HeatTolerance => Value::Real(mean(Gene::HeatTolerance)),
+            Self::Kinship => Value::Real(mean(Gene::Kinship)),
+            Self::Infected => Value::Count(sample.infected as u64),
+            Self::Infections => Value::Count(sample.infections.into()),
+            Self::Genes => Value::Real(sample.genes),
+            Self::MutationChance => Value::Real(sample.mutation_chance),
+            Self::Clustering => Value::Real(clustering),
+            Self::Supply => Value::Real(supply),
+        }
+    }
+}
+
+/// Error returned when parsing an unknown column name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownColumn(pub String);
+
+impl fmt::Display for UnknownColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = Column::ALL.map(Column::name).join(", ");
+        write!(f, "unknown column `{}`, expected one of: {names}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownColumn {}
+
+impl FromStr for Column {
+    type Err = UnknownColumn;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|column| column.name() == name)
+            .ok_or_else(|| UnknownColumn(name.to_string()))
+    }
+}
+
+/// Names of the values recorded for every biot.
+const BIOT_COLUMNS: [&str; 26] = [
+    "step",
+    "id",
+    "lineage",
+    "x",
+    "y",
+    "life",
+    "age",
+    "attack",
+    "defense",
+    "photosynthesis",
+    "motion",
+    "intelligence",
+    "venom",
+    "armor",
+    "camouflage",
+    "vision",
+    "reproduction",
+    "digestion",
+    "immunity",
+    "fecundity",
+    "nurture",
+    "sociality",
+    "heat_tolerance",
+    "kinship",
+    "infected",
+    "genes",
+];
+
+/// Returns the names of the values recorded for the size distribution.
+///
+/// The number of biots and their total life in each size class follow the step.
+fn size_columns() -> Vec<String> {
+    let counts = (0..SIZE_CLASSES).map(|class| format!("count_{class}"));
+    let life = (0..SIZE_CLASSES).map(|class| format!("life_{class}"));
+    iter::once(String::from("step"))
+        .chain(counts)
+        .chain(life)
+        .collect()
+}
+
+/// Writes rows of named values in one of the supported formats.
+struct RowWriter {
+    writer: BufWriter<File>,
+    format: Format,
+}
+
+impl RowWriter {
+    /// Creates the file at `path`, writing the CSV header if needed.
+    fn create(path: impl AsRef<Path>, format: Format, names: &[&str]) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        if format == Format::Csv {
+            writeln!(writer, "{}", names.join(","))?;
+        }
+        Ok(Self { writer, format })
+    }
+
+    /// Writes a single row of values, named by `names`.
+    fn write_row(&mut self, names: &[&str], values: &[Value]) -> io::Result<()> {
+        match self.format {
+            Format::Csv => {
+                let values = values.iter().map(Value::to_string).collect::<Vec<_>>();
+                writeln!(self.writer, "{}", values.join(","))
+            }
+            Format::JsonLines => writeln!(self.writer, "{}", json_object(names, values)),
+        }
+    }
+}
+
+/// Returns a JSON object of the `values` named by `names`.
+fn json_object(names: &[&str], values: &[Value]) -> String {
+    let fields = names
+        .iter()
+        .zip(values)
+        .map(|(name, value)| {
+            // JSON has no representation for NaN or infinity.
+            if value.is_finite() {
+                format!("\"{name}\":{value}")
+            } else {
+                format!("\"{name}\":null")
+            }
+        })
+        .collect::<Vec<_>>();
+    format!("{{{}}}", fields.join(","))
+}
+
+/// Settings of a [`Recorder`].
+#[derive(Debug, Clone)]
+pub struct RecorderOptions {
+    /// Number of steps between two recorded rows.
+    pub interval: u32,
+    /// Format of the written files.
+    pub format: Format,
+    /// Aggregate statistics to record.
+    pub columns: Vec<Column>,
+}
+
+/// Periodically appends aggregate statistics, and optionally the state of
+/// every biot, to files for offline analysis.
+pub struct Recorder {
+    options: RecorderOptions,
+    aggregates: RowWriter,
+    biots: Option<RowWriter>,
+    sizes: Option<RowWriter>,
+    /// Births since the last recorded row.
+    births: u32,
+    /// Deaths since the last recorded row.
+    deaths: u32,
+    /// Infections since the last recorded row.
+    infections: u32,
+    /// Energy flows since the last recorded row.
+    ledger: EnergyLedger,
+}
+
+impl Recorder {
+    /// Creates a recorder writing aggregate statistics to `path` and, if
+    /// given, the state of every biot to `biots_path` and the size
+    /// distribution of the population to `sizes_path`.
+    pub fn create(
+        options: RecorderOptions,
+        path: impl AsRef<Path>,
+        biots_path: Option<impl AsRef<Path>>,
+        sizes_path: Option<impl AsRef<Path>>,
+    ) -> io::Result<Self> {
+        let names = options.columns.iter().map(|c| c.name()).collect::<Vec<_>>();
+        let aggregates = RowWriter::create(path, options.format, &names)?;
+        let biots = biots_path
+            .map(|path| RowWriter::create(path, options.format, &BIOT_COLUMNS))
+            .transpose()?;
+        let size_names = size_columns();
+        let size_names = size_names.iter().map(String::as_str).collect::<Vec<_>>();
+        let sizes = sizes_path
+            .map(|path| RowWriter::create(path, options.format, &size_names))
+            .transpose()?;
+        Ok(Self {
+            options: RecorderOptions {
+                interval: options.interval.max(1),
+                ..options
+            },
+            aggregates,
+            biots,
+            sizes,
+            births: 0,
+            deaths: 0,
+            infections: 0,
+            ledger: EnergyLedger::default(),
+        })
+    }
+
+    /// Records the state of the simulation after a step.
+    pub fn record(&mut self, simulation: &Simulation) -> io::Result<()> {
+        let biots = simulation.biots();
+        self.births += biots.births();
+        self.deaths += biots.deaths().len() as u32;
+        self.infections += biots.infections();
+        self.ledger = self.ledger + *biots.energy_ledger();
+        let step = biots.steps();
+        if !step.is_multiple_of(self.options.interval) {
+            return Ok(());
+        }
+        let (births, deaths, infections) = (self.births, self.deaths, self.infections);
+        let sample = Sample::take(biots, simulation.date(), births, deaths, infections);
+        self.births = 0;
+        self.deaths = 0;
+        self.infections = 0;
+        let columns = &self.options.columns;
+        let names = columns.iter().map(|c| c.name()).collect::<Vec<_>>();
+        let (clustering, supply) = (biots.clustering_index(), simulation.environment().supply());
+        let values = columns
+            .iter()
+            .map(|column| column.value(step, &sample, clustering, supply, &self.ledger))
+            .collect::<Vec<_>>();
+        self.ledger = EnergyLedger::default();
+        self.aggregates.write_row(&names, &values)?;
+        // Rows are flushed right away since the interactive frontend never exits gracefully.
+        self.aggregates.writer.flush()?;
+        if let Some(writer) = &mut self.biots {
+            for biot in biots.iter() {
+                writer.write_row(&BIOT_COLUMNS, &biot_values(step, biot))?;
+            }
+            writer.writer.flush()?;
+        }
+        if let Some(writer) = &mut self.sizes {
+            let distribution = SizeDistribution::take(biots);
+            let counts = distribution.counts.map(|count| Value::Count(count.into()));
+            let life = distribution.life.map(Value::Real);
+            let values = iter::once(Value::Count(step.into()))
+                .chain(counts)
+                .chain(life)
+                .collect::<Vec<_>>();
+            let names = size_columns();
+            let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+            writer.write_row(&names, &values)?;
+            writer.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the recorded values of a single biot, in the order of [`BIOT_COLUMNS`].
+fn biot_values(step: u32, biot: &Biot) -> [Value; BIOT_COLUMNS.len()] {
+    let properties = &biot.properties;
+    [
+        Value::Count(step.into()),
+        Value::Count(biot.id().0),
+        Value::Count(biot.lineage().0),
+        Value::Real(biot.stats.pos.x),
+        Value::Real(biot.stats.pos.y),
+        Value::Real(biot.stats.life.0),
+        Value::Count(biot.stats.age.0.into()),
+        Value::Real(properties.attack),
+        Value::Real(properties.defense),
+        Value::Real(properties.photosynthesis),
+        Value::Real(properties.motion),
+        Value::Real(properties.intelligence),
+        Value::Real(properties.venom),
+        Value::Real(properties.armor),
+        Value::Real(properties.camouflage),
+        Value::Real(properties.vision),
+        Value::Real(properties.reproduction),
+        Value::Real(properties.digestion),
+        Value::Real(properties.immunity),
+        Value::Real(properties.fecundity),
+        Value::Real(properties.nurture),
+        Value::Real(properties.sociality),
+        Value::Real(properties.heat_tolerance),
+        Value::Real(properties.kinship),
+        Value::Count(biot.stats.infected.into()),
+        Value::Count(properties.genes as u64),
+    ]
+}
+
+/// Returns the aggregate statistics after the last step of `simulation` as
+/// a JSON object with a key for every [`Column`].
+///
+/// Unlike in recordings, births, deaths, infections and energy flows cover
+/// the last step only.
+pub fn stats_json(simulation: &Simulation) -> String {
+    let biots = simulation.biots();
+    let deaths = biots.deaths().len() as u32;
+    let date = simulation.date();
+    let sample = Sample::take(biots, date, biots.births(), deaths, biots.infections());
+    let (clustering, supply) = (biots.clustering_index(), simulation.environment().supply());
+    let values = Column::ALL.map(|column| {
+        column.value(
+            biots.steps(),
+            &sample,
+            clustering,
+            supply,
+            biots.energy_ledger(),
+        )
+    });
+    json_object(&Column::ALL.map(Column::name), &values)
+}
+
+/// Returns the state of every living biot of `simulation` as a JSON array
+/// of objects with the keys of the recording of every single biot.
+pub fn biots_json(simulation: &Simulation) -> String {
+    let step = simulation.biots().steps();
+    let objects = simulation
+        .biots()
+        .iter()
+        .map(|biot| json_object(&BIOT_COLUMNS, &biot_values(step, biot)))
+        .collect::<Vec<_>>();
+    format!("[{}]", objects.join(","))
+}
+
+/// Records the state of the simulation after a step.
+///
+/// The recorder is disabled if writing fails.
+pub fn record(recorder: &mut Option<Recorder>, simulation: &Simulation) {
+    if let Some(active) = recorder {
+        if let Err(error) = active.record(simulation) {
+            eprintln!("recording stopped: {error}");
+            *recorder = None;
+        }
+    }
+}
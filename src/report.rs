@@ -0,0 +1,358 @@
+//! Self-contained HTML reports of recorded runs.
+//!
+//! A report is generated from the aggregate statistics written by the
+//! [`Recorder`](crate::recorder::Recorder) and embeds all plots as inline
+//! SVG, so it can be shared as a single file and opened in any browser.
+
+use crate::recorder::Format;
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Size of a plot in pixels.
+const PLOT_WIDTH: f64 = 800.0;
+const PLOT_HEIGHT: f64 = 250.0;
+
+/// Space around the plotted area for the axis labels.
+const PLOT_MARGIN: f64 = 40.0;
+
+/// Colors of the series within a plot, in order.
+const COLORS: [&str; 6] = [
+    "#e6b422", "#e05a5a", "#4a7bd0", "#46a84a", "#9a5fc0", "#e08a2c",
+];
+
+/// The plots of a report: a title and the columns plotted in it.
+const PLOTS: [(&str, &[&str]); 13] = [
+    ("Population", &["population", "births", "deaths"]),
+    ("Diversity", &["lineages", "species"]),
+    (
+        "Mean properties",
+        &[
+            "attack",
+            "defense",
+            "photosynthesis",
+            "motion",
+            "intelligence",
+            "digestion",
+        ],
+    ),
+    (
+        "Defensive and life history traits",
+        &[
+            "venom",
+            "armor",
+            "camouflage",
+            "vision",
+            "reproduction",
+            "immunity",
+        ],
+    ),
+    ("Reproductive strategy", &["fecundity", "nurture"]),
+    ("Sociality", &["sociality"]),
+    ("Heat tolerance", &["heat_tolerance"]),
+    ("Kinship", &["kinship"]),
+    ("Disease", &["infected", "infections"]),
+    ("Genome length", &["genes"]),
+    ("Mutation chance", &["mutation_chance"]),
+    ("Energy flows", &["energy_gained", "energy_spent"]),
+    ("Auto-tuned supply", &["supply"]),
+];
+
+/// Errors that can occur while generating a report.
+#[derive(Debug)]
+pub enum ReportError {
+    /// Reading the recording or writing the report failed.
+    Io(io::Error),
+    /// The recording is malformed.
+    Parse(String),
+}
+
+impl fmt::Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "report I/O failed: {error}"),
+            Self::Parse(message) => write!(f, "malformed recording: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ReportError {}
+
+impl From<io::Error> for ReportError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// The rows of a recording.
+struct Table {
+    names: Vec<String>,
+    rows: Vec<Vec<f64>>,
+}
+
+impl Table {
+    /// Parses the recording in the given format.
+    fn parse(contents: &str, format: Format) -> Result<Self, ReportError> {
+        let mut lines = contents.lines().filter(|line| !line.trim().is_empty());
+        let mut names = Vec::new();
+        let mut rows = Vec::new();
+        match format {
+            Format::Csv => {
+                let header = lines
+                    .next()
+                    .ok_or_else(|| ReportError::Parse(String::from("missing header")))?;
+                names = header
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .collect();
+                for line in lines {
+                    rows.push(line.split(',').map(parse_value).collect());
+                }
+            }
+            Format::JsonLines => {
+                for line in lines {
+                    let object = line
+                        .trim()
+                        .strip_prefix('{')
+                        .and_then(|line| line.strip_suffix('}'))
+                        .ok_or_else(|| ReportError::Parse(format!("not an object: {line}")))?;
+                    let mut row = Vec::new();
+                    for (n, field) in object.split(',').enumerate() {
+                        let (name, value) = field
+                            .split_once(':')
+                            .ok_or_else(|| ReportError::Parse(format!("bad field: {field}")))?;
+                        let name = name.trim().trim_matches('"');
+                        // All lines have the same fields, so the first one names the columns.
+                        if rows.is_empty() {
+                            names.push(name.to_string());
+                        } else if names.get(n).map(String::as_str) != Some(name) {
+                            return Err(ReportError::Parse(format!("unexpected field: {field}")));
+                        }
+                        row.push(parse_value(value));
+                    }
+                    rows.push(row);
+                }
+            }
+        }
+        if let Some(row) = rows.iter().find(|row| row.len() != names.len()) {
+            return Err(ReportError::Parse(format!(
+                "row with {} values for {} columns",
+                row.len(),
+                names.len()
+            )));
+        }
+        Ok(Self { names, rows })
+    }
+
+    /// Returns the values of the column named `name`, if it was recorded.
+    fn column(&self, name: &str) -> Option<Vec<f64>> {
+        let index = self.names.iter().position(|n| n == name)?;
+        Some(self.rows.iter().map(|row| row[index]).collect())
+    }
+
+    /// Returns the steps of the rows, or their indices if no steps were recorded.
+    fn steps(&self) -> Vec<f64> {
+        self.column("step")
+            .unwrap_or_else(|| (0..self.rows.len()).map(|n| n as f64).collect())
+    }
+}
+
+/// Parses a recorded value, where missing and `null` values are NaN.
+fn parse_value(value: &str) -> f64 {
+    value.trim().parse().unwrap_or(f64::NAN)
+}
+
+/// Generates the report of the recording at `input` and writes it to `output`.
+pub fn generate(input: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<(), ReportError> {
+    let input = input.as_ref();
+    let table = Table::parse(&fs::read_to_string(input)?, Format::from_path(input))?;
+    let title = input.file_name().map_or_else(
+        || String::from("recording"),
+        |name| name.to_string_lossy().into_owned(),
+    );
+    fs::write(output, render(&title, &table))?;
+    Ok(())
+}
+
+/// Renders the whole report as HTML.
+fn render(title: &str, table: &Table) -> String {
+    let title = escape(title);
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Life report: {title}</title>\n<style>\n\
+         body {{ font-family: sans-serif; background: #14141c; color: #ddd; margin: 2em; }}\n\
+         svg {{ background: #1c1c28; display: block; margin-bottom: 2em; }}\n\
+         </style>\n</head>\n<body>\n<h1>Life report: {title}</h1>\n"
+    );
+    let steps = table.steps();
+    let _ = writeln!(
+        html,
+        "<p>{} rows from step {} to step {}.</p>",
+        table.rows.len(),
+        steps.first().copied().unwrap_or(0.0),
+        steps.last().copied().unwrap_or(0.0)
+    );
+    html.push_str("<h2>Key events</h2>\n<ul>\n");
+    for event in key_events(table, &steps) {
+        let _ = writeln!(html, "<li>{}</li>", escape(&event));
+    }
+    html.push_str("</ul>\n");
+    for (plot_title, names) in PLOTS {
+        let series = names
+            .iter()
+            .filter_map(|name| table.column(name).map(|values| (*name, values)))
+            .collect::<Vec<_>>();
+        if !series.is_empty() {
+            let _ = writeln!(html, "<h2>{plot_title}</h2>");
+            html.push_str(&plot(&steps, &series));
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Returns notable moments of the run in plain text.
+fn key_events(table: &Table, steps: &[f64]) -> Vec<String> {
+    let mut events = Vec::new();
+    if let Some(population) = table.column("population") {
+        if let Some((value, step)) = extreme(&population, steps, |a, b| a > b) {
+            events.push(format!("peak population of {value} at step {step}"));
+        }
+        if let Some((value, step)) = extreme(&population, steps, |a, b| a < b) {
+            events.push(format!("lowest population of {value} at step {step}"));
+        }
+        if let Some((_, step)) = population
+            .iter()
+            .zip(steps)
+            .find(|(value, _)| **value == 0.0)
+        {
+            events.push(format!("population went extinct at step {step}"));
+        }
+    }
+    if let Some(deaths) = table.column("deaths") {
+        let worst = deaths
+            .iter()
+            .zip(steps)
+            .filter(|(value, _)| value.is_finite())
+            .max_by(|(a, _), (b, _)| a.total_cmp(b));
+        if let Some((value, step)) = worst {
+            if *value > 0.0 {
+                events.push(format!(
+                    "most deaths ({value}) in the interval up to step {step}"
+                ));
+            }
+        }
+    }
+    if let Some(supply) = table.column("supply") {
+        let finite = supply.iter().copied().filter(|value| value.is_finite());
+        let (min, max) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+        if min != 1.0 || max != 1.0 {
+            events.push(format!(
+                "sunlight and food were auto-tuned by the population controller, \
+                 scaled by {min:.2} to {max:.2}"
+            ));
+        }
+    }
+    if let Some(years) = table.column("year") {
+        for (pair, step) in years.windows(2).zip(steps.iter().skip(1)) {
+            if pair[1] > pair[0] {
+                events.push(format!("year {} began by step {step}", pair[1]));
+            }
+        }
+    }
+    events
+}
+
+/// Returns the first finite value that is `better` than all others, with its step.
+fn extreme(values: &[f64], steps: &[f64], better: fn(f64, f64) -> bool) -> Option<(f64, f64)> {
+    let mut best: Option<(f64, f64)> = None;
+    for (&value, &step) in values.iter().zip(steps) {
+        if value.is_finite() && best.is_none_or(|(best, _)| better(value, best)) {
+            best = Some((value, step));
+        }
+    }
+    best
+}
+
+/// Renders the series as lines over the steps in an inline SVG.
+///
+/// # Note
+///
+/// Every series is scaled to its own range, like the statistics plots of
+/// the interactive frontend, and labeled with it in the legend.
+fn plot(steps: &[f64], series: &[(&str, Vec<f64>)]) -> String {
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg width=\"{PLOT_WIDTH}\" height=\"{PLOT_HEIGHT}\" xmlns=\"http://www.w3.org/2000/svg\">"
+    );
+    let (first, last) = (
+        steps.first().copied().unwrap_or(0.0),
+        steps.last().copied().unwrap_or(0.0),
+    );
+    let span = (last - first).max(f64::EPSILON);
+    let inner_width = PLOT_WIDTH - 2.0 * PLOT_MARGIN;
+    let inner_height = PLOT_HEIGHT - 2.0 * PLOT_MARGIN;
+    let margin = PLOT_MARGIN;
+    let label_y = PLOT_HEIGHT - 10.0;
+    let _ = writeln!(
+        svg,
+        "<text x=\"{margin}\" y=\"{label_y}\" fill=\"#999\" font-size=\"12\">step {first}</text>"
+    );
+    let _ = writeln!(
+        svg,
+        "<text x=\"{}\" y=\"{label_y}\" fill=\"#999\" font-size=\"12\" \
+         text-anchor=\"end\">step {last}</text>",
+        PLOT_WIDTH - margin,
+    );
+    for (n, (name, values)) in series.iter().enumerate() {
+        let color = COLORS[n % COLORS.len()];
+        let finite = values.iter().copied().filter(|value| value.is_finite());
+        let (min, max) = finite.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+        let range = (max - min).max(f64::EPSILON);
+        let points = steps
+            .iter()
+            .zip(values)
+            .filter(|(_, value)| value.is_finite())
+            .map(|(step, value)| {
+                let x = margin + inner_width * (step - first) / span;
+                let y = margin + inner_height * (1.0 - (value - min) / range);
+                format!("{x:.1},{y:.1}")
+            })
+            .collect::<Vec<_>>();
+        let _ = writeln!(
+            svg,
+            "<polyline fill=\"none\" stroke=\"{color}\" stroke-width=\"1.5\" points=\"{}\"/>",
+            points.join(" ")
+        );
+        let legend = if min <= max {
+            format!("{name}: {min:.3} to {max:.3}")
+        } else {
+            format!("{name}: no data")
+        };
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" fill=\"{color}\" font-size=\"12\">{}</text>",
+            margin + 180.0 * (n % 4) as f64,
+            15.0 + 14.0 * (n / 4) as f64,
+            escape(&legend)
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Escapes the characters with a special meaning in HTML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,75 @@
+//! Protected reserves where prey is safe from predators.
+//!
+//! Reserves are refuges laid over the world, e.g. to study how a protected
+//! source population feeds the sinks around it. Predators can still roam a
+//! reserve, but they cannot eat any biot within it.
+
+use crate::spawn::Area;
+use glam::Vec2;
+#[cfg(feature = "render")]
+use macroquad::prelude::{draw_circle_lines, draw_rectangle_lines, Color};
+use serde::{Deserialize, Serialize};
+
+/// A protected region of the world.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Reserve {
+    pub area: Area,
+    /// Whether predators may eat biots within the reserve.
+    pub predation: bool,
+    /// Factor applied to the metabolism of the biots within the reserve,
+    /// below one lowering their mortality.
+    pub metabolism: f32,
+}
+
+impl Default for Reserve {
+    fn default() -> Self {
+        Self {
+            area: Area::Rectangle {
+                min: Vec2::ZERO,
+                max: Vec2::ZERO,
+            },
+            predation: false,
+            metabolism: 1.0,
+        }
+    }
+}
+
+impl Reserve {
+    /// Returns `true` if the reserve has a valid area and a finite, non-negative metabolism.
+    pub fn is_valid(&self) -> bool {
+        self.area.is_valid() && self.metabolism.is_finite() && self.metabolism >= 0.0
+    }
+}
+
+/// Returns the first of the `reserves` containing `pos`, if any.
+pub fn at(reserves: &[Reserve], pos: Vec2) -> Option<&Reserve> {
+    reserves.iter().find(|reserve| reserve.area.contains(pos))
+}
+
+/// Returns `true` if a biot at `pos` cannot be eaten.
+pub fn is_protected(reserves: &[Reserve], pos: Vec2) -> bool {
+    at(reserves, pos).is_some_and(|reserve| !reserve.predation)
+}
+
+/// Returns the factor applied to the metabolism of a biot at `pos`.
+pub fn metabolism(reserves: &[Reserve], pos: Vec2) -> f32 {
+    at(reserves, pos).map_or(1.0, |reserve| reserve.metabolism)
+}
+
+/// Draws the outlines of the reserves.
+#[cfg(feature = "render")]
+pub fn draw(reserves: &[Reserve]) {
+    let color = Color::new(0.4, 0.9, 0.5, 0.6);
+    for reserve in reserves {
+        match reserve.area {
+            Area::Rectangle { min, max } => {
+                let size = max - min;
+                draw_rectangle_lines(min.x, min.y, size.x, size.y, 2.0, color);
+            }
+            Area::Circle { center, radius } => {
+                draw_circle_lines(center.x, center.y, radius, 2.0, color);
+            }
+        }
+    }
+}
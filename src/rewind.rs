@@ -0,0 +1,70 @@
+//! Rolling snapshots of a running simulation for rewinding time.
+//!
+//! Rewinding lets users jump back to re-watch an extinction or try a
+//! different intervention. Snapshots are kept encoded in memory, so only a
+//! bounded number of them is kept.
+
+use crate::simulation::{Simulation, SnapshotError};
+use std::collections::VecDeque;
+
+/// Number of steps between two snapshots unless configured otherwise.
+pub const DEFAULT_INTERVAL: u32 = 250;
+
+/// Number of snapshots kept unless configured otherwise.
+pub const DEFAULT_CAPACITY: usize = 16;
+
+/// Ring buffer of encoded snapshots taken every few steps.
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    /// Steps of the snapshots and their encoded states, oldest first.
+    snapshots: VecDeque<(u32, Vec<u8>)>,
+    /// Number of steps between two snapshots.
+    interval: u32,
+    /// Maximum number of snapshots kept.
+    capacity: usize,
+}
+
+impl Timeline {
+    /// Creates an empty timeline keeping `capacity` snapshots taken every `interval` steps.
+    pub fn new(interval: u32, capacity: usize) -> Self {
+        Self {
+            snapshots: VecDeque::new(),
+            interval: interval.max(1),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Takes a snapshot of `simulation` after a step, if one is due.
+    ///
+    /// The oldest snapshot is dropped once the timeline is full.
+    pub fn record(&mut self, simulation: &Simulation) -> Result<(), SnapshotError> {
+        let step = simulation.biots().steps();
+        if !step.is_multiple_of(self.interval) {
+            return Ok(());
+        }
+        let bytes = simulation.encode()?;
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back((step, bytes));
+        Ok(())
+    }
+
+    /// Restores the latest snapshot and removes it from the timeline.
+    ///
+    /// Returns `None` if there is no snapshot left.
+    ///
+    /// # Note
+    ///
+    /// Rewinding repeatedly goes back further in time each time. Stepping
+    /// the restored simulation takes new snapshots in place of the restored ones.
+    pub fn rewind(&mut self) -> Option<Result<Simulation, SnapshotError>> {
+        let (_, bytes) = self.snapshots.pop_back()?;
+        Some(Simulation::decode(&bytes))
+    }
+
+    /// Returns the steps of the kept snapshots, oldest first.
+    pub fn steps(&self) -> impl Iterator<Item = u32> + '_ {
+        self.snapshots.iter().map(|(step, _)| *step)
+    }
+}
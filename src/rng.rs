@@ -0,0 +1,123 @@
+use oorandom::Rand32;
+use serde::{Deserialize, Serialize};
+
+/// The random number generator driving a simulation.
+///
+/// All randomness of a simulation is drawn from an explicitly passed `Rng`,
+/// so the same seed always produces the same evolution. Its state is part of
+/// saved snapshots, so loaded simulations continue deterministically.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "(u64, u64)", into = "(u64, u64)")]
+pub struct Rng(Rand32);
+
+impl Rng {
+    /// Creates a random number generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        Self(Rand32::new(seed))
+    }
+
+    /// Creates one of many independent random number generators from the same seed.
+    ///
+    /// # Note
+    ///
+    /// Generators with different streams produce unrelated sequences, so
+    /// work items can draw random numbers independently of each other.
+    pub fn with_stream(seed: u64, stream: u64) -> Self {
+        Self(Rand32::new_inc(seed, stream))
+    }
+
+    /// Returns a random seed, e.g. for [`Rng::with_stream`].
+    pub fn gen_seed(&mut self) -> u64 {
+        u64::from(self.0.rand_u32()) << 32 | u64::from(self.0.rand_u32())
+    }
+
+    /// Returns a uniformly distributed float in `low..high`.
+    pub fn gen_range(&mut self, low: f32, high: f32) -> f32 {
+        low + (high - low) * self.0.rand_float()
+    }
+
+    /// Returns a uniformly distributed index in `0..len`.
+    pub fn gen_index(&mut self, len: usize) -> usize {
+        self.0.rand_range(0..len as u32) as usize
+    }
+}
+
+impl From<(u64, u64)> for Rng {
+    fn from(state: (u64, u64)) -> Self {
+        Self(Rand32::from_state(state))
+    }
+}
+
+impl From<Rng> for (u64, u64) {
+    fn from(rng: Rng) -> Self {
+        rng.0.state()
+    }
+}
+
+/// Independent random number generators of the subsystems of a simulation.
+///
+/// # Note
+///
+/// Every subsystem draws from its own stream derived from the master seed,
+/// so adding a random draw to one subsystem does not perturb the outcomes
+/// of the others and scenarios stay comparable across versions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RngStreams {
+    /// Placement of new biots, food and terrain.
+    pub placement: Rng,
+    /// Movement of the biots.
+    pub movement: Rng,
+    /// Mutations and recombinations of genomes.
+    pub mutation: Rng,
+    /// Chance encounters like infections and recoveries.
+    pub encounters: Rng,
+}
+
+impl RngStreams {
+    /// Creates the streams of all subsystems from the master `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            placement: Rng::with_stream(seed, 0),
+            movement: Rng::with_stream(seed, 1),
+            mutation: Rng::with_stream(seed, 2),
+            encounters: Rng::with_stream(seed, 3),
+        }
+    }
+
+    /// Draws the seeds of a single step, whose work items get independent
+    /// streams from them, see [`StepSeeds::item`].
+    pub fn step_seeds(&mut self) -> StepSeeds {
+        StepSeeds {
+            placement: self.placement.gen_seed(),
+            movement: self.movement.gen_seed(),
+            mutation: self.mutation.gen_seed(),
+            encounters: self.encounters.gen_seed(),
+        }
+    }
+}
+
+/// Seeds of the streams of the subsystems for a single step.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct StepSeeds {
+    placement: u64,
+    movement: u64,
+    mutation: u64,
+    encounters: u64,
+}
+
+impl StepSeeds {
+    /// Creates the streams of the work `item` of the step, e.g. the identifier of a biot.
+    ///
+    /// # Note
+    ///
+    /// The streams of different items are independent of each other, so work
+    /// items can be processed in any order, e.g. in parallel.
+    pub fn item(&self, item: u64) -> RngStreams {
+        RngStreams {
+            placement: Rng::with_stream(self.placement, item),
+            movement: Rng::with_stream(self.movement, item),
+            mutation: Rng::with_stream(self.mutation, item),
+            encounters: Rng::with_stream(self.encounters, item),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Custom rules intervening in the steps of a simulation.
+//!
+//! Programs embedding the simulation, e.g. with [scripts](crate::script),
+//! register rules to spare prey or to spawn, feed and kill biots without
+//! forking the step itself. The rules are asked from within
+//! [`BiotCollection::step`], so they apply alike to headless runs and the
+//! viewer.
+
+use crate::biot::{Biot, BiotId};
+use crate::biot_collection::BiotCollection;
+use crate::scenario::Archetype;
+use crate::units::Energy;
+use glam::Vec2;
+
+/// A change to the biots requested by a rule.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Intervention {
+    /// Places a biot of `archetype` with its base life at `pos`.
+    Place { archetype: Archetype, pos: Vec2 },
+    /// Adds `life` to the life of the biot `id`, at most its base life.
+    Feed { id: BiotId, life: Energy },
+    /// Kills the biot `id`, leaving its corpse.
+    Kill { id: BiotId },
+}
+
+/// A rule intervening in the steps of a simulation.
+///
+/// # Note
+///
+/// Rules are not part of snapshots and have to be registered again after
+/// loading one. A rule returning an error is dropped, see
+/// [`BiotCollection::rule_failures`].
+pub trait Rules: Send + Sync {
+    /// Returns whether `predator` may bite `prey` in the current step.
+    ///
+    /// Spared prey and its predator push each other apart instead, as if
+    /// neither preyed on the other.
+    fn allows_bite(&mut self, _predator: &Biot, _prey: &Biot) -> Result<bool, String> {
+        Ok(true)
+    }
+
+    /// Returns the interventions in reaction to the step `biots` just
+    /// completed, which are applied at its end.
+    fn react(&mut self, _biots: &BiotCollection) -> Result<Vec<Intervention>, String> {
+        Ok(Vec::new())
+    }
+}
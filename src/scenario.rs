@@ -0,0 +1,346 @@
+//! Hand-made situations for checking the behavior of biots and for
+//! starting simulations from chosen conditions.
+//!
+//! A scenario starts from an empty world, places biots at chosen positions
+//! and runs the simulation for a number of steps, after which the state of
+//! the placed biots can be inspected:
+//!
+//! ```ignore
+//! let mut scenario = Scenario::new();
+//! let predator = scenario.place(Biot::predator, 10.0, 10.0);
+//! let plant = scenario.place(Biot::plant, 15.0, 10.0);
+//! let life = scenario.life(predator);
+//! scenario.run(50);
+//! assert!(scenario.is_dead(plant));
+//! assert!(scenario.life(predator) > life);
+//! ```
+//!
+//! Starting conditions of whole simulations are described by scenario
+//! files, see [`ScenarioSpec`], of which a few are built in, see [`Start`].
+
+use crate::behavior::{Behavior, GenePlugin};
+use crate::biot::{Biot, BiotId, GeneDistribution};
+use crate::config::{ConfigError, SimConfig};
+use crate::migration;
+use crate::rng::Rng;
+use crate::rules::Rules;
+use crate::simulation::Simulation;
+use crate::units::Energy;
+use crate::world::World;
+use glam::vec2;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Size of the world of a scenario unless specified otherwise.
+const DEFAULT_WORLD_SIZE: f32 = 200.0;
+
+/// A simulation that starts out empty and is populated by hand.
+pub struct Scenario {
+    simulation: Simulation,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario {
+    /// Creates an empty scenario with the default configuration.
+    pub fn new() -> Self {
+        Self::with_config(SimConfig::default())
+    }
+
+    /// Creates an empty scenario with the given ecosystem parameters.
+    ///
+    /// # Note
+    ///
+    /// The initial population of `config` is ignored, scenarios start empty.
+    pub fn with_config(config: SimConfig) -> Self {
+        Self::with_world(
+            World::new(DEFAULT_WORLD_SIZE, DEFAULT_WORLD_SIZE),
+            config,
+            0,
+        )
+    }
+
+    /// Creates an empty scenario in `world` whose randomness is derived from `seed`.
+    pub fn with_world(world: World, config: SimConfig, seed: u64) -> Self {
+        let config = SimConfig {
+            initial_population: 0,
+            ..config
+        };
+        Self {
+            simulation: Simulation::new(world, config, seed),
+        }
+    }
+
+    /// Places a biot created by `create` at the given position.
+    ///
+    /// Returns the identifier of the placed biot for later inspection.
+    pub fn place<F>(&mut self, create: F, x: f32, y: f32) -> BiotId
+    where
+        F: FnOnce(&World, &mut Rng) -> Biot,
+    {
+        let world = *self.simulation.world();
+        let mut biot = create(&world, self.simulation.rng_mut());
+        biot.stats.pos = world.wrap(vec2(x, y));
+        self.simulation.biots_mut().spawn(biot)
+    }
+
+    /// Registers a `behavior` steering the intelligent biots, see [`Behavior`].
+    pub fn add_behavior(&mut self, behavior: impl Behavior + 'static) {
+        self.simulation.add_behavior(behavior);
+    }
+
+    /// Registers a `plugin` giving its gene an effect, see [`GenePlugin`].
+    pub fn add_gene_plugin(&mut self, plugin: impl GenePlugin + 'static) {
+        self.simulation.add_gene_plugin(plugin);
+    }
+
+    /// Registers `rules` intervening in the steps, see [`Rules`].
+    pub fn add_rules(&mut self, rules: impl Rules + 'static) {
+        self.simulation.add_rules(rules);
+    }
+
+    /// Computes the given number of simulation steps.
+    pub fn run(&mut self, steps: u32) {
+        for _ in 0..steps {
+            self.simulation.step();
+        }
+    }
+
+    /// Returns the placed biot if it is still alive.
+    pub fn biot(&self, id: BiotId) -> Option<&Biot> {
+        self.simulation.biots().get(id)
+    }
+
+    /// Returns `true` if the placed biot has died.
+    pub fn is_dead(&self, id: BiotId) -> bool {
+        self.biot(id).is_none()
+    }
+
+    /// Returns the life of the placed biot, or zero if it has died.
+    pub fn life(&self, id: BiotId) -> Energy {
+        self.biot(id).map_or(Energy::ZERO, |biot| biot.stats.life)
+    }
+
+    /// Returns the underlying simulation.
+    pub fn simulation(&self) -> &Simulation {
+        &self.simulation
+    }
+
+    /// Returns the underlying simulation, e.g. to show it in the viewer.
+    pub fn into_simulation(self) -> Simulation {
+        self.simulation
+    }
+}
+
+/// Kind of biot placed by a scenario file.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Archetype {
+    /// A random biot as in the random initial population.
+    #[default]
+    Random,
+    Plant,
+    Grazer,
+    Predator,
+    SmartPredator,
+}
+
+impl Archetype {
+    /// Creates a biot of the archetype at a random position.
+    pub fn create(self, world: &World, config: &SimConfig, rng: &mut Rng) -> Biot {
+        match self {
+            Self::Random => {
+                let distribution = GeneDistribution::initial(config);
+                Biot::initial_biot(world, config, &distribution, rng)
+            }
+            Self::Plant => Biot::plant(world, rng),
+            Self::Grazer => Biot::grazer(world, rng),
+            Self::Predator => Biot::predator(world, rng),
+            Self::SmartPredator => Biot::smart_predator(world, rng),
+        }
+    }
+}
+
+/// A group of biots of the same archetype placed by a scenario file.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Placement {
+    pub archetype: Archetype,
+    /// Center of the group.
+    pub x: f32,
+    pub y: f32,
+    /// Number of biots in the group.
+    pub count: usize,
+    /// Radius of the circle the biots are scattered in uniformly.
+    pub radius: f32,
+    /// Multiple of its base life every biot of the group starts with.
+    pub life: f32,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            archetype: Archetype::Random,
+            x: 0.0,
+            y: 0.0,
+            count: 1,
+            radius: 0.0,
+            life: 1.0,
+        }
+    }
+}
+
+impl Placement {
+    /// Returns `true` if the group has a finite position, a non-negative
+    /// radius and a positive life.
+    fn is_valid(&self) -> bool {
+        self.x.is_finite()
+            && self.y.is_finite()
+            && self.radius.is_finite()
+            && self.radius >= 0.0
+            && self.life.is_finite()
+            && self.life > 0.0
+    }
+}
+
+/// Starting conditions of a simulation: the ecosystem parameters and the
+/// biots placed at the start.
+///
+/// # Note
+///
+/// Scenario files are TOML files with the configuration in a `[config]`
+/// table, see [`SimConfig`], and the placed groups in `[[biots]]` tables,
+/// see [`Placement`]. The initial population of the configuration is
+/// ignored, only the placed biots start out.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScenarioSpec {
+    pub config: SimConfig,
+    pub biots: Vec<Placement>,
+}
+
+impl ScenarioSpec {
+    /// Loads the scenario from the TOML file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        Self::from_toml(&fs::read_to_string(path)?)
+    }
+
+    /// Parses the scenario from the contents of a TOML file.
+    ///
+    /// Files of older versions are upgraded first, see [`migration`].
+    pub fn from_toml(contents: &str) -> Result<Self, ConfigError> {
+        let spec: Self = match migration::upgrade_config(contents, &["config"])? {
+            Some(upgraded) => toml::from_str(&upgraded)?,
+            None => toml::from_str(contents)?,
+        };
+        spec.config.check()?;
+        if !spec.biots.iter().all(Placement::is_valid) {
+            let message =
+                "placed biots need a finite position, non-negative radius and positive life";
+            return Err(ConfigError::Invalid(String::from(message)));
+        }
+        Ok(spec)
+    }
+
+    /// Creates a simulation in `world` starting with the placed biots.
+    ///
+    /// Positions outside the world are wrapped into it.
+    pub fn simulation(&self, world: World, seed: u64) -> Simulation {
+        let mut scenario = Scenario::with_world(world, self.config.clone(), seed);
+        for placement in &self.biots {
+            for _ in 0..placement.count {
+                let rng = scenario.simulation.rng_mut();
+                // The square root keeps the density constant towards the rim.
+                let distance = placement.radius * rng.gen_range(0.0, 1.0).sqrt();
+                let angle = rng.gen_range(0.0, std::f32::consts::TAU);
+                let pos =
+                    vec2(placement.x, placement.y) + vec2(angle.cos(), angle.sin()) * distance;
+                let id = scenario.place(
+                    |world, rng| placement.archetype.create(world, &self.config, rng),
+                    pos.x,
+                    pos.y,
+                );
+                let config = &self.config;
+                scenario.simulation.biots_mut().modify(id, |biot| {
+                    biot.stats.life = biot.base_life(config) * placement.life;
+                });
+            }
+        }
+        scenario.into_simulation()
+    }
+}
+
+/// A built-in scenario.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Start {
+    /// Nothing but plants, for watching herbivores and predators evolve from scratch.
+    Plants,
+    /// Prey on one side of the world and predators on the other.
+    PredatorPrey,
+    /// A settled community of plants and grazers invaded by a single super-predator.
+    Invasion,
+}
+
+impl Start {
+    /// All built-in scenarios in the order they are offered to the user.
+    pub const ALL: [Start; 3] = [Self::Plants, Self::PredatorPrey, Self::Invasion];
+
+    /// The name used to select the scenario on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Plants => "plants",
+            Self::PredatorPrey => "predator_prey",
+            Self::Invasion => "invasion",
+        }
+    }
+
+    /// The contents of the scenario file.
+    fn source(self) -> &'static str {
+        match self {
+            Self::Plants => include_str!("../scenarios/plants.toml"),
+            Self::PredatorPrey => include_str!("../scenarios/predator_prey.toml"),
+            Self::Invasion => include_str!("../scenarios/invasion.toml"),
+        }
+    }
+
+    /// Returns the starting conditions of the scenario.
+    ///
+    /// # Errors
+    ///
+    /// If the embedded scenario file is invalid, which the `validate`
+    /// subcommand checks for.
+    pub fn spec(self) -> Result<ScenarioSpec, ConfigError> {
+        ScenarioSpec::from_toml(self.source())
+    }
+}
+
+/// Error returned when parsing an unknown built-in scenario name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownStart(pub String);
+
+impl fmt::Display for UnknownStart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = Start::ALL.map(Start::name).join(", ");
+        write!(f, "unknown scenario `{}`, expected one of: {names}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownStart {}
+
+impl FromStr for Start {
+    type Err = UnknownStart;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|start| start.name() == name)
+            .ok_or_else(|| UnknownStart(name.to_string()))
+    }
+}
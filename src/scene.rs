@@ -0,0 +1,190 @@
+//! Scenes of the interactive frontend, e.g. the main menu or a running simulation.
+//!
+//! Scenes are kept on a stack. Only the topmost scene is shown and it
+//! decides which scene comes next, so going back to the previous scene is
+//! just popping the current one.
+
+use crate::audio::Audio;
+use crate::biot::Genome;
+use crate::capture::Timelapse;
+use crate::clock::DEFAULT_TICK_RATE;
+use crate::config::SimConfig;
+use crate::labels::Labels;
+use crate::pack::{self, ContentPack, MODS_DIR};
+use crate::recorder::Recorder;
+use crate::scenario::ScenarioSpec;
+use crate::simulation::Simulation;
+use crate::world::World;
+use macroquad::prelude::*;
+
+/// State shared by all scenes.
+pub struct Context {
+    /// Configuration of newly created simulations.
+    pub config: SimConfig,
+    /// Seed of the next simulation if it was passed on the command line or
+    /// chosen in the settings.
+    seed: Option<u64>,
+    /// Seed of the latest simulation, if any was created.
+    last_seed: Option<u64>,
+    /// Recorder passed on to the next simulation.
+    pub recorder: Option<Recorder>,
+    /// File the event log of a simulation is written to when its viewer is closed.
+    pub events: Option<String>,
+    /// Genomes the initial population of new simulations is made of, random if empty.
+    pub genomes: Vec<Genome>,
+    /// Fields of the labels shown next to the biots when zoomed in.
+    pub labels: Labels,
+    /// Starting conditions of new simulations replacing the random initial population.
+    pub scenario: Option<ScenarioSpec>,
+    /// Steps per second new simulations start out advancing by.
+    pub ticks_per_second: u32,
+    /// Content packs discovered at startup.
+    pub packs: Vec<ContentPack>,
+    /// Timelapse every viewer writes the frames of its simulation to, if any.
+    pub timelapse: Option<Timelapse>,
+    /// Sound cues of the shown simulation, if the sounds could be loaded.
+    pub audio: Option<Audio>,
+}
+
+impl Context {
+    /// Creates the context from the settings passed on the command line
+    /// and discovers the content packs.
+    pub fn new(
+        config: SimConfig,
+        seed: Option<u64>,
+        recorder: Option<Recorder>,
+        events: Option<String>,
+        genomes: Vec<Genome>,
+        labels: Labels,
+        scenario: Option<ScenarioSpec>,
+    ) -> Self {
+        let packs = pack::discover(MODS_DIR).unwrap_or_else(|error| {
+            eprintln!("failed to discover content packs in {MODS_DIR}: {error}");
+            Vec::new()
+        });
+        Self {
+            config,
+            seed,
+            last_seed: None,
+            recorder,
+            events,
+            genomes,
+            labels,
+            scenario,
+            ticks_per_second: DEFAULT_TICK_RATE,
+            packs,
+            timelapse: None,
+            audio: None,
+        }
+    }
+
+    /// Creates a new simulation filling the screen.
+    ///
+    /// # Note
+    ///
+    /// Only the next simulation uses the seed passed on the command line or
+    /// chosen in the settings, all later ones are seeded randomly. A scenario passed on the command
+    /// line takes the place of the configuration of the context.
+    pub fn new_simulation(&mut self) -> Simulation {
+        match self.scenario.clone() {
+            Some(scenario) => scenario.simulation(Self::screen_world(), self.next_seed()),
+            None => self.new_simulation_with(self.config.clone()),
+        }
+    }
+
+    /// Creates a new simulation filling the screen with the given `config`
+    /// instead of the one of the context.
+    pub fn new_simulation_with(&mut self, config: SimConfig) -> Simulation {
+        let seed = self.next_seed();
+        Simulation::with_genomes(Self::screen_world(), config, seed, &self.genomes)
+    }
+
+    /// Returns the seed of the next simulation and prints it.
+    fn next_seed(&mut self) -> u64 {
+        let seed = self
+            .seed
+            .take()
+            .unwrap_or_else(|| miniquad::date::now().to_bits());
+        println!("seed: {seed}");
+        self.last_seed = Some(seed);
+        seed
+    }
+
+    /// Returns the seed chosen for the next simulation, if any.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Chooses the seed of the next simulation, or a random one for `None`.
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+    }
+
+    /// Returns the seed of the latest simulation, if any was created.
+    pub fn last_seed(&self) -> Option<u64> {
+        self.last_seed
+    }
+
+    /// Returns a world filling the screen.
+    fn screen_world() -> World {
+        World::new(screen_width(), screen_height())
+    }
+}
+
+/// What happens after a scene has been shown for a frame.
+pub enum Transition {
+    /// The scene stays.
+    Stay,
+    /// The given scene is shown on top of the current one.
+    Push(Box<dyn Scene>),
+    /// The scene is closed, going back to the previous one.
+    Pop,
+    /// The scene is closed and the given scene is shown instead.
+    Replace(Box<dyn Scene>),
+}
+
+/// A screen of the interactive frontend.
+pub trait Scene {
+    /// Handles the input and draws a single frame.
+    fn frame(&mut self, context: &mut Context) -> Transition;
+}
+
+/// The stack of open scenes.
+pub struct SceneStack {
+    scenes: Vec<Box<dyn Scene>>,
+}
+
+impl SceneStack {
+    /// Creates a stack showing `scene`.
+    pub fn new(scene: Box<dyn Scene>) -> Self {
+        Self {
+            scenes: vec![scene],
+        }
+    }
+
+    /// Shows `scene` on top of the current scene.
+    pub fn push(&mut self, scene: Box<dyn Scene>) {
+        self.scenes.push(scene);
+    }
+
+    /// Shows the topmost scene for a single frame.
+    ///
+    /// Returns `false` once the last scene has been closed.
+    pub fn frame(&mut self, context: &mut Context) -> bool {
+        let Some(scene) = self.scenes.last_mut() else {
+            return false;
+        };
+        match scene.frame(context) {
+            Transition::Stay => {}
+            Transition::Push(scene) => self.scenes.push(scene),
+            Transition::Pop => {
+                self.scenes.pop();
+            }
+            Transition::Replace(scene) => {
+                self.scenes.pop();
+                self.scenes.push(scene);
+            }
+        }
+        !self.scenes.is_empty()
+    }
+}
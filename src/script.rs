@@ -0,0 +1,283 @@
+//! Scripts reacting to what happens in a simulation, e.g. to spawn biots on
+//! a schedule or to intervene in the fate of chosen lineages.
+//!
+//! A script is a [Rhai](https://rhai.rs) file defining any of the callbacks
+//!
+//! - `on_interact(predator, prey)` before a predator bites its prey,
+//!   sparing the prey if it returns `false`,
+//! - `on_tick(step, population)` after every step,
+//! - `on_birth(biot)` for every offspring born during the step and
+//! - `on_death(death)` for every biot that died during it.
+//!
+//! Biots are passed as maps of their `id`, `lineage`, position `x` and `y`,
+//! `life`, `age` and properties, deaths as maps of the `id`, `age` and
+//! `cause` of the dead biot. Callbacks intervene by calling
+//! `place(archetype, x, y)`, `feed(id, life)` and `kill(id)`, which take
+//! effect at the end of the step.
+//!
+//! Scripts are [`Rules`] registered with the simulation, so they run from
+//! within its steps. Callbacks running too long, e.g. stuck in an endless
+//! loop, or placing more than [`MAX_PLACEMENTS`] biots in a step fail
+//! instead of hanging the run, and `feed` adds at most the base life of the
+//! biot.
+
+use crate::biot::{Biot, BiotId};
+use crate::biot_collection::BiotCollection;
+use crate::rules::{Intervention, Rules};
+use crate::scenario::Archetype;
+use crate::units::Energy;
+use glam::vec2;
+use rhai::{Dynamic, Engine, EvalAltResult, FuncArgs, Map, ParseError, Scope, AST};
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Maximum number of operations of the top level statements or a single
+/// callback.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Maximum depth of nested function calls.
+const MAX_CALL_LEVELS: usize = 64;
+
+/// Maximum number of biots the callbacks may place per step.
+pub const MAX_PLACEMENTS: usize = 100;
+
+/// Errors that can occur while loading or running a script.
+#[derive(Debug)]
+pub enum ScriptError {
+    /// Reading the script file failed.
+    Io(io::Error),
+    /// The script is not valid Rhai.
+    Parse(ParseError),
+    /// The script failed while running.
+    Eval(Box<EvalAltResult>),
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read script: {error}"),
+            Self::Parse(error) => write!(f, "failed to parse script: {error}"),
+            Self::Eval(error) => write!(f, "script failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+impl From<io::Error> for ScriptError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ParseError> for ScriptError {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+impl From<Box<EvalAltResult>> for ScriptError {
+    fn from(error: Box<EvalAltResult>) -> Self {
+        Self::Eval(error)
+    }
+}
+
+/// Interventions requested by the callbacks of the current step.
+type Requests = Arc<Mutex<Vec<Intervention>>>;
+
+/// Locks the interventions requested by the callbacks.
+fn lock(requests: &Requests) -> MutexGuard<'_, Vec<Intervention>> {
+    // Callbacks cannot panic while holding the lock, it is never poisoned.
+    requests
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// A loaded script and the state it needs to find the events of a step.
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    requests: Requests,
+    /// Identifier of the youngest biot seen after the previous step.
+    youngest: Option<BiotId>,
+}
+
+impl Script {
+    /// Loads the script at `path` and runs its top level statements.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ScriptError> {
+        Self::from_source(&fs::read_to_string(path)?)
+    }
+
+    /// Compiles the script `source` and runs its top level statements.
+    pub fn from_source(source: &str) -> Result<Self, ScriptError> {
+        let requests = Requests::default();
+        let mut engine = Engine::new();
+        engine
+            .set_max_operations(MAX_OPERATIONS)
+            .set_max_call_levels(MAX_CALL_LEVELS);
+        let place = Arc::clone(&requests);
+        engine.register_fn(
+            "place",
+            move |archetype: &str, x: Dynamic, y: Dynamic| -> Result<(), Box<EvalAltResult>> {
+                let deserializer: StrDeserializer<ValueError> = archetype.into_deserializer();
+                let archetype = Archetype::deserialize(deserializer)
+                    .map_err(|_| format!("unknown archetype `{archetype}`"))?;
+                let pos = vec2(number(x)?, number(y)?);
+                let mut requests = lock(&place);
+                let placed = requests
+                    .iter()
+                    .filter(|request| matches!(request, Intervention::Place { .. }))
+                    .count();
+                if placed == MAX_PLACEMENTS {
+                    return Err(format!("more than {MAX_PLACEMENTS} biots placed in a step").into());
+                }
+                requests.push(Intervention::Place { archetype, pos });
+                Ok(())
+            },
+        );
+        let feed = Arc::clone(&requests);
+        engine.register_fn(
+            "feed",
+            move |id: i64, life: Dynamic| -> Result<(), Box<EvalAltResult>> {
+                let life = Energy(number(life)?);
+                lock(&feed).push(Intervention::Feed {
+                    id: id_of(id),
+                    life,
+                });
+                Ok(())
+            },
+        );
+        let kill = Arc::clone(&requests);
+        engine.register_fn("kill", move |id: i64| {
+            lock(&kill).push(Intervention::Kill { id: id_of(id) });
+        });
+        let ast = engine.compile(source)?;
+        let mut scope = Scope::new();
+        engine.run_ast_with_scope(&mut scope, &ast)?;
+        // Interventions of the top level statements take effect after the first step.
+        Ok(Self {
+            engine,
+            ast,
+            scope,
+            requests,
+            youngest: None,
+        })
+    }
+
+    /// Calls the callbacks for what happened during the step `biots` just
+    /// completed and returns the interventions they requested.
+    fn callbacks(&mut self, biots: &BiotCollection) -> Result<Vec<Intervention>, ScriptError> {
+        let youngest = self.youngest;
+        let births = biots
+            .iter()
+            .filter(|biot| Some(biot.id()) > youngest && biot.lineage() != biot.id())
+            .map(|biot| Dynamic::from_map(biot_map(biot)))
+            .collect::<Vec<_>>();
+        self.youngest = biots.iter().map(Biot::id).max().max(youngest);
+        let deaths = biots
+            .obituaries()
+            .iter()
+            .map(|obituary| {
+                let mut death = Map::new();
+                death.insert("id".into(), Dynamic::from_int(obituary.id.0 as i64));
+                death.insert("age".into(), Dynamic::from_int(obituary.age.0.into()));
+                death.insert("cause".into(), format!("{:?}", obituary.cause).into());
+                Dynamic::from_map(death)
+            })
+            .collect::<Vec<_>>();
+        let (step, population) = (i64::from(biots.steps()), biots.len() as i64);
+        self.call("on_tick", (step, population))?;
+        for biot in births {
+            self.call("on_birth", (biot,))?;
+        }
+        for death in deaths {
+            self.call("on_death", (death,))?;
+        }
+        Ok(mem::take(&mut *lock(&self.requests)))
+    }
+
+    /// Calls the callback `name` with `args` if the script defines it.
+    ///
+    /// Returns what the callback returned, if it is defined.
+    fn call(&mut self, name: &str, args: impl FuncArgs) -> Result<Option<Dynamic>, ScriptError> {
+        let mut values = Vec::new();
+        args.parse(&mut values);
+        let defined = self
+            .ast
+            .iter_functions()
+            .any(|function| function.name == name && function.params.len() == values.len());
+        if !defined {
+            return Ok(None);
+        }
+        let result = self
+            .engine
+            .call_fn(&mut self.scope, &self.ast, name, values)?;
+        Ok(Some(result))
+    }
+}
+
+impl Rules for Script {
+    fn allows_bite(&mut self, predator: &Biot, prey: &Biot) -> Result<bool, String> {
+        let args = (
+            Dynamic::from_map(biot_map(predator)),
+            Dynamic::from_map(biot_map(prey)),
+        );
+        match self.call("on_interact", args) {
+            // Callbacks returning nothing or anything but `false` let the predator bite.
+            Ok(result) => Ok(result.and_then(|result| result.as_bool().ok()) != Some(false)),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    fn react(&mut self, biots: &BiotCollection) -> Result<Vec<Intervention>, String> {
+        self.callbacks(biots).map_err(|error| error.to_string())
+    }
+}
+
+/// Returns the identifier of a biot passed to a script function.
+fn id_of(id: i64) -> BiotId {
+    BiotId(id.max(0) as u64)
+}
+
+/// Returns a finite integer or floating point number passed to a script function.
+fn number(value: Dynamic) -> Result<f32, Box<EvalAltResult>> {
+    let number = match value.as_float() {
+        Ok(value) => value as f32,
+        Err(_) => value.as_int()? as f32,
+    };
+    if !number.is_finite() {
+        return Err(format!("{number} is not a finite number").into());
+    }
+    Ok(number)
+}
+
+/// Returns the state of `biot` a script sees.
+fn biot_map(biot: &Biot) -> Map {
+    let properties = &biot.properties;
+    let mut map = Map::new();
+    map.insert("id".into(), Dynamic::from_int(biot.id().0 as i64));
+    map.insert("lineage".into(), Dynamic::from_int(biot.lineage().0 as i64));
+    map.insert("age".into(), Dynamic::from_int(biot.stats.age.0.into()));
+    let reals = [
+        ("x", biot.stats.pos.x),
+        ("y", biot.stats.pos.y),
+        ("life", biot.stats.life.0),
+        ("attack", properties.attack),
+        ("defense", properties.defense),
+        ("photosynthesis", properties.photosynthesis),
+        ("motion", properties.motion),
+        ("intelligence", properties.intelligence),
+    ];
+    for (key, value) in reals {
+        map.insert(key.into(), Dynamic::from_float(value.into()));
+    }
+    map
+}
@@ -0,0 +1,74 @@
+//! Prompt for the identifier of a biot to jump to.
+
+use crate::biot::BiotId;
+use macroquad::prelude::*;
+
+/// Prompt opened with `J`, taking the digits of an identifier until `Enter`
+/// confirms or `Escape` cancels it.
+#[derive(Debug, Clone, Default)]
+pub struct Search {
+    /// The digits typed so far while the prompt is open.
+    query: Option<String>,
+}
+
+impl Search {
+    /// Creates a closed prompt.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` while the prompt is open, during which it takes all keys.
+    pub fn is_open(&self) -> bool {
+        self.query.is_some()
+    }
+
+    /// Handles the keys controlling the prompt.
+    ///
+    /// Returns the identifier once it was confirmed.
+    pub fn update(&mut self) -> Option<BiotId> {
+        let Some(query) = &mut self.query else {
+            if is_key_pressed(KeyCode::J) {
+                self.query = Some(String::new());
+                // Drop the `j` typed to open the prompt.
+                while get_char_pressed().is_some() {}
+            }
+            return None;
+        };
+        while let Some(char) = get_char_pressed() {
+            if char.is_ascii_digit() {
+                query.push(char);
+            }
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            query.pop();
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            self.query = None;
+            return None;
+        }
+        if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            let id = query.parse().ok().map(BiotId);
+            self.query = None;
+            return id;
+        }
+        None
+    }
+
+    /// Display the prompt at the top of the screen while it is open.
+    pub fn draw(&self) {
+        let Some(query) = &self.query else {
+            return;
+        };
+        let text = format!("jump to biot: {query}_");
+        let size = measure_text(&text, None, 24, 1.0);
+        let x = (screen_width() - size.width) / 2.;
+        draw_rectangle(
+            x - 8.,
+            40.,
+            size.width + 16.,
+            32.,
+            Color::new(0., 0., 0., 0.8),
+        );
+        draw_text(&text, x, 63., 24., YELLOW);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::biot::Gene;
+use crate::biot_collection::BiotCollection;
+use crate::environment::Date;
+use crate::stats::draw_epoch_markers;
+use macroquad::prelude::*;
+use std::collections::VecDeque;
+
+/// Number of simulation steps over which selection is estimated.
+const WINDOW: u32 = 500;
+
+/// Maximum number of windows kept in the history.
+const MAX_HISTORY: usize = 100;
+
+/// Per-gene selection coefficients of a single window.
+pub type Coefficients = [f32; Gene::EXPRESSED.len()];
+
+/// Estimates how strongly each gene is currently selected for or against.
+///
+/// For every window the selection coefficient of a gene is the relative
+/// difference between its mean count in the genomes of reproducing biots
+/// (counted once per offspring) and its mean count in the population:
+/// positive values mean the gene is over-represented among parents.
+pub struct SelectionAnalysis {
+    /// Summed gene counts of parents, once per offspring.
+    parent_counts: [f32; Gene::EXPRESSED.len()],
+    /// Number of offsprings in the current window.
+    births: u32,
+    /// Summed gene counts of the population, once per biot and step.
+    population_counts: [f32; Gene::EXPRESSED.len()],
+    /// Number of biots sampled in the current window.
+    population: u32,
+    /// Number of steps recorded so far.
+    steps: u32,
+    /// Coefficients of past windows and the year they ended in, oldest first.
+    history: VecDeque<(Coefficients, u64)>,
+}
+
+impl Default for SelectionAnalysis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SelectionAnalysis {
+    /// Creates a new selection analysis without any history.
+    pub fn new() -> Self {
+        Self {
+            parent_counts: Default::default(),
+            births: 0,
+            population_counts: Default::default(),
+            population: 0,
+            steps: 0,
+            history: VecDeque::with_capacity(MAX_HISTORY),
+        }
+    }
+
+    /// Records the state of the biot collection after a simulation step at `date`.
+    pub fn record(&mut self, biots: &BiotCollection, date: Date) {
+        for genome in biots.parents() {
+            accumulate(&mut self.parent_counts, genome.expressed_counts());
+            self.births += 1;
+        }
+        for biot in biots.iter() {
+            accumulate(
+                &mut self.population_counts,
+                biot.genome().expressed_counts(),
+            );
+            self.population += 1;
+        }
+        self.steps += 1;
+        if self.steps.is_multiple_of(WINDOW) {
+            if self.history.len() == MAX_HISTORY {
+                self.history.pop_front();
+            }
+            self.history.push_back((self.coefficients(), date.year));
+            self.parent_counts = Default::default();
+            self.births = 0;
+            self.population_counts = Default::default();
+            self.population = 0;
+        }
+    }
+
+    /// Computes the selection coefficients of the current window.
+    fn coefficients(&self) -> Coefficients {
+        let mut coefficients = Coefficients::default();
+        if self.births == 0 || self.population == 0 {
+            return coefficients;
+        }
+        for (i, coefficient) in coefficients.iter_mut().enumerate() {
+            let parent_mean = self.parent_counts[i] / self.births as f32;
+            let population_mean = self.population_counts[i] / self.population as f32;
+            if population_mean > 0.0 {
+                *coefficient = parent_mean / population_mean - 1.0;
+            }
+        }
+        coefficients
+    }
+
+    /// Display the latest coefficients and their history in the bottom left corner.
+    pub fn draw(&self) {
+        let (x, y, width, height) = (10., screen_height() - 210., 300., 200.);
+        draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.6));
+        draw_rectangle_lines(x, y, width, height, 1., LIGHTGRAY);
+        draw_line(x, y + height / 2., x + width, y + height / 2., 1., DARKGRAY);
+        let years = self.history.iter().map(|(_, year)| *year);
+        draw_epoch_markers(years, MAX_HISTORY, (x, y, width, height));
+        let latest = self
+            .history
+            .back()
+            .map(|(coefficients, _)| *coefficients)
+            .unwrap_or_default();
+        let max = self
+            .history
+            .iter()
+            .flat_map(|(coefficients, _)| coefficients.iter())
+            .fold(0.1f32, |max, c| max.max(c.abs()));
+        for (i, gene) in Gene::EXPRESSED.iter().enumerate() {
+            let color = gene_color(*gene);
+            draw_text(
+                &format!("{:?}: {:+.2}", gene, latest[i]),
+                x + 5.,
+                y + 15. + 15. * i as f32,
+                16.,
+                color,
+            );
+            let to_screen = |n: usize, c: f32| {
+                vec2(
+                    x + width * n as f32 / (MAX_HISTORY - 1) as f32,
+                    y + height / 2. - height / 2. * c / max,
+                )
+            };
+            for (n, (from, to)) in self
+                .history
+                .iter()
+                .zip(self.history.iter().skip(1))
+                .enumerate()
+            {
+                let from = to_screen(n, from.0[i]);
+                let to = to_screen(n + 1, to.0[i]);
+                draw_line(from.x, from.y, to.x, to.y, 1., color);
+            }
+        }
+    }
+}
+
+/// Adds gene counts to the running sums.
+fn accumulate(sums: &mut [f32; Gene::EXPRESSED.len()], counts: [u32; Gene::EXPRESSED.len()]) {
+    for (sum, count) in sums.iter_mut().zip(counts) {
+        *sum += count as f32;
+    }
+}
+
+/// The color used to plot a gene.
+pub fn gene_color(gene: Gene) -> Color {
+    match gene {
+        Gene::Attack => RED,
+        Gene::Defense => DARKBLUE,
+        Gene::Photosynthesis => GREEN,
+        Gene::Motion => BLUE,
+        Gene::Digestion => ORANGE,
+        Gene::Venom => PURPLE,
+        Gene::Armor => BROWN,
+        Gene::Camouflage => DARKGREEN,
+        Gene::Vision => SKYBLUE,
+        Gene::Reproduction => MAGENTA,
+        Gene::Immunity => YELLOW,
+        Gene::Fecundity => PINK,
+        Gene::Nurture => BEIGE,
+        Gene::Sociality => GOLD,
+        Gene::HeatTolerance => MAROON,
+        Gene::Kinship => VIOLET,
+        Gene::Intelligence | Gene::None | Gene::Custom(_) => WHITE,
+    }
+}
@@ -0,0 +1,201 @@
+//! Remote observation of headless runs over HTTP.
+//!
+//! Experiments on remote machines are easier to keep an eye on if their
+//! state can be polled, e.g. with `curl`, instead of logging in to tail the
+//! recordings. The observer answers between two steps with the state after
+//! the last one. The responses are sent by a single background thread, so
+//! slow clients do not hold up the simulation. Each response is computed at
+//! most once per step, however many clients ask for it, and requests beyond
+//! the few waiting for the background thread are turned away.
+//!
+//! - `/stats` returns the aggregate statistics as a JSON object with the
+//!   keys of the recorded columns,
+//! - `/biots` returns every living biot as a JSON array of objects with the
+//!   keys of the recording of every single biot and
+//! - `/snapshot` downloads a checkpoint to continue with `--load`.
+
+use crate::recorder;
+use crate::simulation::{Simulation, SnapshotError, SNAPSHOT_PATH};
+use std::io;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Maximum number of responses waiting for the background thread.
+const MAX_PENDING: usize = 16;
+
+/// Content type of the statistics, the biots and errors.
+const JSON: &str = "application/json";
+
+/// Content type of snapshots.
+const BINARY: &str = "application/octet-stream";
+
+/// The state of a simulation observers can ask for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Resource {
+    Stats,
+    Biots,
+    Snapshot,
+}
+
+impl Resource {
+    /// Returns the resource at `path`, if any.
+    fn at(path: &str) -> Option<Self> {
+        match path {
+            "" | "/stats" => Some(Self::Stats),
+            "/biots" => Some(Self::Biots),
+            "/snapshot" => Some(Self::Snapshot),
+            _ => None,
+        }
+    }
+
+    /// Returns the content type of the resource.
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Stats | Self::Biots => JSON,
+            Self::Snapshot => BINARY,
+        }
+    }
+}
+
+/// A response waiting to be sent by the background thread.
+struct Reply {
+    request: Request,
+    status: u16,
+    content_type: &'static str,
+    body: Arc<Vec<u8>>,
+}
+
+/// Serves the state of a simulation to remote observers.
+pub struct Observer {
+    server: Server,
+    replies: SyncSender<Reply>,
+    /// Step the cached responses belong to.
+    step: Option<u32>,
+    /// Responses computed for `step`, by resource.
+    cache: [Option<Arc<Vec<u8>>>; 3],
+}
+
+impl Observer {
+    /// Listens for observers on `address`, e.g. `0.0.0.0:8080`.
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let server = Server::http(address).map_err(io::Error::other)?;
+        let (replies, pending) = mpsc::sync_channel::<Reply>(MAX_PENDING);
+        // The thread stops once the observer is dropped and the pending replies are sent.
+        thread::spawn(move || {
+            for reply in pending {
+                respond(reply);
+            }
+        });
+        Ok(Self {
+            server,
+            replies,
+            step: None,
+            cache: Default::default(),
+        })
+    }
+
+    /// Returns the address the observer listens on, e.g. to find the port
+    /// chosen for port zero.
+    pub fn address(&self) -> String {
+        self.server.server_addr().to_string()
+    }
+
+    /// Answers all pending requests with the current state of `simulation`.
+    ///
+    /// # Note
+    ///
+    /// Responses are reused until the simulation advanced by a step, so
+    /// interventions between two steps are only seen after the next one.
+    pub fn serve(&mut self, simulation: &Simulation) {
+        let step = simulation.biots().steps();
+        if self.step != Some(step) {
+            self.step = Some(step);
+            self.cache = Default::default();
+        }
+        while let Ok(Some(request)) = self.server.try_recv() {
+            if *request.method() != Method::Get {
+                self.reply(request, 405, JSON, error_json("only GET is supported"));
+                continue;
+            }
+            // Query strings are accepted, e.g. to defeat caches, but ignored.
+            let path = request.url().split('?').next().unwrap_or_default();
+            let Some(resource) = Resource::at(path.trim_end_matches('/')) else {
+                self.reply(request, 404, JSON, error_json("not found"));
+                continue;
+            };
+            match self.body(resource, simulation) {
+                Ok(body) => self.reply(request, 200, resource.content_type(), body),
+                Err(error) => {
+                    let message = error_json(&error.to_string());
+                    self.reply(request, 500, JSON, message);
+                }
+            }
+        }
+    }
+
+    /// Returns the body of `resource`, computed once per step.
+    fn body(
+        &mut self,
+        resource: Resource,
+        simulation: &Simulation,
+    ) -> Result<Arc<Vec<u8>>, SnapshotError> {
+        let cached = &mut self.cache[resource as usize];
+        if let Some(body) = cached {
+            return Ok(Arc::clone(body));
+        }
+        let body = Arc::new(match resource {
+            Resource::Stats => recorder::stats_json(simulation).into_bytes(),
+            Resource::Biots => recorder::biots_json(simulation).into_bytes(),
+            Resource::Snapshot => simulation.encode()?,
+        });
+        *cached = Some(Arc::clone(&body));
+        Ok(body)
+    }
+
+    /// Queues the `body` of the given `content_type` with `status` for the
+    /// background thread, or turns the request away if too many are waiting.
+    fn reply(&self, request: Request, status: u16, content_type: &'static str, body: Arc<Vec<u8>>) {
+        let reply = Reply {
+            request,
+            status,
+            content_type,
+            body,
+        };
+        if let Err(TrySendError::Full(reply) | TrySendError::Disconnected(reply)) =
+            self.replies.try_send(reply)
+        {
+            // An empty response is small enough not to hold up the simulation.
+            let _ = reply.request.respond(Response::empty(503));
+        }
+    }
+}
+
+/// Returns a JSON object describing an error.
+fn error_json(message: &str) -> Arc<Vec<u8>> {
+    let message = message.replace('\\', "\\\\").replace('"', "\\\"");
+    Arc::new(format!("{{\"error\":\"{message}\"}}").into_bytes())
+}
+
+/// Sends a reply, blocking until the client received it.
+fn respond(reply: Reply) {
+    let Reply {
+        request,
+        status,
+        content_type,
+        body,
+    } = reply;
+    let mut response = Response::from_data(body.as_slice()).with_status_code(status);
+    if let Ok(header) = Header::from_bytes("Content-Type", content_type) {
+        response = response.with_header(header);
+    }
+    if content_type == BINARY {
+        let disposition = format!("attachment; filename=\"{SNAPSHOT_PATH}\"");
+        if let Ok(header) = Header::from_bytes("Content-Disposition", disposition) {
+            response = response.with_header(header);
+        }
+    }
+    // Observers hanging up early are of no concern to the simulation.
+    let _ = request.respond(response);
+}
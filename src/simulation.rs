@@ -0,0 +1,411 @@
+use crate::behavior::{Behavior, GenePlugin};
+use crate::biot::{BiotId, Genome};
+use crate::biot_collection::BiotCollection;
+#[cfg(feature = "render")]
+use crate::camera::Camera;
+use crate::config::{ConfigError, SimConfig};
+use crate::current::Currents;
+use crate::dominance::{Alarm, DominanceMonitor};
+use crate::environment::{Date, Environment};
+#[cfg(feature = "render")]
+use crate::filter::Filter;
+use crate::food::FoodCollection;
+#[cfg(feature = "render")]
+use crate::interpolation::Interpolation;
+#[cfg(feature = "render")]
+use crate::layer::LayerMap;
+use crate::migration::{self, OLDEST_SNAPSHOT_VERSION};
+#[cfg(feature = "render")]
+use crate::obstacle;
+use crate::phase::{Phase, Phases, StepHooks};
+#[cfg(feature = "render")]
+use crate::reserve;
+use crate::rng::{Rng, RngStreams};
+use crate::rules::Rules;
+#[cfg(feature = "render")]
+use crate::species::Coloring;
+use crate::terrain::Terrain;
+use crate::world::World;
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Version of the snapshot format written by [`Simulation::save`].
+///
+/// Must be bumped whenever the serialized state changes incompatibly, along
+/// with an upgrade of the previous version in [`migration`] unless
+/// [`OLDEST_SNAPSHOT_VERSION`] is raised instead.
+const SNAPSHOT_VERSION: u32 = 44;
+
+/// File the simulation is saved to and loaded from unless another one is given,
+/// e.g. by the hotkeys of the viewer and the menu.
+pub const SNAPSHOT_PATH: &str = "life_web.snapshot";
+
+/// Errors that can occur while saving or loading a simulation.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// Reading or writing the snapshot file failed.
+    Io(io::Error),
+    /// Encoding or decoding the simulation state failed.
+    Encoding(bincode::Error),
+    /// The snapshot was written with an unsupported format version.
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "snapshot I/O failed: {error}"),
+            Self::Encoding(error) => write!(f, "snapshot encoding failed: {error}"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported snapshot version {version}, expected \
+                 {OLDEST_SNAPSHOT_VERSION} to {SNAPSHOT_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<bincode::Error> for SnapshotError {
+    fn from(error: bincode::Error) -> Self {
+        Self::Encoding(error)
+    }
+}
+
+/// How [`Simulation::resize`] treats the contents of the world.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Resize {
+    /// Everything keeps its position, biots, food and corpses beyond the new
+    /// edges are removed.
+    Cull,
+    /// All positions, the terrain and the scent are stretched along with the world.
+    Scale,
+}
+
+/// A complete simulation: a world, the biots living in it and their food.
+///
+/// The simulation does not depend on a graphics context and can be stepped
+/// in a plain loop, e.g. for long running headless experiments.
+#[derive(Serialize, Deserialize)]
+pub struct Simulation {
+    world: World,
+    terrain: Terrain,
+    currents: Currents,
+    biots: BiotCollection,
+    food: FoodCollection,
+    environment: Environment,
+    dominance: DominanceMonitor,
+    /// Alarm raised by the last step, if any.
+    #[serde(skip)]
+    alarm: Option<Alarm>,
+    /// Biots injected by the last step to counteract a loss of diversity.
+    #[serde(skip)]
+    injected: Vec<BiotId>,
+    rngs: RngStreams,
+    /// Seed the simulation was created with.
+    seed: u64,
+}
+
+impl Simulation {
+    /// Creates a simulation of the ecosystem described by `config` in `world`.
+    ///
+    /// Simulations created with the same `config` and `seed` evolve identically.
+    pub fn new(world: World, config: SimConfig, seed: u64) -> Self {
+        Self::with_genomes(world, config, seed, &[])
+    }
+
+    /// Creates a simulation like [`Simulation::new`] whose initial population
+    /// is made of the given `genomes`, see [`BiotCollection::new_from_genomes`].
+    pub fn with_genomes(world: World, config: SimConfig, seed: u64, genomes: &[Genome]) -> Self {
+        let mut rngs = RngStreams::new(seed);
+        let world = world.with_topology(config.topology);
+        let biots = BiotCollection::new_from_genomes(config, &world, &mut rngs, genomes);
+        let terrain = Terrain::generate(&world, &biots.config().terrain, &mut rngs.placement);
+        let currents = Currents::generate(&world, &biots.config().currents, &mut rngs.placement);
+        Self {
+            world,
+            terrain,
+            currents,
+            biots,
+            food: FoodCollection::new(),
+            environment: Environment::new(),
+            dominance: DominanceMonitor::new(),
+            alarm: None,
+            injected: Vec::new(),
+            rngs,
+            seed,
+        }
+    }
+
+    /// Compute one step of the simulation.
+    pub fn step(&mut self) {
+        self.advance(&mut Phases::unobserved());
+    }
+
+    /// Compute one step of the simulation, reporting every [`Phase`] of it to `hooks`.
+    pub fn step_with(&mut self, hooks: &mut dyn StepHooks) {
+        let step = self.biots.steps() + 1;
+        self.advance(&mut Phases::observed(hooks, step));
+    }
+
+    /// Compute one step of the simulation, entering its phases on `phases`.
+    fn advance(&mut self, phases: &mut Phases) {
+        phases.enter(Phase::Environment);
+        let biots = &self.biots;
+        self.environment
+            .regulate(biots.len(), &biots.config().controller);
+        self.environment.tick();
+        self.biots.drift(&self.currents);
+        let config = self.environment.adjust(self.biots.config());
+        phases.enter(Phase::Food);
+        self.food.step(
+            &self.world,
+            &self.terrain,
+            &config.obstacles,
+            &config.food,
+            &mut self.rngs.placement,
+        );
+        self.biots.step(
+            &self.world,
+            &self.terrain,
+            &self.environment,
+            &mut self.food,
+            &mut self.rngs,
+            phases,
+        );
+        phases.enter(Phase::Dominance);
+        let dominance = &config.dominance;
+        self.alarm = self.dominance.check(&self.biots, dominance);
+        if let Some(alarm) = &mut self.alarm {
+            let biots = &mut self.biots;
+            alarm.mutated =
+                biots.diversify(&alarm.strategy, dominance.mutation, &mut self.rngs.mutation);
+        }
+        phases.enter(Phase::Novelty);
+        let novelty = &config.novelty;
+        self.injected.clear();
+        if novelty.is_enabled() && self.biots.steps().is_multiple_of(novelty.interval) {
+            self.injected = self.biots.inject(&self.world, &mut self.rngs.placement);
+        }
+        phases.finish();
+    }
+
+    /// Returns the seed the simulation was created with.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the world of the simulation.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Changes the size of the world to `width` by `height` as described by `mode`.
+    ///
+    /// Returns the number of biots removed for lying beyond the new edges.
+    ///
+    /// # Note
+    ///
+    /// Areas added to the world continue the terrain and scent at its old
+    /// edges. Obstacles keep their positions either way.
+    pub fn resize(&mut self, width: f32, height: f32, mode: Resize) -> usize {
+        let world = World::new(width.max(1.0), height.max(1.0)).with_topology(self.world.topology);
+        let stretch = vec2(
+            world.width / self.world.width,
+            world.height / self.world.height,
+        );
+        let remap = |pos: Vec2| match mode {
+            Resize::Cull => Some(pos).filter(|pos| pos.x <= world.width && pos.y <= world.height),
+            Resize::Scale => Some(pos * stretch),
+        };
+        let source = |pos: Vec2| match mode {
+            Resize::Cull => pos,
+            Resize::Scale => pos / stretch,
+        };
+        self.terrain = self.terrain.resized(&world, source);
+        self.currents = self.currents.resized(&world, source);
+        self.food.remap(remap);
+        let culled = self.biots.resize(&world, remap, source);
+        self.world = world;
+        culled
+    }
+
+    /// Replaces the ecosystem parameters of the running simulation, e.g. to tune them live.
+    ///
+    /// # Errors
+    ///
+    /// If `config` is invalid, in which case the current parameters are kept.
+    ///
+    /// # Note
+    ///
+    /// Parameters read every step, e.g. the mutation chance, the metabolism
+    /// costs and the food spawn rate, take effect with the next step. Those
+    /// only read at the start, e.g. the terrain, the random currents, the
+    /// topology and the initial population, do not change the running
+    /// simulation, and living biots keep the properties they were born with.
+    pub fn reconfigure(&mut self, config: SimConfig) -> Result<(), ConfigError> {
+        config.check()?;
+        self.biots.set_config(config);
+        Ok(())
+    }
+
+    /// Registers a `behavior` steering the intelligent biots, see [`Behavior`].
+    pub fn add_behavior(&mut self, behavior: impl Behavior + 'static) {
+        self.biots.add_behavior(Box::new(behavior));
+    }
+
+    /// Registers a `plugin` giving its gene an effect, see [`GenePlugin`].
+    pub fn add_gene_plugin(&mut self, plugin: impl GenePlugin + 'static) {
+        self.biots.add_gene_plugin(Box::new(plugin));
+    }
+
+    /// Registers `rules` intervening in the steps, see [`Rules`].
+    pub fn add_rules(&mut self, rules: impl Rules + 'static) {
+        self.biots.add_rules(Box::new(rules));
+    }
+
+    /// Returns the biots of the simulation.
+    pub fn biots(&self) -> &BiotCollection {
+        &self.biots
+    }
+
+    /// Returns the random water currents of the simulation.
+    pub fn currents(&self) -> &Currents {
+        &self.currents
+    }
+
+    /// Returns the days, nights and seasons of the simulation.
+    pub fn environment(&self) -> &Environment {
+        &self.environment
+    }
+
+    /// Returns the alarm raised by the last step if a strategy dominates the population.
+    pub fn alarm(&self) -> Option<&Alarm> {
+        self.alarm.as_ref()
+    }
+
+    /// Returns the biots injected by the last step, see [`SimConfig::novelty`].
+    pub fn injected(&self) -> &[BiotId] {
+        &self.injected
+    }
+
+    /// Returns the current date of the simulation calendar.
+    pub fn date(&self) -> Date {
+        self.environment.date(&self.biots.config().environment)
+    }
+
+    /// Returns the food pellets of the simulation.
+    pub fn food(&self) -> &FoodCollection {
+        &self.food
+    }
+
+    /// Returns the random number generator used to place biots and food.
+    pub fn rng_mut(&mut self) -> &mut Rng {
+        &mut self.rngs.placement
+    }
+
+    /// Returns the biots of the simulation for modification.
+    pub fn biots_mut(&mut self) -> &mut BiotCollection {
+        &mut self.biots
+    }
+
+    /// Saves the full state of the simulation to the file at `path`.
+    ///
+    /// The snapshot is written next to `path` first and then renamed, so that
+    /// an interrupted save never leaves a torn snapshot behind.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+        let mut partial = path.as_os_str().to_owned();
+        partial.push(".partial");
+        let written = File::create(&partial)
+            .map_err(SnapshotError::from)
+            .and_then(|file| self.write(BufWriter::new(file)));
+        if let Err(error) = written {
+            // The partial snapshot is useless, the error is what matters.
+            let _ = fs::remove_file(&partial);
+            return Err(error);
+        }
+        Ok(fs::rename(&partial, path)?)
+    }
+
+    /// Loads a simulation previously saved with [`Simulation::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    /// Encodes the full state of the simulation in the snapshot format.
+    pub fn encode(&self) -> Result<Vec<u8>, SnapshotError> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Decodes a simulation previously encoded with [`Simulation::encode`].
+    pub fn decode(bytes: &[u8]) -> Result<Self, SnapshotError> {
+        Self::read(bytes)
+    }
+
+    /// Writes the snapshot format version followed by the full state of the simulation.
+    fn write(&self, mut writer: impl Write) -> Result<(), SnapshotError> {
+        bincode::serialize_into(&mut writer, &SNAPSHOT_VERSION)?;
+        bincode::serialize_into(&mut writer, self)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a simulation written by [`Simulation::write`], upgrading older format versions.
+    fn read(mut reader: impl Read) -> Result<Self, SnapshotError> {
+        let version: u32 = bincode::deserialize_from(&mut reader)?;
+        if !(OLDEST_SNAPSHOT_VERSION..=SNAPSHOT_VERSION).contains(&version) {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+        let decode = || bincode::deserialize_from(&mut reader);
+        Ok(migration::decode_snapshot(version, decode)?)
+    }
+
+    /// Display the water currents as streamlines, as seen through `camera`.
+    #[cfg(feature = "render")]
+    pub fn draw_currents(&self, camera: &Camera) {
+        let offsets = camera.wrap_offsets(&self.world);
+        let flows = &self.biots.config().currents.flows;
+        self.currents.draw(flows, &self.world, &offsets);
+    }
+
+    /// Display the simulation as seen through `camera`, dimming the biots not matching `filter`.
+    ///
+    /// The scent of the biots is drawn as a heatmap if `scent` is set, the
+    /// `layer` over the terrain if given, and the biots at the positions given
+    /// by `interpolation`.
+    #[cfg(feature = "render")]
+    pub fn draw(
+        &self,
+        camera: &Camera,
+        filter: &Filter,
+        coloring: Coloring,
+        scent: bool,
+        layer: Option<&LayerMap>,
+        interpolation: &Interpolation,
+    ) {
+        let offsets = camera.wrap_offsets(&self.world);
+        self.terrain.draw(&offsets);
+        if let Some(layer) = layer {
+            layer.draw(&offsets);
+        }
+        obstacle::draw(&self.biots.config().obstacles);
+        reserve::draw(&self.biots.config().reserves);
+        self.food.draw(&offsets);
+        self.biots
+            .draw(&self.world, camera, filter, coloring, scent, interpolation);
+    }
+}
@@ -0,0 +1,150 @@
+//! Regions of the world the random initial population is placed in.
+//!
+//! Without any regions random biots are spread uniformly over the world.
+//! Scenarios can instead confine them to weighted areas, e.g. letting the
+//! predators start on one island and the prey on another.
+
+use crate::biot::Biot;
+use crate::rng::Rng;
+use crate::world::World;
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// Shape of a spawn region.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "shape", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Area {
+    /// An axis aligned rectangle from `min` to `max`.
+    Rectangle { min: Vec2, max: Vec2 },
+    /// A circle around `center`.
+    Circle { center: Vec2, radius: f32 },
+}
+
+impl Area {
+    /// Returns a uniformly distributed random position within the area.
+    fn random_position(&self, rng: &mut Rng) -> Vec2 {
+        match *self {
+            Self::Rectangle { min, max } => {
+                vec2(rng.gen_range(min.x, max.x), rng.gen_range(min.y, max.y))
+            }
+            Self::Circle { center, radius } => {
+                // The square root keeps the density constant towards the rim.
+                let distance = radius * rng.gen_range(0.0, 1.0).sqrt();
+                let angle = rng.gen_range(0.0, std::f32::consts::TAU);
+                center + vec2(angle.cos(), angle.sin()) * distance
+            }
+        }
+    }
+
+    /// Returns `true` if `pos` lies within the area.
+    pub fn contains(&self, pos: Vec2) -> bool {
+        match *self {
+            Self::Rectangle { min, max } => {
+                (min.x..=max.x).contains(&pos.x) && (min.y..=max.y).contains(&pos.y)
+            }
+            Self::Circle { center, radius } => pos.distance_squared(center) <= radius * radius,
+        }
+    }
+
+    /// Returns `true` if the area has a finite, non-negative extent.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Self::Rectangle { min, max } => {
+                min.is_finite() && max.is_finite() && min.x <= max.x && min.y <= max.y
+            }
+            Self::Circle { center, radius } => {
+                center.is_finite() && radius.is_finite() && radius >= 0.0
+            }
+        }
+    }
+}
+
+/// Which of the random biots a spawn region receives.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Occupants {
+    /// Every biot.
+    #[default]
+    All,
+    /// Biots classified as predators, see [`Biot::is_predator`].
+    Predators,
+    /// Biots not classified as predators.
+    Prey,
+}
+
+impl Occupants {
+    /// Returns `true` if `biot` belongs to the occupants.
+    fn admit(self, biot: &Biot) -> bool {
+        match self {
+            Self::All => true,
+            Self::Predators => biot.is_predator(),
+            Self::Prey => !biot.is_predator(),
+        }
+    }
+}
+
+/// A weighted area of the world random biots are placed in.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SpawnRegion {
+    pub area: Area,
+    /// Relative chance of the region among all regions admitting a biot.
+    pub weight: f32,
+    /// Which biots the region receives.
+    pub occupants: Occupants,
+}
+
+impl Default for SpawnRegion {
+    fn default() -> Self {
+        Self {
+            area: Area::Rectangle {
+                min: Vec2::ZERO,
+                max: Vec2::ZERO,
+            },
+            weight: 1.0,
+            occupants: Occupants::All,
+        }
+    }
+}
+
+impl SpawnRegion {
+    /// Returns `true` if the region has a valid area and a finite, non-negative weight.
+    pub fn is_valid(&self) -> bool {
+        self.area.is_valid() && self.weight.is_finite() && self.weight >= 0.0
+    }
+}
+
+/// Returns a random position for `biot` within one of the `regions` admitting it.
+///
+/// # Note
+///
+/// Regions are picked in proportion to their weights. Returns `None` if no
+/// region with a positive weight admits the biot. Positions outside the
+/// world are wrapped into it.
+pub fn random_position(
+    regions: &[SpawnRegion],
+    biot: &Biot,
+    world: &World,
+    rng: &mut Rng,
+) -> Option<Vec2> {
+    let admitting = || {
+        regions
+            .iter()
+            .filter(|region| region.weight > 0.0 && region.occupants.admit(biot))
+    };
+    let total = admitting().map(|region| region.weight).sum::<f32>();
+    if total <= 0.0 {
+        return None;
+    }
+    let mut pick = rng.gen_range(0.0, total);
+    // Rounding may leave the pick just above the last weight.
+    let mut chosen = None;
+    for region in admitting() {
+        chosen = Some(region);
+        if pick < region.weight {
+            break;
+        }
+        pick -= region.weight;
+    }
+    chosen.map(|region| world.wrap(region.area.random_position(rng)))
+}
@@ -0,0 +1,104 @@
+//! Grouping of the biots into species of similar genomes.
+//!
+//! Lineages only tell who descends from whom, while species group biots by
+//! what they are, so that diversification is visible even within a single
+//! lineage.
+
+use crate::biot::{Biot, Genome};
+use serde::{Deserialize, Serialize};
+
+/// Genome distance, in genes, within which a biot belongs to a species.
+const SPECIES_DISTANCE: f32 = 6.0;
+
+/// Number of steps between two clustering passes.
+pub const SPECIES_INTERVAL: u32 = 100;
+
+/// Identifier of a species, unique within a simulation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SpeciesId(pub u32);
+
+/// The species of the population, each represented by the genome of one of its members.
+///
+/// # Note
+///
+/// A biot stays with its species while it is close to the representative of
+/// the species, otherwise it joins the first close species or founds a new
+/// one. Newborns inherit the species of their parent until the next pass.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpeciesRegistry {
+    representatives: Vec<(SpeciesId, Genome)>,
+    /// Identifier of the next species.
+    next: u32,
+}
+
+impl SpeciesRegistry {
+    /// Creates a registry without any species.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assigns every biot to a species.
+    ///
+    /// Species without members die out and the first member of every other
+    /// species becomes its new representative, so that species follow the
+    /// gradual evolution of their members.
+    pub fn cluster(&mut self, biots: &mut [Biot]) {
+        let mut members = Vec::<(SpeciesId, Genome)>::new();
+        for biot in biots.iter_mut() {
+            let close = |(_, genome): &&(SpeciesId, Genome)| {
+                genome.distance(biot.genome()) <= SPECIES_DISTANCE
+            };
+            let current = self
+                .representatives
+                .iter()
+                .filter(|(species, _)| Some(*species) == biot.species())
+                .find(close);
+            let species = match current.or_else(|| self.representatives.iter().find(close)) {
+                Some((species, _)) => *species,
+                None => {
+                    let species = SpeciesId(self.next);
+                    self.next += 1;
+                    self.representatives.push((species, biot.genome().clone()));
+                    species
+                }
+            };
+            biot.set_species(species);
+            if members.iter().all(|(member, _)| *member != species) {
+                members.push((species, biot.genome().clone()));
+            }
+        }
+        members.sort_by_key(|(species, _)| *species);
+        self.representatives = members;
+    }
+
+    /// Returns the number of species with living members as of the last pass.
+    pub fn len(&self) -> usize {
+        self.representatives.len()
+    }
+
+    /// Returns `true` if the no species has living members as of the last pass.
+    pub fn is_empty(&self) -> bool {
+        self.representatives.is_empty()
+    }
+}
+
+/// How the biots are colored.
+#[cfg(feature = "render")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Coloring {
+    /// Fixed colors per trait, showing how the body of the biot is built.
+    Traits,
+    /// A distinct hue per species, brighter towards the center of the body.
+    Species,
+}
+
+/// Returns the hue of `species` at the given `lightness`.
+///
+/// # Note
+///
+/// The hues follow the golden ratio, so that consecutive species are far apart on the color wheel.
+#[cfg(feature = "render")]
+pub fn species_color(species: SpeciesId, lightness: f32) -> macroquad::prelude::Color {
+    let hue = (species.0 as f32 * 0.618_034).fract();
+    macroquad::color::hsl_to_rgb(hue, 0.7, lightness)
+}
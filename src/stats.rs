@@ -0,0 +1,379 @@
+use crate::biot::{Gene, MAX_RADIUS};
+use crate::biot_collection::BiotCollection;
+use crate::environment::Date;
+use crate::ledger::EnergyLedger;
+#[cfg(feature = "render")]
+use crate::selection::gene_color;
+use crate::units::Energy;
+#[cfg(feature = "render")]
+use macroquad::prelude::*;
+use std::collections::{HashSet, VecDeque};
+
+/// Number of simulation steps aggregated into a single sample.
+const SAMPLE_INTERVAL: u32 = 10;
+
+/// Maximum number of samples kept in the history.
+const MAX_SAMPLES: usize = 500;
+
+/// Number of size classes of a [`SizeDistribution`].
+pub const SIZE_CLASSES: usize = 8;
+
+/// Number of plotted series: population, births, deaths, infected biots and
+/// the mean properties.
+#[cfg(feature = "render")]
+const SERIES: usize = 4 + Gene::EXPRESSED.len();
+
+/// Population statistics aggregated over [`SAMPLE_INTERVAL`] steps.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Sample {
+    /// Number of biots at the end of the interval.
+    pub population: usize,
+    /// Number of lineages with living descendants at the end of the interval.
+    pub lineages: usize,
+    /// Number of species as of the last clustering pass.
+    pub species: usize,
+    /// Mean properties of the biots, in the order of [`Gene::EXPRESSED`].
+    pub means: [f32; Gene::EXPRESSED.len()],
+    /// Mean number of genes in the genomes of the biots.
+    pub genes: f32,
+    /// Mean chance for each additional mutation of the offspring of the biots.
+    pub mutation_chance: f32,
+    /// Number of offsprings born during the interval.
+    pub births: u32,
+    /// Number of biots that died during the interval.
+    pub deaths: u32,
+    /// Number of biots carrying the pathogen at the end of the interval.
+    pub infected: usize,
+    /// Number of biots infected during the interval.
+    pub infections: u32,
+    /// Energy flows summed over the interval.
+    pub ledger: EnergyLedger,
+    /// Change of the total life over the interval the flows do not explain.
+    pub unbooked: Energy,
+    /// Date at the end of the interval.
+    pub date: Date,
+}
+
+impl Sample {
+    /// Takes a sample of the current state of the biot collection at `date`.
+    ///
+    /// `births`, `deaths` and `infections` are counted over the interval.
+    /// The energy flows are left empty.
+    pub fn take(
+        biots: &BiotCollection,
+        date: Date,
+        births: u32,
+        deaths: u32,
+        infections: u32,
+    ) -> Self {
+        let mut means = [0.0; Gene::EXPRESSED.len()];
+        let mut genes = 0.0;
+        let mut mutation_chance = 0.0;
+        for biot in biots.iter() {
+            genes += biot.genome().len() as f32;
+            mutation_chance += biot.mutation_chance(biots.config());
+            let properties = &biot.properties;
+            let values = [
+                properties.attack,
+                properties.defense,
+                properties.photosynthesis,
+                properties.motion,
+                properties.intelligence,
+                properties.venom,
+                properties.armor,
+                properties.camouflage,
+                properties.vision,
+                properties.reproduction,
+                properties.digestion,
+                properties.immunity,
+                properties.fecundity,
+                properties.nurture,
+                properties.sociality,
+                properties.heat_tolerance,
+                properties.kinship,
+            ];
+            for (mean, value) in means.iter_mut().zip(values) {
+                *mean += value;
+            }
+        }
+        let population = biots.len();
+        let lineages = biots
+            .iter()
+            .map(|biot| biot.lineage())
+            .collect::<HashSet<_>>();
+        if population > 0 {
+            for mean in &mut means {
+                *mean /= population as f32;
+            }
+            genes /= population as f32;
+            mutation_chance /= population as f32;
+        }
+        Self {
+            population,
+            lineages: lineages.len(),
+            species: biots.species_count(),
+            means,
+            genes,
+            mutation_chance,
+            births,
+            deaths,
+            infected: biots.infected_count(),
+            infections,
+            ledger: EnergyLedger::default(),
+            unbooked: Energy::ZERO,
+            date,
+        }
+    }
+}
+
+/// Records how the population and its mean properties evolve over time.
+pub struct PopulationStats {
+    /// Past samples, oldest first.
+    samples: VecDeque<Sample>,
+    /// Births in the current interval.
+    births: u32,
+    /// Deaths in the current interval.
+    deaths: u32,
+    /// Infections in the current interval.
+    infections: u32,
+    /// Energy flows in the current interval.
+    ledger: EnergyLedger,
+    /// Unbooked energy in the current interval.
+    unbooked: Energy,
+    /// Number of steps recorded so far.
+    steps: u32,
+}
+
+impl Default for PopulationStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PopulationStats {
+    /// Creates population statistics without any history.
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::with_capacity(MAX_SAMPLES),
+            births: 0,
+            deaths: 0,
+            infections: 0,
+            ledger: EnergyLedger::default(),
+            unbooked: Energy::ZERO,
+            steps: 0,
+        }
+    }
+
+    /// Records the state of the biot collection after a simulation step at `date`.
+    pub fn record(&mut self, biots: &BiotCollection, date: Date) {
+        self.births += biots.births();
+        self.deaths += biots.deaths().len() as u32;
+        self.infections += biots.infections();
+        self.ledger = self.ledger + *biots.energy_ledger();
+        self.unbooked += biots.unbooked_energy();
+        self.steps += 1;
+        if self.steps.is_multiple_of(SAMPLE_INTERVAL) {
+            if self.samples.len() == MAX_SAMPLES {
+                self.samples.pop_front();
+            }
+            let (births, deaths, infections) = (self.births, self.deaths, self.infections);
+            self.samples.push_back(Sample {
+                ledger: self.ledger,
+                unbooked: self.unbooked,
+                ..Sample::take(biots, date, births, deaths, infections)
+            });
+            self.births = 0;
+            self.deaths = 0;
+            self.infections = 0;
+            self.ledger = EnergyLedger::default();
+            self.unbooked = Energy::ZERO;
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+impl PopulationStats {
+    /// Display the history of the statistics next to the phase plot.
+    ///
+    /// # Note
+    ///
+    /// Every series is scaled to its own maximum so that trends are visible
+    /// regardless of the magnitude of the values.
+    pub fn draw(&self) {
+        let (x, y, width, height) = (220., 10., 300., 250.);
+        draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.6));
+        draw_rectangle_lines(x, y, width, height, 1., LIGHTGRAY);
+        let years = self.samples.iter().map(|sample| sample.date.year);
+        draw_epoch_markers(years, MAX_SAMPLES, (x, y, width, height));
+        let latest = self.samples.back().copied().unwrap_or_default();
+        for series in 0..SERIES {
+            let (label, color) = match series {
+                0 => {
+                    let label = format!(
+                        "population: {} in {} species",
+                        latest.population, latest.species
+                    );
+                    (label, YELLOW)
+                }
+                1 => (format!("births: {}", latest.births), PINK),
+                2 => (format!("deaths: {}", latest.deaths), GRAY),
+                3 => {
+                    let label = format!("infected: {} (+{})", latest.infected, latest.infections);
+                    (label, LIME)
+                }
+                _ => {
+                    let gene = Gene::EXPRESSED[series - 4];
+                    let label = format!("{:?}: {:.2}", gene, latest.means[series - 4]);
+                    (label, gene_color(gene))
+                }
+            };
+            draw_text(&label, x + 5., y + 15. + 15. * series as f32, 16., color);
+            let max = self
+                .samples
+                .iter()
+                .map(|sample| sample.value(series))
+                .fold(f32::EPSILON, f32::max);
+            let to_screen = |n: usize, sample: &Sample| {
+                vec2(
+                    x + width * n as f32 / (MAX_SAMPLES - 1) as f32,
+                    y + height * (1. - sample.value(series) / max),
+                )
+            };
+            for (n, (from, to)) in self
+                .samples
+                .iter()
+                .zip(self.samples.iter().skip(1))
+                .enumerate()
+            {
+                let from = to_screen(n, from);
+                let to = to_screen(n + 1, to);
+                draw_line(from.x, from.y, to.x, to.y, 1., color);
+            }
+        }
+        draw_ledger(&latest);
+    }
+}
+
+/// Display the energy flows of the latest sample below the size distribution.
+///
+/// # Note
+///
+/// The flows are averaged per step. Unbooked energy other than rounding
+/// errors points to a bug in the energy model, see [`EnergyLedger`].
+#[cfg(feature = "render")]
+fn draw_ledger(sample: &Sample) {
+    let (x, y, width, height) = (530., 220., 200., 190.);
+    draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.6));
+    draw_rectangle_lines(x, y, width, height, 1., LIGHTGRAY);
+    let ledger = &sample.ledger;
+    let rows = [
+        ("photosynthesis", ledger.photosynthesis, GREEN),
+        ("food", ledger.food, GREEN),
+        ("scavenging", ledger.scavenging, GREEN),
+        ("metabolism", ledger.metabolism, ORANGE),
+        ("reproduction", ledger.reproduction, ORANGE),
+        ("predation", ledger.predation, ORANGE),
+        ("disease", ledger.disease, ORANGE),
+        ("removed", ledger.removed, GRAY),
+        ("balance", ledger.balance(), WHITE),
+        ("unbooked", sample.unbooked, RED),
+    ];
+    draw_text("energy per step", x + 5., y + 15., 16., LIGHTGRAY);
+    for (n, (label, energy, color)) in rows.into_iter().enumerate() {
+        let row_y = y + 32. + 15. * n as f32;
+        let value = energy.0 / SAMPLE_INTERVAL as f32;
+        draw_text(label, x + 5., row_y, 16., color);
+        draw_text(&format!("{value:.3}"), x + 120., row_y, 16., color);
+    }
+}
+
+/// Number of biots and their total life per size class.
+///
+/// # Note
+///
+/// The classes split the possible radii of the biots into [`SIZE_CLASSES`]
+/// equally wide ranges, smallest first.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SizeDistribution {
+    pub counts: [u32; SIZE_CLASSES],
+    pub life: [f32; SIZE_CLASSES],
+}
+
+impl SizeDistribution {
+    /// Takes the size distribution of the current population.
+    pub fn take(biots: &BiotCollection) -> Self {
+        let mut distribution = Self::default();
+        for biot in biots.iter() {
+            let class = (biot.radius().0 / MAX_RADIUS * SIZE_CLASSES as f32) as usize;
+            let class = class.min(SIZE_CLASSES - 1);
+            distribution.counts[class] += 1;
+            distribution.life[class] += biot.stats.life.0;
+        }
+        distribution
+    }
+}
+
+#[cfg(feature = "render")]
+impl SizeDistribution {
+    /// Display the distribution as a pyramid next to the statistics plots.
+    ///
+    /// # Note
+    ///
+    /// Each bar is centered, with the largest class on top. The bar widths
+    /// show the number of biots, the labels their total life.
+    pub fn draw(&self) {
+        let (x, y, width, height) = (530., 10., 200., 200.);
+        draw_rectangle(x, y, width, height, Color::new(0., 0., 0., 0.6));
+        draw_rectangle_lines(x, y, width, height, 1., LIGHTGRAY);
+        let max = self.counts.iter().copied().max().unwrap_or(0).max(1);
+        let bar_height = height / SIZE_CLASSES as f32;
+        for class in 0..SIZE_CLASSES {
+            let bar_y = y + height - bar_height * (class + 1) as f32;
+            let bar_width = (width - 10.) * self.counts[class] as f32 / max as f32;
+            let bar_x = x + (width - bar_width) / 2.;
+            draw_rectangle(bar_x, bar_y + 1., bar_width, bar_height - 2., DARKGREEN);
+            let label = format!("{} ({:.0})", self.counts[class], self.life[class]);
+            draw_text(&label, x + 5., bar_y + bar_height - 6., 16., LIGHTGRAY);
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+impl Sample {
+    /// Returns the value of the given plotted series.
+    fn value(&self, series: usize) -> f32 {
+        match series {
+            0 => self.population as f32,
+            1 => self.births as f32,
+            2 => self.deaths as f32,
+            3 => self.infected as f32,
+            _ => self.means[series - 4],
+        }
+    }
+}
+
+/// Draws a vertical line wherever a new year starts in a plot of `capacity` samples.
+///
+/// `years` yields the year of every plotted sample, oldest first.
+#[cfg(feature = "render")]
+pub fn draw_epoch_markers(
+    years: impl Iterator<Item = u64>,
+    capacity: usize,
+    (x, y, width, height): (f32, f32, f32, f32),
+) {
+    let mut previous = None;
+    for (n, year) in years.enumerate() {
+        if previous.is_some_and(|previous| previous != year) {
+            let marker_x = x + width * n as f32 / (capacity - 1) as f32;
+            draw_line(marker_x, y, marker_x, y + height, 1., DARKGRAY);
+            draw_text(
+                &format!("year {year}"),
+                marker_x + 2.,
+                y + height - 4.,
+                14.,
+                GRAY,
+            );
+        }
+        previous = Some(year);
+    }
+}
@@ -0,0 +1,206 @@
+//! Terrain of the world, dividing it into tiles of water, plains and rock.
+//!
+//! Every tile modifies the motion costs and photosynthesis of the biots on it
+//! and the chance for food to spawn there.
+
+use crate::config::TerrainConfig;
+use crate::rng::Rng;
+use crate::world::World;
+use glam::Vec2;
+#[cfg(feature = "render")]
+use macroquad::prelude::{draw_rectangle, Color};
+use serde::{Deserialize, Serialize};
+
+/// The kind of ground of a tile.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Tile {
+    /// Swimming is costly, but algae thrive in the sunlit water.
+    Water,
+    /// Neutral ground.
+    #[default]
+    Plains,
+    /// Barren ground where little grows.
+    Rock,
+}
+
+impl Tile {
+    /// Returns the factor applied to the metabolism costs of motion.
+    pub fn motion_cost(self) -> f32 {
+        match self {
+            Self::Water => 2.0,
+            Self::Plains => 1.0,
+            Self::Rock => 1.5,
+        }
+    }
+
+    /// Returns the factor applied to the photosynthesis.
+    pub fn photosynthesis(self) -> f32 {
+        match self {
+            Self::Water => 1.2,
+            Self::Plains => 1.0,
+            Self::Rock => 0.5,
+        }
+    }
+
+    /// Returns the chance for a food pellet to spawn on the tile.
+    pub fn food_spawn(self) -> f32 {
+        match self {
+            Self::Water => 0.5,
+            Self::Plains => 1.0,
+            Self::Rock => 0.2,
+        }
+    }
+
+    /// Returns the color the tile is tinted with, if any.
+    #[cfg(feature = "render")]
+    fn color(self) -> Option<Color> {
+        match self {
+            Self::Water => Some(Color::new(0.1, 0.25, 0.6, 0.35)),
+            Self::Plains => None,
+            Self::Rock => Some(Color::new(0.45, 0.42, 0.4, 0.35)),
+        }
+    }
+}
+
+/// The tiles covering the world.
+#[derive(Serialize, Deserialize)]
+pub struct Terrain {
+    tile_size: f32,
+    columns: usize,
+    rows: usize,
+    /// The tiles row by row.
+    tiles: Vec<Tile>,
+}
+
+impl Terrain {
+    /// Generates a random terrain for `world` as described by `config`.
+    ///
+    /// # Note
+    ///
+    /// The terrain is a smoothly interpolated value noise. The tiles with the
+    /// lowest values become water and the ones with the highest values rock,
+    /// so that they cover the configured fractions of the world.
+    pub fn generate(world: &World, config: &TerrainConfig, rng: &mut Rng) -> Self {
+        let tile_size = config.tile_size.max(1.0);
+        let columns = (world.width / tile_size).ceil().max(1.0) as usize;
+        let rows = (world.height / tile_size).ceil().max(1.0) as usize;
+        let feature_size = config.feature_size.max(tile_size);
+        let noise = ValueNoise::new(world, feature_size, rng);
+        let values = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .map(|(row, column)| {
+                let center = Vec2::new(column as f32 + 0.5, row as f32 + 0.5) * tile_size;
+                noise.sample(center)
+            })
+            .collect::<Vec<_>>();
+        let mut ranked = values.clone();
+        ranked.sort_by(f32::total_cmp);
+        let len = ranked.len();
+        let water_count = (config.water.clamp(0.0, 1.0) * len as f32) as usize;
+        let rock_count = (config.rock.clamp(0.0, 1.0) * len as f32) as usize;
+        let water_below = ranked.get(water_count).copied().unwrap_or(f32::INFINITY);
+        let rock_above = match rock_count {
+            0 => f32::INFINITY,
+            count => ranked[len.saturating_sub(count)],
+        };
+        let tiles = values
+            .into_iter()
+            .map(|value| {
+                if value < water_below {
+                    Tile::Water
+                } else if value >= rock_above {
+                    Tile::Rock
+                } else {
+                    Tile::Plains
+                }
+            })
+            .collect();
+        Self {
+            tile_size,
+            columns,
+            rows,
+            tiles,
+        }
+    }
+
+    /// Returns the terrain covering the resized `world`, each tile copied
+    /// from the one at the position `source` maps its center to.
+    ///
+    /// Beyond the edges of the old world its edge tiles continue.
+    pub fn resized(&self, world: &World, source: impl Fn(Vec2) -> Vec2) -> Self {
+        let columns = (world.width / self.tile_size).ceil().max(1.0) as usize;
+        let rows = (world.height / self.tile_size).ceil().max(1.0) as usize;
+        let tiles = (0..rows)
+            .flat_map(|row| (0..columns).map(move |column| (row, column)))
+            .map(|(row, column)| {
+                let center = Vec2::new(column as f32 + 0.5, row as f32 + 0.5) * self.tile_size;
+                self.tile(source(center))
+            })
+            .collect();
+        Self {
+            tile_size: self.tile_size,
+            columns,
+            rows,
+            tiles,
+        }
+    }
+
+    /// Returns the tile at `pos`.
+    pub fn tile(&self, pos: Vec2) -> Tile {
+        // Casts saturate, so negative and NaN coordinates end up in the first tile.
+        let column = ((pos.x / self.tile_size) as usize).min(self.columns - 1);
+        let row = ((pos.y / self.tile_size) as usize).min(self.rows - 1);
+        self.tiles[row * self.columns + column]
+    }
+}
+
+#[cfg(feature = "render")]
+impl Terrain {
+    /// Display the tiles, repeated at the given offsets for a wrapped world.
+    pub fn draw(&self, offsets: &[Vec2]) {
+        for offset in offsets {
+            for (idx, tile) in self.tiles.iter().enumerate() {
+                if let Some(color) = tile.color() {
+                    let column = (idx % self.columns) as f32;
+                    let row = (idx / self.columns) as f32;
+                    let pos = Vec2::new(column, row) * self.tile_size + *offset;
+                    draw_rectangle(pos.x, pos.y, self.tile_size, self.tile_size, color);
+                }
+            }
+        }
+    }
+}
+
+/// Random values on a lattice, smoothly interpolated in between.
+pub struct ValueNoise {
+    spacing: f32,
+    columns: usize,
+    values: Vec<f32>,
+}
+
+impl ValueNoise {
+    /// Creates noise covering `world` with features of roughly `spacing` in size.
+    pub fn new(world: &World, spacing: f32, rng: &mut Rng) -> Self {
+        // One extra lattice point in each direction covers the far edges.
+        let columns = (world.width / spacing).ceil() as usize + 2;
+        let rows = (world.height / spacing).ceil() as usize + 2;
+        let values = (0..columns * rows).map(|_| rng.gen_range(0., 1.)).collect();
+        Self {
+            spacing,
+            columns,
+            values,
+        }
+    }
+
+    /// Returns the noise value in `0..1` at `pos`.
+    pub fn sample(&self, pos: Vec2) -> f32 {
+        let lattice = pos / self.spacing;
+        let (column, row) = (lattice.x as usize, lattice.y as usize);
+        let value = |column: usize, row: usize| self.values[row * self.columns + column];
+        let smooth = |t: f32| t * t * (3.0 - 2.0 * t);
+        let (tx, ty) = (smooth(lattice.x.fract()), smooth(lattice.y.fract()));
+        let top = value(column, row) * (1.0 - tx) + value(column + 1, row) * tx;
+        let bottom = value(column, row + 1) * (1.0 - tx) + value(column + 1, row + 1) * tx;
+        top * (1.0 - ty) + bottom * ty
+    }
+}
@@ -0,0 +1,163 @@
+//! Tools for intervening in a running simulation with the mouse.
+//!
+//! Besides inspecting biots, users can spawn random biots or clones of the
+//! selected biot at the cursor and kill or feed the biots below a brush.
+
+use crate::biot::{Biot, BiotId, Genome};
+#[cfg(feature = "render")]
+use crate::camera::Camera;
+use crate::simulation::Simulation;
+use crate::units::Distance;
+use glam::Vec2;
+#[cfg(feature = "render")]
+use macroquad::prelude::{
+    draw_circle_lines, is_key_down, is_key_pressed, is_mouse_button_down, is_mouse_button_pressed,
+    KeyCode, MouseButton, GREEN, RED,
+};
+
+/// Radius of the kill and feed brushes.
+pub const BRUSH_RADIUS: Distance = Distance(30.0);
+
+/// Fraction of their base life the feed brush adds to the biots below it per frame.
+pub const FEED_FRACTION: f32 = 0.02;
+
+/// What clicking into the world does.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Tool {
+    /// Select biots to inspect them.
+    #[default]
+    Inspect,
+    /// Spawn a random biot, or a clone of the selected biot while shift is held.
+    Spawn,
+    /// Kill the biots below the brush while the button is held.
+    Kill,
+    /// Feed the biots below the brush while the button is held.
+    Feed,
+}
+
+impl Tool {
+    /// Returns the name of the tool shown in the status line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Inspect => "inspect",
+            Self::Spawn => "spawn",
+            Self::Kill => "kill",
+            Self::Feed => "feed",
+        }
+    }
+}
+
+/// Spawns a random biot at `pos` as the founder of a new lineage.
+pub fn spawn_random(simulation: &mut Simulation, pos: Vec2) -> BiotId {
+    let world = *simulation.world();
+    let config = simulation.biots().config().clone();
+    let mut biot = Biot::random_biot(&world, &config, simulation.rng_mut());
+    biot.stats.pos = world.wrap(pos);
+    simulation.biots_mut().spawn(biot)
+}
+
+/// Spawns a biot with a copy of `genome` at `pos` as the founder of a new lineage.
+///
+/// # Note
+///
+/// Only the genome is copied, the clone starts out young with its base life.
+pub fn spawn_clone(simulation: &mut Simulation, genome: &Genome, pos: Vec2) -> BiotId {
+    let world = *simulation.world();
+    let mut biot = Biot::from_genome(genome.clone(), &world, simulation.rng_mut());
+    biot.stats.pos = world.wrap(pos);
+    biot.stats.life = biot.base_life(simulation.biots().config());
+    simulation.biots_mut().spawn(biot)
+}
+
+/// Kills the biots within the brush around `pos` and returns their number.
+pub fn kill(simulation: &mut Simulation, pos: Vec2) -> usize {
+    let world = *simulation.world();
+    simulation
+        .biots_mut()
+        .kill_within(&world, pos, BRUSH_RADIUS)
+}
+
+/// Feeds the biots within the brush around `pos` for a frame and returns their number.
+pub fn feed(simulation: &mut Simulation, pos: Vec2) -> usize {
+    let world = *simulation.world();
+    simulation
+        .biots_mut()
+        .feed_within(&world, pos, BRUSH_RADIUS, FEED_FRACTION)
+}
+
+/// The selected tool and its input handling in the viewer.
+#[cfg(feature = "render")]
+#[derive(Debug, Clone, Default)]
+pub struct Tools {
+    tool: Tool,
+}
+
+#[cfg(feature = "render")]
+impl Tools {
+    /// Creates the tools with the inspect tool selected.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the selected tool.
+    pub fn tool(&self) -> Tool {
+        self.tool
+    }
+
+    /// Selects a tool by its hotkey: `I` inspects, `B` spawns, `X` kills and `E` feeds.
+    pub fn handle_keys(&mut self) {
+        if is_key_pressed(KeyCode::I) {
+            self.tool = Tool::Inspect;
+        } else if is_key_pressed(KeyCode::B) {
+            self.tool = Tool::Spawn;
+        } else if is_key_pressed(KeyCode::X) {
+            self.tool = Tool::Kill;
+        } else if is_key_pressed(KeyCode::E) {
+            self.tool = Tool::Feed;
+        }
+    }
+
+    /// Applies the selected tool at the mouse position according to the mouse input of this frame.
+    ///
+    /// Shift-clicking with the spawn tool clones the `selected` biot, if any.
+    pub fn apply(&self, simulation: &mut Simulation, camera: &Camera, selected: Option<BiotId>) {
+        let pos = camera.mouse_world_position(simulation.world());
+        match self.tool {
+            Tool::Inspect => {}
+            Tool::Spawn if is_mouse_button_pressed(MouseButton::Left) => {
+                let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+                let genome = selected
+                    .filter(|_| shift)
+                    .and_then(|id| simulation.biots().get(id))
+                    .map(|biot| biot.genome().clone());
+                match genome {
+                    Some(genome) => spawn_clone(simulation, &genome, pos),
+                    None => spawn_random(simulation, pos),
+                };
+            }
+            Tool::Spawn => {}
+            Tool::Kill if is_mouse_button_down(MouseButton::Left) => {
+                kill(simulation, pos);
+            }
+            Tool::Feed if is_mouse_button_down(MouseButton::Left) => {
+                feed(simulation, pos);
+            }
+            Tool::Kill | Tool::Feed => {}
+        }
+    }
+
+    /// Outlines the brush around the mouse position if a brush is selected.
+    ///
+    /// # Note
+    ///
+    /// Must be called while `camera` is active.
+    pub fn draw_brush(&self, simulation: &Simulation, camera: &Camera) {
+        let color = match self.tool {
+            Tool::Kill => RED,
+            Tool::Feed => GREEN,
+            Tool::Inspect | Tool::Spawn => return,
+        };
+        let pos = camera.mouse_world_position(simulation.world());
+        draw_circle_lines(pos.x, pos.y, BRUSH_RADIUS.0, 1.5, color);
+    }
+}
@@ -0,0 +1,102 @@
+//! Fading trails of the recent positions of the biots and arrows of their velocities.
+//!
+//! Trails make the pursuits of hunters and the escapes of their prey visible,
+//! which a single frame cannot show.
+
+use crate::biot::BiotId;
+use crate::biot_collection::BiotCollection;
+use crate::camera::Camera;
+use crate::interpolation::Interpolation;
+use crate::world::World;
+use glam::Vec2;
+use macroquad::prelude::{draw_line, Color, WHITE, YELLOW};
+use std::collections::{HashMap, VecDeque};
+
+/// Number of past positions kept per biot.
+const TRAIL_LEN: usize = 40;
+
+/// Number of steps the velocity arrows look ahead.
+const ARROW_STEPS: f32 = 10.0;
+
+/// Recent positions of every living biot, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct Trails {
+    history: HashMap<BiotId, VecDeque<Vec2>>,
+}
+
+impl Trails {
+    /// Creates empty trails.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the current positions of the biots to their trails after a step.
+    ///
+    /// Trails of biots that died are dropped, only the latest
+    /// [`TRAIL_LEN`] positions of every biot are kept.
+    pub fn record(&mut self, biots: &BiotCollection) {
+        self.history.retain(|id, _| biots.get(*id).is_some());
+        for biot in biots.iter() {
+            let trail = self.history.entry(biot.id()).or_default();
+            if trail.len() == TRAIL_LEN {
+                trail.pop_front();
+            }
+            trail.push_back(biot.stats.pos);
+        }
+    }
+
+    /// Forgets all trails, e.g. after the simulation was replaced.
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    /// Draws the trail and the velocity arrow of every biot.
+    ///
+    /// # Note
+    ///
+    /// The trails fade towards their oldest positions and end at the
+    /// interpolated positions the biots are drawn at. Segments crossing the
+    /// edges of a torus are left out.
+    pub fn draw(
+        &self,
+        world: &World,
+        camera: &Camera,
+        biots: &BiotCollection,
+        interpolation: &Interpolation,
+    ) {
+        let offsets = camera.wrap_offsets(world);
+        for biot in biots.iter() {
+            let pos = interpolation.position(world, biot);
+            let trail = self.history.get(&biot.id());
+            let points = trail.into_iter().flatten().copied().chain([pos]);
+            let segments = points.clone().zip(points.skip(1));
+            let len = trail.map_or(0, VecDeque::len);
+            for offset in &offsets {
+                for (idx, (from, to)) in segments.clone().enumerate() {
+                    if (to - from).length_squared() > world.delta(from, to).length_squared() + 1.0 {
+                        continue;
+                    }
+                    let alpha = 0.6 * (idx + 1) as f32 / len.max(1) as f32;
+                    let (from, to) = (from + *offset, to + *offset);
+                    draw_line(from.x, from.y, to.x, to.y, 1.0, Color { a: alpha, ..WHITE });
+                }
+                let velocity = biot.stats.speed * ARROW_STEPS;
+                if velocity.length_squared() > 1.0 {
+                    Self::draw_arrow(pos + *offset, velocity);
+                }
+            }
+        }
+    }
+
+    /// Draws an arrow from `start` along `vector`.
+    fn draw_arrow(start: Vec2, vector: Vec2) {
+        let end = start + vector;
+        let dir = vector.normalize();
+        let head = 4.0_f32.min(vector.length() / 2.0);
+        let (back, side) = (end - dir * head, dir.perp() * head / 2.0);
+        draw_line(start.x, start.y, end.x, end.y, 1.0, YELLOW);
+        for barb in [back + side, back - side] {
+            draw_line(end.x, end.y, barb.x, barb.y, 1.0, YELLOW);
+        }
+    }
+}
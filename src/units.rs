@@ -0,0 +1,134 @@
+//! Newtypes for the physical units of the simulation.
+//!
+//! They make unit mix-ups, like comparing a squared distance against a
+//! linear threshold, a compile time error.
+
+use core::ops;
+use glam::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// An amount of energy, e.g. the life of a biot.
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Energy(pub f32);
+
+impl Energy {
+    /// No energy at all.
+    pub const ZERO: Self = Self(0.0);
+}
+
+impl ops::Add for Energy {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl ops::AddAssign for Energy {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl ops::Sub for Energy {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl ops::SubAssign for Energy {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl ops::Mul<f32> for Energy {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self(self.0 * rhs)
+    }
+}
+
+impl ops::Div for Energy {
+    type Output = f32;
+
+    fn div(self, rhs: Self) -> f32 {
+        self.0 / rhs.0
+    }
+}
+
+/// A linear distance in world units.
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct Distance(pub f32);
+
+impl Distance {
+    /// Returns the squared distance, for comparisons with [`SquaredDistance`].
+    pub fn squared(self) -> SquaredDistance {
+        SquaredDistance(self.0 * self.0)
+    }
+}
+
+impl ops::Add for Distance {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl ops::Mul<f32> for Distance {
+    type Output = Self;
+
+    fn mul(self, rhs: f32) -> Self {
+        Self(self.0 * rhs)
+    }
+}
+
+/// A squared distance in squared world units.
+///
+/// Spatial queries work with squared distances to avoid square roots.
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct SquaredDistance(pub f32);
+
+impl SquaredDistance {
+    /// Returns the squared distance between two positions.
+    pub fn between(a: Vec2, b: Vec2) -> Self {
+        Self((a - b).length_squared())
+    }
+
+    /// Returns the linear distance.
+    pub fn sqrt(self) -> Distance {
+        Distance(self.0.sqrt())
+    }
+}
+
+/// A duration in simulation steps.
+#[derive(
+    Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct Ticks(pub u32);
+
+impl ops::Add for Ticks {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl ops::AddAssign for Ticks {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl ops::Div for Ticks {
+    type Output = f32;
+
+    fn div(self, rhs: Self) -> f32 {
+        self.0 as f32 / rhs.0 as f32
+    }
+}
@@ -0,0 +1,188 @@
+//! Uploads of the files written by headless runs.
+//!
+//! Fleets of headless simulations on remote machines are easier to follow
+//! if their autosaves and statistics end up in one place. Uploads run on a
+//! background thread, so slow destinations do not hold up the simulation.
+//! They upload a copy of every file taken when it was queued, so runs can
+//! rewrite their files, e.g. the next autosave, while an upload is running.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::thread::{self, JoinHandle};
+
+/// Where written files are uploaded to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Destination {
+    /// A local directory, e.g. a mounted network share, files are copied into.
+    Directory(PathBuf),
+    /// A prefix within an S3-compatible bucket, e.g. `s3://bucket/runs`.
+    ///
+    /// Files are uploaded with the `aws` command line tool, which picks up
+    /// the credentials and the endpoint, e.g. `AWS_ENDPOINT_URL`, from its
+    /// usual configuration.
+    S3(String),
+    /// A shell command run for every file, with the path of the file as `$1`.
+    Command(String),
+}
+
+impl From<&str> for Destination {
+    /// Parses `s3://<bucket>/<prefix>` as an S3 prefix, `cmd:<command>` as a
+    /// command and everything else as the path of a directory.
+    fn from(destination: &str) -> Self {
+        if destination.starts_with("s3://") {
+            Self::S3(destination.trim_end_matches('/').to_string())
+        } else if let Some(command) = destination.strip_prefix("cmd:") {
+            Self::Command(command.to_string())
+        } else {
+            Self::Directory(PathBuf::from(destination))
+        }
+    }
+}
+
+impl fmt::Display for Destination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Directory(dir) => write!(f, "{}", dir.display()),
+            Self::S3(prefix) => write!(f, "{prefix}"),
+            Self::Command(command) => write!(f, "cmd:{command}"),
+        }
+    }
+}
+
+impl Destination {
+    /// Uploads the file at `path`, blocking until it is done.
+    pub fn upload(&self, path: &Path) -> io::Result<()> {
+        let name = path
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a file"))?;
+        let status = match self {
+            Self::Directory(dir) => {
+                fs::create_dir_all(dir)?;
+                fs::copy(path, dir.join(name))?;
+                return Ok(());
+            }
+            Self::S3(prefix) => Command::new("aws")
+                .args(["s3", "cp", "--quiet"])
+                .arg(path)
+                .arg(format!("{prefix}/{}", name.to_string_lossy()))
+                .status()?,
+            Self::Command(command) => Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .arg("sh")
+                .arg(path)
+                .status()?,
+        };
+        if !status.success() {
+            return Err(io::Error::other(format!(
+                "upload command failed with {status}"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Number of uploaders created by this process, keeping their staging
+/// directories apart.
+static UPLOADERS: AtomicU32 = AtomicU32::new(0);
+
+/// A file queued for upload.
+struct Staged {
+    /// Path of the written file, as reported to the user.
+    path: PathBuf,
+    /// Path of the copy taken when the file was queued, with the same name.
+    copy: PathBuf,
+}
+
+/// Background thread uploading files to a destination in the order they were written.
+///
+/// # Note
+///
+/// Failed uploads are reported and skipped. Dropping the uploader waits for
+/// the pending uploads to finish. Every file is copied to a staging
+/// directory when it is queued, so the upload is not affected by rewriting
+/// the file before it finished.
+pub struct Uploader {
+    files: Option<Sender<Staged>>,
+    worker: Option<JoinHandle<()>>,
+    /// Directory of the copies of the queued files.
+    staging: PathBuf,
+    /// Number of files queued so far.
+    queued: u32,
+}
+
+impl Uploader {
+    /// Starts the background thread uploading to `destination`.
+    pub fn new(destination: Destination) -> Self {
+        let (files, received) = mpsc::channel::<Staged>();
+        let worker = thread::spawn(move || {
+            for Staged { path, copy } in received {
+                match destination.upload(&copy) {
+                    Ok(()) => println!("uploaded {} to {destination}", path.display()),
+                    Err(error) => eprintln!(
+                        "failed to upload {} to {destination}: {error}",
+                        path.display()
+                    ),
+                }
+                if let Some(dir) = copy.parent() {
+                    let _ = fs::remove_dir_all(dir);
+                }
+            }
+        });
+        let uploader = UPLOADERS.fetch_add(1, Ordering::Relaxed);
+        let staging =
+            std::env::temp_dir().join(format!("life_web_staging_{}_{uploader}", process::id()));
+        Self {
+            files: Some(files),
+            worker: Some(worker),
+            staging,
+            queued: 0,
+        }
+    }
+
+    /// Queues the file at `path` for upload after it was written.
+    ///
+    /// Files that cannot be copied to the staging directory are reported
+    /// and skipped.
+    pub fn upload(&mut self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let Some(files) = &self.files else {
+            return;
+        };
+        let Some(name) = path.file_name() else {
+            eprintln!("failed to upload {}: not a file", path.display());
+            return;
+        };
+        // Each copy gets a directory of its own, so it keeps the name of the file.
+        let dir = self.staging.join(self.queued.to_string());
+        self.queued += 1;
+        let copy = dir.join(name);
+        if let Err(error) = fs::create_dir_all(&dir).and_then(|()| fs::copy(path, &copy)) {
+            eprintln!("failed to stage {} for upload: {error}", path.display());
+            return;
+        }
+        // The worker only stops once the uploader is dropped.
+        let _ = files.send(Staged {
+            path: path.to_path_buf(),
+            copy,
+        });
+    }
+}
+
+impl Drop for Uploader {
+    fn drop(&mut self) {
+        // Closing the channel lets the worker finish the pending uploads and stop.
+        self.files = None;
+        if let Some(worker) = self.worker.take() {
+            if worker.join().is_err() {
+                eprintln!("the upload thread panicked");
+            }
+        }
+        let _ = fs::remove_dir_all(&self.staging);
+    }
+}
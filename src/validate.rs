@@ -0,0 +1,2868 @@
+//! Runtime self-check of the invariants of the simulation.
+//!
+//! Runs a battery of small seeded simulations and hand-made scenarios and
+//! reports every invariant that does not hold.
+
+use crate::archipelago::{Archipelago, Migration};
+use crate::behavior::{Behavior, Expression, GenePlugin, Situation};
+use crate::biot::{
+    interaction_radius, Biot, BiotId, CauseOfDeath, Drive, Gene, GeneDistribution, GenomeBuilder,
+    Locus, GENOME_LEN, MAX_EXPRESSION, MAX_GENOME_LEN, MAX_INTERACTION_RADIUS, MAX_MUTATION_CHANCE,
+};
+use crate::biot_collection::BiotCollection;
+use crate::clock::Clock;
+use crate::config::{
+    AgingConfig, BrainConfig, CollisionConfig, ControllerConfig, CurrentsConfig, DiseaseConfig,
+    DominanceConfig, DriveConfig, FlockingConfig, GrowthConfig, KinshipConfig, LitterConfig,
+    MutationConfig, NonviablePolicy, NoveltyConfig, PredationConfig, PursuitConfig, Reproduction,
+    ScentConfig, SimConfig, TemperatureConfig, WatchdogAction, WatchdogConfig,
+};
+use crate::current::Flow;
+use crate::environment::Environment;
+use crate::events::{Event, EventLog, EVENT_CAPACITY};
+use crate::experiment::{Experiment, Sweep};
+use crate::fixture;
+use crate::layer::{Layer, LayerMap};
+use crate::library;
+use crate::lineages::LineageStats;
+use crate::obstacle::Obstacle;
+use crate::phase::{Phase, StepHooks};
+use crate::phylogeny::Phylogeny;
+use crate::preset::Preset;
+use crate::reserve::Reserve;
+use crate::rewind::Timeline;
+use crate::rng::{Rng, RngStreams};
+use crate::scenario::{Scenario, Start};
+#[cfg(feature = "scripting")]
+use crate::script::Script;
+#[cfg(feature = "server")]
+use crate::server::Observer;
+use crate::simulation::{Resize, Simulation};
+use crate::spawn::{Area, Occupants, SpawnRegion};
+use crate::tools;
+use crate::units::{Distance, Energy, Ticks};
+use crate::upload::{Destination, Uploader};
+use crate::watchdog::{Anomaly, Watchdog};
+use crate::world::{Topology, World};
+use glam::{vec2, Vec2};
+use std::collections::{HashSet, VecDeque};
+use std::fmt;
+#[cfg(feature = "server")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "server")]
+use std::net::TcpStream;
+#[cfg(feature = "server")]
+use std::thread;
+use std::time::Duration;
+#[cfg(feature = "server")]
+use std::time::Instant;
+
+/// Seeds of the micro-simulations.
+const SEEDS: [u64; 8] = [0, 1, 2, 3, 5, 8, 13, 21];
+
+/// Number of steps computed per micro-simulation.
+const STEPS: u32 = 2000;
+
+/// Initial population of a micro-simulation.
+const POPULATION: usize = 100;
+
+/// Population above which a micro-simulation is considered to have exploded.
+const MAX_POPULATION: usize = 20 * POPULATION;
+
+/// Size of the world of a micro-simulation.
+const WORLD_SIZE: f32 = 300.0;
+
+/// Relative tolerance for rounding errors when comparing total energies.
+const ENERGY_TOLERANCE: f32 = 1e-3;
+
+/// An invariant that did not hold.
+#[derive(Debug)]
+pub struct Violation {
+    /// Describes where the violation happened.
+    pub context: String,
+    /// Describes the violated invariant.
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.message)
+    }
+}
+
+/// Registers checks without parameters under their names.
+///
+/// Each check also becomes a unit test of the same name, so that `cargo test`
+/// runs them, see [`run`] for running them all at once.
+macro_rules! checks {
+    ($($(#[$attribute:meta])* $name:literal => $check:ident,)*) => {
+        /// Checks without parameters with their names, in the order they run.
+        const CHECKS: &[(&str, fn() -> Result<(), String>)] = &[
+            $($(#[$attribute])* ($name, $check),)*
+        ];
+
+        #[cfg(test)]
+        mod checks {
+            $(
+                $(#[$attribute])*
+                #[test]
+                fn $check() {
+                    if let Err(message) = super::$check() {
+                        panic!("{}: {message}", $name);
+                    }
+                }
+            )*
+        }
+    };
+}
+
+checks! {
+    "predation scenario" => check_predation,
+    "tile borders scenario" => check_tile_borders,
+    "obstacle scenario" => check_obstacles,
+    "dominance scenario" => check_dominance,
+    "controller scenario" => check_controller,
+    "mating scenario" => check_mating,
+    "litter scenario" => check_litters,
+    "venom scenario" => check_venom,
+    "wound scenario" => check_wounds,
+    "disease scenario" => check_disease,
+    "world resizing" => check_resize,
+    "aging" => check_aging,
+    "growth" => check_growth,
+    "pursuit" => check_pursuit,
+    "novelty injection" => check_novelty,
+    "event log" => check_events,
+    "lineage statistics" => check_lineages,
+    "reserves" => check_reserves,
+    "rewinding" => check_rewind,
+    "intervention tools" => check_tools,
+    "mature ecosystem cache" => check_fixture,
+    "genome library" => check_library,
+    "biot cards" => check_card_data,
+    "uploads" => check_upload,
+    "experiments" => check_experiment,
+    "fixed timestep" => check_clock,
+    "watchdog" => check_watchdog,
+    "sanitized biots" => check_sanitized,
+    "step hooks" => check_step_hooks,
+    "live tuning" => check_reconfigure,
+    "stable identifiers" => check_stable_ids,
+    "random streams" => check_rng_streams,
+    "fleeing scenario" => check_fleeing,
+    "drives scenario" => check_drives,
+    "flocking scenario" => check_flocking,
+    "behavior scenario" => check_behaviors,
+    "gene plugins" => check_gene_plugins,
+    #[cfg(feature = "scripting")]
+    "scripting" => check_script,
+    #[cfg(feature = "server")]
+    "observation server" => check_server,
+    "currents scenario" => check_currents,
+    "islands" => check_islands,
+    "temperature scenario" => check_temperature,
+    "kinship scenario" => check_kinship,
+    "scent scenario" => check_scent,
+    "collision scenario" => check_collisions,
+    "giant biots scenario" => check_giants,
+    "spawn regions" => check_spawn_regions,
+    "visualization layers" => check_layers,
+    "initial genes" => check_initial_genes,
+    "custom genes" => check_custom_genes,
+    "response curves" => check_responses,
+    "neighbours across edges" => check_wrapped_neighbours,
+}
+
+/// Runs all checks and prints a report.
+///
+/// Returns `true` if all invariants held.
+pub fn run() -> bool {
+    let mut results = SEEDS
+        .iter()
+        .map(|&seed| {
+            let context = format!("micro-simulation with seed {seed}");
+            (context, check_micro_simulation(seed))
+        })
+        .collect::<Vec<_>>();
+    let checks = CHECKS
+        .iter()
+        .map(|(name, check)| (name.to_string(), check()));
+    results.extend(checks);
+    results.extend(check_variants());
+    let mut violations = Vec::new();
+    for (context, result) in results {
+        report(&context, &result);
+        if let Err(message) = result {
+            violations.push(Violation { context, message });
+        }
+    }
+    if violations.is_empty() {
+        println!("all invariants held");
+    } else {
+        println!("{} checks failed:", violations.len());
+        for violation in &violations {
+            println!("  {violation}");
+        }
+    }
+    violations.is_empty()
+}
+
+/// Runs the checks of every topology, nonviable policy, preset and
+/// built-in scenario, with their names.
+fn check_variants() -> Vec<(String, Result<(), String>)> {
+    let mut results = Vec::new();
+    for topology in [Topology::Torus, Topology::Walls, Topology::Bouncing] {
+        let context = format!("{topology:?} topology scenario");
+        results.push((context, check_topology(topology)));
+    }
+    for policy in [
+        NonviablePolicy::DieAtBirth,
+        NonviablePolicy::BasalWeight(0.5),
+    ] {
+        let context = format!("degenerate genomes with {policy:?} policy");
+        let config = SimConfig {
+            nonviable: policy,
+            ..SimConfig::default()
+        };
+        results.push((context, check_degenerate_genomes(config)));
+    }
+    for preset in Preset::ALL {
+        let context = format!("{} preset", preset.name());
+        let result = preset.config().map(drop).map_err(|error| error.to_string());
+        results.push((context, result));
+    }
+    for start in Start::ALL {
+        let context = format!("{} scenario", start.name());
+        results.push((context, check_start(start)));
+    }
+    results
+}
+
+/// Prints the outcome of a single check.
+fn report(context: &str, result: &Result<(), String>) {
+    match result {
+        Ok(()) => println!("{context}: ok"),
+        Err(_) => println!("{context}: FAILED"),
+    }
+}
+
+/// Runs a small random simulation and checks the invariants after every step.
+///
+/// The mutation chance evolves in the simulations with odd seeds, brains
+/// steer the intelligent biots in every third one.
+fn check_micro_simulation(seed: u64) -> Result<(), String> {
+    let config = SimConfig {
+        initial_population: POPULATION,
+        mutations: MutationConfig {
+            rate_drift: if seed % 2 == 1 { 0.05 } else { 0.0 },
+            ..MutationConfig::default()
+        },
+        brains: BrainConfig {
+            enabled: seed % 3 == 2,
+            ..BrainConfig::default()
+        },
+        ..SimConfig::default()
+    };
+    let mut simulation = Simulation::new(World::new(WORLD_SIZE, WORLD_SIZE), config, seed);
+    for step in 0..STEPS {
+        let biots = simulation.biots();
+        let life_before = total_life(biots.iter());
+        let last_id = biots.iter().map(Biot::id).max();
+        simulation.step();
+        let at_step = |message: String| format!("step {step}: {message}");
+        check_state(&simulation).map_err(at_step)?;
+        // The income depends on the light, food and corpses found during the step.
+        let income = simulation.biots().energy_ledger().income();
+        check_energy(simulation.biots(), life_before, income, last_id).map_err(at_step)?;
+        simulation.biots().check_energy_books().map_err(at_step)?;
+    }
+    Ok(())
+}
+
+/// Checks that all biots are in a valid state and can be found by their identifier,
+/// and that no biot was bitten after it was killed in the last step.
+fn check_state(simulation: &Simulation) -> Result<(), String> {
+    let world = simulation.world();
+    let biots = simulation.biots();
+    if biots.len() > MAX_POPULATION {
+        return Err(format!(
+            "population of {} exceeds {MAX_POPULATION}",
+            biots.len()
+        ));
+    }
+    if biots.phylogeny().len() < biots.len() {
+        return Err(format!(
+            "phylogeny of {} biots misses living ones",
+            biots.phylogeny().len()
+        ));
+    }
+    biots.check_index(world)?;
+    let mut killed = HashSet::new();
+    for fight in biots.fights() {
+        if killed.contains(&fight.prey) {
+            return Err(format!(
+                "biot {} was bitten after it was killed",
+                fight.prey.0
+            ));
+        }
+        if fight.killed {
+            killed.insert(fight.prey);
+        }
+    }
+    let mut last_id = None;
+    for biot in biots.iter() {
+        let id = biot.id().0;
+        let pos = biot.stats.pos;
+        if !pos.is_finite() || !biot.stats.speed.is_finite() || !biot.stats.life.0.is_finite() {
+            return Err(format!(
+                "biot {id} has a non-finite state: {:?}",
+                biot.stats
+            ));
+        }
+        if !(0.0..=world.width).contains(&pos.x) || !(0.0..=world.height).contains(&pos.y) {
+            return Err(format!("biot {id} is outside of the world at {pos}"));
+        }
+        if last_id >= Some(biot.id()) {
+            return Err(format!("biot {id} is out of order"));
+        }
+        last_id = Some(biot.id());
+        if !(1..=MAX_GENOME_LEN).contains(&biot.genome().len()) {
+            return Err(format!(
+                "biot {id} has a genome of {} genes",
+                biot.genome().len()
+            ));
+        }
+        if let Some(chance) = biot.genome().mutation_chance() {
+            if !(0.0..=MAX_MUTATION_CHANCE).contains(&chance) {
+                return Err(format!("biot {id} has a mutation chance of {chance}"));
+            }
+        }
+        let expressed = |locus: &&Locus| (0.0..=MAX_EXPRESSION).contains(&locus.expression);
+        if let Some(locus) = biot.genome().loci().find(|locus| !expressed(locus)) {
+            return Err(format!(
+                "biot {id} expresses a {:?} gene by {}",
+                locus.gene, locus.expression
+            ));
+        }
+        // The first step clusters all biots and newborns inherit the species of their parent.
+        if biot.species().is_none() {
+            return Err(format!("biot {id} does not belong to a species"));
+        }
+        let brain = biot.genome().brain();
+        if brain.is_some_and(|brain| !brain.weights().iter().all(|w| w.is_finite())) {
+            return Err(format!("biot {id} has a brain with non-finite weights"));
+        }
+        let founder = biot.id() == biot.lineage();
+        if !founder && biots.phylogeny().parent(biot.id()).is_none() {
+            return Err(format!("biot {id} is missing from the phylogeny"));
+        }
+        if biots.get(biot.id()).map(Biot::id) != Some(biot.id()) {
+            return Err(format!("biot {id} cannot be found by its identifier"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the biots did not gain more life than photosynthesis, food and corpses provide.
+///
+/// # Note
+///
+/// Predation, reproduction and metabolism only ever lose energy, except for
+/// the life newborns start with, which their parents pay for.
+fn check_energy(
+    biots: &BiotCollection,
+    life_before: Energy,
+    income: Energy,
+    last_id: Option<BiotId>,
+) -> Result<(), String> {
+    let is_newborn = |biot: &&Biot| Some(biot.id()) > last_id;
+    let life_after = total_life(biots.iter().filter(|biot| !is_newborn(biot)));
+    let limit = life_before + income;
+    let tolerance = ENERGY_TOLERANCE * (limit.0.abs() + 1.0);
+    if life_after.0 > limit.0 + tolerance {
+        return Err(format!(
+            "total life grew from {:.3} to {:.3} with an income of only {:.3}",
+            life_before.0, life_after.0, income.0
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the total life of the given biots.
+fn total_life<'a>(biots: impl Iterator<Item = &'a Biot>) -> Energy {
+    biots.fold(Energy::ZERO, |total, biot| total + biot.stats.life)
+}
+
+/// Checks that a predator eats a plant it touches, gains energy from it and
+/// that the fight is reported.
+fn check_predation() -> Result<(), String> {
+    let mut scenario = Scenario::new();
+    let predator = scenario.place(Biot::predator, 10.0, 10.0);
+    let plant = scenario.place(Biot::plant, 15.0, 10.0);
+    let life = scenario.life(predator);
+    scenario.run(1);
+    if !scenario.is_dead(plant) {
+        return Err(String::from("the plant survived"));
+    }
+    let fights = scenario.simulation().biots().fights();
+    if !fights
+        .iter()
+        .any(|fight| fight.predator == predator && fight.prey == plant)
+    {
+        return Err(format!("the fight is missing from {fights:?}"));
+    }
+    if scenario.life(predator) <= life {
+        return Err(String::from("the predator gained no energy"));
+    }
+    Ok(())
+}
+
+/// Checks that predators catch prey across the borders of the tiles whose
+/// interactions are computed independently, including the edge of the torus.
+fn check_tile_borders() -> Result<(), String> {
+    let mut scenario = Scenario::new();
+    let world = *scenario.simulation().world();
+    let cell = MAX_INTERACTION_RADIUS.0;
+    let rows = (world.height / cell) as usize;
+    let plants = (0..rows)
+        .map(|row| {
+            let (x, y) = (10.0 + 40.0 * (row % 4) as f32, row as f32 * cell);
+            scenario.place(Biot::predator, x, (y - 2.5).rem_euclid(world.height));
+            scenario.place(Biot::plant, x, y + 2.5)
+        })
+        .collect::<Vec<_>>();
+    scenario.run(1);
+    if let Some(plant) = plants.iter().find(|plant| !scenario.is_dead(**plant)) {
+        return Err(format!("the plant {plant:?} survived on a border"));
+    }
+    Ok(())
+}
+
+/// Checks that moving biots never end up within a wall or a rock.
+fn check_obstacles() -> Result<(), String> {
+    let obstacles = vec![
+        Obstacle::Rectangle {
+            min: vec2(90.0, 0.0),
+            max: vec2(110.0, 200.0),
+        },
+        Obstacle::Circle {
+            center: vec2(150.0, 100.0),
+            radius: 15.0,
+        },
+    ];
+    let config = SimConfig {
+        obstacles: obstacles.clone(),
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    for y in [20.0, 60.0, 100.0, 140.0, 180.0] {
+        scenario.place(Biot::grazer, 80.0, y);
+        scenario.place(Biot::smart_predator, 120.0, y);
+    }
+    for step in 0..500 {
+        scenario.run(1);
+        let biots = scenario.simulation().biots();
+        let blocked = |biot: &&Biot| obstacles.iter().any(|o| o.contains(biot.stats.pos));
+        if let Some(biot) = biots.iter().find(blocked) {
+            return Err(format!(
+                "step {step}: biot {} is within an obstacle at {}",
+                biot.id().0,
+                biot.stats.pos
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a population of clones raises the dominance alarm after the
+/// configured duration and is diversified in response.
+fn check_dominance() -> Result<(), String> {
+    let duration = 100;
+    let config = SimConfig {
+        dominance: DominanceConfig {
+            threshold: 0.5,
+            duration,
+            mutation: 1.0,
+        },
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    for n in 0..10 {
+        scenario.place(Biot::plant, 10.0 + 20.0 * n as f32, 100.0);
+    }
+    for step in 0..5 * duration {
+        scenario.run(1);
+        if let Some(alarm) = scenario.simulation().alarm() {
+            if step < duration {
+                return Err(format!("step {step}: alarm raised too early"));
+            }
+            if alarm.mutated == 0 {
+                return Err(format!(
+                    "step {step}: no biot mutated in response to the alarm"
+                ));
+            }
+            return Ok(());
+        }
+    }
+    Err(String::from("no alarm raised for a population of clones"))
+}
+
+/// Checks that the population controller raises the supply of a population
+/// below its target without exceeding the configured bounds.
+fn check_controller() -> Result<(), String> {
+    let controller = ControllerConfig {
+        target: 100,
+        gain: 0.01,
+        max_factor: 2.0,
+    };
+    let config = SimConfig {
+        controller: controller.clone(),
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    scenario.place(Biot::plant, 100.0, 100.0);
+    for step in 0..200 {
+        scenario.run(1);
+        let supply = scenario.simulation().environment().supply();
+        if !(1.0 / controller.max_factor..=controller.max_factor).contains(&supply) {
+            return Err(format!("step {step}: supply {supply} is out of bounds"));
+        }
+    }
+    let supply = scenario.simulation().environment().supply();
+    if supply <= 1.0 {
+        return Err(format!(
+            "supply {supply} was not raised for a small population"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that sexually reproducing biots need a partner within the mating
+/// range and combine both genomes.
+///
+/// # Note
+///
+/// Without mutations, every gene of an offspring comes from one of the
+/// placed plants and grazers at the same slot of the genome.
+fn check_mating() -> Result<(), String> {
+    let config = SimConfig {
+        reproduction: Reproduction::Sexual(20.0),
+        mutation_chance: 0.0,
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    let loner = scenario.place(Biot::plant, 20.0, 20.0);
+    let plant = scenario.place(Biot::plant, 150.0, 150.0);
+    // A grazer would eat its partner, so two plants pair up.
+    let partner = scenario.place(Biot::plant, 155.0, 150.0);
+    let genomes = [plant, partner].map(|id| scenario.biot(id).map(|biot| biot.genome().clone()));
+    let [Some(plant), Some(partner)] = genomes else {
+        return Err(String::from("placed biots are missing"));
+    };
+    let mut births = 0;
+    for step in 0..3000 {
+        scenario.run(1);
+        let biots = scenario.simulation().biots();
+        births += biots.births();
+        if biots.parents().len() != 2 * biots.births() as usize {
+            return Err(format!("step {step}: offsprings without two parents"));
+        }
+        let foreign = |biot: &&Biot| {
+            let mut genes = biot
+                .genome()
+                .genes()
+                .zip(plant.genes().zip(partner.genes()));
+            genes.any(|(gene, (a, b))| gene != a && gene != b)
+        };
+        if let Some(biot) = biots.iter().find(foreign) {
+            return Err(format!(
+                "step {step}: biot {} has a gene of neither parent",
+                biot.id().0
+            ));
+        }
+    }
+    let offspring_of_loner = |biot: &Biot| biot.lineage() == loner && biot.id() != loner;
+    if scenario.simulation().biots().iter().any(offspring_of_loner) {
+        return Err(String::from("a biot without a partner reproduced"));
+    }
+    if births == 0 {
+        return Err(String::from("no offspring was born to a pair of biots"));
+    }
+    Ok(())
+}
+
+/// Checks that a fecund plant bears a whole litter sharing the life of a
+/// single offspring with balanced energy books, and that the fecundity gene
+/// only evolves with litters.
+fn check_litters() -> Result<(), String> {
+    if Gene::pool(&SimConfig::default()).contains(&Gene::Fecundity) {
+        return Err(String::from("the fecundity gene evolves without litters"));
+    }
+    let fecund = GenomeBuilder::new()
+        .with(Gene::Photosynthesis, 27)
+        .with(Gene::Fecundity, 5)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let config = SimConfig {
+        litters: LitterConfig {
+            max_size: 4,
+            ..LitterConfig::default()
+        },
+        mutation_chance: 0.0,
+        ..SimConfig::default()
+    };
+    if !Gene::pool(&config).contains(&Gene::Nurture) {
+        return Err(String::from(
+            "the nurture gene does not evolve with litters",
+        ));
+    }
+    let mut scenario = Scenario::with_config(config.clone());
+    let parent = scenario.place(
+        |world, rng| Biot::from_genome(fecund, world, rng),
+        100.0,
+        100.0,
+    );
+    let (size, investment) = scenario
+        .biot(parent)
+        .map(|biot| biot.litter(&config))
+        .ok_or("the placed plant is missing")?;
+    if size != 3 {
+        return Err(format!("five fecundity genes give a litter of {size}"));
+    }
+    for _ in 0..3000 {
+        scenario.run(1);
+        let biots = scenario.simulation().biots();
+        biots.check_energy_books()?;
+        if biots.births() == 0 {
+            continue;
+        }
+        if biots.births() != 3 {
+            return Err(format!("a litter of {} instead of 3", biots.births()));
+        }
+        let base_life = scenario
+            .biot(parent)
+            .map(|biot| biot.base_life(&config))
+            .ok_or("the parent died giving birth")?;
+        let newborns = biots
+            .iter()
+            .filter(|biot| biot.lineage() == parent && biot.id() != parent);
+        for newborn in newborns {
+            let expected = base_life * investment;
+            if (newborn.stats.life - expected).0.abs() > 1e-3 {
+                return Err(format!(
+                    "an offspring was born with {:.3} instead of {:.3} life",
+                    newborn.stats.life.0, expected.0
+                ));
+            }
+        }
+        return Ok(());
+    }
+    Err(String::from("the fecund plant never reproduced"))
+}
+
+/// Checks that overlapping plants push each other apart, the lighter one further.
+fn check_collisions() -> Result<(), String> {
+    let heavy = GenomeBuilder::new()
+        .with(Gene::Photosynthesis, 30)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let light = GenomeBuilder::new()
+        .with(Gene::Photosynthesis, 10)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let config = SimConfig {
+        collisions: CollisionConfig { stiffness: 0.5 },
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    let heavy = scenario.place(
+        |world, rng| Biot::from_genome(heavy, world, rng),
+        100.0,
+        100.0,
+    );
+    let light = scenario.place(
+        |world, rng| Biot::from_genome(light, world, rng),
+        102.0,
+        100.0,
+    );
+    scenario.run(10);
+    let biots = scenario.simulation().biots();
+    let (heavy, light) = biots
+        .get(heavy)
+        .zip(biots.get(light))
+        .ok_or("a plant died")?;
+    let overlap = interaction_radius(heavy, light).0 - (light.stats.pos - heavy.stats.pos).x;
+    if overlap > 1.0 {
+        return Err(format!("the plants still overlap by {overlap:.1}"));
+    }
+    let moved = |biot: &Biot, x: f32| (biot.stats.pos.x - x).abs();
+    if moved(heavy, 100.0) >= moved(light, 102.0) {
+        return Err(String::from(
+            "the heavy plant gave way further than the light one",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the largest biots interact as soon as they touch, farther
+/// apart than smaller biots ever do, unless the interaction radius is capped.
+fn check_giants() -> Result<(), String> {
+    let giant = GenomeBuilder::new()
+        .with(Gene::Photosynthesis, MAX_GENOME_LEN)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let gap = |max_interaction_radius: f32| {
+        let config = SimConfig {
+            collisions: CollisionConfig { stiffness: 1.0 },
+            max_interaction_radius,
+            ..SimConfig::default()
+        };
+        let mut scenario = Scenario::with_config(config);
+        let place = |scenario: &mut Scenario, x| {
+            let genome = giant.clone();
+            scenario.place(|world, rng| Biot::from_genome(genome, world, rng), x, 100.0)
+        };
+        let (first, second) = (place(&mut scenario, 100.0), place(&mut scenario, 160.0));
+        scenario.run(1);
+        let biots = scenario.simulation().biots();
+        let (first, second) = biots
+            .get(first)
+            .zip(biots.get(second))
+            .ok_or("a giant died")?;
+        Ok::<_, String>((
+            interaction_radius(first, second).0,
+            second.stats.pos.x - first.stats.pos.x,
+        ))
+    };
+    let (touching, pushed) = gap(MAX_INTERACTION_RADIUS.0)?;
+    if touching <= 60.0 || pushed < touching - 1.0 {
+        return Err(format!(
+            "giants touching within {touching:.1} are still {pushed:.1} apart"
+        ));
+    }
+    let (_, capped) = gap(40.0)?;
+    if (capped - 60.0).abs() > 1e-3 {
+        return Err(format!(
+            "giants beyond the maximum interaction radius were pushed {capped:.1} apart"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the layers count the biots of a region and average their traits.
+fn check_layers() -> Result<(), String> {
+    let plant = GenomeBuilder::new()
+        .with(Gene::Photosynthesis, 20)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let hunter = GenomeBuilder::new()
+        .with(Gene::Attack, 10)
+        .with(Gene::Photosynthesis, 10)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let world = World::new(1000.0, 1000.0);
+    let mut scenario = Scenario::with_world(world, SimConfig::default(), 0);
+    for x in [20.0, 30.0] {
+        let genome = plant.clone();
+        scenario.place(|world, rng| Biot::from_genome(genome, world, rng), x, 20.0);
+    }
+    scenario.place(
+        |world, rng| Biot::from_genome(hunter, world, rng),
+        600.0,
+        600.0,
+    );
+    let simulation = scenario.simulation();
+    if LayerMap::compute(Layer::Density, simulation.world(), simulation.biots()).is_some() {
+        return Err(String::from(
+            "a layer was computed before the biots were indexed",
+        ));
+    }
+    scenario.run(1);
+    let simulation = scenario.simulation();
+    let compute = |layer| {
+        LayerMap::compute(layer, simulation.world(), simulation.biots())
+            .ok_or("the biots were not indexed after a step")
+    };
+    let plants = vec2(25.0, 20.0);
+    let density = compute(Layer::Density)?;
+    if density.value(plants) != Some(2.0) || density.value(vec2(300.0, 800.0)).is_some() {
+        return Err(String::from(
+            "the density does not count the biots of each region",
+        ));
+    }
+    let attack = compute(Layer::Attack)?;
+    let hunters = attack.value(vec2(600.0, 600.0)).unwrap_or_default();
+    if attack.value(plants) != Some(0.0) || hunters <= 0.0 {
+        return Err(format!("a mean attack of {hunters} around the hunter"));
+    }
+    if attack.is_stale(Layer::Attack, simulation.biots().steps() + 1)
+        || !attack.is_stale(Layer::Photosynthesis, simulation.biots().steps())
+    {
+        return Err(String::from("layers are recomputed at the wrong time"));
+    }
+    if Layer::ALL.iter().fold(None, |layer, _| Layer::cycle(layer)) != Some(Layer::Photosynthesis)
+        || Layer::cycle(Some(Layer::Photosynthesis)).is_some()
+    {
+        return Err(String::from(
+            "the layers do not cycle in order and back to none",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the initial population starts within the regions admitting each biot.
+fn check_spawn_regions() -> Result<(), String> {
+    let left = Area::Rectangle {
+        min: vec2(0.0, 0.0),
+        max: vec2(100.0, 200.0),
+    };
+    let right = Area::Circle {
+        center: vec2(300.0, 100.0),
+        radius: 50.0,
+    };
+    let config = SimConfig {
+        initial_population: 200,
+        spawn_regions: vec![
+            SpawnRegion {
+                area: left,
+                weight: 1.0,
+                occupants: Occupants::Predators,
+            },
+            SpawnRegion {
+                area: right,
+                weight: 1.0,
+                occupants: Occupants::Prey,
+            },
+        ],
+        ..SimConfig::default()
+    };
+    let simulation = Simulation::new(World::new(400.0, 200.0), config, 0);
+    for biot in simulation.biots().iter() {
+        let pos = biot.stats.pos;
+        let inside = if biot.is_predator() {
+            pos.x <= 100.0
+        } else {
+            pos.distance(vec2(300.0, 100.0)) <= 50.0 + 1e-3
+        };
+        if !inside {
+            return Err(format!(
+                "biot {} started at {pos:?} outside of its spawn region",
+                biot.id().0
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that an intelligent biot runs away from a biot that could eat it.
+fn check_fleeing() -> Result<(), String> {
+    let hunter = GenomeBuilder::new()
+        .with(Gene::Attack, 20)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let prey = GenomeBuilder::new()
+        .with(Gene::Photosynthesis, 10)
+        .with(Gene::Motion, 20)
+        .with(Gene::Intelligence, 1)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let mut scenario = Scenario::new();
+    let hunter = scenario.place(
+        |world, rng| Biot::from_genome(hunter, world, rng),
+        160.0,
+        100.0,
+    );
+    let prey = scenario.place(
+        |world, rng| Biot::from_genome(prey, world, rng),
+        100.0,
+        100.0,
+    );
+    let distance = |scenario: &Scenario| {
+        let biots = scenario.simulation().biots();
+        let (hunter, prey) = (biots.get(hunter), biots.get(prey));
+        let (hunter, prey) = hunter.zip(prey).map(|(h, p)| (h.stats.pos, p.stats.pos))?;
+        Some(scenario.simulation().world().delta(hunter, prey).length())
+    };
+    let before = distance(&scenario).ok_or("the biots were not placed")?;
+    scenario.run(30);
+    let after = distance(&scenario).ok_or("the prey was eaten")?;
+    if after < before + 20.0 {
+        return Err(format!(
+            "the prey only got from {before:.1} to {after:.1} away from the hunter"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that a starving hunter heads for a plant next to a close threat,
+/// while a sated one flees from the threat instead.
+fn check_drives() -> Result<(), String> {
+    let hunter = GenomeBuilder::new()
+        .with(Gene::Attack, 10)
+        .with(Gene::Motion, 20)
+        .with(Gene::Intelligence, 1)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let threat = GenomeBuilder::new()
+        .with(Gene::Attack, 30)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let config = SimConfig {
+        drives: DriveConfig {
+            enabled: true,
+            ..DriveConfig::default()
+        },
+        ..SimConfig::default()
+    };
+    for (fraction, starving) in [(0.1, true), (0.9, false)] {
+        let mut scenario = Scenario::with_config(config.clone());
+        let plant = scenario.place(Biot::plant, 100.0, 100.0);
+        let hunter = scenario.place(
+            |world, rng| {
+                let mut hunter = Biot::from_genome(hunter.clone(), world, rng);
+                hunter.stats.life = hunter.reproduction_threshold(&config) * fraction;
+                hunter.stats.hunger = 1.0 - fraction;
+                hunter
+            },
+            160.0,
+            100.0,
+        );
+        let threat = scenario.place(
+            |world, rng| Biot::from_genome(threat.clone(), world, rng),
+            160.0,
+            160.0,
+        );
+        let expected = if starving {
+            Drive::Starving
+        } else {
+            Drive::Sated
+        };
+        let drive = scenario.biot(hunter).map(|biot| biot.drive(&config));
+        if drive != Some(expected) {
+            return Err(format!("a hunter with {fraction} of its life is {drive:?}"));
+        }
+        let distances = |scenario: &Scenario| {
+            let biots = scenario.simulation().biots();
+            let hunter = biots.get(hunter)?.stats.pos;
+            let world = scenario.simulation().world();
+            let to = |id| {
+                biots
+                    .get(id)
+                    .map(|biot| world.delta(hunter, biot.stats.pos).length())
+            };
+            Some((to(plant), to(threat)))
+        };
+        let before = distances(&scenario).ok_or("the hunter was not placed")?;
+        scenario.run(15);
+        let after = distances(&scenario).ok_or("the hunter died")?;
+        let approached = match (before.0, after.0) {
+            (Some(before), Some(after)) => after < before - 10.0,
+            // The plant got eaten.
+            (Some(_), None) => true,
+            _ => false,
+        };
+        if approached != starving {
+            return Err(format!(
+                "a hunter with {fraction} of its life went from {before:?} to {after:?} away \
+                 from the plant and the threat"
+            ));
+        }
+        if !starving && after.1 <= before.1 {
+            return Err(String::from("a sated hunter did not flee from the threat"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that hunters with the kinship gene spare their relatives but not
+/// strangers, and that the gene only evolves with kin recognition.
+fn check_kinship() -> Result<(), String> {
+    if Gene::pool(&SimConfig::default()).contains(&Gene::Kinship) {
+        return Err(String::from(
+            "the kinship gene evolves without kin recognition",
+        ));
+    }
+    let genome = |attack| {
+        GenomeBuilder::new()
+            .with(Gene::Attack, attack)
+            .with(Gene::Photosynthesis, 10)
+            .with(Gene::Kinship, 2)
+            .build()
+            .map_err(|error| error.to_string())
+    };
+    let (hunter, relative) = (genome(12)?, genome(10)?);
+    let survivors = |enabled: bool| {
+        let config = SimConfig {
+            kinship: KinshipConfig {
+                enabled,
+                ..KinshipConfig::default()
+            },
+            ..SimConfig::default()
+        };
+        let mut scenario = Scenario::with_config(config);
+        let genome = hunter.clone();
+        scenario.place(
+            |world, rng| Biot::from_genome(genome, world, rng),
+            100.0,
+            100.0,
+        );
+        let genome = relative.clone();
+        let relative = scenario.place(
+            |world, rng| Biot::from_genome(genome, world, rng),
+            104.0,
+            100.0,
+        );
+        let stranger = scenario.place(Biot::plant, 96.0, 100.0);
+        scenario.run(1);
+        (!scenario.is_dead(relative), !scenario.is_dead(stranger))
+    };
+    match survivors(true) {
+        (true, false) => {}
+        (relative, stranger) => {
+            return Err(format!(
+                "with kin recognition the relative survived: {relative}, the stranger: {stranger}"
+            ))
+        }
+    }
+    if survivors(false).0 {
+        return Err(String::from(
+            "a relative survived a hunter without kin recognition",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the equator is warmer than the poles, that heat raises the
+/// metabolism and that the heat tolerance gene only evolves with temperatures.
+fn check_temperature() -> Result<(), String> {
+    if Gene::pool(&SimConfig::default()).contains(&Gene::HeatTolerance) {
+        return Err(String::from(
+            "the heat tolerance gene evolves without temperatures",
+        ));
+    }
+    let config = SimConfig {
+        temperature: TemperatureConfig {
+            enabled: true,
+            ..TemperatureConfig::default()
+        },
+        ..SimConfig::default()
+    };
+    if !Gene::pool(&config).contains(&Gene::HeatTolerance) {
+        return Err(String::from(
+            "the heat tolerance gene does not evolve with temperatures",
+        ));
+    }
+    let world = World::new(200.0, 200.0);
+    let temperature = |y| Environment::new().temperature(&config, &world, vec2(100.0, y));
+    let (equator, pole) = (temperature(100.0), temperature(0.0));
+    if (equator - 1.0).abs() > 1e-6 || pole.abs() > 1e-6 {
+        return Err(format!("the equator is {equator} and the pole {pole} warm"));
+    }
+    let shell = GenomeBuilder::new()
+        .with(Gene::Defense, 10)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let mut scenario = Scenario::with_config(config);
+    let hot = scenario.place(
+        |world, rng| Biot::from_genome(shell.clone(), world, rng),
+        50.0,
+        100.0,
+    );
+    let cold = scenario.place(
+        |world, rng| Biot::from_genome(shell.clone(), world, rng),
+        150.0,
+        2.0,
+    );
+    scenario.run(10);
+    let (hot, cold) = (scenario.life(hot), scenario.life(cold));
+    if hot >= cold {
+        return Err(format!(
+            "a biot at the equator kept {hot:?} and one at the pole {cold:?} of its life"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that a flow carries a plant downstream and that the fastest random
+/// current has the configured strength.
+fn check_currents() -> Result<(), String> {
+    let config = SimConfig {
+        currents: CurrentsConfig {
+            flows: vec![Flow {
+                area: Area::Rectangle {
+                    min: vec2(0.0, 0.0),
+                    max: vec2(200.0, 200.0),
+                },
+                velocity: vec2(1.0, 0.0),
+            }],
+            ..CurrentsConfig::default()
+        },
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    let plant = scenario.place(Biot::plant, 50.0, 100.0);
+    scenario.run(30);
+    let pos = scenario
+        .biot(plant)
+        .ok_or("the drifting plant died")?
+        .stats
+        .pos;
+    if pos.x < 60.0 || (pos.y - 100.0).abs() > 1.0 {
+        return Err(format!("the flow carried a plant from (50, 100) to {pos}"));
+    }
+    let config = SimConfig {
+        currents: CurrentsConfig {
+            strength: 2.0,
+            ..CurrentsConfig::default()
+        },
+        ..SimConfig::default()
+    };
+    let scenario = Scenario::with_config(config);
+    let currents = scenario.simulation().currents();
+    let fastest = (0..100)
+        .flat_map(|row| (0..100).map(move |column| vec2(column as f32, row as f32) * 2.0))
+        .map(|pos| currents.velocity(&[], pos).length())
+        .fold(0.0, f32::max);
+    if (fastest - 2.0).abs() > 1e-3 {
+        return Err(format!(
+            "the fastest random current flows at {fastest} instead of 2"
+        ));
+    }
+    Ok(())
+}
+
+/// Steers the intelligent biots east, except for the ones in the eastern half.
+struct Eastwards;
+
+impl Behavior for Eastwards {
+    fn steer(&self, situation: &Situation) -> Option<Vec2> {
+        let pos = situation.biot.stats.pos;
+        (pos.x < situation.world.width / 2.0).then_some(Vec2::X)
+    }
+}
+
+/// Checks that a registered behavior steers intelligent biots and leaves the
+/// steering to their genes where it returns no direction.
+fn check_behaviors() -> Result<(), String> {
+    let hunter = GenomeBuilder::new()
+        .with(Gene::Intelligence, 4)
+        .with(Gene::Motion, 6)
+        .build()
+        .map_err(|error| error.to_string())?;
+    // Without food there is nothing else to head towards.
+    let mut config = SimConfig::default();
+    config.food.spawn_rate = 0.0;
+    let mut scenario = Scenario::with_config(config);
+    scenario.add_behavior(Eastwards);
+    let west = scenario.place(
+        |world, rng| Biot::from_genome(hunter.clone(), world, rng),
+        50.0,
+        100.0,
+    );
+    let east = scenario.place(
+        |world, rng| Biot::from_genome(hunter.clone(), world, rng),
+        150.0,
+        100.0,
+    );
+    scenario.run(1);
+    let feed_dir = |id| scenario.biot(id).and_then(|biot| biot.stats.feed_dir);
+    match (feed_dir(west), feed_dir(east)) {
+        (Some(west), None) if west == Vec2::X => Ok(()),
+        dirs => Err(format!("the behavior steered the biots towards {dirs:?}")),
+    }
+}
+
+/// Pushes the biots expressing the camouflage gene east in proportion to its expression.
+struct CamouflageCurrent;
+
+impl GenePlugin for CamouflageCurrent {
+    fn gene(&self) -> Gene {
+        Gene::Camouflage
+    }
+
+    fn push(&self, expression: &Expression) -> Option<(Vec2, f32)> {
+        Some((Vec2::X, expression.expression))
+    }
+}
+
+/// Checks that a registered gene plugin pushes the moving biots expressing
+/// its gene and leaves the others alone.
+fn check_gene_plugins() -> Result<(), String> {
+    let genome = |camouflage| {
+        GenomeBuilder::new()
+            .with(Gene::Motion, 8)
+            .with(Gene::Camouflage, camouflage)
+            .build()
+            .map_err(|error| error.to_string())
+    };
+    let (camouflaged, plain) = (genome(1)?, genome(0)?);
+    let positions = |plugin: bool| {
+        let mut scenario = Scenario::new();
+        if plugin {
+            scenario.add_gene_plugin(CamouflageCurrent);
+        }
+        let camouflaged = scenario.place(
+            |world, rng| Biot::from_genome(camouflaged.clone(), world, rng),
+            50.0,
+            50.0,
+        );
+        let plain = scenario.place(
+            |world, rng| Biot::from_genome(plain.clone(), world, rng),
+            50.0,
+            150.0,
+        );
+        scenario.run(10);
+        let pos = |id| scenario.biot(id).map(|biot| biot.stats.pos);
+        (pos(camouflaged), pos(plain))
+    };
+    match (positions(false), positions(true)) {
+        ((Some(before), Some(plain)), (Some(pushed), Some(unpushed)))
+            if pushed.x > before.x && unpushed == plain =>
+        {
+            Ok(())
+        }
+        (before, after) => Err(format!(
+            "the plugin moved the biots from {before:?} to {after:?}"
+        )),
+    }
+}
+
+/// Checks that the callbacks of a script are called from within the steps,
+/// that its interventions take effect at their end, that it can spare prey
+/// and that runaway callbacks, mass placements and excessive feeding are
+/// stopped.
+#[cfg(feature = "scripting")]
+fn check_script() -> Result<(), String> {
+    let load = |source: &str| Script::from_source(source).map_err(|error| error.to_string());
+    if load("place(\"dragon\", 0, 0);").is_ok() {
+        return Err(String::from("a script spawned an unknown archetype"));
+    }
+    if load("feed(0, 1e300);").is_ok() {
+        return Err(String::from("a script fed an infinite life"));
+    }
+    let mut scenario = Scenario::new();
+    let plant = scenario.place(Biot::plant, 50.0, 50.0);
+    scenario.add_rules(load(&format!(
+        "fn on_tick(step, population) {{
+             if step == 1 && population == 1 {{
+                 kill({});
+                 place(\"grazer\", 100, 120.5);
+             }}
+         }}",
+        plant.0
+    ))?);
+    scenario.run(1);
+    let biots = scenario.simulation().biots();
+    if biots.get(plant).is_some() {
+        return Err(String::from("the script did not kill the plant"));
+    }
+    match biots.iter().map(|biot| biot.stats.pos).collect::<Vec<_>>()[..] {
+        [pos] if pos == vec2(100.0, 120.5) => {}
+        ref positions => return Err(format!("the script spawned biots at {positions:?}")),
+    }
+    let mut scenario = Scenario::new();
+    let predator = scenario.place(Biot::predator, 10.0, 10.0);
+    let plant = scenario.place(Biot::plant, 15.0, 10.0);
+    scenario.add_rules(load(&format!(
+        "fn on_interact(predator, prey) {{ prey.id != {} || predator.id != {} }}",
+        plant.0, predator.0
+    ))?);
+    scenario.run(1);
+    if scenario.is_dead(plant) || !scenario.simulation().biots().fights().is_empty() {
+        return Err(String::from("the script did not spare the plant"));
+    }
+    let runaways = [
+        "fn on_tick(step, population) { loop {} }",
+        "fn on_tick(step, population) { for n in 0..101 { place(\"plant\", n, 10); } }",
+    ];
+    for source in runaways {
+        let mut scenario = Scenario::new();
+        scenario.place(Biot::plant, 50.0, 50.0);
+        scenario.add_rules(load(source)?);
+        scenario.run(1);
+        let biots = scenario.simulation().biots();
+        if biots.rule_failures().is_empty() || biots.len() != 1 {
+            return Err(format!("the runaway script `{source}` was not stopped"));
+        }
+        scenario.run(1);
+        if !scenario.simulation().biots().rule_failures().is_empty() {
+            return Err(format!("the runaway script `{source}` was not dropped"));
+        }
+    }
+    // Feeding takes effect after the step, which is the same with and without it.
+    let fed = |source: Option<&str>| -> Result<(Energy, Energy), String> {
+        let mut scenario = Scenario::new();
+        let grazer = scenario.place(Biot::grazer, 50.0, 50.0);
+        if let Some(source) = source {
+            scenario.add_rules(load(&source.replace("ID", &grazer.0.to_string()))?);
+        }
+        scenario.run(1);
+        let config = scenario.simulation().biots().config().clone();
+        let biot = scenario
+            .simulation()
+            .biots()
+            .get(grazer)
+            .ok_or("the grazer died")?;
+        Ok((biot.stats.life, biot.base_life(&config)))
+    };
+    let (life, base_life) = fed(None)?;
+    let (fed, _) = fed(Some(
+        "fn on_tick(step, population) { feed(ID, -1000.0); feed(ID, 1000000.0); }",
+    ))?;
+    let expected = life + base_life;
+    if (fed.0 - expected.0).abs() > 1e-3 {
+        return Err(format!(
+            "feeding grew the life from {life:?} to {fed:?} instead of {expected:?}"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the observation server answers with the statistics, the
+/// biots and a snapshot of the simulation.
+#[cfg(feature = "server")]
+fn check_server() -> Result<(), String> {
+    let mut scenario = Scenario::new();
+    scenario.place(Biot::plant, 50.0, 50.0);
+    scenario.run(1);
+    let simulation = scenario.into_simulation();
+    let mut observer = Observer::bind("127.0.0.1:0").map_err(|error| error.to_string())?;
+    let address = observer.address();
+    let mut get = |path: &str| -> Result<Vec<u8>, String> {
+        let request = format!("GET {path} HTTP/1.0\r\nHost: {address}\r\n\r\n");
+        let client = thread::spawn({
+            let address = address.clone();
+            move || -> io::Result<Vec<u8>> {
+                let mut stream = TcpStream::connect(address)?;
+                stream.write_all(request.as_bytes())?;
+                let mut response = Vec::new();
+                stream.read_to_end(&mut response)?;
+                Ok(response)
+            }
+        });
+        let start = Instant::now();
+        while !client.is_finished() && start.elapsed() < Duration::from_secs(5) {
+            observer.serve(&simulation);
+            thread::sleep(Duration::from_millis(1));
+        }
+        if !client.is_finished() {
+            return Err(format!("no response to {path}"));
+        }
+        let response = client
+            .join()
+            .map_err(|_| String::from("the client panicked"))?
+            .map_err(|error| error.to_string())?;
+        let end = response
+            .windows(4)
+            .position(|window| window == b"\r\n\r\n")
+            .ok_or_else(|| format!("malformed response to {path}"))?;
+        Ok(response[end + 4..].to_vec())
+    };
+    let stats = String::from_utf8(get("/stats")?).map_err(|error| error.to_string())?;
+    if !stats.contains("\"population\":1,") {
+        return Err(format!("unexpected statistics {stats}"));
+    }
+    let biots = String::from_utf8(get("/biots")?).map_err(|error| error.to_string())?;
+    if !biots.starts_with("[{\"step\":1,") || !biots.ends_with("}]") {
+        return Err(format!("unexpected biots {biots}"));
+    }
+    let snapshot = get("/snapshot")?;
+    if get("/snapshot")? != snapshot {
+        return Err(String::from("the snapshot changed without a step"));
+    }
+    match Simulation::decode(&snapshot) {
+        Ok(decoded) if decoded.biots().len() == 1 => Ok(()),
+        Ok(decoded) => Err(format!(
+            "the snapshot holds {} biots",
+            decoded.biots().len()
+        )),
+        Err(error) => Err(format!("the snapshot cannot be decoded: {error}")),
+    }
+}
+
+/// Checks that migrants move on to the next island as emigrants rather than
+/// deaths, never landing within obstacles, that islands shown in the viewer
+/// are seeded like headless ones and that swapping the shown island keeps
+/// the order of the islands.
+fn check_islands() -> Result<(), String> {
+    let mut crowded = Scenario::new();
+    for n in 0..10 {
+        crowded.place(Biot::plant, 20.0 + 30.0 * n as f32, 100.0);
+    }
+    // The right half of the island is covered by an obstacle.
+    let walled = SimConfig {
+        obstacles: vec![Obstacle::Rectangle {
+            min: vec2(100.0, -1000.0),
+            max: vec2(1000.0, 1000.0),
+        }],
+        ..SimConfig::default()
+    };
+    let walled = Scenario::with_config(walled).into_simulation();
+    let islands = vec![crowded.into_simulation(), walled];
+    let migration = Migration {
+        interval: 1,
+        fraction: 0.5,
+    };
+    let mut archipelago = Archipelago::new(islands, migration, 0);
+    let migrated = archipelago.step();
+    let populations = archipelago
+        .islands()
+        .iter()
+        .map(|island| island.biots().len())
+        .collect::<Vec<_>>();
+    if migrated != 5 || populations != [5, 5] {
+        return Err(format!(
+            "{migrated} biots migrated, leaving populations of {populations:?}"
+        ));
+    }
+    if archipelago
+        .islands()
+        .iter()
+        .any(|island| !island.biots().deaths().is_empty())
+    {
+        return Err(String::from("migrants were counted as deaths"));
+    }
+    let walled = &archipelago.islands()[1];
+    let obstacles = &walled.biots().config().obstacles;
+    if let Some(biot) = walled.biots().iter().find(|biot| {
+        obstacles
+            .iter()
+            .any(|obstacle| obstacle.contains(biot.stats.pos))
+    }) {
+        return Err(format!(
+            "a migrant landed within an obstacle at {}",
+            biot.stats.pos
+        ));
+    }
+    let config = SimConfig {
+        initial_population: POPULATION,
+        ..SimConfig::default()
+    };
+    let configs = [config.clone(), config];
+    let world = World::new(WORLD_SIZE, WORLD_SIZE);
+    let frequent = Migration {
+        interval: 5,
+        fraction: 0.1,
+    };
+    let mut headless = Archipelago::generate(world, &configs, 3, frequent);
+    let mut shown = Simulation::new(world, configs[0].clone(), 3);
+    let mut viewed = Archipelago::generate_around(world, &configs[1..], 3, frequent);
+    for _ in 0..20 {
+        headless.step();
+        shown.step();
+        viewed.step_around(&mut shown, 0);
+    }
+    let ids = |island: &Simulation| island.biots().iter().map(Biot::id).collect::<Vec<_>>();
+    if ids(&headless.islands()[0]) != ids(&shown)
+        || ids(&headless.islands()[1]) != ids(&viewed.islands()[0])
+    {
+        return Err(String::from(
+            "the islands of the viewer evolved apart from headless ones",
+        ));
+    }
+    // Ancestors of living biots stay in the tree, marked as emigrants.
+    let mut phylogeny = Phylogeny::new();
+    phylogeny.found(BiotId(0), 0);
+    phylogeny.birth(BiotId(1), BiotId(0), 1);
+    phylogeny.emigration(BiotId(0), 2);
+    if !phylogeny
+        .to_dot()
+        .contains("0 [label=\"0\\nstep 0\", style=dashed]")
+    {
+        return Err(String::from(
+            "the emigrant is not marked in the family tree",
+        ));
+    }
+    let world = World::new(100.0, 100.0);
+    let island = |seed| Simulation::new(world, SimConfig::default(), seed);
+    let mut shown = island(0);
+    let mut archipelago = Archipelago::new(vec![island(1), island(2)], Migration::default(), 0);
+    let seeds = |shown: &Simulation, archipelago: &Archipelago| {
+        let others = archipelago.islands().iter().map(Simulation::seed);
+        (shown.seed(), others.collect::<Vec<_>>())
+    };
+    archipelago.swap(&mut shown, 0, 2);
+    let last = seeds(&shown, &archipelago);
+    archipelago.swap(&mut shown, 2, 1);
+    match (last, seeds(&shown, &archipelago)) {
+        ((2, last), (1, middle)) if last == [0, 1] && middle == [0, 2] => Ok(()),
+        seeds => Err(format!("swapping islands left the seeds {seeds:?}")),
+    }
+}
+
+/// Checks that two social biots of the same species are drawn towards each
+/// other and that the sociality gene only evolves with flocking.
+fn check_flocking() -> Result<(), String> {
+    if Gene::pool(&SimConfig::default()).contains(&Gene::Sociality) {
+        return Err(String::from("the sociality gene evolves without flocking"));
+    }
+    let social = GenomeBuilder::new()
+        .with(Gene::Photosynthesis, 12)
+        .with(Gene::Motion, 10)
+        .with(Gene::Sociality, 10)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let config = SimConfig {
+        flocking: FlockingConfig {
+            enabled: true,
+            ..FlockingConfig::default()
+        },
+        ..SimConfig::default()
+    };
+    if !Gene::pool(&config).contains(&Gene::Sociality) {
+        return Err(String::from(
+            "the sociality gene does not evolve with flocking",
+        ));
+    }
+    let mut scenario = Scenario::with_config(config);
+    let left = scenario.place(
+        |world, rng| Biot::from_genome(social.clone(), world, rng),
+        80.0,
+        100.0,
+    );
+    let right = scenario.place(
+        |world, rng| Biot::from_genome(social.clone(), world, rng),
+        120.0,
+        100.0,
+    );
+    scenario.run(1);
+    let flock_dir = |id| scenario.biot(id).and_then(|biot| biot.stats.flock_dir);
+    match (flock_dir(left), flock_dir(right)) {
+        (Some(left), Some(right)) if left.x > 0.0 && right.x < 0.0 => Ok(()),
+        dirs => Err(format!("two social biots flock in the directions {dirs:?}")),
+    }
+}
+
+/// Checks that the scent of a plant spreads around it and fades with the distance.
+fn check_scent() -> Result<(), String> {
+    let config = SimConfig {
+        scent: ScentConfig {
+            deposit: 1.0,
+            ..ScentConfig::default()
+        },
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    scenario.place(Biot::plant, 100.0, 100.0);
+    scenario.run(20);
+    let scent = scenario.simulation().biots().scent();
+    let [at, near, far] = [100.0, 130.0, 300.0].map(|x| scent.value(vec2(x, 100.0)));
+    if !(at > near && near > far && near > 0.0) {
+        return Err(format!(
+            "the scent at, near and far from the plant is {at}, {near} and {far}"
+        ));
+    }
+    if scent.gradient(vec2(130.0, 100.0)).x >= 0.0 {
+        return Err(String::from("the scent does not lead towards the plant"));
+    }
+    Ok(())
+}
+
+/// Checks that a biting predator wounds a plant without killing it, gaining
+/// its share of exactly the life the plant lost, and that a strong enough
+/// bite kills it.
+fn check_wounds() -> Result<(), String> {
+    for (bite, lethal) in [(0.5, false), (1000.0, true)] {
+        let config = SimConfig {
+            predation: PredationConfig {
+                bite,
+                ..PredationConfig::default()
+            },
+            ..SimConfig::default()
+        };
+        let mut scenario = Scenario::with_config(config);
+        let predator = scenario.place(Biot::predator, 10.0, 10.0);
+        let plant = scenario.place(Biot::plant, 15.0, 10.0);
+        scenario.run(1);
+        let biots = scenario.simulation().biots();
+        biots.check_energy_books()?;
+        let fight = biots
+            .fights()
+            .iter()
+            .find(|fight| fight.predator == predator && fight.prey == plant)
+            .ok_or("the predator did not bite the plant")?;
+        if fight.killed != lethal || scenario.is_dead(plant) != lethal {
+            let dead = scenario.is_dead(plant);
+            return Err(format!(
+                "a bite of {bite} left the plant dead: {dead}, {fight:?}"
+            ));
+        }
+        if fight.wound <= Energy::ZERO {
+            return Err(format!("a bite of {bite} did not wound the plant"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that a pathogen spreads from a carrier to a plant it touches but
+/// not to an immune one, drains the life of its carriers with balanced
+/// energy books and only evolves the immunity gene while enabled.
+fn check_disease() -> Result<(), String> {
+    if Gene::pool(&SimConfig::default()).contains(&Gene::Immunity) {
+        return Err(String::from("the immunity gene evolves without a disease"));
+    }
+    let immune = GenomeBuilder::new()
+        .with(Gene::Photosynthesis, 28)
+        .with(Gene::Immunity, 10)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let config = SimConfig {
+        disease: DiseaseConfig {
+            transmission: 1.0,
+            recovery: 0.0,
+            ..DiseaseConfig::default()
+        },
+        ..SimConfig::default()
+    };
+    if !Gene::pool(&config).contains(&Gene::Immunity) {
+        return Err(String::from(
+            "the immunity gene does not evolve with a disease",
+        ));
+    }
+    let mut scenario = Scenario::with_config(config);
+    let carrier = |world: &World, rng: &mut Rng| {
+        let mut carrier = Biot::plant(world, rng);
+        carrier.stats.infected = true;
+        carrier
+    };
+    scenario.place(carrier, 100.0, 100.0);
+    let healthy = scenario.place(Biot::plant, 110.0, 100.0);
+    let immune = scenario.place(
+        |world, rng| Biot::from_genome(immune, world, rng),
+        90.0,
+        100.0,
+    );
+    scenario.run(1);
+    let biots = scenario.simulation().biots();
+    biots.check_energy_books()?;
+    if biots.energy_ledger().disease <= Energy::ZERO {
+        return Err(String::from("the pathogen drained no life"));
+    }
+    let infected = |id| scenario.biot(id).map(|biot| biot.stats.infected);
+    if infected(healthy) != Some(true) {
+        return Err(String::from(
+            "the pathogen did not spread to a touching plant",
+        ));
+    }
+    if infected(immune) != Some(false) {
+        return Err(String::from("the pathogen infected an immune plant"));
+    }
+    Ok(())
+}
+
+/// Checks that stretching the world keeps all biots, that shrinking it
+/// removes the ones beyond its new edges and that the simulation goes on.
+fn check_resize() -> Result<(), String> {
+    let config = SimConfig {
+        initial_population: 100,
+        ..SimConfig::default()
+    };
+    let mut simulation = Simulation::new(World::new(400.0, 300.0), config, 0);
+    simulation.step();
+    let population = simulation.biots().len();
+    let culled = simulation.resize(800.0, 600.0, Resize::Scale);
+    if culled > 0 || simulation.biots().len() != population {
+        return Err(format!("stretching the world removed {culled} biots"));
+    }
+    let culled = simulation.resize(400.0, 300.0, Resize::Cull);
+    if culled == 0 || simulation.biots().len() + culled != population {
+        return Err(format!(
+            "shrinking the world removed {culled} of {population} biots"
+        ));
+    }
+    let world = *simulation.world();
+    let outside = |biot: &&Biot| biot.stats.pos.x > world.width || biot.stats.pos.y > world.height;
+    if let Some(biot) = simulation.biots().iter().find(outside) {
+        return Err(format!("biot {} lies beyond the new edges", biot.id().0));
+    }
+    for _ in 0..10 {
+        simulation.step();
+        check_state(&simulation)?;
+    }
+    Ok(())
+}
+
+/// Checks that juveniles and senile biots are less vigorous than adults and
+/// that senescence raises the metabolism with balanced energy books.
+fn check_aging() -> Result<(), String> {
+    let config = SimConfig {
+        aging: AgingConfig {
+            maturity: 0.2,
+            senescence_onset: 0.5,
+            senescence_exponent: 1.0,
+            ..AgingConfig::default()
+        },
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    let aged = |age| {
+        move |world: &World, rng: &mut Rng| {
+            let mut plant = Biot::plant(world, rng);
+            plant.stats.age = Ticks(age);
+            plant
+        }
+    };
+    let juvenile = scenario.place(aged(0), 100.0, 100.0);
+    let adult = scenario.place(aged(3000), 300.0, 100.0);
+    let senile = scenario.place(aged(9000), 500.0, 100.0);
+    let life = |scenario: &Scenario, id| scenario.biot(id).map(|biot| biot.stats.life);
+    let before = (life(&scenario, adult), life(&scenario, senile));
+    scenario.run(1);
+    scenario.simulation().biots().check_energy_books()?;
+    let vigor = |id| scenario.biot(id).map_or(0.0, |biot| biot.stats.vigor);
+    if (vigor(adult) - 1.0).abs() > 1e-4 {
+        return Err(format!("an adult has a vigor of {}", vigor(adult)));
+    }
+    if vigor(juvenile) >= 1.0 || vigor(senile) >= 1.0 {
+        return Err(format!(
+            "a juvenile has a vigor of {} and a senile biot of {}",
+            vigor(juvenile),
+            vigor(senile)
+        ));
+    }
+    let after = (life(&scenario, adult), life(&scenario, senile));
+    let gain = |before: Option<Energy>, after: Option<Energy>| {
+        before.zip(after).map(|(before, after)| (after - before).0)
+    };
+    match (gain(before.0, after.0), gain(before.1, after.1)) {
+        (Some(adult), Some(senile)) if senile < adult => Ok(()),
+        gains => Err(format!(
+            "senescence did not raise the metabolism: {gains:?}"
+        )),
+    }
+}
+
+/// Checks that hunters keep pursuing the prey they locked onto when other
+/// prey comes closer, and give up on it once it is out of sight.
+fn check_pursuit() -> Result<(), String> {
+    let config = SimConfig {
+        pursuit: PursuitConfig { patience: 20 },
+        ..SimConfig::default()
+    };
+    let world = World::new(1000.0, 1000.0);
+    let mut scenario = Scenario::with_world(world, config, 0);
+    let hunter = scenario.place(Biot::smart_predator, 100.0, 100.0);
+    let locked = scenario.place(Biot::plant, 160.0, 100.0);
+    let other = scenario.place(Biot::plant, 20.0, 100.0);
+    let mut simulation = scenario.into_simulation();
+    simulation.step();
+    let target = |simulation: &Simulation| {
+        simulation
+            .biots()
+            .get(hunter)
+            .and_then(|biot| biot.stats.target)
+            .filter(|target| target.patience > 0)
+            .map(|target| target.id)
+    };
+    if target(&simulation) != Some(locked) {
+        return Err(format!("the hunter locked onto {:?}", target(&simulation)));
+    }
+    let pos = simulation
+        .biots()
+        .get(hunter)
+        .ok_or("the hunter died")?
+        .stats
+        .pos;
+    simulation
+        .biots_mut()
+        .modify(other, |biot| biot.stats.pos = pos - vec2(25.0, 0.0));
+    simulation.step();
+    let heading = simulation
+        .biots()
+        .get(hunter)
+        .and_then(|biot| biot.stats.feed_dir);
+    if target(&simulation) != Some(locked) || heading.is_none_or(|dir| dir.x <= 0.0) {
+        return Err(String::from("the hunter switched to closer prey"));
+    }
+    simulation
+        .biots_mut()
+        .modify(locked, |biot| biot.stats.pos = vec2(900.0, 900.0));
+    for _ in 0..20 {
+        simulation.step();
+    }
+    if target(&simulation) == Some(locked) {
+        return Err(String::from("the hunter kept pursuing prey out of sight"));
+    }
+    Ok(())
+}
+
+/// Checks that newborns start small and grow with the life they store, up to
+/// the full size of their genome, and that their radius follows their size.
+fn check_growth() -> Result<(), String> {
+    let config = SimConfig {
+        growth: GrowthConfig {
+            newborn_size: 0.5,
+            rate: 0.01,
+        },
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config.clone());
+    let sized = |stored: f32| {
+        let config = config.clone();
+        move |world: &World, rng: &mut Rng| {
+            let mut plant = Biot::plant(world, rng);
+            plant.stats.size = 0.5;
+            plant.stats.life = plant.base_life(&config) * stored;
+            plant
+        }
+    };
+    let starving = scenario.place(sized(0.2), 100.0, 100.0);
+    let fed = scenario.place(sized(0.9), 300.0, 100.0);
+    let parent = scenario.place(
+        |world, rng| {
+            let mut plant = Biot::plant(world, rng);
+            plant.stats.life = plant.reproduction_threshold(&config) * 2.0;
+            plant
+        },
+        500.0,
+        100.0,
+    );
+    scenario.run(1);
+    let size = |id| scenario.biot(id).map_or(0.0, |biot| biot.stats.size);
+    if (size(starving) - 0.5).abs() > 1e-4 {
+        return Err(format!("a starving biot grew to {}", size(starving)));
+    }
+    if (size(fed) - 0.51).abs() > 1e-4 {
+        return Err(format!("a fed biot grew to {} instead of 0.51", size(fed)));
+    }
+    if (size(parent) - 1.0).abs() > 1e-4 {
+        return Err(format!(
+            "a full grown parent has a size of {}",
+            size(parent)
+        ));
+    }
+    let biots = scenario.simulation().biots();
+    let newborns = biots.iter().filter(|biot| biot.id() > parent);
+    if let Some(newborn) = newborns.clone().find(|biot| biot.stats.size > 0.5 + 0.01) {
+        return Err(format!("a newborn has a size of {}", newborn.stats.size));
+    }
+    if newborns.count() == 0 {
+        return Err(String::from("the parent had no offspring"));
+    }
+    let (fed, parent) = biots
+        .get(fed)
+        .zip(biots.get(parent))
+        .ok_or("a plant died")?;
+    let ratio = fed.radius().0 / parent.radius().0;
+    if (ratio - 0.51).abs() > 1e-3 {
+        return Err(format!(
+            "a biot of half size has {ratio} of the full radius"
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that novel genomes from the library are injected at the configured
+/// interval, founding lineages of their own.
+fn check_novelty() -> Result<(), String> {
+    let genes = [Gene::Attack, Gene::Attack, Gene::Motion, Gene::Defense];
+    let config = SimConfig {
+        novelty: NoveltyConfig {
+            interval: 5,
+            count: 2,
+            library: vec![genes.to_vec()],
+        },
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    scenario.place(Biot::plant, 100.0, 100.0);
+    let mut injected = Vec::new();
+    for _ in 0..10 {
+        scenario.run(1);
+        injected.extend_from_slice(scenario.simulation().injected());
+    }
+    if injected.len() != 4 {
+        return Err(format!("injected {} instead of 4 biots", injected.len()));
+    }
+    for id in injected {
+        let Some(biot) = scenario.biot(id) else {
+            continue;
+        };
+        if biot.lineage() != id || !biot.genome().genes().copied().take(genes.len()).eq(genes) {
+            return Err(format!("biot {} is no novel founder", id.0));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the event log records an eaten plant with its cause of death
+/// and keeps no more events than its capacity.
+fn check_events() -> Result<(), String> {
+    let mut scenario = Scenario::new();
+    scenario.place(Biot::predator, 10.0, 10.0);
+    let plant = scenario.place(Biot::plant, 15.0, 10.0);
+    let mut log = EventLog::new(EVENT_CAPACITY);
+    let mut small = EventLog::new(1);
+    for _ in 0..5 {
+        scenario.run(1);
+        log.record(scenario.simulation());
+        small.record(scenario.simulation());
+    }
+    let eaten = |event: &Event| match *event {
+        Event::Death { id, cause, .. } => id == plant && cause == CauseOfDeath::Predation,
+        _ => false,
+    };
+    if !log.iter().any(|logged| eaten(&logged.event)) {
+        let events = log.iter().map(ToString::to_string).collect::<Vec<_>>();
+        return Err(format!("the eaten plant is not logged: {events:?}"));
+    }
+    if small.iter().count() > 1 {
+        return Err(String::from("the event log exceeds its capacity"));
+    }
+    Ok(())
+}
+
+/// Checks that the lineage statistics account for every biot, rank the
+/// lineages by population and consider vanished lineages endangered.
+fn check_lineages() -> Result<(), String> {
+    let config = SimConfig {
+        initial_population: 100,
+        ..SimConfig::default()
+    };
+    let mut simulation = Simulation::new(World::new(400.0, 300.0), config, 0);
+    let mut lineages = LineageStats::new();
+    for _ in 0..100 {
+        simulation.step();
+        lineages.record(simulation.biots());
+    }
+    let ranking = lineages.ranking();
+    let counted = ranking
+        .iter()
+        .map(|(_, sample)| sample.population)
+        .sum::<usize>();
+    if counted != simulation.biots().len() {
+        return Err(format!(
+            "the lineages count {counted} of {} biots",
+            simulation.biots().len()
+        ));
+    }
+    if ranking
+        .windows(2)
+        .any(|pair| pair[0].1.population < pair[1].1.population)
+    {
+        return Err(String::from("the lineages are not ranked by population"));
+    }
+    for (lineage, _) in &ranking {
+        let risk = lineages.risk(*lineage);
+        let samples = lineages.history(*lineage).map_or(0, VecDeque::len);
+        if !(0.0..=1.0).contains(&risk) || samples == 0 {
+            return Err(format!(
+                "lineage {} has a risk of {risk} after {samples} samples",
+                lineage.0
+            ));
+        }
+    }
+    if !lineages.is_endangered(BiotId(u64::MAX)) {
+        return Err(String::from("a vanished lineage is not endangered"));
+    }
+    Ok(())
+}
+
+/// Checks that a predator cannot eat a plant within a reserve but one just
+/// outside of it, and that the reserve lowers the metabolism as configured.
+fn check_reserves() -> Result<(), String> {
+    let config = SimConfig {
+        reserves: vec![Reserve {
+            area: Area::Rectangle {
+                min: vec2(0.0, 0.0),
+                max: vec2(100.0, 100.0),
+            },
+            metabolism: 0.0,
+            ..Reserve::default()
+        }],
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    let guest = scenario.place(Biot::predator, 50.0, 50.0);
+    let protected = scenario.place(Biot::plant, 55.0, 50.0);
+    scenario.place(Biot::predator, 300.0, 50.0);
+    let exposed = scenario.place(Biot::plant, 305.0, 50.0);
+    let life = |scenario: &Scenario| scenario.biot(guest).map(|biot| biot.stats.life);
+    let before = life(&scenario);
+    scenario.run(1);
+    scenario.simulation().biots().check_energy_books()?;
+    if scenario.is_dead(protected) || !scenario.is_dead(exposed) {
+        return Err(format!(
+            "the protected plant died: {}, the exposed one: {}",
+            scenario.is_dead(protected),
+            scenario.is_dead(exposed)
+        ));
+    }
+    if life(&scenario) != before {
+        return Err(format!(
+            "a predator within the reserve went from {before:?} to {:?} life",
+            life(&scenario)
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the intervention tools spawn, kill and feed biots at the given positions only.
+fn check_tools() -> Result<(), String> {
+    let config = SimConfig {
+        initial_population: 0,
+        ..SimConfig::default()
+    };
+    let mut simulation = Simulation::new(World::new(400.0, 300.0), config, 0);
+    let original = tools::spawn_random(&mut simulation, vec2(100.0, 100.0));
+    let genome = simulation
+        .biots()
+        .get(original)
+        .map(|biot| biot.genome().clone())
+        .ok_or("the spawned biot is missing")?;
+    let clone = tools::spawn_clone(&mut simulation, &genome, vec2(105.0, 100.0));
+    let distant = tools::spawn_clone(&mut simulation, &genome, vec2(300.0, 200.0));
+    let biots = simulation.biots();
+    if biots.get(clone).map(Biot::genome) != Some(&genome) || biots.len() != 3 {
+        return Err(String::from(
+            "the clone does not carry the genome of the original",
+        ));
+    }
+    let life = |simulation: &Simulation, id| simulation.biots().get(id).map(|biot| biot.stats.life);
+    let before = life(&simulation, distant);
+    let fed = tools::feed(&mut simulation, vec2(300.0, 200.0));
+    if fed != 1 || life(&simulation, distant) <= before {
+        return Err(format!("the feed brush fed {fed} instead of one biot"));
+    }
+    let killed = tools::kill(&mut simulation, vec2(102.0, 100.0));
+    let biots = simulation.biots();
+    if killed != 2 || biots.get(original).is_some() || biots.get(distant).is_none() {
+        return Err(format!(
+            "the kill brush killed {killed} instead of two biots"
+        ));
+    }
+    simulation.step();
+    simulation.biots().check_energy_books()
+}
+
+/// Checks that a cached mature ecosystem is loaded exactly as it was simulated.
+fn check_fixture() -> Result<(), String> {
+    let dir = std::env::temp_dir().join("life_web_fixtures");
+    let (seed, steps) = (3, 200);
+    // Start without a cached snapshot, it may not exist yet.
+    let _ = std::fs::remove_file(fixture::fixture_path(&dir, seed, steps));
+    let encode = |dir: &std::path::Path| {
+        fixture::mature(dir, seed, steps)
+            .and_then(|simulation| simulation.encode())
+            .map_err(|error| error.to_string())
+    };
+    let simulated = encode(&dir)?;
+    if !fixture::fixture_path(&dir, seed, steps).exists() {
+        return Err(String::from("the mature ecosystem was not cached"));
+    }
+    if encode(&dir)? != simulated {
+        return Err(String::from(
+            "the cached ecosystem differs from the simulated one",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that exported genomes are read back unchanged and seed new simulations.
+fn check_library() -> Result<(), String> {
+    let dir = std::env::temp_dir().join("life_web_genomes");
+    let mut rng = Rng::new(5);
+    let world = World::new(400.0, 300.0);
+    let exported = [
+        Biot::grazer(&world, &mut rng),
+        Biot::predator(&world, &mut rng),
+    ]
+    .iter()
+    .enumerate()
+    .map(|(n, biot)| {
+        library::export(biot.genome(), &dir, &format!("check-{n}")).map(|_| biot.genome().clone())
+    })
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|error| error.to_string())?;
+    let paths = (0..exported.len())
+        .map(|n| {
+            dir.join(format!("check-{n}.{}", library::EXTENSION))
+                .display()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    let genomes = library::resolve(&paths.join(",")).map_err(|error| error.to_string())?;
+    if genomes != exported {
+        return Err(String::from(
+            "the genomes changed on their way through the library",
+        ));
+    }
+    let config = SimConfig {
+        initial_population: 10,
+        ..SimConfig::default()
+    };
+    let simulation = Simulation::with_genomes(world, config, 0, &genomes);
+    let biots = simulation.biots();
+    let seeded = biots
+        .iter()
+        .filter(|biot| genomes.contains(biot.genome()))
+        .count();
+    if seeded != 10 || biots.len() != 10 {
+        return Err(format!(
+            "{seeded} of {} biots carry a genome of the library",
+            biots.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the data shown on biot cards: genome strings, lineage depths and the seed.
+fn check_card_data() -> Result<(), String> {
+    let config = SimConfig {
+        initial_population: 100,
+        ..SimConfig::default()
+    };
+    let mut simulation = Simulation::new(World::new(400.0, 300.0), config, 11);
+    for _ in 0..300 {
+        simulation.step();
+    }
+    let phylogeny = simulation.biots().phylogeny();
+    for biot in simulation.biots().iter() {
+        let depth = phylogeny.depth(biot.id());
+        if (depth == 0) != (biot.id() == biot.lineage()) {
+            return Err(format!("biot {} is {depth} generations deep", biot.id().0));
+        }
+        if biot.genome().code().chars().count() != biot.genome().len() {
+            return Err(format!("the genome string of biot {} is off", biot.id().0));
+        }
+    }
+    let bytes = simulation.encode().map_err(|error| error.to_string())?;
+    let decoded = Simulation::decode(&bytes).map_err(|error| error.to_string())?;
+    if decoded.seed() != 11 {
+        return Err(format!("the seed {} was not restored", decoded.seed()));
+    }
+    Ok(())
+}
+
+/// Checks that a built-in scenario is valid and starts with exactly the biots it places.
+fn check_start(start: Start) -> Result<(), String> {
+    let spec = start.spec().map_err(|error| error.to_string())?;
+    let simulation = spec.simulation(World::new(800.0, 600.0), 0);
+    let placed = spec
+        .biots
+        .iter()
+        .map(|placement| placement.count)
+        .sum::<usize>();
+    if simulation.biots().len() != placed {
+        return Err(format!(
+            "started with {} instead of {placed} biots",
+            simulation.biots().len()
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that queued files are uploaded to a directory before the uploader is
+/// dropped, in the state they were queued in.
+fn check_upload() -> Result<(), String> {
+    // Named after the process, so that parallel runs do not collide.
+    let id = std::process::id();
+    let dir = std::env::temp_dir().join(format!("life_web_uploads_{id}"));
+    let name = format!("life_web_upload_{id}.csv");
+    let source = std::env::temp_dir().join(&name);
+    let uploaded = dir.join(&name);
+    let _ = std::fs::remove_file(&uploaded);
+    std::fs::write(&source, "step,population\n0,1\n").map_err(|error| error.to_string())?;
+    let destination = Destination::from(dir.to_string_lossy().as_ref());
+    if destination != Destination::Directory(dir.clone()) {
+        return Err(format!("{destination} was not parsed as a directory"));
+    }
+    let mut uploader = Uploader::new(destination);
+    uploader.upload(&source);
+    // Rewriting the file, e.g. by the next autosave, must not tear the upload.
+    std::fs::write(&source, "step,population\n").map_err(|error| error.to_string())?;
+    drop(uploader);
+    let result = match std::fs::read(&uploaded) {
+        Ok(contents) if contents == b"step,population\n0,1\n" => Ok(()),
+        Ok(_) => Err(String::from(
+            "the uploaded file differs from the queued one",
+        )),
+        Err(error) => Err(format!("the file was not uploaded: {error}")),
+    };
+    let _ = std::fs::remove_file(&source);
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+/// Checks that the clock advances the simulation by the same number of
+/// steps per second at any frame rate and does not catch up with hitches.
+fn check_clock() -> Result<(), String> {
+    for fps in [24, 60, 75, 144, 240] {
+        let mut clock = Clock::new(120);
+        let steps = (0..10 * fps)
+            .map(|_| clock.advance(1.0 / fps as f32))
+            .sum::<u32>();
+        if steps.abs_diff(1200) > 1 {
+            return Err(format!(
+                "{steps} steps in 10 seconds at {fps} frames per second"
+            ));
+        }
+    }
+    let mut clock = Clock::new(120);
+    if clock.advance(2.0) > 120 / 10 {
+        return Err(String::from("a hitch of two seconds was caught up with"));
+    }
+    clock.faster();
+    let faster = clock.ticks_per_second();
+    clock.slower();
+    if faster <= 120 || clock.ticks_per_second() != 120 {
+        return Err(format!(
+            "speeds {faster} and {} around 120",
+            clock.ticks_per_second()
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the watchdog stays quiet for a healthy simulation and reports
+/// each kind of pathological state once when it appears.
+fn check_watchdog() -> Result<(), String> {
+    let config = SimConfig {
+        initial_population: 150,
+        watchdog: WatchdogConfig {
+            interval: 5,
+            max_growth: 2.0,
+            action: WatchdogAction::Pause,
+            ..WatchdogConfig::default()
+        },
+        ..SimConfig::default()
+    };
+    let world = World::new(WORLD_SIZE, WORLD_SIZE);
+    let mut simulation = Simulation::new(world, config, 0);
+    let mut watchdog = Watchdog::new();
+    for _ in 0..50 {
+        simulation.step();
+        if let Some((anomalies, _)) = watchdog.check(&simulation, None) {
+            return Err(format!("a healthy simulation raised {anomalies:?}"));
+        }
+    }
+    let slow = Some(Duration::from_secs(60));
+    match watchdog.check(&simulation, slow) {
+        Some((anomalies, WatchdogAction::Pause)) if anomalies.len() == 1 => {}
+        reported => return Err(format!("a slow step raised {reported:?}")),
+    }
+    if watchdog.check(&simulation, slow).is_some() {
+        return Err(String::from("a lasting slow step was reported twice"));
+    }
+    let mut rng = Rng::new(0);
+    for _ in 0..500 {
+        let biot = Biot::random_biot(&world, simulation.biots().config(), &mut rng);
+        simulation.biots_mut().spawn(biot);
+    }
+    let mut growth = false;
+    for _ in 0..5 {
+        simulation.step();
+        let anomalies = watchdog
+            .check(&simulation, None)
+            .map(|(anomalies, _)| anomalies);
+        growth |= anomalies
+            .unwrap_or_default()
+            .iter()
+            .any(|anomaly| matches!(anomaly, Anomaly::Growth { .. }));
+    }
+    if !growth {
+        return Err(String::from(
+            "a population more than doubling was not reported",
+        ));
+    }
+    let mut broken = Biot::random_biot(&world, simulation.biots().config(), &mut rng);
+    broken.stats.life = Energy(f32::NAN);
+    simulation.biots_mut().spawn(broken);
+    match watchdog.check(&simulation, None) {
+        Some((anomalies, _)) if matches!(anomalies[..], [Anomaly::NonFinite { .. }]) => Ok(()),
+        reported => Err(format!("non-finite lives raised {reported:?}")),
+    }
+}
+
+/// Checks that biots with non-finite state are sanitized and reported for
+/// the step they were sanitized in only.
+fn check_sanitized() -> Result<(), String> {
+    let world = World::new(WORLD_SIZE, WORLD_SIZE);
+    let config = SimConfig {
+        initial_population: 0,
+        ..SimConfig::default()
+    };
+    let mut simulation = Simulation::new(world, config, 4);
+    let mut broken = Biot::random_biot(&world, simulation.biots().config(), &mut Rng::new(4));
+    broken.stats.speed = Vec2::NAN;
+    let genome = broken.genome().clone();
+    let id = simulation.biots_mut().spawn(broken);
+    simulation.step();
+    let sanitized = simulation.biots().sanitized();
+    if sanitized.len() != 1 || sanitized[0] != genome {
+        return Err(format!("sanitizing biot {} reported {sanitized:?}", id.0));
+    }
+    let biot = simulation
+        .biots()
+        .get(id)
+        .ok_or("the sanitized biot died")?;
+    if !biot.stats.pos.is_finite() || !biot.stats.speed.is_finite() {
+        return Err(format!("biot {} kept a non-finite state", id.0));
+    }
+    simulation.step();
+    if !simulation.biots().sanitized().is_empty() {
+        return Err(String::from("a biot sanitized before was reported again"));
+    }
+    Ok(())
+}
+
+/// Checks that reconfiguring a running simulation rejects invalid parameters
+/// and that valid ones take effect with the next step.
+fn check_reconfigure() -> Result<(), String> {
+    let world = World::new(WORLD_SIZE, WORLD_SIZE);
+    let mut tuned = Simulation::new(world, SimConfig::default(), 9);
+    let mut plain = Simulation::new(world, SimConfig::default(), 9);
+    let invalid = SimConfig {
+        max_interaction_radius: 0.0,
+        ..SimConfig::default()
+    };
+    if tuned.reconfigure(invalid).is_ok() {
+        return Err(String::from("an invalid configuration was accepted"));
+    }
+    if tuned.biots().config().max_interaction_radius <= 0.0 {
+        return Err(String::from("a rejected configuration was applied"));
+    }
+    let mut config = SimConfig::default();
+    config.metabolism.scale *= 10.0;
+    tuned
+        .reconfigure(config)
+        .map_err(|error| error.to_string())?;
+    for _ in 0..20 {
+        tuned.step();
+        plain.step();
+    }
+    if tuned.biots().total_life() >= plain.biots().total_life() {
+        return Err(String::from(
+            "a tenfold metabolism did not cost the biots any life",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the identifier of a biot keeps referring to the same biot
+/// while others are born and die around it, so the viewer can follow it.
+fn check_stable_ids() -> Result<(), String> {
+    let config = SimConfig {
+        mutation_chance: 0.0,
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    for n in 0..20 {
+        let (x, y) = (10.0 * (n % 5) as f32, 10.0 * (n / 5) as f32);
+        scenario.place(Biot::plant, 50.0 + x, 50.0 + y);
+    }
+    let followed = scenario.place(Biot::grazer, 75.0, 75.0);
+    let mut turnover = 0;
+    let mut age = Ticks(0);
+    for _ in 0..200 {
+        scenario.run(1);
+        let biots = scenario.simulation().biots();
+        turnover += biots.births() as usize + biots.deaths().len();
+        let Some(biot) = scenario.biot(followed) else {
+            break;
+        };
+        if biot.id() != followed || biot.lineage() != followed || biot.stats.age.0 != age.0 + 1 {
+            return Err(format!("identifier {} moved to another biot", followed.0));
+        }
+        age = biot.stats.age;
+    }
+    if turnover == 0 {
+        return Err(String::from(
+            "no biot was born or died around the followed one",
+        ));
+    }
+    Ok(())
+}
+
+/// Hooks remembering every phase that began or ended.
+#[derive(Default)]
+struct PhaseTrace {
+    /// The phase, the step and whether the phase began rather than ended.
+    calls: Vec<(Phase, u32, bool)>,
+}
+
+impl StepHooks for PhaseTrace {
+    fn begin(&mut self, phase: Phase, step: u32) {
+        self.calls.push((phase, step, true));
+    }
+
+    fn end(&mut self, phase: Phase, step: u32, _elapsed: Duration) {
+        self.calls.push((phase, step, false));
+    }
+}
+
+/// Checks that every phase of a step begins and ends once, in order, and
+/// that observing the phases does not change the simulation.
+fn check_step_hooks() -> Result<(), String> {
+    let config = SimConfig {
+        initial_population: 60,
+        reproduction: Reproduction::Sexual(20.0),
+        ..SimConfig::default()
+    };
+    let world = World::new(WORLD_SIZE, WORLD_SIZE);
+    let mut observed = Simulation::new(world, config.clone(), 4);
+    let mut plain = Simulation::new(world, config, 4);
+    let mut trace = PhaseTrace::default();
+    for _ in 0..20 {
+        observed.step_with(&mut trace);
+        plain.step();
+    }
+    let expected = (1..=20).flat_map(|step| {
+        Phase::ALL
+            .into_iter()
+            .flat_map(move |phase| [(phase, step, true), (phase, step, false)])
+    });
+    if !trace.calls.iter().copied().eq(expected) {
+        return Err(String::from(
+            "the phases were not reported once per step in order",
+        ));
+    }
+    let (observed, plain) = (observed.biots(), plain.biots());
+    if observed.len() != plain.len() || observed.total_life() != plain.total_life() {
+        return Err(String::from("observing the phases changed the simulation"));
+    }
+    Ok(())
+}
+
+/// Checks that an experiment runs every combination of the swept values
+/// with every seed and that its results do not depend on the number of threads.
+fn check_experiment() -> Result<(), String> {
+    let sweeps = ["initial_population=20,40", "mutation_chance=0.1,0.3"]
+        .iter()
+        .map(|sweep| sweep.parse::<Sweep>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| error.to_string())?;
+    if "initial_population".parse::<Sweep>().is_ok() {
+        return Err(String::from("a sweep without values was accepted"));
+    }
+    let experiment = Experiment::new("", sweeps, 2, 0, 30).map_err(|error| error.to_string())?;
+    if experiment.runs() != 8 {
+        return Err(format!("{} runs instead of 8", experiment.runs()));
+    }
+    let sequential = experiment.run(1, |_| {});
+    let parallel = experiment.run(3, |_| {});
+    if sequential != parallel {
+        return Err(String::from("the results depend on the number of threads"));
+    }
+    let path = std::env::temp_dir().join("life_web_experiment.csv");
+    experiment
+        .save(&parallel, &path)
+        .map_err(|error| error.to_string())?;
+    let contents = std::fs::read_to_string(&path).map_err(|error| error.to_string())?;
+    let mut lines = contents.lines();
+    let header = lines.next().unwrap_or_default();
+    if !header.starts_with("initial_population,mutation_chance,seed,") {
+        return Err(format!("unexpected header `{header}`"));
+    }
+    match lines.next() {
+        Some(row) if row.starts_with("20,0.1,0,30,") && contents.lines().count() == 9 => Ok(()),
+        _ => Err(String::from("the results are not one row per run in order")),
+    }
+}
+
+/// Checks that the initial population follows the configured gene chances
+/// and that the remaining chance is shared by the other genes.
+fn check_initial_genes() -> Result<(), String> {
+    let uniform = GeneDistribution::initial(&SimConfig::default());
+    if (uniform.chance(Gene::Attack) - uniform.chance(Gene::Photosynthesis)).abs() > 1e-6 {
+        return Err(String::from("genes are not equally likely without chances"));
+    }
+    let config = SimConfig::from_toml(
+        "initial_population = 400\n[initial_genes]\nphotosynthesis = 0.7\nattack = 0.0",
+    )
+    .map_err(|error| error.to_string())?;
+    let distribution = GeneDistribution::initial(&config);
+    let others = Gene::pool(&config).len() - 2;
+    if (distribution.chance(Gene::Defense) - 0.3 / others as f32).abs() > 1e-6 {
+        return Err(format!(
+            "a chance of {} of defense instead of sharing the rest",
+            distribution.chance(Gene::Defense)
+        ));
+    }
+    let simulation = Simulation::new(World::new(WORLD_SIZE, WORLD_SIZE), config, 0);
+    let genes = simulation
+        .biots()
+        .iter()
+        .flat_map(|biot| biot.genome().genes().copied())
+        .collect::<Vec<_>>();
+    let share = |wanted: Gene| {
+        genes.iter().filter(|gene| **gene == wanted).count() as f32 / genes.len() as f32
+    };
+    if (share(Gene::Photosynthesis) - 0.7).abs() > 0.03 || share(Gene::Attack) > 0.0 {
+        return Err(format!(
+            "{:.2} photosynthesis and {:.2} attack genes instead of 0.7 and none",
+            share(Gene::Photosynthesis),
+            share(Gene::Attack)
+        ));
+    }
+    if SimConfig::from_toml("[initial_genes]\nphotosynthesis = 0.7\nmotion = 0.5").is_ok() {
+        return Err(String::from(
+            "chances adding up to more than 1 were accepted",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the timeline keeps a bounded number of snapshots and that a
+/// rewound simulation evolves exactly like the original one did.
+fn check_rewind() -> Result<(), String> {
+    let config = SimConfig {
+        initial_population: 100,
+        ..SimConfig::default()
+    };
+    let mut simulation = Simulation::new(World::new(400.0, 300.0), config, 0);
+    let mut timeline = Timeline::new(10, 3);
+    let mut population = 0;
+    for step in 1..=50 {
+        simulation.step();
+        timeline
+            .record(&simulation)
+            .map_err(|error| error.to_string())?;
+        if step == 40 {
+            population = simulation.biots().len();
+        }
+    }
+    if !timeline.steps().eq([30, 40, 50]) {
+        let steps = timeline.steps().collect::<Vec<_>>();
+        return Err(format!(
+            "the timeline kept the snapshots of steps {steps:?}"
+        ));
+    }
+    timeline.rewind();
+    let mut rewound = match timeline.rewind() {
+        Some(rewound) => rewound.map_err(|error| error.to_string())?,
+        None => return Err(String::from("the timeline ran out of snapshots")),
+    };
+    if rewound.biots().steps() != 40 || rewound.biots().len() != population {
+        return Err(format!(
+            "rewound to step {} with {} instead of {population} biots",
+            rewound.biots().steps(),
+            rewound.biots().len()
+        ));
+    }
+    for _ in 0..10 {
+        rewound.step();
+    }
+    if rewound.biots().len() != simulation.biots().len() {
+        return Err(String::from("the rewound simulation evolved differently"));
+    }
+    Ok(())
+}
+
+/// Checks that the random streams of the subsystems do not depend on each other.
+fn check_rng_streams() -> Result<(), String> {
+    let (mut first, mut second) = (RngStreams::new(7), RngStreams::new(7));
+    for _ in 0..10 {
+        first.placement.gen_seed();
+        first.mutation.gen_seed();
+    }
+    if first.movement.gen_seed() != second.movement.gen_seed() {
+        return Err(String::from(
+            "drawing placements changed the movement stream",
+        ));
+    }
+    if first.encounters.gen_seed() != second.encounters.gen_seed() {
+        return Err(String::from(
+            "drawing mutations changed the encounter stream",
+        ));
+    }
+    if second.placement.gen_seed() == second.movement.gen_seed() {
+        return Err(String::from("the placement and movement streams coincide"));
+    }
+    let seeds = first.step_seeds();
+    let (mut item, mut again) = (seeds.item(3), seeds.item(3));
+    if item.mutation.gen_seed() != again.mutation.gen_seed() {
+        return Err(String::from(
+            "the streams of a work item are not reproducible",
+        ));
+    }
+    if seeds.item(4).mutation.gen_seed() == seeds.item(3).mutation.gen_seed() {
+        return Err(String::from("two work items share a stream"));
+    }
+    Ok(())
+}
+
+/// Checks that eating a venomous plant kills a predator unless its armor shields it.
+fn check_venom() -> Result<(), String> {
+    let venomous = GenomeBuilder::new()
+        .with(Gene::Photosynthesis, 10)
+        .with(Gene::Venom, 22)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let armored = GenomeBuilder::new()
+        .with(Gene::Attack, 16)
+        .with(Gene::Motion, 6)
+        .with(Gene::Armor, 22)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let mut scenario = Scenario::new();
+    let predator = scenario.place(Biot::predator, 10.0, 10.0);
+    let genome = venomous.clone();
+    scenario.place(
+        |world, rng| Biot::from_genome(genome, world, rng),
+        15.0,
+        10.0,
+    );
+    let knight = scenario.place(
+        |world, rng| Biot::from_genome(armored, world, rng),
+        210.0,
+        10.0,
+    );
+    scenario.place(
+        |world, rng| Biot::from_genome(venomous, world, rng),
+        215.0,
+        10.0,
+    );
+    scenario.run(1);
+    if !scenario.is_dead(predator) {
+        return Err(String::from(
+            "the predator survived eating a venomous plant",
+        ));
+    }
+    if !scenario
+        .simulation()
+        .biots()
+        .deaths()
+        .contains(&CauseOfDeath::Venom)
+    {
+        return Err(String::from("the predator did not die of venom"));
+    }
+    if scenario.is_dead(knight) {
+        return Err(String::from("the armor did not shield against the venom"));
+    }
+    Ok(())
+}
+
+/// Checks that custom genes keep their identifiers and take the effects of their definition.
+fn check_custom_genes() -> Result<(), String> {
+    let config = SimConfig::from_toml(
+        "[[genes]]\nid = 3\nname = \"spikes\"\nattack = 0.5\ndefense = 0.5\ncost = 0.1\n",
+    )
+    .map_err(|error| error.to_string())?;
+    let duplicate = "[[genes]]\nid = 1\n[[genes]]\nid = 1\n";
+    if SimConfig::from_toml(duplicate).is_ok() {
+        return Err(String::from("two genes with the same id were accepted"));
+    }
+    let genes = Gene::EXPRESSED
+        .into_iter()
+        .chain([Gene::None, Gene::Custom(3)]);
+    for gene in genes {
+        if Gene::try_from(u8::from(gene)) != Ok(gene) {
+            return Err(format!("{gene:?} does not keep its id"));
+        }
+    }
+    if !Gene::pool(&config).contains(&Gene::Custom(3)) {
+        return Err(String::from("the custom gene does not evolve"));
+    }
+    let genome = GenomeBuilder::new()
+        .with(Gene::Custom(3), 10)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let world = World::new(100.0, 100.0);
+    let mut biot = Biot::from_genome(genome.clone(), &world, &mut Rng::new(0));
+    biot.set_genome(genome, &config);
+    let properties = &biot.properties;
+    if (properties.attack - 0.5).abs() > 1e-4 || (properties.defense - 0.5).abs() > 1e-4 {
+        return Err(format!(
+            "ten spikes genes give an attack of {} and a defense of {}",
+            properties.attack, properties.defense
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that response curves shape the properties but not their metabolism.
+fn check_responses() -> Result<(), String> {
+    let toml = "[responses.intelligence]\nshape = \"sqrt\"\n\
+                [responses.attack]\ncap = 2.0\n\
+                [responses.defense]\nthreshold = 1.0\nshape = { power = 0.5 }\n";
+    let config = SimConfig::from_toml(toml).map_err(|error| error.to_string())?;
+    let logistic = "[responses.motion]\nshape = { logistic = { midpoint = 4.0, steepness = -1.0, \
+                    height = 8.0 } }\n";
+    if SimConfig::from_toml(logistic).is_ok() {
+        return Err(String::from("a falling logistic response was accepted"));
+    }
+    let genome = GenomeBuilder::new()
+        .with(Gene::Intelligence, 4)
+        .with(Gene::Attack, 5)
+        .with(Gene::Defense, 4)
+        .build()
+        .map_err(|error| error.to_string())?;
+    let world = World::new(100.0, 100.0);
+    let mut biot = Biot::from_genome(genome.clone(), &world, &mut Rng::new(0));
+    let linear = biot.properties.clone();
+    biot.set_genome(genome, &config);
+    let properties = &biot.properties;
+    // Four genes are worth sqrt(4) = 2 genes.
+    if (properties.intelligence - linear.intelligence / 2.0).abs() > 1e-4 {
+        return Err(format!(
+            "four intelligence genes give an intelligence of {}",
+            properties.intelligence
+        ));
+    }
+    if (properties.attack - 0.4 * linear.attack).abs() > 1e-4 {
+        return Err(format!(
+            "five attack genes capped at two give an attack of {}",
+            properties.attack
+        ));
+    }
+    // Three genes beyond the threshold are worth 3^0.5 genes.
+    if (properties.defense - linear.defense / 4.0 * 3f32.sqrt()).abs() > 1e-4 {
+        return Err(format!(
+            "four defense genes beyond one without effect give a defense of {}",
+            properties.defense
+        ));
+    }
+    if properties.expressed != linear.expressed {
+        return Err(String::from(
+            "response curves change the expressed properties",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that biots close to the edges stay within a world of the given topology.
+///
+/// # Note
+///
+/// Only walls may kill biots hitting an edge.
+fn check_topology(topology: Topology) -> Result<(), String> {
+    let config = SimConfig {
+        topology,
+        ..SimConfig::default()
+    };
+    let mut scenario = Scenario::with_config(config);
+    for offset in [1.0, 5.0, 10.0] {
+        scenario.place(Biot::grazer, offset, 100.0);
+        scenario.place(Biot::smart_predator, 100.0, 200.0 - offset);
+        scenario.place(Biot::grazer, 200.0 - offset, 200.0 - offset);
+    }
+    for step in 0..500 {
+        scenario.run(1);
+        let simulation = scenario.simulation();
+        let world = simulation.world();
+        let outside = |biot: &&Biot| {
+            let pos = biot.stats.pos;
+            pos.x < 0.0 || pos.y < 0.0 || pos.x > world.width || pos.y > world.height
+        };
+        if let Some(biot) = simulation.biots().iter().find(outside) {
+            return Err(format!(
+                "step {step}: biot {} left the world at {}",
+                biot.id().0,
+                biot.stats.pos
+            ));
+        }
+        let hit_wall = simulation.biots().deaths().contains(&CauseOfDeath::Wall);
+        if hit_wall && topology != Topology::Walls {
+            return Err(format!("step {step}: a biot died at a wall"));
+        }
+    }
+    Ok(())
+}
+
+/// Checks that biots near opposite edges of a torus sense and pick each
+/// other across the edge, but not through the walls of a bounded world.
+fn check_wrapped_neighbours() -> Result<(), String> {
+    let sensed = |topology: Topology| {
+        let config = SimConfig {
+            topology,
+            ..SimConfig::default()
+        };
+        let mut scenario = Scenario::with_config(config);
+        let hunter = scenario.place(Biot::smart_predator, 10.0, 100.0);
+        let size = scenario.simulation().world().width;
+        scenario.place(Biot::plant, size - 10.0, 100.0);
+        scenario.run(1);
+        let feed_dir = scenario
+            .biot(hunter)
+            .ok_or("the hunter died")?
+            .stats
+            .feed_dir;
+        Ok::<_, String>(feed_dir)
+    };
+    match sensed(Topology::Torus)? {
+        Some(dir) if dir.x < 0.0 => {}
+        dir => {
+            return Err(format!(
+                "the hunter heads {dir:?} instead of across the edge"
+            ))
+        }
+    }
+    if let Some(dir) = sensed(Topology::Walls)?.filter(|dir| dir.x < 0.0) {
+        return Err(format!("the hunter heads {dir} through a wall"));
+    }
+    let mut scenario = Scenario::new();
+    let plant = scenario.place(Biot::plant, 1.0, 100.0);
+    let simulation = scenario.simulation();
+    let world = simulation.world();
+    let picked = simulation
+        .biots()
+        .pick(world, vec2(world.width - 1.0, 100.0), Distance(0.0))
+        .map(Biot::id);
+    if picked != Some(plant) {
+        return Err(String::from("a plant was not picked across the edge"));
+    }
+    Ok(())
+}
+
+/// Checks that biots with degenerate genomes never reach a non-finite state.
+///
+/// # Note
+///
+/// Several biots are stacked at the same position so that intelligent biots
+/// sense prey at a distance of zero.
+fn check_degenerate_genomes(config: SimConfig) -> Result<(), String> {
+    let genomes = [
+        // No weight at all, so the biot is born without life.
+        GenomeBuilder::new(),
+        GenomeBuilder::new().with(Gene::Attack, GENOME_LEN),
+        GenomeBuilder::new().with(Gene::Motion, GENOME_LEN),
+        // Intelligence does not add any weight either.
+        GenomeBuilder::new().with(Gene::Intelligence, GENOME_LEN),
+        GenomeBuilder::new()
+            .with(Gene::Attack, 1)
+            .with(Gene::Motion, 1)
+            .with(Gene::Intelligence, 1),
+    ];
+    let mut scenario = Scenario::with_config(config);
+    for builder in genomes {
+        let genome = builder.build().map_err(|error| error.to_string())?;
+        for _ in 0..5 {
+            let create =
+                |world: &World, rng: &mut Rng| Biot::from_genome(genome.clone(), world, rng);
+            scenario.place(create, 100.0, 100.0);
+        }
+    }
+    for step in 0..100 {
+        scenario.run(1);
+        check_state(scenario.simulation()).map_err(|message| format!("step {step}: {message}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Panics with all failed checks of `results`.
+    fn assert_held(results: impl IntoIterator<Item = (String, Result<(), String>)>) {
+        let violations = results
+            .into_iter()
+            .filter_map(|(context, result)| Some(format!("{context}: {}", result.err()?)))
+            .collect::<Vec<_>>();
+        assert!(violations.is_empty(), "{}", violations.join("\n"));
+    }
+
+    #[test]
+    fn micro_simulations() {
+        assert_held(SEEDS.iter().map(|&seed| {
+            let context = format!("micro-simulation with seed {seed}");
+            (context, check_micro_simulation(seed))
+        }));
+    }
+
+    #[test]
+    fn variants() {
+        assert_held(check_variants());
+    }
+}
@@ -0,0 +1,785 @@
+use crate::archipelago::Archipelago;
+use crate::audio::Audio;
+use crate::biot::{Biot, BiotId, CauseOfDeath};
+use crate::camera::Camera;
+use crate::capture::{self, Timelapse};
+use crate::card;
+use crate::clock::Clock;
+use crate::control_panel::{ControlPanel, Setting, Views};
+use crate::events::{EventLog, Toasts, EVENT_CAPACITY};
+use crate::filter::Filter;
+use crate::hotspot::AutoCamera;
+use crate::inspector::Inspector;
+use crate::interpolation::Interpolation;
+use crate::labels::Labels;
+use crate::layer::{Layer, LayerMap};
+use crate::library::{self, LIBRARY_DIR};
+use crate::lineages::LineageStats;
+use crate::minimap;
+use crate::phase_plot::PhasePlot;
+use crate::recorder::{self, Recorder};
+use crate::rewind::{self, Timeline};
+use crate::scene::{Context, Scene, Transition};
+use crate::search::Search;
+use crate::selection::SelectionAnalysis;
+use crate::simulation::{Resize, Simulation, SNAPSHOT_PATH};
+use crate::species::Coloring;
+use crate::stats::{PopulationStats, SizeDistribution};
+use crate::tools::{Tool, Tools};
+use crate::trails::Trails;
+use crate::watchdog::{self, Watchdog};
+use macroquad::prelude::*;
+use std::time::Duration;
+
+/// Sensing interval used when approximate sensing is enabled.
+const APPROXIMATE_SENSING_INTERVAL: u32 = 8;
+
+/// Zoom of the auto-camera.
+const AUTO_CAMERA_ZOOM: f32 = 2.0;
+
+/// Factor the world grows by when enlarged by the hotkey, it shrinks by its inverse.
+const WORLD_GROWTH: f32 = 1.25;
+
+/// Number of seconds the latest dominance alarm is shown.
+const ALARM_SECONDS: f64 = 8.0;
+
+/// File the family tree of the living biots is exported to by the hotkey.
+const PHYLOGENY_PATH: &str = "life_web.nwk";
+
+/// File the history of the living lineages is exported to by the hotkey.
+const LINEAGES_PATH: &str = "life_web_lineages.csv";
+
+/// Button going back to the menu on touch screens, which lack an `Escape` key.
+const BACK_BUTTON: Rect = Rect {
+    x: 10.,
+    y: 10.,
+    w: 80.,
+    h: 36.,
+};
+
+/// Scene showing a running simulation with its overlays.
+pub struct Viewer {
+    simulation: Simulation,
+    camera: Camera,
+    auto_camera: AutoCamera,
+    /// Whether the auto-camera moves the camera.
+    follow_hotspots: bool,
+    /// Whether the camera tracks the biot selected in the inspector.
+    follow_selected: bool,
+    /// Prompt for the identifier of a biot to jump to.
+    search: Search,
+    recorder: Option<Recorder>,
+    inspector: Inspector,
+    /// Tool applied by clicking into the world.
+    tools: Tools,
+    labels: Labels,
+    filter: Filter,
+    /// Panel tuning the parameters of the simulation while it runs.
+    control_panel: ControlPanel,
+    coloring: Coloring,
+    show_scent: bool,
+    show_currents: bool,
+    /// Layer shown below the biots, if any.
+    layer: Option<Layer>,
+    /// The latest computation of the shown layer.
+    layer_map: Option<LayerMap>,
+    /// Recent positions of the biots, only recorded while shown.
+    trails: Trails,
+    show_trails: bool,
+    phase_plot: PhasePlot,
+    show_phase_plot: bool,
+    selection: SelectionAnalysis,
+    show_selection: bool,
+    stats: PopulationStats,
+    show_stats: bool,
+    show_sizes: bool,
+    lineages: LineageStats,
+    show_lineages: bool,
+    starved: u32,
+    died_of_age: u32,
+    eaten: u32,
+    nonviable: u32,
+    hit_wall: u32,
+    poisoned: u32,
+    diseased: u32,
+    /// The latest dominance alarm and the time it was raised at.
+    alarm: Option<(String, f64)>,
+    events: EventLog,
+    /// Notifications about the notable events of the simulation.
+    toasts: Toasts,
+    /// File the event log is written to when the viewer is closed.
+    events_path: Option<String>,
+    paused: bool,
+    /// Turns the time that passed into simulation steps.
+    clock: Clock,
+    /// Positions the biots are drawn at in between two steps.
+    interpolation: Interpolation,
+    /// Recent snapshots of the simulation to rewind to.
+    timeline: Timeline,
+    /// Detects pathological states of the simulation.
+    watchdog: Watchdog,
+    /// Writes a frame every few steps, if any.
+    timelapse: Option<Timelapse>,
+    /// The islands exchanging migrants with the shown simulation, if any.
+    archipelago: Option<Archipelago>,
+    /// Index of the shown simulation among the islands.
+    island: usize,
+}
+
+impl Viewer {
+    /// Creates a viewer for `simulation`, recording it with `recorder` if given.
+    ///
+    /// The event log is written to `events_path`, if given, once the viewer is closed.
+    /// `labels` are shown next to the biots when zoomed in. The simulation
+    /// starts out advancing by `ticks_per_second` steps per second, its
+    /// frames are written to `timelapse` if given.
+    pub fn new(
+        simulation: Simulation,
+        recorder: Option<Recorder>,
+        events_path: Option<String>,
+        labels: Labels,
+        ticks_per_second: u32,
+        timelapse: Option<Timelapse>,
+    ) -> Self {
+        let camera = Camera::new(simulation.world());
+        let auto_camera = AutoCamera::new(simulation.world());
+        Self {
+            simulation,
+            camera,
+            auto_camera,
+            follow_hotspots: false,
+            follow_selected: false,
+            search: Search::new(),
+            recorder,
+            inspector: Inspector::new(),
+            tools: Tools::new(),
+            labels,
+            filter: Filter::new(),
+            control_panel: ControlPanel::new(),
+            coloring: Coloring::Traits,
+            show_scent: false,
+            show_currents: false,
+            layer: None,
+            layer_map: None,
+            trails: Trails::new(),
+            show_trails: false,
+            phase_plot: PhasePlot::new(),
+            show_phase_plot: false,
+            selection: SelectionAnalysis::new(),
+            show_selection: false,
+            stats: PopulationStats::new(),
+            show_stats: false,
+            show_sizes: false,
+            lineages: LineageStats::new(),
+            show_lineages: false,
+            starved: 0,
+            died_of_age: 0,
+            eaten: 0,
+            nonviable: 0,
+            hit_wall: 0,
+            poisoned: 0,
+            diseased: 0,
+            alarm: None,
+            events: EventLog::new(EVENT_CAPACITY),
+            toasts: Toasts::new(),
+            events_path,
+            paused: false,
+            clock: Clock::new(ticks_per_second),
+            interpolation: Interpolation::new(),
+            timeline: Timeline::new(rewind::DEFAULT_INTERVAL, rewind::DEFAULT_CAPACITY),
+            watchdog: Watchdog::new(),
+            timelapse,
+            archipelago: None,
+            island: 0,
+        }
+    }
+
+    /// Shows the simulation of the viewer as the first of several islands
+    /// exchanging migrants with the `archipelago`, see `Tab`.
+    pub fn with_islands(mut self, archipelago: Archipelago) -> Self {
+        self.archipelago = Some(archipelago);
+        self
+    }
+
+    /// Computes a single simulation step and records it.
+    fn step(&mut self) {
+        let simulation = &mut self.simulation;
+        let start = get_time();
+        simulation.step();
+        let elapsed = Duration::from_secs_f64(get_time() - start);
+        if let Some(archipelago) = &mut self.archipelago {
+            archipelago.step_around(simulation, self.island);
+        }
+        if let Some((anomalies, action)) = self.watchdog.check(simulation, Some(elapsed)) {
+            self.paused |= watchdog::respond(simulation, &anomalies, action);
+            let anomalies = anomalies.iter().map(ToString::to_string);
+            let message = format!("watchdog: {}", anomalies.collect::<Vec<_>>().join(", "));
+            self.alarm = Some((message, get_time()));
+        }
+        for failure in simulation.biots().rule_failures() {
+            eprintln!("rules dropped: {failure}");
+            self.alarm = Some((format!("rules dropped: {failure}"), get_time()));
+        }
+        for genome in simulation.biots().sanitized() {
+            eprintln!("sanitized non-finite state of a biot with genome {genome:?}");
+        }
+        recorder::record(&mut self.recorder, simulation);
+        self.inspector.record(simulation);
+        self.auto_camera.record(simulation);
+        self.phase_plot.record(simulation.biots());
+        self.events.record(simulation);
+        self.toasts.notify(&self.events);
+        let date = simulation.date();
+        self.selection.record(simulation.biots(), date);
+        self.stats.record(simulation.biots(), date);
+        self.lineages.record(simulation.biots());
+        if self.show_trails {
+            self.trails.record(simulation.biots());
+        }
+        if let Err(error) = self.timeline.record(simulation) {
+            eprintln!("failed to take a rewind snapshot: {error}");
+        }
+        if let Some(alarm) = simulation.alarm() {
+            self.alarm = Some((format!("{date}: {alarm}"), get_time()));
+        }
+        for cause in simulation.biots().deaths() {
+            match cause {
+                CauseOfDeath::Starvation => self.starved += 1,
+                CauseOfDeath::OldAge => self.died_of_age += 1,
+                CauseOfDeath::Predation => self.eaten += 1,
+                CauseOfDeath::Nonviable => self.nonviable += 1,
+                CauseOfDeath::Wall => self.hit_wall += 1,
+                CauseOfDeath::Venom => self.poisoned += 1,
+                CauseOfDeath::Disease => self.diseased += 1,
+            }
+        }
+    }
+
+    /// Handles the hotkeys controlling the simulation and its overlays.
+    fn handle_keys(&mut self) {
+        if is_key_pressed(KeyCode::Space) {
+            self.paused = !self.paused;
+        }
+        if is_key_pressed(KeyCode::Equal) || is_key_pressed(KeyCode::KpAdd) {
+            self.clock.faster();
+        }
+        if is_key_pressed(KeyCode::Minus) || is_key_pressed(KeyCode::KpSubtract) {
+            self.clock.slower();
+        }
+        self.tools.handle_keys();
+        // Spawn archetypes at the mouse position.
+        let world = *self.simulation.world();
+        let rng = self.simulation.rng_mut();
+        let archetype = if is_key_pressed(KeyCode::Key1) {
+            Some(Biot::plant(&world, rng))
+        } else if is_key_pressed(KeyCode::Key2) {
+            Some(Biot::grazer(&world, rng))
+        } else if is_key_pressed(KeyCode::Key3) {
+            Some(Biot::predator(&world, rng))
+        } else if is_key_pressed(KeyCode::Key4) {
+            Some(Biot::smart_predator(&world, rng))
+        } else {
+            None
+        };
+        if let Some(mut biot) = archetype {
+            biot.stats.pos = self.camera.mouse_world_position(&world);
+            self.simulation.biots_mut().spawn(biot);
+        }
+        // Enlarge or shrink the world, removing whatever ends up beyond its edges.
+        let factor = if is_key_pressed(KeyCode::PageUp) {
+            Some(WORLD_GROWTH)
+        } else if is_key_pressed(KeyCode::PageDown) {
+            Some(1.0 / WORLD_GROWTH)
+        } else {
+            None
+        };
+        if let Some(factor) = factor {
+            let (width, height) = (world.width * factor, world.height * factor);
+            let culled = self.simulation.resize(width, height, Resize::Cull);
+            self.auto_camera = AutoCamera::new(self.simulation.world());
+            println!("world resized to {width:.0}x{height:.0}, {culled} biots removed");
+        }
+        if is_key_pressed(KeyCode::A) {
+            let biots = self.simulation.biots_mut();
+            let interval = if biots.sensing_interval() == 1 {
+                APPROXIMATE_SENSING_INTERVAL
+            } else {
+                1
+            };
+            biots.set_sensing_interval(interval);
+        }
+        if is_key_pressed(KeyCode::F5) {
+            if let Err(error) = self.simulation.save(SNAPSHOT_PATH) {
+                eprintln!("failed to save simulation: {error}");
+            }
+        }
+        if is_key_pressed(KeyCode::F6) {
+            let phylogeny = self.simulation.biots().phylogeny();
+            match phylogeny.save(PHYLOGENY_PATH) {
+                Ok(()) => println!("phylogeny written to {PHYLOGENY_PATH}"),
+                Err(error) => eprintln!("failed to export phylogeny: {error}"),
+            }
+        }
+        if is_key_pressed(KeyCode::F7) {
+            match self.lineages.save(LINEAGES_PATH) {
+                Ok(()) => println!("lineage statistics written to {LINEAGES_PATH}"),
+                Err(error) => eprintln!("failed to export lineage statistics: {error}"),
+            }
+        }
+        if is_key_pressed(KeyCode::F8) {
+            match self.inspector.selected(&self.simulation) {
+                Some(biot) => {
+                    let name = format!("biot-{}", biot.id().0);
+                    match library::export(biot.genome(), LIBRARY_DIR, &name) {
+                        Ok(path) => println!("genome written to {}", path.display()),
+                        Err(error) => eprintln!("failed to export genome: {error}"),
+                    }
+                }
+                None => println!("select a biot to export its genome"),
+            }
+        }
+        if is_key_pressed(KeyCode::F10) {
+            match self.inspector.selected(&self.simulation) {
+                Some(biot) => {
+                    let path = card::file_name(biot.id());
+                    card::export(&self.simulation, biot, &path);
+                    println!("card written to {path}");
+                }
+                None => println!("select a biot to export its card"),
+            }
+        }
+        if is_key_pressed(KeyCode::F9) {
+            match Simulation::load(SNAPSHOT_PATH) {
+                Ok(mut loaded) => {
+                    self.auto_camera = AutoCamera::new(loaded.world());
+                    self.trails.clear();
+                    self.keep_rules(&mut loaded);
+                    self.simulation = loaded;
+                }
+                Err(error) => eprintln!("failed to load simulation: {error}"),
+            }
+        }
+        if is_key_pressed(KeyCode::R) {
+            let step = self.timeline.steps().last();
+            match self.timeline.rewind() {
+                Some(Ok(mut rewound)) => {
+                    self.auto_camera = AutoCamera::new(rewound.world());
+                    self.trails.clear();
+                    self.keep_rules(&mut rewound);
+                    self.simulation = rewound;
+                    println!("rewound to step {}", step.unwrap_or_default());
+                }
+                Some(Err(error)) => eprintln!("failed to rewind: {error}"),
+                None => println!("no earlier snapshot to rewind to"),
+            }
+        }
+        if is_key_pressed(KeyCode::Tab) {
+            self.show_next_island();
+        }
+        if is_key_pressed(KeyCode::P) {
+            self.show_phase_plot = !self.show_phase_plot;
+        }
+        if is_key_pressed(KeyCode::G) {
+            self.show_selection = !self.show_selection;
+        }
+        if is_key_pressed(KeyCode::S) {
+            self.show_stats = !self.show_stats;
+        }
+        if is_key_pressed(KeyCode::H) {
+            self.show_sizes = !self.show_sizes;
+        }
+        if is_key_pressed(KeyCode::L) {
+            self.show_lineages = !self.show_lineages;
+        }
+        if is_key_pressed(KeyCode::C) {
+            self.follow_hotspots = !self.follow_hotspots;
+            self.follow_selected = false;
+        }
+        if is_key_pressed(KeyCode::W) {
+            self.follow_selected = !self.follow_selected;
+            self.follow_hotspots = false;
+        }
+        if is_key_pressed(KeyCode::M) {
+            self.show_scent = !self.show_scent;
+        }
+        if is_key_pressed(KeyCode::D) {
+            self.show_currents = !self.show_currents;
+        }
+        if is_key_pressed(KeyCode::V) {
+            self.show_trails = !self.show_trails;
+            self.trails.clear();
+        }
+        if is_key_pressed(KeyCode::T) {
+            self.layer = Layer::cycle(self.layer);
+        }
+        if is_key_pressed(KeyCode::K) {
+            self.coloring = match self.coloring {
+                Coloring::Traits => Coloring::Species,
+                Coloring::Species => Coloring::Traits,
+            };
+        }
+    }
+
+    /// Applies the adjustments made in the control panel.
+    fn update_control_panel(&mut self) {
+        let Some((setting, steps)) = self.control_panel.update() else {
+            return;
+        };
+        match setting {
+            Setting::TickRate if steps > 0.0 => self.clock.faster(),
+            Setting::TickRate => self.clock.slower(),
+            Setting::Layer => self.layer = Layer::cycle(self.layer),
+            Setting::Trails => {
+                self.show_trails = !self.show_trails;
+                self.trails.clear();
+            }
+            _ => {
+                let mut config = self.simulation.biots().config().clone();
+                setting.adjust(&mut config, steps);
+                if let Err(error) = self.simulation.reconfigure(config) {
+                    eprintln!("failed to apply the setting: {error}");
+                }
+            }
+        }
+    }
+
+    /// Recomputes the shown layer every [`LAYER_INTERVAL`](crate::layer::LAYER_INTERVAL) steps.
+    fn update_layer(&mut self) {
+        let Some(layer) = self.layer else {
+            self.layer_map = None;
+            return;
+        };
+        let steps = self.simulation.biots().steps();
+        if self
+            .layer_map
+            .as_ref()
+            .is_none_or(|map| map.is_stale(layer, steps))
+        {
+            let simulation = &self.simulation;
+            let map = LayerMap::compute(layer, simulation.world(), simulation.biots());
+            // Keep showing the last computation of the layer until the biots are indexed.
+            let last = self.layer_map.take().filter(|map| map.layer() == layer);
+            self.layer_map = map.or(last);
+        }
+    }
+
+    /// Moves the rules of the shown simulation to `replacement`, which
+    /// snapshots do not hold.
+    fn keep_rules(&mut self, replacement: &mut Simulation) {
+        for rules in self.simulation.biots_mut().take_rules() {
+            replacement.biots_mut().add_rules(rules);
+        }
+    }
+
+    /// Shows the next of the islands, if there are several.
+    fn show_next_island(&mut self) {
+        let Some(archipelago) = &mut self.archipelago else {
+            return;
+        };
+        let next = (self.island + 1) % (archipelago.islands().len() + 1);
+        archipelago.swap(&mut self.simulation, self.island, next);
+        self.island = next;
+        self.auto_camera = AutoCamera::new(self.simulation.world());
+        self.trails.clear();
+        // Rewinding would bring back the state of the previously shown island.
+        self.timeline = Timeline::new(rewind::DEFAULT_INTERVAL, rewind::DEFAULT_CAPACITY);
+    }
+
+    /// Selects and follows the biot searched for, if it is alive.
+    fn jump_to(&mut self, id: BiotId) {
+        if self.simulation.biots().get(id).is_none() {
+            println!("biot {} is not alive", id.0);
+            return;
+        }
+        self.inspector.select(id);
+        self.follow_selected = true;
+        self.follow_hotspots = false;
+    }
+
+    /// Centers the camera on the followed biot, stopping to follow it once it died.
+    fn follow(&mut self) {
+        if !self.follow_selected {
+            return;
+        }
+        match self.inspector.selected(&self.simulation) {
+            Some(biot) => {
+                let pos = self.interpolation.position(self.simulation.world(), biot);
+                self.camera.jump(pos);
+            }
+            None => self.follow_selected = false,
+        }
+    }
+
+    /// Returns `true` if the back button of touch screens was tapped.
+    fn is_back_tapped(&self) -> bool {
+        let (x, y) = mouse_position();
+        self.camera.is_touched()
+            && is_mouse_button_pressed(MouseButton::Left)
+            && BACK_BUTTON.contains(vec2(x, y))
+    }
+
+    /// Writes the drawn frame to a screenshot if asked for and to the timelapse.
+    ///
+    /// # Note
+    ///
+    /// Screenshots are only taken once the frame was drawn, so `F12` is
+    /// handled here rather than with the other hotkeys.
+    fn capture(&mut self) {
+        let step = self.simulation.biots().steps();
+        if is_key_pressed(KeyCode::F12) {
+            let path = capture::screenshot_path(step);
+            capture::save_frame(&path);
+            println!("screenshot written to {path}");
+        }
+        if let Some(timelapse) = &mut self.timelapse {
+            timelapse.capture(step);
+        }
+    }
+
+    /// Draws the simulation, its overlays and the status lines, including
+    /// the state of the `audio` if there is any.
+    fn draw(&self, audio: Option<&Audio>) {
+        let simulation = &self.simulation;
+        let config = &simulation.biots().config().environment;
+        clear_background(simulation.environment().background(config));
+        self.camera.activate();
+        simulation.draw(
+            &self.camera,
+            &self.filter,
+            self.coloring,
+            self.show_scent,
+            self.layer_map.as_ref(),
+            &self.interpolation,
+        );
+        if self.show_currents {
+            simulation.draw_currents(&self.camera);
+        }
+        if self.show_trails {
+            let world = simulation.world();
+            self.trails
+                .draw(world, &self.camera, simulation.biots(), &self.interpolation);
+        }
+        self.inspector
+            .draw_marker(simulation, &self.camera, &self.interpolation);
+        self.tools.draw_brush(simulation, &self.camera);
+        set_default_camera();
+        self.labels
+            .draw(simulation, &self.camera, &self.interpolation);
+        self.inspector.draw_panel(simulation);
+        self.filter.draw_panel();
+        let views = Views {
+            ticks_per_second: self.clock.ticks_per_second(),
+            layer: self.layer,
+            trails: self.show_trails,
+        };
+        self.control_panel.draw(
+            simulation.biots().config(),
+            &views,
+            self.inspector.selected(simulation),
+        );
+        if self.show_phase_plot {
+            self.phase_plot.draw();
+        }
+        if self.show_selection {
+            self.selection.draw();
+        }
+        if self.show_stats {
+            self.stats.draw();
+        }
+        if self.show_sizes {
+            SizeDistribution::take(simulation.biots()).draw();
+        }
+        if self.show_lineages {
+            self.lineages.draw();
+        }
+        if let Some((alarm, raised_at)) = &self.alarm {
+            if get_time() - raised_at < ALARM_SECONDS {
+                let size = measure_text(alarm, None, 20, 1.0);
+                let x = (screen_width() - size.width) / 2.;
+                draw_rectangle(
+                    x - 8.,
+                    6.,
+                    size.width + 16.,
+                    28.,
+                    Color::new(0., 0., 0., 0.6),
+                );
+                draw_text(alarm, x, 26., 20., ORANGE);
+            }
+        }
+        self.toasts.draw();
+        self.search.draw();
+        if self.camera.is_touched() {
+            let Rect { x, y, w, h } = BACK_BUTTON;
+            draw_rectangle(x, y, w, h, Color::new(0., 0., 0., 0.6));
+            draw_rectangle_lines(x, y, w, h, 1., LIGHTGRAY);
+            draw_text("Menu", x + 14., y + 25., 24., LIGHTGRAY);
+        }
+        minimap::draw(
+            simulation.world(),
+            simulation.biots(),
+            &self.camera,
+            self.coloring,
+        );
+        let mut speed = if self.paused {
+            String::from("paused")
+        } else {
+            format!("{} steps/s", self.clock.ticks_per_second())
+        };
+        // Flag runs whose dynamics are distorted by the population controller.
+        if simulation.biots().config().controller.is_enabled() {
+            speed = format!(
+                "{speed}, auto-tuned supply: {:.2}",
+                simulation.environment().supply()
+            );
+        }
+        let mut status = format!(
+            "FPS: {}, biots: {}, {speed}, tool: {}",
+            get_fps(),
+            simulation.biots().len(),
+            self.tools.tool().name()
+        );
+        if let Some(layer) = self.layer {
+            status = format!("{status}, layer: {}", layer.name());
+        }
+        if let Some(archipelago) = &self.archipelago {
+            let islands = archipelago.islands().len() + 1;
+            status = format!("{status}, island {} of {islands}", self.island + 1);
+        }
+        if let Some(biot) = self
+            .inspector
+            .selected(simulation)
+            .filter(|_| self.follow_selected)
+        {
+            status = format!("{status}, following biot {}", biot.id().0);
+        }
+        if let Some(audio) = audio {
+            status = format!("{status}, {}", audio.status());
+        }
+        draw_text(
+            &status,
+            screen_width() - measure_text(&status, None, 18, 1.0).width - 10.,
+            screen_height() - 5.,
+            18.,
+            LIGHTGRAY,
+        );
+        draw_text(
+            &format!(
+                "{}, food: {}, corpses: {}, clustering: {:.2}",
+                simulation.date(),
+                simulation.food().len(),
+                simulation.biots().corpses().len(),
+                simulation.biots().clustering_index()
+            ),
+            screen_width() - 480.,
+            screen_height() - 23.,
+            18.,
+            LIGHTGRAY,
+        );
+        draw_text(
+            &format!(
+                "infected: {}, dying: {}, starved: {}, old age: {}, eaten: {}, nonviable: {}, \
+                 walls: {}, poisoned: {}, diseased: {}",
+                simulation.biots().infected_count(),
+                simulation.biots().dying_count(),
+                self.starved,
+                self.died_of_age,
+                self.eaten,
+                self.nonviable,
+                self.hit_wall,
+                self.poisoned,
+                self.diseased
+            ),
+            screen_width() - 820.,
+            screen_height() - 41.,
+            18.,
+            LIGHTGRAY,
+        );
+    }
+}
+
+impl Scene for Viewer {
+    fn frame(&mut self, context: &mut Context) -> Transition {
+        // The search prompt takes all keys while it is open, including `Escape`.
+        let typing = self.search.is_open();
+        if let Some(id) = self.search.update() {
+            self.jump_to(id);
+        }
+        if !typing {
+            if is_key_pressed(KeyCode::Escape) || self.is_back_tapped() {
+                if let Some(audio) = &mut context.audio {
+                    audio.silence();
+                }
+                return Transition::Pop;
+            }
+            self.handle_keys();
+        }
+        // While paused the period key advances the simulation by a single step.
+        let steps = if !self.paused {
+            self.clock.advance(get_frame_time())
+        } else if !typing && is_key_pressed(KeyCode::Period) {
+            1
+        } else {
+            0
+        };
+        for n in 0..steps {
+            if n + 1 == steps {
+                self.interpolation.capture(self.simulation.biots());
+            }
+            self.step();
+            if let Some(audio) = &mut context.audio {
+                audio.listen(&self.events);
+            }
+        }
+        if let Some(audio) = &mut context.audio {
+            if !typing {
+                audio.handle_keys();
+            }
+            audio.play(self.simulation.biots().len());
+        }
+        let alpha = if self.paused { 1.0 } else { self.clock.alpha() };
+        self.interpolation.set_alpha(alpha);
+        self.camera.update();
+        if self.follow_hotspots {
+            let world = self.simulation.world();
+            self.auto_camera
+                .steer(&mut self.camera, world, AUTO_CAMERA_ZOOM);
+        }
+        // Clicks into the minimap move the camera instead of applying the tool.
+        let world = *self.simulation.world();
+        let jumped = minimap::click(&world, &mut self.camera);
+        if jumped {
+            self.follow_hotspots = false;
+            self.follow_selected = false;
+        } else if self.tools.tool() == Tool::Inspect {
+            self.inspector.update(&self.simulation, &self.camera);
+        } else {
+            let selected = self.inspector.selected(&self.simulation).map(Biot::id);
+            self.tools
+                .apply(&mut self.simulation, &self.camera, selected);
+        }
+        self.follow();
+        // The arrow keys are reserved for the control panel while it is shown.
+        if !typing {
+            self.update_control_panel();
+        }
+        if !typing && !self.control_panel.is_visible() {
+            self.filter
+                .update(self.inspector.selected(&self.simulation));
+        }
+        self.update_layer();
+        self.draw(context.audio.as_ref());
+        self.capture();
+        Transition::Stay
+    }
+}
+
+impl Drop for Viewer {
+    fn drop(&mut self) {
+        if let Some(path) = &self.events_path {
+            match self.events.save(path) {
+                Ok(()) => println!("event log written to {path}"),
+                Err(error) => eprintln!("failed to write event log to {path}: {error}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Runtime detection of pathological simulation states.
+//!
+//! Unattended long runs can silently go wrong, e.g. with a biot at a NaN
+//! position poisoning every query near it. The watchdog checks the
+//! simulation after every step and reports such states once they appear,
+//! so the frontends can respond as configured, see [`WatchdogConfig`].
+
+use crate::config::{WatchdogAction, WatchdogConfig};
+use crate::simulation::Simulation;
+use std::fmt;
+use std::time::Duration;
+
+/// File the state of the simulation is saved to by the
+/// [`WatchdogAction::Checkpoint`] action.
+pub const WATCHDOG_SNAPSHOT_PATH: &str = "life_web_watchdog.snapshot";
+
+/// Population below which growth is never pathological, as small
+/// populations, e.g. right after a bottleneck, may well double quickly.
+const GROWTH_FLOOR: usize = 100;
+
+/// A pathological state of the simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Anomaly {
+    /// A biot has a NaN or infinite position, speed or life.
+    NonFinite {
+        /// Identifier of the first such biot.
+        id: u64,
+    },
+    /// The population grew by more than the configured factor.
+    Growth {
+        from: usize,
+        to: usize,
+        /// Number of steps the population grew within.
+        steps: u32,
+    },
+    /// A single step took longer than the configured wall time.
+    SlowStep(Duration),
+    /// The spatial index does not match the biots.
+    Index(String),
+}
+
+impl Anomaly {
+    /// Returns `true` if both anomalies are of the same kind.
+    fn is_like(&self, other: &Self) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFinite { id } => write!(f, "biot {id} has a non-finite state"),
+            Self::Growth { from, to, steps } => {
+                write!(
+                    f,
+                    "population grew from {from} to {to} within {steps} steps"
+                )
+            }
+            Self::SlowStep(elapsed) => write!(f, "a step took {elapsed:.2?}"),
+            Self::Index(message) => write!(f, "inconsistent spatial index: {message}"),
+        }
+    }
+}
+
+/// Checks a simulation for pathological states after every step.
+#[derive(Debug, Clone, Default)]
+pub struct Watchdog {
+    /// Step and population of the last growth check.
+    baseline: Option<(u32, usize)>,
+    /// Anomalies of the last check, which are not reported again while they last.
+    active: Vec<Anomaly>,
+}
+
+impl Watchdog {
+    /// Creates a watchdog that has not seen the simulation yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks `simulation` right after a step that took `elapsed` wall time, if measured.
+    ///
+    /// Returns the anomalies that newly appeared and the action to take
+    /// for them, nothing if the watchdog is disabled.
+    pub fn check(
+        &mut self,
+        simulation: &Simulation,
+        elapsed: Option<Duration>,
+    ) -> Option<(Vec<Anomaly>, WatchdogAction)> {
+        let biots = simulation.biots();
+        let config = &biots.config().watchdog;
+        if !config.enabled {
+            return None;
+        }
+        let mut anomalies = Vec::new();
+        let broken = biots.iter().find(|biot| {
+            let stats = &biot.stats;
+            !stats.pos.is_finite() || !stats.speed.is_finite() || !stats.life.0.is_finite()
+        });
+        if let Some(biot) = broken {
+            anomalies.push(Anomaly::NonFinite { id: biot.id().0 });
+        }
+        anomalies.extend(self.check_growth(simulation, config));
+        let limit = Duration::from_secs_f32(config.max_step_seconds);
+        if let Some(elapsed) = elapsed.filter(|elapsed| !limit.is_zero() && *elapsed > limit) {
+            anomalies.push(Anomaly::SlowStep(elapsed));
+        }
+        if let Err(message) = biots.check_index(simulation.world()) {
+            anomalies.push(Anomaly::Index(message));
+        }
+        // Growth is only checked every few steps, so it is reported whenever detected.
+        let new = anomalies
+            .iter()
+            .filter(|anomaly| {
+                matches!(anomaly, Anomaly::Growth { .. })
+                    || !self.active.iter().any(|active| active.is_like(anomaly))
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        self.active = anomalies;
+        (!new.is_empty()).then_some((new, config.action))
+    }
+
+    /// Compares the population to the one of the last growth check, every
+    /// [`WatchdogConfig::interval`] steps.
+    fn check_growth(
+        &mut self,
+        simulation: &Simulation,
+        config: &WatchdogConfig,
+    ) -> Option<Anomaly> {
+        let (step, population) = (simulation.biots().steps(), simulation.biots().len());
+        let Some((last_step, last_population)) = self.baseline else {
+            self.baseline = Some((step, population));
+            return None;
+        };
+        // Rewinding or loading a snapshot starts over.
+        if step < last_step {
+            self.baseline = Some((step, population));
+            return None;
+        }
+        if step - last_step < config.interval {
+            return None;
+        }
+        self.baseline = Some((step, population));
+        let limit = config.max_growth * last_population.max(GROWTH_FLOOR) as f32;
+        (population as f32 > limit).then_some(Anomaly::Growth {
+            from: last_population,
+            to: population,
+            steps: step - last_step,
+        })
+    }
+}
+
+/// Prints the `anomalies` detected in `simulation` and takes the `action`,
+/// saving a snapshot for [`WatchdogAction::Checkpoint`].
+///
+/// Returns `true` if the run should pause.
+pub fn respond(simulation: &Simulation, anomalies: &[Anomaly], action: WatchdogAction) -> bool {
+    let step = simulation.biots().steps();
+    for anomaly in anomalies {
+        eprintln!("watchdog: step {step}: {anomaly}");
+    }
+    match action {
+        WatchdogAction::Log => false,
+        WatchdogAction::Pause => true,
+        WatchdogAction::Checkpoint => {
+            match simulation.save(WATCHDOG_SNAPSHOT_PATH) {
+                Ok(()) => eprintln!("watchdog: state saved to {WATCHDOG_SNAPSHOT_PATH}"),
+                Err(error) => eprintln!("watchdog: failed to save the state: {error}"),
+            }
+            false
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::rng::Rng;
+use core::ops;
+use glam::{vec2, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// What happens at the edges of the world.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topology {
+    /// The edges wrap around, making the world a torus.
+    #[default]
+    Torus,
+    /// Biots whose center crosses an edge die.
+    Walls,
+    /// Biots bounce off the edges.
+    Bouncing,
+}
+
+/// The simulated area.
+///
+/// The world is independent of the render target, so it can be larger or
+/// smaller than the screen. By default its edges wrap around, making it a torus.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+pub struct World {
+    pub width: f32,
+    pub height: f32,
+    pub topology: Topology,
+}
+
+impl World {
+    /// Creates a toroidal world of the given size.
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            topology: Topology::Torus,
+        }
+    }
+
+    /// Returns the same world with the given topology.
+    pub fn with_topology(self, topology: Topology) -> Self {
+        Self { topology, ..self }
+    }
+
+    /// The area of the world.
+    pub fn area(&self) -> f32 {
+        self.width * self.height
+    }
+
+    /// Returns a uniformly distributed random position within the world.
+    pub fn random_position(&self, rng: &mut Rng) -> Vec2 {
+        vec2(
+            rng.gen_range(0., 1.) * self.width,
+            rng.gen_range(0., 1.) * self.height,
+        )
+    }
+
+    /// Returns `true` if the edges of the world wrap around.
+    pub fn is_torus(&self) -> bool {
+        self.topology == Topology::Torus
+    }
+
+    /// Maps a position into the world, wrapping it around the edges of a
+    /// torus and clamping it to the edges otherwise.
+    pub fn wrap(&self, pos: Vec2) -> Vec2 {
+        match self.topology {
+            Topology::Torus => vec2(modulus(pos.x, self.width), modulus(pos.y, self.height)),
+            Topology::Walls | Topology::Bouncing => {
+                vec2(pos.x.clamp(0.0, self.width), pos.y.clamp(0.0, self.height))
+            }
+        }
+    }
+
+    /// Keeps a body moving with `speed` within the world.
+    ///
+    /// Returns `false` if the center of the body crossed a wall that kills it.
+    pub fn confine(&self, pos: &mut Vec2, speed: &mut Vec2) -> bool {
+        let outside = |value: f32, size: f32| !(0.0..=size).contains(&value);
+        match self.topology {
+            Topology::Torus => {
+                *pos = self.wrap(*pos);
+                true
+            }
+            Topology::Walls => {
+                let hit = outside(pos.x, self.width) || outside(pos.y, self.height);
+                *pos = self.wrap(*pos);
+                !hit
+            }
+            Topology::Bouncing => {
+                if outside(pos.x, self.width) {
+                    speed.x = -speed.x;
+                    pos.x = reflect(pos.x, self.width);
+                }
+                if outside(pos.y, self.height) {
+                    speed.y = -speed.y;
+                    pos.y = reflect(pos.y, self.height);
+                }
+                // Bodies faster than the whole world would still be outside.
+                *pos = self.wrap(*pos);
+                true
+            }
+        }
+    }
+
+    /// Returns the shortest vector from `from` to `to`.
+    ///
+    /// # Note
+    ///
+    /// On a torus the shortest way may cross an edge of the world.
+    pub fn delta(&self, from: Vec2, to: Vec2) -> Vec2 {
+        let delta = to - from;
+        if !self.is_torus() {
+            return delta;
+        }
+        let shortest = |delta: f32, size: f32| delta - size * (delta / size).round();
+        vec2(
+            shortest(delta.x, self.width),
+            shortest(delta.y, self.height),
+        )
+    }
+}
+
+/// Mirrors a coordinate beyond an edge back into `0..=size`.
+fn reflect(value: f32, size: f32) -> f32 {
+    if value < 0.0 {
+        -value
+    } else {
+        2.0 * size - value
+    }
+}
+
+/// Modulus operator to get toroidal world topology
+fn modulus<T>(a: T, b: T) -> T
+where
+    T: ops::Rem<Output = T> + ops::Add<Output = T> + Copy,
+{
+    ((a % b) + b) % b
+}
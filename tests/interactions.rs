@@ -0,0 +1,23 @@
+//! Checks how the interactions of a step play out between several biots.
+
+use life_web::prelude::*;
+
+#[test]
+fn prey_is_eaten_only_once() {
+    let mut scenario = Scenario::new();
+    let first = scenario.place(Biot::predator, 10.0, 10.0);
+    let second = scenario.place(Biot::predator, 20.0, 10.0);
+    let plant = scenario.place(Biot::plant, 15.0, 10.0);
+    let lives = [scenario.life(first), scenario.life(second)];
+    scenario.run(1);
+    assert!(scenario.is_dead(plant));
+    let fights = scenario.simulation().biots().fights();
+    let bites = fights.iter().filter(|fight| fight.prey == plant).count();
+    assert_eq!(bites, 1, "the plant was eaten in {fights:?}");
+    let fed = [first, second]
+        .iter()
+        .zip(lives)
+        .filter(|(id, life)| scenario.life(**id) > *life)
+        .count();
+    assert_eq!(fed, 1, "both predators fed on the plant");
+}
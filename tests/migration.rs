@@ -0,0 +1,77 @@
+//! Loads snapshots and configuration files written by older versions from
+//! the fixtures in `tests/fixtures`.
+
+use life_web::config::Shape;
+use life_web::migration::CONFIG_VERSION;
+use life_web::prelude::*;
+
+/// Returns the path of the fixture `name`.
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn snapshots_of_version_43_are_upgraded() {
+    let mut simulation =
+        Simulation::load(fixture("snapshot_v43.bin")).expect("the old snapshot loads");
+    let biots = simulation.biots();
+    assert_eq!(biots.steps(), 300);
+    let ids = biots.iter().map(|biot| biot.id().0).collect::<Vec<_>>();
+    assert_eq!(ids, [3, 5, 7, 11, 12, 13, 14]);
+    // The parent of two living biots was killed just before the snapshot.
+    let dot = biots.phylogeny().to_dot();
+    assert!(dot.contains("8 [label=\"8\\nstep 0\"];"), "{dot}");
+    assert!(!dot.contains("dashed"), "{dot}");
+    simulation.step();
+    let bytes = simulation
+        .encode()
+        .expect("the upgraded simulation encodes");
+    let restored = Simulation::decode(&bytes).expect("the upgraded snapshot decodes");
+    assert_eq!(restored.biots().steps(), 301);
+}
+
+#[test]
+fn snapshots_older_than_version_43_are_rejected() {
+    let mut bytes = Simulation::load(fixture("snapshot_v43.bin"))
+        .expect("the old snapshot loads")
+        .encode()
+        .expect("the simulation encodes");
+    bytes[..4].copy_from_slice(&42u32.to_le_bytes());
+    let error = Simulation::decode(&bytes).err();
+    assert!(
+        matches!(error, Some(SnapshotError::UnsupportedVersion(42))),
+        "{error:?}"
+    );
+}
+
+#[test]
+fn configs_of_version_1_are_upgraded() {
+    let config = SimConfig::load(fixture("config_v1.toml")).expect("the old config loads");
+    assert_eq!(config.initial_population, 300);
+    assert_eq!(config.metabolism.attack, 6.0);
+    let motion = &config.responses.motion;
+    assert_eq!((motion.threshold, motion.shape), (2.0, Shape::Power(0.5)));
+    let armor = &config.responses.armor;
+    assert_eq!((armor.shape, armor.cap), (Shape::Linear, Some(10.0)));
+}
+
+#[test]
+fn scenarios_upgrade_their_config() {
+    let spec = ScenarioSpec::from_toml(
+        "version = 1\n[config.responses.vision]\nexponent = 0.25\n[[biots]]\narchetype = \"plant\"",
+    )
+    .expect("the old scenario parses");
+    assert_eq!(spec.config.responses.vision.shape, Shape::Power(0.25));
+    assert_eq!(spec.biots.len(), 1);
+}
+
+#[test]
+fn configs_of_newer_versions_are_rejected() {
+    let current = format!("version = {CONFIG_VERSION}\n[responses.motion]\nshape = \"sqrt\"");
+    let config = SimConfig::from_toml(&current).expect("the current config parses");
+    assert_eq!(config.responses.motion.shape, Shape::Sqrt);
+    let newer = format!("version = {}", CONFIG_VERSION + 1);
+    assert!(SimConfig::from_toml(&newer).is_err());
+    let renamed = format!("version = {CONFIG_VERSION}\n[responses.motion]\nexponent = 0.5");
+    assert!(SimConfig::from_toml(&renamed).is_err());
+}
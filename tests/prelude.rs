@@ -0,0 +1,45 @@
+//! Embeds the simulation through the prelude, as other crates do.
+
+use life_web::prelude::*;
+
+#[test]
+fn predation_is_reported_through_the_prelude() {
+    let mut scenario = Scenario::new();
+    let predator = scenario.place(Biot::predator, 10.0, 10.0);
+    let plant = scenario.place(Biot::plant, 15.0, 10.0);
+    scenario.run(1);
+    let biots: &BiotCollection = scenario.simulation().biots();
+    let fights: &[Fight] = biots.fights();
+    assert!(
+        fights
+            .iter()
+            .any(|fight| fight.predator == predator && fight.prey == plant && fight.killed),
+        "the kill is missing from {fights:?}"
+    );
+    let obituaries: &[Obituary] = biots.obituaries();
+    assert!(
+        obituaries
+            .iter()
+            .any(|obituary| obituary.id == plant && obituary.cause == CauseOfDeath::Predation),
+        "the death of the plant is missing from {obituaries:?}"
+    );
+    assert!(scenario.is_dead(plant));
+}
+
+#[test]
+fn simulations_step_and_restore_from_snapshots() {
+    let world = World::new(300.0, 200.0);
+    let config = SimConfig {
+        initial_population: 40,
+        ..SimConfig::default()
+    };
+    let mut simulation = Simulation::new(world, config, 7);
+    for _ in 0..20 {
+        simulation.step();
+    }
+    assert_eq!(simulation.biots().steps(), 20);
+    let bytes = simulation.encode().expect("the simulation encodes");
+    let restored = Simulation::decode(&bytes).expect("the snapshot decodes");
+    let ids = |simulation: &Simulation| simulation.biots().iter().map(Biot::id).collect::<Vec<_>>();
+    assert_eq!(ids(&restored), ids(&simulation));
+}